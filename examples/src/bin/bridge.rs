@@ -241,7 +241,8 @@ impl bridged_device_basic_information::ClusterHandler for BridgedHandler {
         .with_revision(1)
         .with_features(0)
         .with_attrs(with!(required))
-        .with_cmds(with!());
+        .with_cmds(with!())
+        .with_events(with!());
 
     fn dataver(&self) -> u32 {
         self.dataver.get()
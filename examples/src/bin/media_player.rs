@@ -228,7 +228,8 @@ impl media_playback::ClusterAsyncHandler for MediaHandler {
             media_playback::CommandId::Play
                 | media_playback::CommandId::Pause
                 | media_playback::CommandId::Stop
-        ));
+        ))
+        .with_events(with!());
 
     fn dataver(&self) -> u32 {
         self.dataver.get()
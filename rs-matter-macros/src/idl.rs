@@ -19,6 +19,7 @@ use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 
 mod bitmap;
+mod client;
 mod cluster;
 mod enumeration;
 mod field;
@@ -52,13 +53,15 @@ impl IdlGenerateContext {
 /// Return a token stream containing Rust types corresponding to all definitions
 /// in the provided IDL cluster:
 ///
-pub fn cluster(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
-    cluster_internal(cluster, true, context)
+/// If `with_client` is true, a typed client for the cluster is generated as well.
+pub fn cluster(cluster: &Cluster, with_client: bool, context: &IdlGenerateContext) -> TokenStream {
+    cluster_internal(cluster, true, with_client, context)
 }
 
 fn cluster_internal(
     cluster: &Cluster,
     with_async: bool,
+    with_client: bool,
     context: &IdlGenerateContext,
 ) -> TokenStream {
     let cluster_module_name = Ident::new(
@@ -80,6 +83,7 @@ fn cluster_internal(
     let attribute_id = cluster::attribute_id(cluster, context);
     let command_id = cluster::command_id(cluster, context);
     let command_response_id = cluster::command_response_id(cluster, context);
+    let event_id = cluster::event_id(cluster, context);
     let cluster_meta = cluster::cluster(cluster, context);
 
     let handler = handler::handler(false, false, cluster, context);
@@ -103,6 +107,8 @@ fn cluster_internal(
 
         #command_response_id
 
+        #event_id
+
         #cluster_meta
 
         #handler
@@ -130,6 +136,18 @@ fn cluster_internal(
         quote
     };
 
+    let quote = if with_client {
+        let client = client::client(cluster, context);
+
+        quote!(
+            #quote
+
+            #client
+        )
+    } else {
+        quote
+    };
+
     quote!(
         #[doc = #cluster_module_doc]
         #[allow(async_fn_in_trait)]
@@ -170,11 +188,11 @@ mod tests {
 
         // panic!(
         //     "====\n{}\n====",
-        //     &cluster_internal(cluster, false, &context)
+        //     &cluster_internal(cluster, false, false, &context)
         // );
 
         assert_tokenstreams_eq!(
-            &cluster_internal(cluster, false, &context),
+            &cluster_internal(cluster, false, false, &context),
             &TOKEN_STREAM_OUTPUT
         );
     }
@@ -561,6 +579,23 @@ pub mod unit_testing {
         SizeOfResponseBuffer = 1,
         FillCharacter = 2,
     }
+    #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+    #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
+    #[repr(u8)]
+    pub enum TestEventTag {
+        Arg1 = 1,
+        Arg2 = 2,
+        Arg3 = 3,
+        Arg4 = 4,
+        Arg5 = 5,
+        Arg6 = 6,
+    }
+    #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+    #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
+    #[repr(u8)]
+    pub enum TestFabricScopedEventTag {
+        FabricIndex = 254,
+    }
     #[derive(PartialEq, Eq, Clone, Hash)]
     pub struct SimpleStruct<'a>(rs_matter_crate::tlv::TLVElement<'a>);
     impl<'a> SimpleStruct<'a> {
@@ -5377,6 +5412,203 @@ pub mod unit_testing {
             rs_matter_crate::reexport::defmt::write!(f, "}}")
         }
     }
+    #[derive(PartialEq, Eq, Clone, Hash)]
+    pub struct TestEvent<'a>(rs_matter_crate::tlv::TLVElement<'a>);
+    impl<'a> TestEvent<'a> {
+        #[doc = "Create a new instance"]
+        pub const fn new(element: rs_matter_crate::tlv::TLVElement<'a>) -> Self {
+            Self(element)
+        }
+        #[doc = "Return the underlying TLV element"]
+        pub const fn tlv_element(&self) -> &rs_matter_crate::tlv::TLVElement<'a> {
+            &self.0
+        }
+        pub fn arg_1(&self) -> Result<u8, rs_matter_crate::error::Error> {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(1)?)
+        }
+        pub fn arg_2(&self) -> Result<SimpleEnum, rs_matter_crate::error::Error> {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(2)?)
+        }
+        pub fn arg_3(&self) -> Result<bool, rs_matter_crate::error::Error> {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(3)?)
+        }
+        pub fn arg_4(&self) -> Result<SimpleStruct<'_>, rs_matter_crate::error::Error> {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(4)?)
+        }
+        pub fn arg_5(
+            &self,
+        ) -> Result<
+            rs_matter_crate::tlv::TLVArray<'_, SimpleStruct<'_>>,
+            rs_matter_crate::error::Error,
+        > {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(5)?)
+        }
+        pub fn arg_6(
+            &self,
+        ) -> Result<rs_matter_crate::tlv::TLVArray<'_, SimpleEnum>, rs_matter_crate::error::Error>
+        {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(6)?)
+        }
+    }
+    impl<'a> rs_matter_crate::tlv::FromTLV<'a> for TestEvent<'a> {
+        fn from_tlv(
+            element: &rs_matter_crate::tlv::TLVElement<'a>,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Ok(Self::new(element.clone()))
+        }
+    }
+    impl rs_matter_crate::tlv::ToTLV for TestEvent<'_> {
+        fn to_tlv<W: rs_matter_crate::tlv::TLVWrite>(
+            &self,
+            tag: &rs_matter_crate::tlv::TLVTag,
+            tw: W,
+        ) -> Result<(), rs_matter_crate::error::Error> {
+            self.0.to_tlv(tag, tw)
+        }
+        fn tlv_iter(
+            &self,
+            tag: rs_matter_crate::tlv::TLVTag,
+        ) -> impl Iterator<Item = Result<rs_matter_crate::tlv::TLV, rs_matter_crate::error::Error>>
+        {
+            self.0.tlv_iter(tag)
+        }
+    }
+    impl core::fmt::Debug for TestEvent<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{} {{", "TestEvent")?;
+            match self.arg_1() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_1", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_1", e)?,
+            }
+            match self.arg_2() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_2", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_2", e)?,
+            }
+            match self.arg_3() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_3", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_3", e)?,
+            }
+            match self.arg_4() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_4", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_4", e)?,
+            }
+            match self.arg_5() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_5", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_5", e)?,
+            }
+            match self.arg_6() {
+                Ok(value) => write!(f, "{}: {:?},", "arg_6", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "arg_6", e)?,
+            }
+            write!(f, "}}")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl rs_matter_crate::reexport::defmt::Format for TestEvent<'_> {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "{} {{", "TestEvent");
+            match self.arg_1() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_1", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_1", e),
+            }
+            match self.arg_2() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_2", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_2", e),
+            }
+            match self.arg_3() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_3", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_3", e),
+            }
+            match self.arg_4() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_4", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_4", e),
+            }
+            match self.arg_5() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_5", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_5", e),
+            }
+            match self.arg_6() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "arg_6", value)
+                }
+                Err(e) => rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "arg_6", e),
+            }
+            rs_matter_crate::reexport::defmt::write!(f, "}}")
+        }
+    }
+    #[derive(PartialEq, Eq, Clone, Hash)]
+    pub struct TestFabricScopedEvent<'a>(rs_matter_crate::tlv::TLVElement<'a>);
+    impl<'a> TestFabricScopedEvent<'a> {
+        #[doc = "Create a new instance"]
+        pub const fn new(element: rs_matter_crate::tlv::TLVElement<'a>) -> Self {
+            Self(element)
+        }
+        #[doc = "Return the underlying TLV element"]
+        pub const fn tlv_element(&self) -> &rs_matter_crate::tlv::TLVElement<'a> {
+            &self.0
+        }
+        pub fn fabric_index(&self) -> Result<u8, rs_matter_crate::error::Error> {
+            rs_matter_crate::tlv::FromTLV::from_tlv(&self.0.structure()?.ctx(254)?)
+        }
+    }
+    impl<'a> rs_matter_crate::tlv::FromTLV<'a> for TestFabricScopedEvent<'a> {
+        fn from_tlv(
+            element: &rs_matter_crate::tlv::TLVElement<'a>,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Ok(Self::new(element.clone()))
+        }
+    }
+    impl rs_matter_crate::tlv::ToTLV for TestFabricScopedEvent<'_> {
+        fn to_tlv<W: rs_matter_crate::tlv::TLVWrite>(
+            &self,
+            tag: &rs_matter_crate::tlv::TLVTag,
+            tw: W,
+        ) -> Result<(), rs_matter_crate::error::Error> {
+            self.0.to_tlv(tag, tw)
+        }
+        fn tlv_iter(
+            &self,
+            tag: rs_matter_crate::tlv::TLVTag,
+        ) -> impl Iterator<Item = Result<rs_matter_crate::tlv::TLV, rs_matter_crate::error::Error>>
+        {
+            self.0.tlv_iter(tag)
+        }
+    }
+    impl core::fmt::Debug for TestFabricScopedEvent<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{} {{", "TestFabricScopedEvent")?;
+            match self.fabric_index() {
+                Ok(value) => write!(f, "{}: {:?},", "fabric_index", value)?,
+                Err(e) => write!(f, "{}: ??? {:?},", "fabric_index", e)?,
+            }
+            write!(f, "}}")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl rs_matter_crate::reexport::defmt::Format for TestFabricScopedEvent<'_> {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "{} {{", "TestFabricScopedEvent");
+            match self.fabric_index() {
+                Ok(value) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: {:?},", "fabric_index", value)
+                }
+                Err(e) => {
+                    rs_matter_crate::reexport::defmt::write!(f, "{}: ??? {:?},", "fabric_index", e)
+                }
+            }
+            rs_matter_crate::reexport::defmt::write!(f, "}}")
+        }
+    }
     pub struct SimpleStructBuilder<P, const F: usize = 0usize>(P);
     impl<P> SimpleStructBuilder<P>
     where
@@ -16892,63 +17124,577 @@ pub mod unit_testing {
             self.0
         }
     }
-    #[doc = "The attribute IDs for the cluster."]
-    #[derive(
-        Copy, Clone, Debug, Eq, PartialEq, Hash, rs_matter_crate :: reexport :: strum :: FromRepr,
-    )]
-    #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
-    #[repr(u32)]
-    pub enum AttributeId {
-        Boolean = 0,
-        Bitmap8 = 1,
-        Bitmap16 = 2,
-        Bitmap32 = 3,
-        Bitmap64 = 4,
-        Int8u = 5,
-        Int16u = 6,
-        Int24u = 7,
-        Int32u = 8,
-        Int40u = 9,
-        Int48u = 10,
-        Int56u = 11,
-        Int64u = 12,
-        Int8s = 13,
-        Int16s = 14,
-        Int24s = 15,
-        Int32s = 16,
-        Int40s = 17,
-        Int48s = 18,
-        Int56s = 19,
-        Int64s = 20,
-        Enum8 = 21,
-        Enum16 = 22,
-        FloatSingle = 23,
-        FloatDouble = 24,
-        OctetString = 25,
-        ListInt8u = 26,
-        ListOctetString = 27,
-        ListStructOctetString = 28,
-        LongOctetString = 29,
-        CharString = 30,
-        LongCharString = 31,
-        EpochUs = 32,
-        EpochS = 33,
-        VendorId = 34,
-        ListNullablesAndOptionalsStruct = 35,
-        EnumAttr = 36,
-        StructAttr = 37,
-        RangeRestrictedInt8u = 38,
-        RangeRestrictedInt8s = 39,
-        RangeRestrictedInt16u = 40,
-        RangeRestrictedInt16s = 41,
-        ListLongOctetString = 42,
-        ListFabricScoped = 43,
-        TimedWriteBoolean = 48,
-        GeneralErrorBoolean = 49,
-        ClusterErrorBoolean = 50,
-        Unsupported = 255,
-        NullableBoolean = 16384,
-        NullableBitmap8 = 16385,
+    pub struct TestEventBuilder<P, const F: usize = 1usize>(P);
+    impl<P> TestEventBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Create a new instance"]
+        pub fn new(
+            mut parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            parent.writer().start_struct(tag)?;
+            Ok(Self(parent))
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> TestEventBuilder<P, 1>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent
+            + core::fmt::Debug
+            + rs_matter_crate::reexport::defmt::Format,
+    {
+        pub fn arg_1(
+            mut self,
+            value: u8,
+        ) -> Result<TestEventBuilder<P, 2usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "defmt")]
+            rs_matter_crate::reexport::defmt::debug!("{:?}::{} -> {:?} +", self, "arg1", value);
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg1", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(1),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    #[cfg(not(feature = "defmt"))]
+    impl<P> TestEventBuilder<P, 1>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent + core::fmt::Debug,
+    {
+        pub fn arg_1(
+            mut self,
+            value: u8,
+        ) -> Result<TestEventBuilder<P, 2usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg1", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(1),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> TestEventBuilder<P, 2>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent
+            + core::fmt::Debug
+            + rs_matter_crate::reexport::defmt::Format,
+    {
+        pub fn arg_2(
+            mut self,
+            value: SimpleEnum,
+        ) -> Result<TestEventBuilder<P, 3usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "defmt")]
+            rs_matter_crate::reexport::defmt::debug!("{:?}::{} -> {:?} +", self, "arg2", value);
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg2", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(2),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    #[cfg(not(feature = "defmt"))]
+    impl<P> TestEventBuilder<P, 2>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent + core::fmt::Debug,
+    {
+        pub fn arg_2(
+            mut self,
+            value: SimpleEnum,
+        ) -> Result<TestEventBuilder<P, 3usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg2", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(2),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> TestEventBuilder<P, 3>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent
+            + core::fmt::Debug
+            + rs_matter_crate::reexport::defmt::Format,
+    {
+        pub fn arg_3(
+            mut self,
+            value: bool,
+        ) -> Result<TestEventBuilder<P, 4usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "defmt")]
+            rs_matter_crate::reexport::defmt::debug!("{:?}::{} -> {:?} +", self, "arg3", value);
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg3", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(3),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    #[cfg(not(feature = "defmt"))]
+    impl<P> TestEventBuilder<P, 3>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent + core::fmt::Debug,
+    {
+        pub fn arg_3(
+            mut self,
+            value: bool,
+        ) -> Result<TestEventBuilder<P, 4usize>, rs_matter_crate::error::Error> {
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!("{:?}::{} -> {:?} +", self, "arg3", value);
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(3),
+                self.0.writer(),
+            )?;
+            Ok(TestEventBuilder(self.0))
+        }
+    }
+    impl<P> TestEventBuilder<P, 4>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        pub fn arg_4(
+            self,
+        ) -> Result<SimpleStructBuilder<TestEventBuilder<P, 5usize>>, rs_matter_crate::error::Error>
+        {
+            rs_matter_crate::tlv::TLVBuilder::new(
+                TestEventBuilder(self.0),
+                &rs_matter_crate::tlv::TLVTag::Context(4),
+            )
+        }
+    }
+    impl<P> TestEventBuilder<P, 5>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        pub fn arg_5(
+            self,
+        ) -> Result<
+            SimpleStructArrayBuilder<TestEventBuilder<P, 6usize>>,
+            rs_matter_crate::error::Error,
+        > {
+            rs_matter_crate::tlv::TLVBuilder::new(
+                TestEventBuilder(self.0),
+                &rs_matter_crate::tlv::TLVTag::Context(5),
+            )
+        }
+    }
+    impl<P> TestEventBuilder<P, 6>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        pub fn arg_6(
+            self,
+        ) -> Result<
+            rs_matter_crate::tlv::ToTLVArrayBuilder<TestEventBuilder<P, 7usize>, SimpleEnum>,
+            rs_matter_crate::error::Error,
+        > {
+            rs_matter_crate::tlv::TLVBuilder::new(
+                TestEventBuilder(self.0),
+                &rs_matter_crate::tlv::TLVTag::Context(6),
+            )
+        }
+    }
+    impl<P> TestEventBuilder<P, 7usize>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Finish the struct and return the parent"]
+        pub fn end(mut self) -> Result<P, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            self.0.writer().end_container()?;
+            Ok(self.0)
+        }
+    }
+    impl<P, const F: usize> core::fmt::Debug for TestEventBuilder<P, F>
+    where
+        P: core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{:?}::{}", self.0, "TestEvent")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P, const F: usize> rs_matter_crate::reexport::defmt::Format for TestEventBuilder<P, F>
+    where
+        P: rs_matter_crate::reexport::defmt::Format,
+    {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "{:?}::{}", self.0, "TestEvent")
+        }
+    }
+    impl<P, const F: usize> rs_matter_crate::tlv::TLVBuilderParent for TestEventBuilder<P, F>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        type Write = P::Write;
+        fn writer(&mut self) -> &mut P::Write {
+            self.0.writer()
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilder<P> for TestEventBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        fn new(
+            parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Self::new(parent, tag)
+        }
+        fn unchecked_into_parent(self) -> P {
+            self.0
+        }
+    }
+    pub struct TestEventArrayBuilder<P>(P);
+    impl<P> TestEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Create a new instance"]
+        pub fn new(
+            mut parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            parent.writer().start_array(tag)?;
+            Ok(Self(parent))
+        }
+        #[doc = "Push a new element into the array"]
+        pub fn push(
+            self,
+        ) -> Result<TestEventBuilder<TestEventArrayBuilder<P>>, rs_matter_crate::error::Error>
+        {
+            rs_matter_crate::tlv::TLVBuilder::new(
+                TestEventArrayBuilder(self.0),
+                &rs_matter_crate::tlv::TLVTag::Anonymous,
+            )
+        }
+        #[doc = "Finish the array and return the parent"]
+        pub fn end(mut self) -> Result<P, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            self.0.writer().end_container()?;
+            Ok(self.0)
+        }
+    }
+    impl<P> core::fmt::Debug for TestEventArrayBuilder<P>
+    where
+        P: core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{:?}::{}", self.0, "TestEvent[]")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> rs_matter_crate::reexport::defmt::Format for TestEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::reexport::defmt::Format,
+    {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "{:?}::{}", self.0, "TestEvent[]")
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilderParent for TestEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        type Write = P::Write;
+        fn writer(&mut self) -> &mut P::Write {
+            self.0.writer()
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilder<P> for TestEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        fn new(
+            parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Self::new(parent, tag)
+        }
+        fn unchecked_into_parent(self) -> P {
+            self.0
+        }
+    }
+    pub struct TestFabricScopedEventBuilder<P, const F: usize = 254usize>(P);
+    impl<P> TestFabricScopedEventBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Create a new instance"]
+        pub fn new(
+            mut parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            parent.writer().start_struct(tag)?;
+            Ok(Self(parent))
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> TestFabricScopedEventBuilder<P, 254>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent
+            + core::fmt::Debug
+            + rs_matter_crate::reexport::defmt::Format,
+    {
+        pub fn fabric_index(
+            mut self,
+            value: u8,
+        ) -> Result<TestFabricScopedEventBuilder<P, 255usize>, rs_matter_crate::error::Error>
+        {
+            #[cfg(feature = "defmt")]
+            rs_matter_crate::reexport::defmt::debug!(
+                "{:?}::{} -> {:?} +",
+                self,
+                "fabricIndex",
+                value
+            );
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!(
+                "{:?}::{} -> {:?} +",
+                self,
+                "fabricIndex",
+                value
+            );
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(254),
+                self.0.writer(),
+            )?;
+            Ok(TestFabricScopedEventBuilder(self.0))
+        }
+    }
+    #[cfg(not(feature = "defmt"))]
+    impl<P> TestFabricScopedEventBuilder<P, 254>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent + core::fmt::Debug,
+    {
+        pub fn fabric_index(
+            mut self,
+            value: u8,
+        ) -> Result<TestFabricScopedEventBuilder<P, 255usize>, rs_matter_crate::error::Error>
+        {
+            #[cfg(feature = "log")]
+            rs_matter_crate::reexport::log::debug!(
+                "{:?}::{} -> {:?} +",
+                self,
+                "fabricIndex",
+                value
+            );
+            rs_matter_crate::tlv::ToTLV::to_tlv(
+                &value,
+                &rs_matter_crate::tlv::TLVTag::Context(254),
+                self.0.writer(),
+            )?;
+            Ok(TestFabricScopedEventBuilder(self.0))
+        }
+    }
+    impl<P> TestFabricScopedEventBuilder<P, 255usize>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Finish the struct and return the parent"]
+        pub fn end(mut self) -> Result<P, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            self.0.writer().end_container()?;
+            Ok(self.0)
+        }
+    }
+    impl<P, const F: usize> core::fmt::Debug for TestFabricScopedEventBuilder<P, F>
+    where
+        P: core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{:?}::{}", self.0, "TestFabricScopedEvent")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P, const F: usize> rs_matter_crate::reexport::defmt::Format
+        for TestFabricScopedEventBuilder<P, F>
+    where
+        P: rs_matter_crate::reexport::defmt::Format,
+    {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "{:?}::{}", self.0, "TestFabricScopedEvent")
+        }
+    }
+    impl<P, const F: usize> rs_matter_crate::tlv::TLVBuilderParent
+        for TestFabricScopedEventBuilder<P, F>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        type Write = P::Write;
+        fn writer(&mut self) -> &mut P::Write {
+            self.0.writer()
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilder<P> for TestFabricScopedEventBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        fn new(
+            parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Self::new(parent, tag)
+        }
+        fn unchecked_into_parent(self) -> P {
+            self.0
+        }
+    }
+    pub struct TestFabricScopedEventArrayBuilder<P>(P);
+    impl<P> TestFabricScopedEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        #[doc = "Create a new instance"]
+        pub fn new(
+            mut parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            parent.writer().start_array(tag)?;
+            Ok(Self(parent))
+        }
+        #[doc = "Push a new element into the array"]
+        pub fn push(
+            self,
+        ) -> Result<
+            TestFabricScopedEventBuilder<TestFabricScopedEventArrayBuilder<P>>,
+            rs_matter_crate::error::Error,
+        > {
+            rs_matter_crate::tlv::TLVBuilder::new(
+                TestFabricScopedEventArrayBuilder(self.0),
+                &rs_matter_crate::tlv::TLVTag::Anonymous,
+            )
+        }
+        #[doc = "Finish the array and return the parent"]
+        pub fn end(mut self) -> Result<P, rs_matter_crate::error::Error> {
+            use rs_matter_crate::tlv::TLVWrite;
+            self.0.writer().end_container()?;
+            Ok(self.0)
+        }
+    }
+    impl<P> core::fmt::Debug for TestFabricScopedEventArrayBuilder<P>
+    where
+        P: core::fmt::Debug,
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "{:?}::{}", self.0, "TestFabricScopedEvent[]")
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl<P> rs_matter_crate::reexport::defmt::Format for TestFabricScopedEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::reexport::defmt::Format,
+    {
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(
+                f,
+                "{:?}::{}",
+                self.0,
+                "TestFabricScopedEvent[]"
+            )
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilderParent for TestFabricScopedEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        type Write = P::Write;
+        fn writer(&mut self) -> &mut P::Write {
+            self.0.writer()
+        }
+    }
+    impl<P> rs_matter_crate::tlv::TLVBuilder<P> for TestFabricScopedEventArrayBuilder<P>
+    where
+        P: rs_matter_crate::tlv::TLVBuilderParent,
+    {
+        fn new(
+            parent: P,
+            tag: &rs_matter_crate::tlv::TLVTag,
+        ) -> Result<Self, rs_matter_crate::error::Error> {
+            Self::new(parent, tag)
+        }
+        fn unchecked_into_parent(self) -> P {
+            self.0
+        }
+    }
+    #[doc = "The attribute IDs for the cluster."]
+    #[derive(
+        Copy, Clone, Debug, Eq, PartialEq, Hash, rs_matter_crate :: reexport :: strum :: FromRepr,
+    )]
+    #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
+    #[repr(u32)]
+    pub enum AttributeId {
+        Boolean = 0,
+        Bitmap8 = 1,
+        Bitmap16 = 2,
+        Bitmap32 = 3,
+        Bitmap64 = 4,
+        Int8u = 5,
+        Int16u = 6,
+        Int24u = 7,
+        Int32u = 8,
+        Int40u = 9,
+        Int48u = 10,
+        Int56u = 11,
+        Int64u = 12,
+        Int8s = 13,
+        Int16s = 14,
+        Int24s = 15,
+        Int32s = 16,
+        Int40s = 17,
+        Int48s = 18,
+        Int56s = 19,
+        Int64s = 20,
+        Enum8 = 21,
+        Enum16 = 22,
+        FloatSingle = 23,
+        FloatDouble = 24,
+        OctetString = 25,
+        ListInt8u = 26,
+        ListOctetString = 27,
+        ListStructOctetString = 28,
+        LongOctetString = 29,
+        CharString = 30,
+        LongCharString = 31,
+        EpochUs = 32,
+        EpochS = 33,
+        VendorId = 34,
+        ListNullablesAndOptionalsStruct = 35,
+        EnumAttr = 36,
+        StructAttr = 37,
+        RangeRestrictedInt8u = 38,
+        RangeRestrictedInt8s = 39,
+        RangeRestrictedInt16u = 40,
+        RangeRestrictedInt16s = 41,
+        ListLongOctetString = 42,
+        ListFabricScoped = 43,
+        TimedWriteBoolean = 48,
+        GeneralErrorBoolean = 49,
+        ClusterErrorBoolean = 50,
+        Unsupported = 255,
+        NullableBoolean = 16384,
+        NullableBitmap8 = 16385,
         NullableBitmap16 = 16386,
         NullableBitmap32 = 16387,
         NullableBitmap64 = 16388,
@@ -18591,7 +19337,63 @@ pub mod unit_testing {
                 .ok_or_else(|| rs_matter_crate::error::ErrorCode::CommandNotFound.into())
         }
     }
-    #[doc = "The cluster metadata. By default, all cluster attributes and commands are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
+    #[doc = "The event IDs for the cluster."]
+    #[derive(
+        Copy, Clone, Debug, Eq, PartialEq, Hash, rs_matter_crate :: reexport :: strum :: FromRepr,
+    )]
+    #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
+    #[repr(u32)]
+    pub enum EventId {
+        TestEvent = 1,
+        TestFabricScopedEvent = 2,
+    }
+    impl core::convert::TryFrom<rs_matter_crate::dm::EventId> for EventId {
+        type Error = rs_matter_crate::error::Error;
+        fn try_from(id: rs_matter_crate::dm::EventId) -> Result<Self, Self::Error> {
+            EventId::from_repr(id)
+                .ok_or_else(|| rs_matter_crate::error::ErrorCode::EventNotFound.into())
+        }
+    }
+    impl core::fmt::Debug for MetadataDebug<EventId> {
+        #[allow(unreachable_code)]
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "Event::")?;
+            match self.0 {
+                EventId::TestEvent => {
+                    write!(f, "{}(0x{:02x})", "TestEvent", EventId::TestEvent as u32)?
+                }
+                EventId::TestFabricScopedEvent => write!(
+                    f,
+                    "{}(0x{:02x})",
+                    "TestFabricScopedEvent",
+                    EventId::TestFabricScopedEvent as u32
+                )?,
+            }
+            Ok(())
+        }
+    }
+    #[cfg(feature = "defmt")]
+    impl rs_matter_crate::reexport::defmt::Format for MetadataDebug<EventId> {
+        #[allow(unreachable_code)]
+        fn format(&self, f: rs_matter_crate::reexport::defmt::Formatter<'_>) {
+            rs_matter_crate::reexport::defmt::write!(f, "Event::");
+            match self.0 {
+                EventId::TestEvent => rs_matter_crate::reexport::defmt::write!(
+                    f,
+                    "{}(0x{:02x})",
+                    "TestEvent",
+                    EventId::TestEvent as u32
+                ),
+                EventId::TestFabricScopedEvent => rs_matter_crate::reexport::defmt::write!(
+                    f,
+                    "{}(0x{:02x})",
+                    "TestFabricScopedEvent",
+                    EventId::TestFabricScopedEvent as u32
+                ),
+            }
+        }
+    }
+    #[doc = "The cluster metadata. By default, all cluster attributes, commands and events are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
     pub const FULL_CLUSTER: rs_matter_crate::dm::Cluster<'static> =
         rs_matter_crate::dm::Cluster::new(
             4294048773,
@@ -20070,6 +20872,21 @@ pub mod unit_testing {
                     rs_matter_crate::dm::Access::WO,
                 ),
             ],
+            &[
+                rs_matter_crate::dm::Event::new(
+                    EventId::TestEvent as _,
+                    rs_matter_crate::dm::EventPriority::Info,
+                    rs_matter_crate::dm::Access::READ.union(rs_matter_crate::dm::Access::NEED_VIEW),
+                ),
+                rs_matter_crate::dm::Event::new(
+                    EventId::TestFabricScopedEvent as _,
+                    rs_matter_crate::dm::EventPriority::Info,
+                    rs_matter_crate::dm::Access::READ
+                        .union(rs_matter_crate::dm::Access::NEED_VIEW)
+                        .union(rs_matter_crate::dm::Access::FAB_SENSITIVE),
+                ),
+            ],
+            |_, _, _| true,
             |_, _, _| true,
             |_, _, _| true,
         );
@@ -22359,6 +23176,85 @@ pub mod unit_testing {
     #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
     #[cfg_attr(feature = "defmt", derive(rs_matter_crate::reexport::defmt::Format))]
     pub struct HandlerAdaptor<T>(pub T);
+    impl<T> HandlerAdaptor<T> {
+        #[doc = "Emit the `TestEvent` event on the provided endpoint. The event payload is built by `f` which - as per `Events::emit` - might be called more than once. Return the number assigned to the event."]
+        pub fn emit_test_event<const D: usize, const I: usize, const C: usize, F>(
+            &self,
+            events: &rs_matter_crate::dm::events::Events<D, I, C>,
+            endpoint_id: rs_matter_crate::dm::EndptId,
+            mut f: F,
+        ) -> Result<u64, rs_matter_crate::error::Error>
+        where
+            F: for<'b, 'c> FnMut(
+                TestEventBuilder<
+                    rs_matter_crate::tlv::TLVWriteParent<
+                        &'static str,
+                        &'b mut rs_matter_crate::utils::storage::WriteBuf<'c>,
+                    >,
+                >,
+            ) -> Result<
+                rs_matter_crate::tlv::TLVWriteParent<
+                    &'static str,
+                    &'b mut rs_matter_crate::utils::storage::WriteBuf<'c>,
+                >,
+                rs_matter_crate::error::Error,
+            >,
+        {
+            events.emit(
+                endpoint_id,
+                4294048773u32,
+                EventId::TestEvent as _,
+                rs_matter_crate::dm::EventPriority::Info,
+                None,
+                |wb| {
+                    f(rs_matter_crate::tlv::TLVBuilder::new(
+                        rs_matter_crate::tlv::TLVWriteParent::new("TestEvent", wb),
+                        &rs_matter_crate::tlv::TLVTag::Anonymous,
+                    )?)?;
+                    Ok(())
+                },
+            )
+        }
+        #[doc = "Emit the `TestFabricScopedEvent` event on the provided endpoint. The event payload is built by `f` which - as per `Events::emit` - might be called more than once. Return the number assigned to the event."]
+        pub fn emit_test_fabric_scoped_event<const D: usize, const I: usize, const C: usize, F>(
+            &self,
+            events: &rs_matter_crate::dm::events::Events<D, I, C>,
+            endpoint_id: rs_matter_crate::dm::EndptId,
+            fab_idx: core::num::NonZeroU8,
+            mut f: F,
+        ) -> Result<u64, rs_matter_crate::error::Error>
+        where
+            F: for<'b, 'c> FnMut(
+                TestFabricScopedEventBuilder<
+                    rs_matter_crate::tlv::TLVWriteParent<
+                        &'static str,
+                        &'b mut rs_matter_crate::utils::storage::WriteBuf<'c>,
+                    >,
+                >,
+            ) -> Result<
+                rs_matter_crate::tlv::TLVWriteParent<
+                    &'static str,
+                    &'b mut rs_matter_crate::utils::storage::WriteBuf<'c>,
+                >,
+                rs_matter_crate::error::Error,
+            >,
+        {
+            events.emit(
+                endpoint_id,
+                4294048773u32,
+                EventId::TestFabricScopedEvent as _,
+                rs_matter_crate::dm::EventPriority::Info,
+                Some(fab_idx),
+                |wb| {
+                    f(rs_matter_crate::tlv::TLVBuilder::new(
+                        rs_matter_crate::tlv::TLVWriteParent::new("TestFabricScopedEvent", wb),
+                        &rs_matter_crate::tlv::TLVTag::Anonymous,
+                    )?)?;
+                    Ok(())
+                },
+            )
+        }
+    }
     impl<T> rs_matter_crate::dm::Handler for HandlerAdaptor<T>
     where
         T: ClusterHandler,
@@ -0,0 +1,659 @@
+/*
+ * Copyright (c) 2025 Project CHIP Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! A module for generating the typed client of a given IDL cluster.
+
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+
+use super::cluster::{GLOBAL_ATTR, NO_RESPONSE};
+use super::field::{field_type, field_type_builder, BuilderPolicy};
+use super::id::{ident, idl_attribute_name_to_enum_variant_name, idl_field_name_to_rs_name};
+use super::parser::{Attribute, Cluster, Command, DataType};
+use super::IdlGenerateContext;
+
+/// Return a token stream defining the typed client struct for the provided IDL cluster.
+///
+/// The client contains methods for reading and writing all attributes and for invoking all commands
+/// of the IDL cluster on a remote node, over an already established exchange.
+///
+/// Attribute values and command requests are encoded with the builders generated by `struct_out`,
+/// while attribute values and command responses are decoded with the types generated by `struct_in`.
+///
+/// ## Arguments
+/// - `cluster`: The IDL cluster for which the client is generated.
+/// - `context`: The context containing the information needed to generate the client.
+pub fn client(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
+    let krate = context.rs_matter_crate.clone();
+
+    let client_attribute_methods = cluster
+        .attributes
+        .iter()
+        .filter(|attr| !GLOBAL_ATTR.contains(&attr.field.field.code))
+        .map(|attr| client_attribute(attr, cluster, &krate));
+
+    let client_attribute_write_methods = cluster
+        .attributes
+        .iter()
+        .filter(|attr| !GLOBAL_ATTR.contains(&attr.field.field.code))
+        .filter(|attr| !attr.is_read_only)
+        .map(|attr| client_attribute_write(attr, cluster, &krate));
+
+    let client_command_methods = cluster
+        .commands
+        .iter()
+        .map(|cmd| client_command(cmd, cluster, &krate));
+
+    quote!(
+        #[doc = "The typed client for the cluster."]
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct ClusterClient(());
+
+        impl ClusterClient {
+            #[doc = "Create a new instance."]
+            pub const fn new() -> Self {
+                Self(())
+            }
+
+            #(#client_attribute_methods)*
+
+            #(#client_attribute_write_methods)*
+
+            #(#client_command_methods)*
+        }
+    )
+}
+
+/// Return a token stream defining the client method for reading the provided IDL attribute.
+///
+/// Attributes of a scalar type are returned by value, while attributes of a type
+/// borrowing from the response (strings, structs and arrays) are passed to a callback.
+///
+/// # Arguments
+/// - `attr`: The IDL attribute for which the client method is generated.
+/// - `cluster`: The IDL cluster for which the client method is generated.
+/// - `krate`: The crate name to use for the generated code.
+fn client_attribute(attr: &Attribute, cluster: &Cluster, krate: &Ident) -> TokenStream {
+    let attr_name = ident(&format!(
+        "read_{}",
+        &idl_field_name_to_rs_name(&attr.field.field.id)
+    ));
+    let attr_id = ident(&idl_attribute_name_to_enum_variant_name(
+        &attr.field.field.id,
+    ));
+    let attr_doc = Literal::string(&format!("Read the `{}` attribute.", attr.field.field.id));
+
+    let cluster_id = Literal::u32_unsuffixed(cluster.code as u32);
+
+    let attr_type = field_type(
+        &attr.field.field.data_type,
+        attr.field.is_nullable,
+        false,
+        cluster,
+        krate,
+    );
+
+    if borrows(&attr.field.field.data_type, cluster) {
+        quote!(
+            #[doc = #attr_doc]
+            pub async fn #attr_name<F, R>(
+                &self,
+                exchange: &mut #krate::transport::exchange::Exchange<'_>,
+                endpoint: #krate::dm::EndptId,
+                f: F,
+            ) -> Result<R, #krate::error::Error>
+            where
+                F: FnOnce(#attr_type) -> Result<R, #krate::error::Error>,
+            {
+                #krate::im::client::ImClient::read_attr(
+                    exchange,
+                    endpoint,
+                    #cluster_id,
+                    AttributeId::#attr_id as _,
+                    |data| f(#krate::tlv::FromTLV::from_tlv(data)?),
+                )
+                .await
+            }
+        )
+    } else {
+        quote!(
+            #[doc = #attr_doc]
+            pub async fn #attr_name(
+                &self,
+                exchange: &mut #krate::transport::exchange::Exchange<'_>,
+                endpoint: #krate::dm::EndptId,
+            ) -> Result<#attr_type, #krate::error::Error> {
+                #krate::im::client::ImClient::read_attr(
+                    exchange,
+                    endpoint,
+                    #cluster_id,
+                    AttributeId::#attr_id as _,
+                    |data| #krate::tlv::FromTLV::from_tlv(data),
+                )
+                .await
+            }
+        )
+    }
+}
+
+/// Return a token stream defining the client method for writing the provided IDL attribute.
+///
+/// Attributes of a scalar or a string type are passed by value, while attributes of a struct
+/// or an array type are written with a callback, using the corresponding builder.
+///
+/// # Arguments
+/// - `attr`: The IDL attribute for which the client method is generated.
+/// - `cluster`: The IDL cluster for which the client method is generated.
+/// - `krate`: The crate name to use for the generated code.
+fn client_attribute_write(attr: &Attribute, cluster: &Cluster, krate: &Ident) -> TokenStream {
+    let attr_name = ident(&format!(
+        "write_{}",
+        &idl_field_name_to_rs_name(&attr.field.field.id)
+    ));
+    let attr_id = ident(&idl_attribute_name_to_enum_variant_name(
+        &attr.field.field.id,
+    ));
+    let attr_doc = Literal::string(&format!("Write the `{}` attribute.", attr.field.field.id));
+    let attr_name_str = Literal::string(&attr.field.field.id);
+
+    let cluster_id = Literal::u32_unsuffixed(cluster.code as u32);
+
+    let (timeout_param, timeout) = if attr.is_timed_write {
+        (quote!(timeout_ms: u16,), quote!(Some(timeout_ms)))
+    } else {
+        (quote!(), quote!(None))
+    };
+
+    let (attr_type, builder) = field_type_builder(
+        &attr.field.field.data_type,
+        attr.field.is_nullable,
+        false,
+        BuilderPolicy::NonCopy,
+        quote!(#krate::im::client::ClientParent<'w, 'b>),
+        cluster,
+        krate,
+    );
+
+    if builder {
+        quote!(
+            #[doc = #attr_doc]
+            pub async fn #attr_name<F>(
+                &self,
+                exchange: &mut #krate::transport::exchange::Exchange<'_>,
+                endpoint: #krate::dm::EndptId,
+                #timeout_param
+                value: F,
+            ) -> Result<(), #krate::error::Error>
+            where
+                F: for<'w, 'b> Fn(#attr_type) -> Result<#krate::im::client::ClientParent<'w, 'b>, #krate::error::Error>,
+            {
+                #krate::im::client::ImClient::write_attr(
+                    exchange,
+                    endpoint,
+                    #cluster_id,
+                    AttributeId::#attr_id as _,
+                    #timeout,
+                    |tag, wb| {
+                        value(#krate::tlv::TLVBuilder::new(
+                            #krate::tlv::TLVWriteParent::new(#attr_name_str, wb),
+                            tag,
+                        )?)?;
+
+                        Ok(())
+                    },
+                )
+                .await
+            }
+        )
+    } else {
+        quote!(
+            #[doc = #attr_doc]
+            pub async fn #attr_name(
+                &self,
+                exchange: &mut #krate::transport::exchange::Exchange<'_>,
+                endpoint: #krate::dm::EndptId,
+                #timeout_param
+                value: #attr_type,
+            ) -> Result<(), #krate::error::Error> {
+                #krate::im::client::ImClient::write_attr(
+                    exchange,
+                    endpoint,
+                    #cluster_id,
+                    AttributeId::#attr_id as _,
+                    #timeout,
+                    |tag, wb| #krate::tlv::ToTLV::to_tlv(&value, tag, wb),
+                )
+                .await
+            }
+        )
+    }
+}
+
+/// Return a token stream defining the client method for invoking the provided IDL command.
+///
+/// The command request (if any) is written with a callback, using the builder of the request struct,
+/// while the command response (if any) is passed to a callback.
+///
+/// # Arguments
+/// - `cmd`: The IDL command for which the client method is generated.
+/// - `cluster`: The IDL cluster for which the client method is generated.
+/// - `krate`: The crate name to use for the generated code.
+fn client_command(cmd: &Command, cluster: &Cluster, krate: &Ident) -> TokenStream {
+    let cmd_name = ident(&idl_field_name_to_rs_name(&cmd.id));
+    let cmd_id = ident(&cmd.id);
+    let cmd_doc = Literal::string(&format!("Invoke the `{}` command.", cmd.id));
+    let cmd_name_str = Literal::string(&cmd.id);
+
+    let cluster_id = Literal::u32_unsuffixed(cluster.code as u32);
+
+    let (timeout_param, timeout) = if cmd.is_timed {
+        (quote!(timeout_ms: u16,), quote!(Some(timeout_ms)))
+    } else {
+        (quote!(), quote!(None))
+    };
+
+    let (request_generic, request_param, request_bound, request) = if let Some(input) =
+        cmd.input.as_ref()
+    {
+        let request_builder = ident(&format!("{input}Builder"));
+
+        (
+            Some(quote!(F)),
+            quote!(request: F,),
+            quote!(F: for<'w, 'b> Fn(#request_builder<#krate::im::client::ClientParent<'w, 'b>>) -> Result<#krate::im::client::ClientParent<'w, 'b>, #krate::error::Error>,),
+            quote!(
+                |tag, wb| {
+                    request(#request_builder::new(
+                        #krate::tlv::TLVWriteParent::new(#cmd_name_str, wb),
+                        tag,
+                    )?)?;
+
+                    Ok(())
+                }
+            ),
+        )
+    } else {
+        (
+            None,
+            quote!(),
+            quote!(),
+            quote!(
+                |tag, wb| {
+                    use #krate::tlv::TLVWrite;
+
+                    wb.start_struct(tag)?;
+                    wb.end_container()
+                }
+            ),
+        )
+    };
+
+    let (response_generic, response_param, response_bound, response_type, response) =
+        if cmd.output != NO_RESPONSE {
+            let response_type = field_type(
+                &DataType {
+                    name: cmd.output.clone(),
+                    is_list: false,
+                    max_length: None,
+                },
+                false,
+                false,
+                cluster,
+                krate,
+            );
+
+            (
+                Some(quote!(G, R)),
+                quote!(response: G,),
+                quote!(G: FnOnce(#response_type) -> Result<R, #krate::error::Error>,),
+                quote!(R),
+                quote!(
+                    |data| response(#krate::tlv::FromTLV::from_tlv(
+                        data.ok_or(#krate::error::ErrorCode::InvalidData)?
+                    )?)
+                ),
+            )
+        } else {
+            (None, quote!(), quote!(), quote!(()), quote!(|_| Ok(())))
+        };
+
+    let generics = request_generic.into_iter().chain(response_generic);
+
+    let (generics, where_clause) = if cmd.input.is_some() || cmd.output != NO_RESPONSE {
+        (
+            quote!(<#(#generics),*>),
+            quote!(where #request_bound #response_bound),
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
+    quote!(
+        #[doc = #cmd_doc]
+        pub async fn #cmd_name #generics(
+            &self,
+            exchange: &mut #krate::transport::exchange::Exchange<'_>,
+            endpoint: #krate::dm::EndptId,
+            #timeout_param
+            #request_param
+            #response_param
+        ) -> Result<#response_type, #krate::error::Error>
+        #where_clause
+        {
+            #krate::im::client::ImClient::invoke_cmd(
+                exchange,
+                endpoint,
+                #cluster_id,
+                CommandId::#cmd_id as _,
+                #timeout,
+                #request,
+                #response,
+            )
+            .await
+        }
+    )
+}
+
+/// Return `true` if the Rust type corresponding to the provided IDL type borrows
+/// from the TLV data it is decoded from (i.e. it is a string, a struct or an array).
+fn borrows(data_type: &DataType, cluster: &Cluster) -> bool {
+    data_type.is_list
+        || data_type.is_utf8_string()
+        || data_type.is_octet_string()
+        || cluster.structs.iter().any(|s| s.id == data_type.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use crate::idl::tests::{get_cluster_named, parse_idl};
+    use crate::idl::IdlGenerateContext;
+
+    use super::client;
+
+    const IDL: &str = "
+        cluster Sample = 1234 {
+            revision 1;
+
+            struct SampleStruct {
+                int8u value = 0;
+            }
+
+            readonly attribute boolean flag = 0;
+            attribute nullable int16u level = 1;
+            attribute char_string<32> label = 2;
+            timedwrite attribute SampleStruct entries[] = 3;
+            readonly attribute attrib_id attributeList[] = 65531;
+            readonly attribute int16u clusterRevision = 65533;
+
+            request struct AddRequest {
+                int8u value = 0;
+            }
+
+            response struct AddResponse = 2 {
+                int16u total = 0;
+            }
+
+            command Reset(): DefaultSuccess = 0;
+            command Add(AddRequest): AddResponse = 1;
+            timed command Lock(): DefaultSuccess = 3;
+        }
+    ";
+
+    #[test]
+    fn test_client() {
+        let idl = parse_idl(IDL);
+
+        let cluster = get_cluster_named(&idl, "Sample").expect("Cluster exists");
+        let context = IdlGenerateContext::new("rs_matter_crate");
+
+        // panic!("====\n{}\n====", &client(cluster, &context));
+
+        // Not using `assert_tokenstreams_eq!` here, as it formats the token streams
+        // with the 2015 edition, which does not support `async fn`
+        assert_eq!(
+            client(cluster, &context).to_string(),
+            quote!(
+                #[doc = "The typed client for the cluster."]
+                #[derive(Debug, Default, Clone, Copy)]
+                pub struct ClusterClient(());
+                impl ClusterClient {
+                    #[doc = "Create a new instance."]
+                    pub const fn new() -> Self {
+                        Self(())
+                    }
+                    #[doc = "Read the `flag` attribute."]
+                    pub async fn read_flag(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                    ) -> Result<bool, rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::read_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Flag as _,
+                            |data| rs_matter_crate::tlv::FromTLV::from_tlv(data),
+                        )
+                        .await
+                    }
+                    #[doc = "Read the `level` attribute."]
+                    pub async fn read_level(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                    ) -> Result<rs_matter_crate::tlv::Nullable<u16>, rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::read_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Level as _,
+                            |data| rs_matter_crate::tlv::FromTLV::from_tlv(data),
+                        )
+                        .await
+                    }
+                    #[doc = "Read the `label` attribute."]
+                    pub async fn read_label<F, R>(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        f: F,
+                    ) -> Result<R, rs_matter_crate::error::Error>
+                    where
+                        F: FnOnce(rs_matter_crate::tlv::Utf8Str<'_>) -> Result<R, rs_matter_crate::error::Error>,
+                    {
+                        rs_matter_crate::im::client::ImClient::read_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Label as _,
+                            |data| f(rs_matter_crate::tlv::FromTLV::from_tlv(data)?),
+                        )
+                        .await
+                    }
+                    #[doc = "Read the `entries` attribute."]
+                    pub async fn read_entries<F, R>(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        f: F,
+                    ) -> Result<R, rs_matter_crate::error::Error>
+                    where
+                        F: FnOnce(
+                            rs_matter_crate::tlv::TLVArray<'_, SampleStruct<'_> >
+                        ) -> Result<R, rs_matter_crate::error::Error>,
+                    {
+                        rs_matter_crate::im::client::ImClient::read_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Entries as _,
+                            |data| f(rs_matter_crate::tlv::FromTLV::from_tlv(data)?),
+                        )
+                        .await
+                    }
+                    #[doc = "Write the `level` attribute."]
+                    pub async fn write_level(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        value: rs_matter_crate::tlv::Nullable<u16>,
+                    ) -> Result<(), rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::write_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Level as _,
+                            None,
+                            |tag, wb| rs_matter_crate::tlv::ToTLV::to_tlv(&value, tag, wb),
+                        )
+                        .await
+                    }
+                    #[doc = "Write the `label` attribute."]
+                    pub async fn write_label(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        value: rs_matter_crate::tlv::Utf8Str<'_>,
+                    ) -> Result<(), rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::write_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Label as _,
+                            None,
+                            |tag, wb| rs_matter_crate::tlv::ToTLV::to_tlv(&value, tag, wb),
+                        )
+                        .await
+                    }
+                    #[doc = "Write the `entries` attribute."]
+                    pub async fn write_entries<F>(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        timeout_ms: u16,
+                        value: F,
+                    ) -> Result<(), rs_matter_crate::error::Error>
+                    where
+                        F: for<'w, 'b> Fn(
+                            SampleStructArrayBuilder<rs_matter_crate::im::client::ClientParent<'w, 'b> >
+                        ) -> Result<
+                            rs_matter_crate::im::client::ClientParent<'w, 'b>,
+                            rs_matter_crate::error::Error
+                        >,
+                    {
+                        rs_matter_crate::im::client::ImClient::write_attr(
+                            exchange,
+                            endpoint,
+                            1234,
+                            AttributeId::Entries as _,
+                            Some(timeout_ms),
+                            |tag, wb| {
+                                value(rs_matter_crate::tlv::TLVBuilder::new(
+                                    rs_matter_crate::tlv::TLVWriteParent::new("entries", wb),
+                                    tag,
+                                )?)?;
+                                Ok(())
+                            },
+                        )
+                        .await
+                    }
+                    #[doc = "Invoke the `Reset` command."]
+                    pub async fn reset(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                    ) -> Result<(), rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::invoke_cmd(
+                            exchange,
+                            endpoint,
+                            1234,
+                            CommandId::Reset as _,
+                            None,
+                            |tag, wb| {
+                                use rs_matter_crate::tlv::TLVWrite;
+                                wb.start_struct(tag)?;
+                                wb.end_container()
+                            },
+                            |_| Ok(()),
+                        )
+                        .await
+                    }
+                    #[doc = "Invoke the `Add` command."]
+                    pub async fn add<F, G, R>(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        request: F,
+                        response: G,
+                    ) -> Result<R, rs_matter_crate::error::Error>
+                    where
+                        F: for<'w, 'b> Fn(
+                            AddRequestBuilder<rs_matter_crate::im::client::ClientParent<'w, 'b>>
+                        ) -> Result<
+                            rs_matter_crate::im::client::ClientParent<'w, 'b>,
+                            rs_matter_crate::error::Error
+                        >,
+                        G: FnOnce(AddResponse<'_>) -> Result<R, rs_matter_crate::error::Error>,
+                    {
+                        rs_matter_crate::im::client::ImClient::invoke_cmd(
+                            exchange,
+                            endpoint,
+                            1234,
+                            CommandId::Add as _,
+                            None,
+                            |tag, wb| {
+                                request(AddRequestBuilder::new(
+                                    rs_matter_crate::tlv::TLVWriteParent::new("Add", wb),
+                                    tag,
+                                )?)?;
+                                Ok(())
+                            },
+                            |data| response(rs_matter_crate::tlv::FromTLV::from_tlv(
+                                data.ok_or(rs_matter_crate::error::ErrorCode::InvalidData)?
+                            )?),
+                        )
+                        .await
+                    }
+                    #[doc = "Invoke the `Lock` command."]
+                    pub async fn lock(
+                        &self,
+                        exchange: &mut rs_matter_crate::transport::exchange::Exchange<'_>,
+                        endpoint: rs_matter_crate::dm::EndptId,
+                        timeout_ms: u16,
+                    ) -> Result<(), rs_matter_crate::error::Error> {
+                        rs_matter_crate::im::client::ImClient::invoke_cmd(
+                            exchange,
+                            endpoint,
+                            1234,
+                            CommandId::Lock as _,
+                            Some(timeout_ms),
+                            |tag, wb| {
+                                use rs_matter_crate::tlv::TLVWrite;
+                                wb.start_struct(tag)?;
+                                wb.end_container()
+                            },
+                            |_| Ok(()),
+                        )
+                        .await
+                    }
+                }
+            )
+            .to_string()
+        );
+    }
+}
@@ -17,13 +17,13 @@
 //! A module for generating the cluster metadata for a given IDL cluster.
 //!
 //! In other words, the `Cluster<'static>` static instance as well as simple enums for
-//! the IDs of the cluster attributes, commands and command responses.
+//! the IDs of the cluster attributes, commands, command responses and events.
 
 use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 
 use super::id::{ident, idl_attribute_name_to_enum_variant_name};
-use super::parser::{AccessPrivilege, Cluster, StructType};
+use super::parser::{AccessPrivilege, Cluster, EventPriority, StructType};
 use super::IdlGenerateContext;
 
 pub(crate) const NO_RESPONSE: &str = "DefaultSuccess";
@@ -223,6 +223,108 @@ pub fn command_id(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStrea
     )
 }
 
+/// Return a TokenStream containing a simple enum with variants for each
+/// event in the given IDL cluster.
+pub fn event_id(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
+    let krate = context.rs_matter_crate.clone();
+
+    let events = cluster
+        .events
+        .iter()
+        .map(|event| {
+            let event_name = ident(&event.id);
+            let event_code = Literal::i64_unsuffixed(event.code as i64);
+
+            quote!(
+                #event_name = #event_code
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let events_debug = cluster.events.iter().map(|event| {
+        let event_name = ident(&event.id);
+        let event_name_str = Literal::string(&event.id);
+
+        quote!(
+            EventId::#event_name => write!(f, "{}(0x{:02x})", #event_name_str, EventId::#event_name as u32)?,
+        )
+    });
+
+    let events_format = cluster.events.iter().map(|event| {
+        let event_name = ident(&event.id);
+        let event_name_str = Literal::string(&event.id);
+
+        quote!(
+            EventId::#event_name => #krate::reexport::defmt::write!(f, "{}(0x{:02x})", #event_name_str, EventId::#event_name as u32),
+        )
+    });
+
+    let repr = if !events.is_empty() {
+        quote!(#[repr(u32)])
+    } else {
+        quote!()
+    };
+
+    let try_from = if !events.is_empty() {
+        quote!(
+            impl core::convert::TryFrom<#krate::dm::EventId> for EventId {
+                type Error = #krate::error::Error;
+
+                fn try_from(id: #krate::dm::EventId) -> Result<Self, Self::Error> {
+                    EventId::from_repr(id).ok_or_else(|| #krate::error::ErrorCode::EventNotFound.into())
+                }
+            }
+        )
+    } else {
+        quote!(
+            impl core::convert::TryFrom<#krate::dm::EventId> for EventId {
+                type Error = #krate::error::Error;
+
+                fn try_from(id: #krate::dm::EventId) -> Result<Self, Self::Error> {
+                    Err(#krate::error::ErrorCode::EventNotFound.into())
+                }
+            }
+        )
+    };
+
+    quote!(
+        #[doc = "The event IDs for the cluster."]
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, #krate::reexport::strum::FromRepr)]
+        #[cfg_attr(feature = "defmt", derive(#krate::reexport::defmt::Format))]
+        #repr
+        pub enum EventId {
+            #(#events),*
+        }
+
+        #try_from
+
+        impl core::fmt::Debug for MetadataDebug<EventId> {
+            #[allow(unreachable_code)]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "Event::")?;
+
+                match self.0 {
+                    #(#events_debug)*
+                }
+
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl #krate::reexport::defmt::Format for MetadataDebug<EventId> {
+            #[allow(unreachable_code)]
+            fn format(&self, f: #krate::reexport::defmt::Formatter<'_>) {
+                #krate::reexport::defmt::write!(f, "Event::");
+
+                match self.0 {
+                    #(#events_format)*
+                }
+            }
+        }
+    )
+}
+
 /// Return a TokenStream containing a simple enum with variants for each
 /// command response in the given IDL cluster.
 pub fn command_response_id(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
@@ -430,17 +532,50 @@ pub fn cluster(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
         )
     });
 
+    let events = cluster.events.iter().map(|event| {
+        let event_name = ident(&event.id);
+
+        let priority = match event.priority {
+            EventPriority::Debug => quote!(#krate::dm::EventPriority::Debug),
+            EventPriority::Info => quote!(#krate::dm::EventPriority::Info),
+            EventPriority::Critical => quote!(#krate::dm::EventPriority::Critical),
+        };
+
+        let acl = match event.access {
+            AccessPrivilege::View => quote!(#krate::dm::Access::NEED_VIEW),
+            AccessPrivilege::Operate => quote!(#krate::dm::Access::NEED_OPERATE.union(#krate::dm::Access::NEED_MANAGE.union(#krate::dm::Access::NEED_ADMIN))),
+            AccessPrivilege::Manage => quote!(#krate::dm::Access::NEED_MANAGE.union(#krate::dm::Access::NEED_ADMIN)),
+            AccessPrivilege::Administer => quote!(#krate::dm::Access::NEED_ADMIN),
+        };
+
+        let mut access = quote!(#krate::dm::Access::READ.union(#acl));
+
+        if event.is_fabric_sensitive {
+            access = quote!(#access.union(#krate::dm::Access::FAB_SENSITIVE));
+        }
+
+        quote!(
+            #krate::dm::Event::new(
+                EventId::#event_name as _,
+                #priority,
+                #access,
+            ),
+        )
+    });
+
     let cluster_id = Literal::u32_unsuffixed(cluster.code as u32);
     let cluster_revision = Literal::u16_unsuffixed(cluster.revision as u16);
 
     quote!(
-        #[doc = "The cluster metadata. By default, all cluster attributes and commands are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
+        #[doc = "The cluster metadata. By default, all cluster attributes, commands and events are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
         pub const FULL_CLUSTER: #krate::dm::Cluster<'static> = #krate::dm::Cluster::new(
             #cluster_id,
             #cluster_revision,
             0,
             &[#(#attributes)*],
             &[#(#commands)*],
+            &[#(#events)*],
+            |_, _, _| true,
             |_, _, _| true,
             |_, _, _| true,
         );
@@ -547,7 +682,7 @@ mod tests {
         assert_tokenstreams_eq!(
             &cluster(cluster_meta, &context),
             &quote!(
-                #[doc = "The cluster metadata. By default, all cluster attributes and commands are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
+                #[doc = "The cluster metadata. By default, all cluster attributes, commands and events are allowed, and the revision is the latest one. Use `Cluster::with_*` to reconfigure."]
                 pub const FULL_CLUSTER: rs_matter_crate::dm::Cluster<'static> =
                     rs_matter_crate::dm::Cluster::new(
                         6,
@@ -674,6 +809,8 @@ mod tests {
                                 rs_matter_crate::dm::Access::WO,
                             ),
                         ],
+                        &[],
+                        |_, _, _| true,
                         |_, _, _| true,
                         |_, _, _| true,
                     );
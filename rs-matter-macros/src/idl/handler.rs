@@ -23,7 +23,7 @@ use quote::quote;
 use super::cluster::{GLOBAL_ATTR, NO_RESPONSE};
 use super::field::{field_type, field_type_builder, BuilderPolicy};
 use super::id::{ident, idl_attribute_name_to_enum_variant_name, idl_field_name_to_rs_name};
-use super::parser::{Attribute, Cluster, Command, DataType, StructType};
+use super::parser::{Attribute, Cluster, Command, DataType, Event, EventPriority, StructType};
 use super::IdlGenerateContext;
 
 /// Return a token stream defining the handler trait for the provided IDL cluster.
@@ -244,6 +244,22 @@ pub fn handler_adaptor(
         )
     };
 
+    let handler_adaptor_event_emit_methods = cluster
+        .events
+        .iter()
+        .map(|event| handler_adaptor_event_emit(event, cluster, &krate))
+        .collect::<Vec<_>>();
+
+    let handler_adaptor_events = if !handler_adaptor_event_emit_methods.is_empty() {
+        quote!(
+            impl<T> #handler_adaptor_name<T> {
+                #(#handler_adaptor_event_emit_methods)*
+            }
+        )
+    } else {
+        quote!()
+    };
+
     let pasync = if asynch { quote!(async) } else { quote!() };
 
     let stream = quote!(
@@ -252,6 +268,8 @@ pub fn handler_adaptor(
         #[cfg_attr(feature = "defmt", derive(#krate::reexport::defmt::Format))]
         pub struct #handler_adaptor_name<T>(pub T);
 
+        #handler_adaptor_events
+
         impl<T> #krate::dm::#generic_handler_name for #handler_adaptor_name<T>
         where
             T: #handler_name,
@@ -343,6 +361,76 @@ pub fn handler_adaptor(
     }
 }
 
+/// Return a token stream defining a method, `emit_foo(...)` that is used by the adaptor
+/// to log an instance of the provided IDL event into the `rs-matter` event log.
+///
+/// The payload of the event is encoded by the user-supplied closure, using the builder
+/// generated for the event structure.
+///
+/// # Arguments
+/// - `event`: The IDL event for which the method is generated.
+/// - `cluster`: The IDL cluster for which the method is generated.
+/// - `krate`: The crate name to use for the generated code.
+fn handler_adaptor_event_emit(event: &Event, cluster: &Cluster, krate: &Ident) -> TokenStream {
+    let event_name = ident(&event.id);
+    let event_name_str = Literal::string(&event.id);
+    let event_builder_name = ident(&format!("{}Builder", event.id));
+    let event_method_name = ident(&format!("emit_{}", &idl_field_name_to_rs_name(&event.id)));
+    let cluster_code = Literal::u32_suffixed(cluster.code as _);
+
+    let doc = Literal::string(&format!(
+        "Emit the `{}` event on the provided endpoint. The event payload is built by `f` which - as per `Events::emit` - might be called more than once. Return the number assigned to the event.",
+        event.id
+    ));
+
+    let priority = match event.priority {
+        EventPriority::Debug => quote!(#krate::dm::EventPriority::Debug),
+        EventPriority::Info => quote!(#krate::dm::EventPriority::Info),
+        EventPriority::Critical => quote!(#krate::dm::EventPriority::Critical),
+    };
+
+    let (fab_idx_arg, fab_idx) = if event.is_fabric_sensitive {
+        (
+            quote!(fab_idx: core::num::NonZeroU8,),
+            quote!(Some(fab_idx)),
+        )
+    } else {
+        (quote!(), quote!(None))
+    };
+
+    let parent = quote!(#krate::tlv::TLVWriteParent<&'static str, &'b mut #krate::utils::storage::WriteBuf<'c>>);
+
+    quote!(
+        #[doc = #doc]
+        pub fn #event_method_name<const D: usize, const I: usize, const C: usize, F>(
+            &self,
+            events: &#krate::dm::events::Events<D, I, C>,
+            endpoint_id: #krate::dm::EndptId,
+            #fab_idx_arg
+            mut f: F,
+        ) -> Result<u64, #krate::error::Error>
+        where
+            F: for<'b, 'c> FnMut(#event_builder_name<#parent>) -> Result<#parent, #krate::error::Error>,
+        {
+            events.emit(
+                endpoint_id,
+                #cluster_code,
+                EventId::#event_name as _,
+                #priority,
+                #fab_idx,
+                |wb| {
+                    f(#krate::tlv::TLVBuilder::new(
+                        #krate::tlv::TLVWriteParent::new(#event_name_str, wb),
+                        &#krate::tlv::TLVTag::Anonymous,
+                    )?)?;
+
+                    Ok(())
+                },
+            )
+        }
+    )
+}
+
 /// Return a token stream defining the handler trait method for reading the provided IDL attribute.
 ///
 /// # Arguments
@@ -21,15 +21,21 @@ use quote::quote;
 
 use super::field::field_type;
 use super::id::{ident, idl_field_name_to_rs_name, idl_field_name_to_rs_type_name};
-use super::parser::{ApiMaturity, Cluster, Struct, StructField};
+use super::parser::{ApiMaturity, Cluster, Struct, StructField, StructType};
 use super::IdlGenerateContext;
 
 /// Return a token stream containing simple enums with the tag IDs of
-/// all structures in the given IDL cluster.
+/// all structures (including the event payloads) in the given IDL cluster.
 pub fn struct_tags(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
     let krate = context.rs_matter_crate.clone();
 
-    let struct_tags = cluster.structs.iter().map(|s| struct_tag(s, &krate));
+    let event_structs = event_structs(cluster);
+
+    let struct_tags = cluster
+        .structs
+        .iter()
+        .chain(event_structs.iter())
+        .map(|s| struct_tag(s, &krate));
 
     quote!(
         #(#struct_tags)*
@@ -37,11 +43,14 @@ pub fn struct_tags(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStre
 }
 
 /// Return a token stream containing the structure definitions
-/// for all structures in the given IDL cluster.
+/// for all structures (including the event payloads) in the given IDL cluster.
 pub fn structs(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
+    let event_structs = event_structs(cluster);
+
     let structs = cluster
         .structs
         .iter()
+        .chain(event_structs.iter())
         .map(|s| structure(s, cluster, context));
 
     quote!(
@@ -49,6 +58,26 @@ pub fn structs(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
     )
 }
 
+/// Return the structures corresponding to the payloads of all events
+/// in the given IDL cluster.
+///
+/// Event payloads are encoded just like regular structures, so the payload of
+/// each event is modeled as a structure with the same name as the event.
+pub(crate) fn event_structs(cluster: &Cluster) -> Vec<Struct> {
+    cluster
+        .events
+        .iter()
+        .map(|e| Struct {
+            doc_comment: e.doc_comment.clone(),
+            maturity: e.maturity,
+            struct_type: StructType::Regular,
+            id: e.id.clone(),
+            fields: e.fields.clone(),
+            is_fabric_scoped: false,
+        })
+        .collect()
+}
+
 /// Create the token stream corresponding to a structure
 /// tag definition.
 ///
@@ -58,10 +87,17 @@ fn struct_tag(s: &Struct, krate: &Ident) -> TokenStream {
 
     let fields = s.fields.iter().map(struct_tag_field);
 
+    // Zero-variant enums (i.e. tags of structures without fields) cannot have a `repr`
+    let repr = if !s.fields.is_empty() {
+        quote!(#[repr(u8)])
+    } else {
+        quote!()
+    };
+
     quote!(
         #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
         #[cfg_attr(feature = "defmt", derive(#krate::reexport::defmt::Format))]
-        #[repr(u8)]
+        #repr
         pub enum #name { #(#fields)* }
     )
 }
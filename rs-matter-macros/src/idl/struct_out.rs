@@ -23,15 +23,18 @@ use quote::quote;
 use super::field::{field_type_builder, BuilderPolicy};
 use super::id::{ident, idl_field_name_to_rs_name};
 use super::parser::{Cluster, Struct, StructField};
-use super::struct_in::struct_field_comment;
+use super::struct_in::{event_structs, struct_field_comment};
 use super::IdlGenerateContext;
 
 /// Return the token stream of all structure builders corresponding
-/// to the structures defined by the provided IDL cluster.
+/// to the structures (including the event payloads) defined by the provided IDL cluster.
 pub fn struct_builders(cluster: &Cluster, context: &IdlGenerateContext) -> TokenStream {
+    let event_structs = event_structs(cluster);
+
     let struct_builders = cluster
         .structs
         .iter()
+        .chain(event_structs.iter())
         .map(|s| struct_builder(s, cluster, context));
 
     quote!(
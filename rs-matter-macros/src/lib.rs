@@ -49,6 +49,9 @@ pub fn derive_totlv(item: TokenStream) -> TokenStream {
 /// at this time only "standard" clusters can be imported.
 ///
 /// `import!(OnOff)` imports the OnOff cluster
+///
+/// `import!(OnOff; client)` imports the OnOff cluster, and additionally generates a typed
+/// `ClusterClient` for reading and writing its attributes and invoking its commands on a remote node
 #[proc_macro]
 pub fn import(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as MatterImportArgs);
@@ -88,7 +91,7 @@ pub fn import(item: TokenStream) -> TokenStream {
         .clusters
         .iter()
         .filter(|c| input.clusters.is_empty() || input.clusters.contains(&c.id))
-        .map(|c| cluster(c, &context));
+        .map(|c| cluster(c, input.client, &context));
 
     let result = quote!(
         // IDL-generated code:
@@ -124,6 +127,9 @@ struct MatterImportArgs {
     /// What clusters to import. If the set is empty, all clusters will be imported
     clusters: HashSet<String>,
 
+    /// Whether to generate typed clients for the imported clusters
+    client: bool,
+
     /// Whether to print timings for the macro execution
     print_timings: bool,
 
@@ -138,13 +144,14 @@ impl Parse for MatterImportArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut clusters = HashSet::new();
 
+        let mut client = false;
         let mut print_timings = false;
         let mut cap_parse = None;
         let mut cap_codegen = None;
 
         let mut parse_timings = false;
 
-        // Argument is "[Cluster1[[,] Cluster2[,] ...][; [print_timings][[,] cap_parse=XXX][[,] cap_codegen=YYY]]"
+        // Argument is "[Cluster1[[,] Cluster2[,] ...][; [client][[,] print_timings][[,] cap_parse=XXX][[,] cap_codegen=YYY]]"
         while !input.is_empty() {
             if input.peek(Token![,]) {
                 input.parse::<Punct>()?;
@@ -167,6 +174,9 @@ impl Parse for MatterImportArgs {
                     let param: Ident = input.parse()?;
 
                     match param.to_string().as_str() {
+                        "client" => {
+                            client = true;
+                        }
                         "print_timings" => {
                             print_timings = true;
                         }
@@ -189,7 +199,7 @@ impl Parse for MatterImportArgs {
                         _ => {
                             return Err(syn::Error::new(
                                 param.span(),
-                                "Unknown parameter, expected 'print_timings', 'cap_parse', or 'cap_codegen'",
+                                "Unknown parameter, expected 'client', 'print_timings', 'cap_parse', or 'cap_codegen'",
                             ));
                         }
                     }
@@ -200,6 +210,7 @@ impl Parse for MatterImportArgs {
         Ok(MatterImportArgs {
             rs_matter_crate: get_crate_name(),
             clusters,
+            client,
             print_timings,
             cap_parse,
             cap_codegen,
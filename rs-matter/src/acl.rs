@@ -241,6 +241,12 @@ impl<'a> Accessor<'a> {
                 Some(AuthMode::Pase),
                 fabric_mgr,
             ),
+            SessionMode::Group { fab_idx, group_id } => Accessor::new(
+                fab_idx.get(),
+                AccessorSubjects::new(*group_id as u64),
+                Some(AuthMode::Group),
+                fabric_mgr,
+            ),
             SessionMode::PlainText => Accessor::new(0, AccessorSubjects::new(1), None, fabric_mgr),
         }
     }
@@ -275,6 +281,29 @@ impl<'a> Accessor<'a> {
     pub fn auth_mode(&self) -> Option<AuthMode> {
         self.auth_mode
     }
+
+    /// Return `true` if the provided endpoint can be targeted by the accessor.
+    ///
+    /// Group accessors can only target the endpoints which are members of their group,
+    /// as per the group table of their fabric, while all other accessors can target any endpoint.
+    pub fn is_endpoint_member(&self, endpoint_id: EndptId) -> bool {
+        if self.auth_mode != Some(AuthMode::Group) {
+            return true;
+        }
+
+        let Some(fab_idx) = NonZeroU8::new(self.fab_idx) else {
+            return false;
+        };
+
+        // For group accessors, the only subject is the group ID
+        let group_id = self.subjects.0[0] as u16;
+
+        self.fabric_mgr
+            .borrow()
+            .get(fab_idx)
+            .map(|fabric| fabric.is_group_member(group_id, endpoint_id))
+            .unwrap_or(false)
+    }
 }
 
 /// Access Descriptor Object
@@ -0,0 +1,1155 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! An implementation of the Bulk Data Exchange (BDX) protocol, as per section 11.22 of the Matter spec.
+//!
+//! BDX moves data of arbitrary size between two nodes over a single `Exchange`, split in blocks.
+//! The data is pulled from a `BdxSource` on the sending side and pushed into a `BdxSink` on the
+//! receiving side, one block at a time, so that it never has to be held in RAM as a whole.
+//!
+//! The initiator of a transfer uses `Bdx::send` (`SendInit`) or `Bdx::receive` (`ReceiveInit`),
+//! while the responder handles incoming transfers with `BdxResponder` and a user-supplied `BdxHandler`.
+//!
+//! All of the sender-drive, receiver-drive and asynchronous transfer modes are supported.
+//! In asynchronous mode - which is only possible with sender drive - the sender does not wait for a
+//! `BlockAck` after each block, and relies on the reliability of the underlying transport (MRP or TCP) instead.
+
+use core::borrow::Borrow;
+
+use num_derive::FromPrimitive;
+
+use crate::alloc;
+use crate::error::{Error, ErrorCode};
+use crate::respond::ExchangeHandler;
+use crate::sc::{self, GeneralCode, StatusReport, PROTO_ID_SECURE_CHANNEL};
+use crate::transport::exchange::{Exchange, MessageMeta, MAX_EXCHANGE_TX_BUF_SIZE};
+use crate::utils::bitflags::bitflags;
+use crate::utils::storage::{ReadBuf, WriteBuf};
+
+/* Bulk Data Exchange Protocol ID as per the Matter Spec */
+pub const PROTO_ID_BDX: u16 = 0x02;
+
+/// The version of the BDX protocol implemented by this module
+pub const BDX_VERSION: u8 = 0;
+
+const BLOCK_COUNTER_LEN: usize = 4;
+
+/// The max size of a block which can be exchanged, so that a `Block` message fits in a single packet
+pub const MAX_BLOCK_SIZE: usize = MAX_EXCHANGE_TX_BUF_SIZE - BLOCK_COUNTER_LEN;
+
+const VERSION_MASK: u8 = 0x0f;
+
+#[derive(FromPrimitive, Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OpCode {
+    SendInit = 0x01,
+    SendAccept = 0x02,
+    ReceiveInit = 0x04,
+    ReceiveAccept = 0x05,
+    BlockQuery = 0x10,
+    Block = 0x11,
+    BlockEOF = 0x12,
+    BlockAck = 0x13,
+    BlockAckEOF = 0x14,
+    BlockQueryWithSkip = 0x15,
+}
+
+impl OpCode {
+    pub fn meta(&self) -> MessageMeta {
+        MessageMeta {
+            proto_id: PROTO_ID_BDX,
+            proto_opcode: *self as u8,
+            reliable: true,
+        }
+    }
+}
+
+impl From<OpCode> for MessageMeta {
+    fn from(op: OpCode) -> Self {
+        op.meta()
+    }
+}
+
+/// The BDX-specific status codes, reported in the `StatusReport` messages aborting a transfer
+#[derive(FromPrimitive, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BdxStatusCode {
+    LengthTooLarge = 0x0012,
+    LengthTooShort = 0x0013,
+    LengthMismatch = 0x0014,
+    LengthRequired = 0x0015,
+    BadMessageContents = 0x0016,
+    BadBlockCounter = 0x0017,
+    UnexpectedMessage = 0x0018,
+    ResponderBusy = 0x0019,
+    TransferFailedUnknownError = 0x001f,
+    TransferMethodNotSupported = 0x0050,
+    FileDesignatorUnknown = 0x0051,
+    StartOffsetNotSupported = 0x0052,
+    VersionNotSupported = 0x0053,
+    Unknown = 0x005f,
+}
+
+impl BdxStatusCode {
+    pub fn as_report(&self) -> StatusReport<'static> {
+        let general_code = match self {
+            BdxStatusCode::ResponderBusy => GeneralCode::Busy,
+            BdxStatusCode::LengthTooLarge
+            | BdxStatusCode::LengthTooShort
+            | BdxStatusCode::LengthMismatch
+            | BdxStatusCode::LengthRequired
+            | BdxStatusCode::BadMessageContents
+            | BdxStatusCode::BadBlockCounter => GeneralCode::BadRequest,
+            BdxStatusCode::UnexpectedMessage => GeneralCode::Unexpected,
+            BdxStatusCode::TransferMethodNotSupported
+            | BdxStatusCode::StartOffsetNotSupported
+            | BdxStatusCode::VersionNotSupported => GeneralCode::Unsupported,
+            BdxStatusCode::FileDesignatorUnknown => GeneralCode::NotFound,
+            BdxStatusCode::TransferFailedUnknownError | BdxStatusCode::Unknown => {
+                GeneralCode::Failure
+            }
+        };
+
+        StatusReport {
+            general_code,
+            proto_id: PROTO_ID_BDX as u32,
+            proto_code: *self as u16,
+            proto_data: &[],
+        }
+    }
+}
+
+bitflags! {
+    /// The transfer modes, as proposed in the `SendInit` and `ReceiveInit` messages,
+    /// and as chosen in the `SendAccept` and `ReceiveAccept` messages
+    #[repr(transparent)]
+    #[derive(Default)]
+    #[cfg_attr(not(feature = "defmt"), derive(Debug, Copy, Clone, Eq, PartialEq, Hash))]
+    pub struct TransferModes: u8 {
+        const SENDER_DRIVE = 0x10;
+        const RECEIVER_DRIVE = 0x20;
+        const ASYNC = 0x40;
+    }
+}
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Default)]
+    #[cfg_attr(not(feature = "defmt"), derive(Debug, Copy, Clone, Eq, PartialEq, Hash))]
+    struct RangeControl: u8 {
+        const DEF_LEN = 0x01;
+        const START_OFFSET = 0x02;
+        const WIDE_RANGE = 0x10;
+    }
+}
+
+/// The parameters of a transfer, as proposed by its initiator in a `SendInit` or a `ReceiveInit` message
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransferInit<'a> {
+    /// The proposed transfer modes; at least one of sender drive and receiver drive
+    pub modes: TransferModes,
+    /// The proposed max block size
+    pub max_block_size: u16,
+    /// The offset in the file to start the transfer from, if any
+    pub start_offset: Option<u64>,
+    /// The length of the data for `SendInit`, or the max length of the data to receive for `ReceiveInit`, if known
+    pub length: Option<u64>,
+    /// The file designator, identifying the data to transfer
+    pub file_designator: &'a [u8],
+    /// Optional TLV metadata; empty if none
+    pub metadata: &'a [u8],
+}
+
+impl<'a> TransferInit<'a> {
+    /// Create the parameters of a transfer of the provided file, with all transfer modes proposed
+    /// and with the largest possible block size
+    pub const fn new(file_designator: &'a [u8]) -> Self {
+        Self {
+            modes: TransferModes::all(),
+            max_block_size: MAX_BLOCK_SIZE as u16,
+            start_offset: None,
+            length: None,
+            file_designator,
+            metadata: &[],
+        }
+    }
+
+    pub fn read(payload: &'a [u8]) -> Result<Self, Error> {
+        let mut rb = ReadBuf::new(payload);
+
+        let control = rb.le_u8()?;
+        let range = RangeControl::from_bits_truncate(rb.le_u8()?);
+        let max_block_size = rb.le_u16()?;
+        let start_offset = read_range(&mut rb, range, RangeControl::START_OFFSET)?;
+        let length = read_range(&mut rb, range, RangeControl::DEF_LEN)?;
+        let file_designator_len = rb.le_u16()? as usize;
+        let file_designator = read_slice(&mut rb, payload, file_designator_len)?;
+
+        Ok(Self {
+            modes: check_version(control)?,
+            max_block_size,
+            start_offset,
+            length,
+            file_designator,
+            metadata: &payload[rb.slice_range().0..],
+        })
+    }
+
+    pub fn write(&self, wb: &mut WriteBuf) -> Result<(), Error> {
+        let range = range_control(self.start_offset, self.length);
+
+        wb.le_u8(self.modes.bits() | BDX_VERSION)?;
+        wb.le_u8(range.bits())?;
+        wb.le_u16(self.max_block_size)?;
+        write_range(wb, range, self.start_offset)?;
+        write_range(wb, range, self.length)?;
+        wb.le_u16(self.file_designator.len() as _)?;
+        wb.append(self.file_designator)?;
+        wb.append(self.metadata)?;
+
+        Ok(())
+    }
+}
+
+/// The parameters of a transfer, as accepted by the responder in a `SendAccept` or a `ReceiveAccept` message
+#[derive(Debug, Clone)]
+struct TransferAccept<'a> {
+    modes: TransferModes,
+    max_block_size: u16,
+    start_offset: Option<u64>,
+    length: Option<u64>,
+    metadata: &'a [u8],
+}
+
+impl<'a> TransferAccept<'a> {
+    /// Negotiate the parameters of a transfer proposed by the initiator.
+    ///
+    /// Exactly one of the drive modes is chosen - preferably the one where the responder drives the
+    /// transfer, unless asynchronous mode (which is only possible with sender drive) is proposed.
+    fn negotiate(init: &TransferInit<'_>, responder_sends: bool) -> Result<Self, BdxStatusCode> {
+        let proposed = init.modes;
+
+        let modes = if proposed.contains(TransferModes::SENDER_DRIVE | TransferModes::ASYNC) {
+            TransferModes::SENDER_DRIVE | TransferModes::ASYNC
+        } else {
+            let (preferred, fallback) = if responder_sends {
+                (TransferModes::SENDER_DRIVE, TransferModes::RECEIVER_DRIVE)
+            } else {
+                (TransferModes::RECEIVER_DRIVE, TransferModes::SENDER_DRIVE)
+            };
+
+            if proposed.contains(preferred) {
+                preferred
+            } else if proposed.contains(fallback) {
+                fallback
+            } else {
+                return Err(BdxStatusCode::TransferMethodNotSupported);
+            }
+        };
+
+        if init.max_block_size == 0 {
+            return Err(BdxStatusCode::BadMessageContents);
+        }
+
+        Ok(Self {
+            modes,
+            max_block_size: init.max_block_size.min(MAX_BLOCK_SIZE as _),
+            start_offset: init.start_offset,
+            length: init.length,
+            metadata: &[],
+        })
+    }
+
+    fn read(opcode: OpCode, payload: &'a [u8]) -> Result<Self, Error> {
+        let mut rb = ReadBuf::new(payload);
+
+        let modes = check_version(rb.le_u8()?)?;
+
+        let accept = if opcode == OpCode::ReceiveAccept {
+            let range = RangeControl::from_bits_truncate(rb.le_u8()?);
+            let max_block_size = rb.le_u16()?;
+
+            Self {
+                modes,
+                max_block_size,
+                start_offset: read_range(&mut rb, range, RangeControl::START_OFFSET)?,
+                length: read_range(&mut rb, range, RangeControl::DEF_LEN)?,
+                metadata: &[],
+            }
+        } else {
+            Self {
+                modes,
+                max_block_size: rb.le_u16()?,
+                start_offset: None,
+                length: None,
+                metadata: &[],
+            }
+        };
+
+        Ok(Self {
+            metadata: &payload[rb.slice_range().0..],
+            ..accept
+        })
+    }
+
+    fn write(&self, opcode: OpCode, wb: &mut WriteBuf) -> Result<(), Error> {
+        wb.le_u8(self.modes.bits() | BDX_VERSION)?;
+
+        if opcode == OpCode::ReceiveAccept {
+            let range = range_control(self.start_offset, self.length);
+
+            wb.le_u8(range.bits())?;
+            wb.le_u16(self.max_block_size)?;
+            write_range(wb, range, self.start_offset)?;
+            write_range(wb, range, self.length)?;
+        } else {
+            wb.le_u16(self.max_block_size)?;
+        }
+
+        wb.append(self.metadata)
+    }
+
+    /// Check that the accepted parameters are a valid choice among the proposed ones
+    fn check(&self, init: &TransferInit<'_>) -> Result<(), Error> {
+        let drive = self.modes & (TransferModes::SENDER_DRIVE | TransferModes::RECEIVER_DRIVE);
+
+        if !init.modes.contains(self.modes)
+            || drive.bits().count_ones() != 1
+            || (self.modes.contains(TransferModes::ASYNC) && drive != TransferModes::SENDER_DRIVE)
+            || self.max_block_size == 0
+            || self.max_block_size > init.max_block_size
+            || self.max_block_size as usize > MAX_BLOCK_SIZE
+        {
+            error!("Invalid transfer parameters accepted by the responder");
+            Err(ErrorCode::Invalid)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A source of the data sent with BDX
+pub trait BdxSource {
+    /// Read the next chunk of data into the provided buffer.
+    ///
+    /// Return the number of bytes read, where 0 means that the end of the data is reached.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+}
+
+impl<T> BdxSource for &mut T
+where
+    T: BdxSource,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        (*self).read(buf).await
+    }
+}
+
+impl BdxSource for &[u8] {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let len = buf.len().min(self.len());
+
+        buf[..len].copy_from_slice(&self[..len]);
+        *self = &self[len..];
+
+        Ok(len)
+    }
+}
+
+/// A sink for the data received with BDX
+pub trait BdxSink {
+    /// Write the next chunk of the received data
+    async fn write(&mut self, data: &[u8]) -> Result<(), Error>;
+}
+
+impl<T> BdxSink for &mut T
+where
+    T: BdxSink,
+{
+    async fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        (*self).write(data).await
+    }
+}
+
+/// A handler for the transfers initiated by peers, as used by `BdxResponder`.
+pub trait BdxHandler {
+    /// The sink for the data of the transfers initiated with `SendInit`
+    type Sink<'a>: BdxSink
+    where
+        Self: 'a;
+
+    /// The source of the data of the transfers initiated with `ReceiveInit`
+    type Source<'a>: BdxSource
+    where
+        Self: 'a;
+
+    /// Accept or reject a transfer proposed by a peer with `SendInit`.
+    ///
+    /// Return the sink for the data sent by the peer, or the status code to reject the transfer with.
+    fn accept_send(&self, init: &TransferInit<'_>) -> Result<Self::Sink<'_>, BdxStatusCode>;
+
+    /// Accept or reject a transfer proposed by a peer with `ReceiveInit`.
+    ///
+    /// Return the source of the data to send to the peer and - if known - the length of the data,
+    /// or the status code to reject the transfer with.
+    fn accept_receive(
+        &self,
+        init: &TransferInit<'_>,
+    ) -> Result<(Self::Source<'_>, Option<u64>), BdxStatusCode>;
+}
+
+impl<T> BdxHandler for &T
+where
+    T: BdxHandler,
+{
+    type Sink<'a>
+        = T::Sink<'a>
+    where
+        Self: 'a;
+
+    type Source<'a>
+        = T::Source<'a>
+    where
+        Self: 'a;
+
+    fn accept_send(&self, init: &TransferInit<'_>) -> Result<Self::Sink<'_>, BdxStatusCode> {
+        (*self).accept_send(init)
+    }
+
+    fn accept_receive(
+        &self,
+        init: &TransferInit<'_>,
+    ) -> Result<(Self::Source<'_>, Option<u64>), BdxStatusCode> {
+        (*self).accept_receive(init)
+    }
+}
+
+/// The negotiated parameters of a transfer in progress
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Transfer {
+    modes: TransferModes,
+    max_block_size: usize,
+    length: Option<u64>,
+}
+
+impl Transfer {
+    fn new(accept: &TransferAccept<'_>, length: Option<u64>) -> Self {
+        Self {
+            modes: accept.modes,
+            max_block_size: accept.max_block_size as _,
+            length,
+        }
+    }
+
+    fn is_receiver_drive(&self) -> bool {
+        self.modes.contains(TransferModes::RECEIVER_DRIVE)
+    }
+
+    fn is_async(&self) -> bool {
+        self.modes.contains(TransferModes::ASYNC)
+    }
+}
+
+/// The BDX protocol
+pub struct Bdx(());
+
+impl Bdx {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self(())
+    }
+
+    /// Initiate a transfer of data to the peer of the exchange (`SendInit`).
+    ///
+    /// Return the number of bytes sent.
+    ///
+    /// # Arguments
+    /// - `exchange`: A newly-initiated exchange with the peer
+    /// - `init`: The proposed parameters of the transfer
+    /// - `source`: The source of the data to send; if `init.length` is provided,
+    ///   it must contain at least that many bytes
+    pub async fn send<S>(
+        &self,
+        exchange: &mut Exchange<'_>,
+        init: &TransferInit<'_>,
+        source: S,
+    ) -> Result<u64, Error>
+    where
+        S: BdxSource,
+    {
+        let transfer = self.initiate(exchange, OpCode::SendInit, init).await?;
+
+        debug!("BDX: Sending {:?}", transfer);
+
+        self.transmit(exchange, &transfer, source).await
+    }
+
+    /// Initiate a transfer of data from the peer of the exchange (`ReceiveInit`).
+    ///
+    /// Return the number of bytes received.
+    ///
+    /// # Arguments
+    /// - `exchange`: A newly-initiated exchange with the peer
+    /// - `init`: The proposed parameters of the transfer
+    /// - `sink`: The sink for the received data
+    pub async fn receive<S>(
+        &self,
+        exchange: &mut Exchange<'_>,
+        init: &TransferInit<'_>,
+        sink: S,
+    ) -> Result<u64, Error>
+    where
+        S: BdxSink,
+    {
+        let transfer = self.initiate(exchange, OpCode::ReceiveInit, init).await?;
+
+        debug!("BDX: Receiving {:?}", transfer);
+
+        self.collect(exchange, &transfer, sink).await
+    }
+
+    /// Handle a transfer initiated by the peer of the exchange with `SendInit` or `ReceiveInit`,
+    /// by accepting or rejecting it with the provided handler.
+    pub async fn handle<H>(&self, exchange: &mut Exchange<'_>, handler: H) -> Result<(), Error>
+    where
+        H: BdxHandler,
+    {
+        let rx = exchange.recv_fetch().await?;
+
+        let meta = rx.meta();
+        if meta.proto_id != PROTO_ID_BDX {
+            Err(ErrorCode::InvalidProto)?;
+        }
+
+        let opcode = meta.opcode::<OpCode>()?;
+        if !matches!(opcode, OpCode::SendInit | OpCode::ReceiveInit) {
+            error!("Invalid opcode: {:?}", opcode);
+            return Self::fail(exchange, BdxStatusCode::UnexpectedMessage).await;
+        }
+
+        let init = match TransferInit::read(rx.payload()) {
+            Ok(init) => init,
+            Err(e) if e.code() == ErrorCode::Invalid => {
+                return Self::fail(exchange, BdxStatusCode::VersionNotSupported).await
+            }
+            Err(_) => return Self::fail(exchange, BdxStatusCode::BadMessageContents).await,
+        };
+
+        if opcode == OpCode::SendInit {
+            let accepted = TransferAccept::negotiate(&init, false)
+                .and_then(|accept| handler.accept_send(&init).map(|sink| (accept, sink)));
+
+            let (accept, sink) = match accepted {
+                Ok(accepted) => accepted,
+                Err(status) => return Self::reject(exchange, status).await,
+            };
+
+            let transfer = Transfer::new(&accept, accept.length);
+
+            exchange
+                .send_with(|_, wb| {
+                    accept.write(OpCode::SendAccept, wb)?;
+
+                    Ok(Some(OpCode::SendAccept.into()))
+                })
+                .await?;
+
+            debug!("BDX: Receiving {:?}", transfer);
+
+            self.collect(exchange, &transfer, sink).await?;
+        } else {
+            let accepted = TransferAccept::negotiate(&init, true).and_then(|accept| {
+                handler
+                    .accept_receive(&init)
+                    .map(|(source, length)| (accept, source, length))
+            });
+
+            let (accept, source) = match accepted {
+                Ok((accept, source, length)) => (TransferAccept { length, ..accept }, source),
+                Err(status) => return Self::reject(exchange, status).await,
+            };
+
+            let transfer = Transfer::new(&accept, accept.length);
+
+            exchange
+                .send_with(|_, wb| {
+                    accept.write(OpCode::ReceiveAccept, wb)?;
+
+                    Ok(Some(OpCode::ReceiveAccept.into()))
+                })
+                .await?;
+
+            debug!("BDX: Sending {:?}", transfer);
+
+            self.transmit(exchange, &transfer, source).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send the `SendInit` or `ReceiveInit` message, wait for the peer to accept the transfer,
+    /// and return the negotiated transfer parameters
+    async fn initiate(
+        &self,
+        exchange: &mut Exchange<'_>,
+        opcode: OpCode,
+        init: &TransferInit<'_>,
+    ) -> Result<Transfer, Error> {
+        if !init
+            .modes
+            .intersects(TransferModes::SENDER_DRIVE | TransferModes::RECEIVER_DRIVE)
+        {
+            error!("No drive mode proposed");
+            Err(ErrorCode::InvalidArgument)?;
+        }
+
+        exchange
+            .send_with(|_, wb| {
+                init.write(wb)?;
+
+                Ok(Some(opcode.into()))
+            })
+            .await?;
+
+        let accept_opcode = if opcode == OpCode::SendInit {
+            OpCode::SendAccept
+        } else {
+            OpCode::ReceiveAccept
+        };
+
+        Self::recv(exchange, &[accept_opcode]).await?;
+
+        let transfer =
+            TransferAccept::read(accept_opcode, exchange.rx()?.payload()).and_then(|accept| {
+                accept.check(init)?;
+
+                // The data is sent with the length proposed by the initiator,
+                // or received with the length accepted by the responder
+                let length = if opcode == OpCode::SendInit {
+                    init.length
+                } else {
+                    accept.length
+                };
+
+                Ok(Transfer::new(&accept, length))
+            });
+
+        let transfer = match transfer {
+            Ok(transfer) => transfer,
+            Err(e) => {
+                return Self::fail(exchange, BdxStatusCode::BadMessageContents)
+                    .await
+                    .map_err(|_| e)
+            }
+        };
+
+        if let (Some(max), Some(length)) = (init.length, transfer.length) {
+            if opcode == OpCode::ReceiveInit && length > max {
+                return Self::fail(exchange, BdxStatusCode::LengthTooLarge).await;
+            }
+        }
+
+        exchange.rx_done()?;
+
+        Ok(transfer)
+    }
+
+    /// Send the data of the source as the sending party of the transfer
+    async fn transmit<S>(
+        &self,
+        exchange: &mut Exchange<'_>,
+        transfer: &Transfer,
+        mut source: S,
+    ) -> Result<u64, Error>
+    where
+        S: BdxSource,
+    {
+        let mut buf = alloc!([0; MAX_BLOCK_SIZE]); // TODO LARGE BUFFER
+        let buf = &mut buf[..transfer.max_block_size];
+
+        let mut counter: u32 = 0;
+        let mut sent: u64 = 0;
+
+        loop {
+            if transfer.is_receiver_drive() {
+                let opcode =
+                    Self::recv(exchange, &[OpCode::BlockQuery, OpCode::BlockQueryWithSkip]).await?;
+
+                let mut rb = ReadBuf::new(exchange.rx()?.payload());
+                if rb.le_u32()? != counter {
+                    return Self::fail(exchange, BdxStatusCode::BadBlockCounter).await;
+                }
+
+                if opcode == OpCode::BlockQueryWithSkip {
+                    let skip = rb.le_u64()?;
+                    exchange.rx_done()?;
+
+                    let skipped = Self::skip(&mut source, transfer, sent, skip, buf).await;
+                    match skipped {
+                        Ok(skipped) => sent += skipped,
+                        Err(e) => return Self::abort(exchange, e).await,
+                    }
+                }
+            }
+
+            let len = match Self::fill(&mut source, transfer, sent, buf).await {
+                Ok(len) => len,
+                Err(e) => return Self::abort(exchange, e).await,
+            };
+
+            sent += len as u64;
+
+            let eof = len < buf.len() || transfer.length == Some(sent);
+            if eof {
+                if let Some(length) = transfer.length {
+                    if sent != length {
+                        error!("BDX: The source has fewer bytes than the transfer length");
+                        return Self::fail(exchange, BdxStatusCode::LengthMismatch).await;
+                    }
+                }
+            }
+
+            let opcode = if eof { OpCode::BlockEOF } else { OpCode::Block };
+            let data = &buf[..len];
+
+            exchange
+                .send_with(|_, wb| {
+                    wb.le_u32(counter)?;
+                    wb.append(data)?;
+
+                    Ok(Some(opcode.into()))
+                })
+                .await?;
+
+            if eof || !(transfer.is_receiver_drive() || transfer.is_async()) {
+                let ack_opcode = if eof {
+                    OpCode::BlockAckEOF
+                } else {
+                    OpCode::BlockAck
+                };
+
+                Self::recv(exchange, &[ack_opcode]).await?;
+
+                let mut rb = ReadBuf::new(exchange.rx()?.payload());
+                if rb.le_u32()? != counter {
+                    return Self::fail(exchange, BdxStatusCode::BadBlockCounter).await;
+                }
+            }
+
+            if eof {
+                exchange.acknowledge().await?;
+
+                debug!("BDX: Sent {} bytes", sent);
+
+                break Ok(sent);
+            }
+
+            counter = counter.wrapping_add(1);
+        }
+    }
+
+    /// Receive the data into the sink as the receiving party of the transfer
+    async fn collect<S>(
+        &self,
+        exchange: &mut Exchange<'_>,
+        transfer: &Transfer,
+        mut sink: S,
+    ) -> Result<u64, Error>
+    where
+        S: BdxSink,
+    {
+        let mut buf = alloc!([0; MAX_BLOCK_SIZE]); // TODO LARGE BUFFER
+        let buf = &mut buf[..transfer.max_block_size];
+
+        let mut counter: u32 = 0;
+        let mut received: u64 = 0;
+
+        loop {
+            if transfer.is_receiver_drive() {
+                exchange
+                    .send_with(|_, wb| {
+                        wb.le_u32(counter)?;
+
+                        Ok(Some(OpCode::BlockQuery.into()))
+                    })
+                    .await?;
+            }
+
+            let opcode = Self::recv(exchange, &[OpCode::Block, OpCode::BlockEOF]).await?;
+            let eof = opcode == OpCode::BlockEOF;
+
+            let mut rb = ReadBuf::new(exchange.rx()?.payload());
+            if rb.le_u32()? != counter {
+                return Self::fail(exchange, BdxStatusCode::BadBlockCounter).await;
+            }
+
+            let data = rb.as_slice();
+            if data.len() > buf.len() || (data.is_empty() && !eof) {
+                return Self::fail(exchange, BdxStatusCode::BadMessageContents).await;
+            }
+
+            received += data.len() as u64;
+
+            if let Some(length) = transfer.length {
+                if received > length {
+                    return Self::fail(exchange, BdxStatusCode::LengthTooLarge).await;
+                } else if eof && received < length {
+                    return Self::fail(exchange, BdxStatusCode::LengthTooShort).await;
+                }
+            }
+
+            // Do not hold the RX buffer of the transport while the sink is processing the data
+            let len = data.len();
+            buf[..len].copy_from_slice(data);
+            exchange.rx_done()?;
+
+            if let Err(e) = sink.write(&buf[..len]).await {
+                return Self::abort(exchange, e).await;
+            }
+
+            if eof || !(transfer.is_receiver_drive() || transfer.is_async()) {
+                let ack_opcode = if eof {
+                    OpCode::BlockAckEOF
+                } else {
+                    OpCode::BlockAck
+                };
+
+                exchange
+                    .send_with(|_, wb| {
+                        wb.le_u32(counter)?;
+
+                        Ok(Some(ack_opcode.into()))
+                    })
+                    .await?;
+            } else if transfer.is_async() {
+                exchange.acknowledge().await?;
+            }
+
+            if eof {
+                debug!("BDX: Received {} bytes", received);
+
+                break Ok(received);
+            }
+
+            counter = counter.wrapping_add(1);
+        }
+    }
+
+    /// Fill the provided block buffer from the source, up to the end of the data
+    async fn fill<S>(
+        source: &mut S,
+        transfer: &Transfer,
+        sent: u64,
+        buf: &mut [u8],
+    ) -> Result<usize, Error>
+    where
+        S: BdxSource,
+    {
+        let max = transfer
+            .length
+            .map(|length| (length - sent).min(buf.len() as u64) as usize)
+            .unwrap_or(buf.len());
+
+        let mut len = 0;
+
+        while len < max {
+            let read = source.read(&mut buf[len..max]).await?;
+            if read == 0 {
+                break;
+            }
+
+            len += read;
+        }
+
+        Ok(len)
+    }
+
+    /// Skip the requested number of bytes from the source, up to the end of the data
+    async fn skip<S>(
+        source: &mut S,
+        transfer: &Transfer,
+        sent: u64,
+        skip: u64,
+        buf: &mut [u8],
+    ) -> Result<u64, Error>
+    where
+        S: BdxSource,
+    {
+        let skip = transfer
+            .length
+            .map(|length| skip.min(length - sent))
+            .unwrap_or(skip);
+
+        let mut skipped = 0;
+
+        while skipped < skip {
+            let max = (skip - skipped).min(buf.len() as u64) as usize;
+
+            let read = source.read(&mut buf[..max]).await?;
+            if read == 0 {
+                break;
+            }
+
+            skipped += read as u64;
+        }
+
+        Ok(skipped)
+    }
+
+    /// Wait for a message with one of the expected opcodes.
+    ///
+    /// Fail the transfer if an unexpected message is received, and log the status report
+    /// if the peer aborted the transfer.
+    async fn recv(exchange: &mut Exchange<'_>, expected: &[OpCode]) -> Result<OpCode, Error> {
+        let meta = exchange.recv_fetch().await?.meta();
+
+        if meta.proto_id == PROTO_ID_BDX {
+            if let Some(opcode) = meta
+                .opcode::<OpCode>()
+                .ok()
+                .filter(|opcode| expected.contains(opcode))
+            {
+                return Ok(opcode);
+            }
+        } else if meta.proto_id == PROTO_ID_SECURE_CHANNEL
+            && meta.proto_opcode == sc::OpCode::StatusReport as u8
+        {
+            let mut rb = ReadBuf::new(exchange.rx()?.payload());
+
+            match StatusReport::read(&mut rb) {
+                Ok(status_report) => error!("BDX: Transfer aborted: {:?}", status_report),
+                Err(e) => error!("BDX: Failed to parse Status Report: {:?}", e),
+            }
+
+            exchange.rx_done()?;
+            exchange.acknowledge().await?;
+
+            Err(ErrorCode::Failure)?;
+        }
+
+        error!("BDX: Unexpected message {}, expected: {:?}", meta, expected);
+
+        Self::fail(exchange, BdxStatusCode::UnexpectedMessage).await
+    }
+
+    /// Abort the transfer because of a failure of the source or the sink
+    async fn abort<T>(exchange: &mut Exchange<'_>, e: Error) -> Result<T, Error> {
+        error!("BDX: Transfer failed: {:?}", e);
+
+        Self::send_status(exchange, BdxStatusCode::TransferFailedUnknownError).await?;
+
+        Err(e)
+    }
+
+    /// Reject a proposed transfer
+    async fn reject(exchange: &mut Exchange<'_>, status: BdxStatusCode) -> Result<(), Error> {
+        warn!("BDX: Rejecting transfer: {:?}", status);
+
+        Self::send_status(exchange, status).await
+    }
+
+    /// Abort the transfer because of a protocol violation by the peer
+    async fn fail<T>(exchange: &mut Exchange<'_>, status: BdxStatusCode) -> Result<T, Error> {
+        Self::send_status(exchange, status).await?;
+
+        Err(ErrorCode::Invalid.into())
+    }
+
+    async fn send_status(exchange: &mut Exchange<'_>, status: BdxStatusCode) -> Result<(), Error> {
+        exchange
+            .send_with(|_, wb| {
+                status.as_report().write(wb)?;
+
+                Ok(Some(sc::OpCode::StatusReport.meta()))
+            })
+            .await
+    }
+}
+
+impl Default for Bdx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An exchange handler for the transfers initiated by peers, which are accepted or rejected
+/// by the provided `BdxHandler`.
+pub struct BdxResponder<H>(H);
+
+impl<H> BdxResponder<H>
+where
+    H: BdxHandler,
+{
+    pub const fn new(handler: H) -> Self {
+        Self(handler)
+    }
+}
+
+impl<H> ExchangeHandler for BdxResponder<H>
+where
+    H: BdxHandler,
+{
+    async fn handle(&self, exchange: &mut Exchange<'_>) -> Result<(), Error> {
+        Bdx::new().handle(exchange, &self.0).await
+    }
+}
+
+/// Check the protocol version of the provided transfer control field, and return its transfer modes
+fn check_version(control: u8) -> Result<TransferModes, Error> {
+    if control & VERSION_MASK != BDX_VERSION {
+        error!("BDX: Unsupported version {}", control & VERSION_MASK);
+        Err(ErrorCode::Invalid)?;
+    }
+
+    Ok(TransferModes::from_bits_truncate(control))
+}
+
+fn range_control(start_offset: Option<u64>, length: Option<u64>) -> RangeControl {
+    let mut range = RangeControl::empty();
+
+    if start_offset.is_some() {
+        range |= RangeControl::START_OFFSET;
+    }
+
+    if length.is_some() {
+        range |= RangeControl::DEF_LEN;
+    }
+
+    if start_offset.max(length).unwrap_or(0) > u32::MAX as u64 {
+        range |= RangeControl::WIDE_RANGE;
+    }
+
+    range
+}
+
+fn read_range<T>(
+    rb: &mut ReadBuf<T>,
+    range: RangeControl,
+    flag: RangeControl,
+) -> Result<Option<u64>, Error>
+where
+    T: Borrow<[u8]>,
+{
+    if !range.contains(flag) {
+        Ok(None)
+    } else if range.contains(RangeControl::WIDE_RANGE) {
+        rb.le_u64().map(Some)
+    } else {
+        rb.le_u32().map(|value| Some(value as _))
+    }
+}
+
+fn write_range(wb: &mut WriteBuf, range: RangeControl, value: Option<u64>) -> Result<(), Error> {
+    match value {
+        Some(value) if range.contains(RangeControl::WIDE_RANGE) => wb.le_u64(value),
+        Some(value) => wb.le_u32(value as _),
+        None => Ok(()),
+    }
+}
+
+fn read_slice<'a>(
+    rb: &mut ReadBuf<&'a [u8]>,
+    payload: &'a [u8],
+    len: usize,
+) -> Result<&'a [u8], Error> {
+    let (start, _) = rb.slice_range();
+
+    rb.parse_head_with(len, |_| ())?;
+
+    Ok(&payload[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::storage::WriteBuf;
+
+    use super::{BdxStatusCode, OpCode, TransferAccept, TransferInit, TransferModes};
+
+    #[test]
+    fn test_init_roundtrip() {
+        let init = TransferInit {
+            modes: TransferModes::RECEIVER_DRIVE,
+            max_block_size: 512,
+            start_offset: Some(0x1_0000_0000),
+            length: Some(100),
+            file_designator: b"file.bin",
+            metadata: &[0x15, 0x18],
+        };
+
+        let mut buf = [0; 64];
+        let mut wb = WriteBuf::new(&mut buf);
+        unwrap!(init.write(&mut wb));
+
+        let read = unwrap!(TransferInit::read(wb.as_slice()));
+
+        assert_eq!(read.modes, init.modes);
+        assert_eq!(read.max_block_size, init.max_block_size);
+        assert_eq!(read.start_offset, init.start_offset);
+        assert_eq!(read.length, init.length);
+        assert_eq!(read.file_designator, init.file_designator);
+        assert_eq!(read.metadata, init.metadata);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let all = TransferInit::new(b"file.bin");
+
+        // Async is preferred whenever proposed
+        let accept = unwrap!(TransferAccept::negotiate(&all, false));
+        assert_eq!(
+            accept.modes,
+            TransferModes::SENDER_DRIVE | TransferModes::ASYNC
+        );
+
+        // Otherwise the responder drives the transfer
+        let sync = TransferInit {
+            modes: TransferModes::SENDER_DRIVE | TransferModes::RECEIVER_DRIVE,
+            ..TransferInit::new(b"file.bin")
+        };
+        assert_eq!(
+            unwrap!(TransferAccept::negotiate(&sync, false)).modes,
+            TransferModes::RECEIVER_DRIVE
+        );
+        assert_eq!(
+            unwrap!(TransferAccept::negotiate(&sync, true)).modes,
+            TransferModes::SENDER_DRIVE
+        );
+
+        let accept = unwrap!(TransferAccept::negotiate(&sync, true));
+        unwrap!(accept.check(&sync));
+
+        let mut buf = [0; 32];
+        let mut wb = WriteBuf::new(&mut buf);
+        unwrap!(accept.write(OpCode::ReceiveAccept, &mut wb));
+
+        let read = unwrap!(TransferAccept::read(OpCode::ReceiveAccept, wb.as_slice()));
+        assert_eq!(read.modes, accept.modes);
+        assert_eq!(read.max_block_size, accept.max_block_size);
+
+        // Async alone is not a drive mode
+        let invalid = TransferInit {
+            modes: TransferModes::ASYNC,
+            ..TransferInit::new(b"file.bin")
+        };
+        assert!(matches!(
+            TransferAccept::negotiate(&invalid, false),
+            Err(BdxStatusCode::TransferMethodNotSupported)
+        ));
+    }
+}
@@ -27,11 +27,20 @@ use crate::tlv::{FromTLV, Octets, TLVArray, TLVElement, TLVList, ToTLV};
 use crate::utils::epoch::MATTER_CERT_DOESNT_EXPIRE;
 use crate::utils::iter::TryFindIterator;
 
+use self::der::MAX_DER_SIGNATURE_LEN;
 use self::printer::CertPrinter;
 
 pub use self::asn1_writer::ASN1Writer;
 
+pub mod ca;
+pub mod cd;
+pub mod devatt;
+pub mod pem;
+pub mod sec1;
+pub mod x509;
+
 mod asn1_writer;
+pub(crate) mod der;
 mod printer;
 
 // As per section 6.1.3 "Certificate Sizes" of the Matter 1.1 spec
@@ -680,6 +689,35 @@ impl<'a> CertRef<'a> {
         Ok(w.as_slice().len())
     }
 
+    /// Encode the certificate as a complete X.509 DER certificate,
+    /// i.e. unlike `as_asn1`, including the signature.
+    pub fn as_x509_der(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut signature = [0; MAX_DER_SIGNATURE_LEN];
+        let signature_len = der::ecdsa_sig_to_der(self.signature()?, &mut signature)?;
+
+        let mut w = ASN1Writer::new(buf);
+
+        w.start_seq("")?;
+        self.encode(&mut w)?;
+        w.start_seq("")?;
+        w.oid("", &OID_ECDSA_WITH_SHA256)?;
+        w.end_seq()?;
+        w.bitstr("", false, &signature[..signature_len])?;
+        w.end_seq()?;
+
+        Ok(w.as_slice().len())
+    }
+
+    /// Write the certificate as a PEM-encoded X.509 certificate.
+    ///
+    /// The provided buffer is used for the intermediate DER encoding.
+    pub fn write_pem<W: Write>(&self, buf: &mut [u8], out: &mut W) -> Result<(), Error> {
+        let len = self.as_x509_der(buf)?;
+
+        pem::encode(pem::PEM_LABEL_CERTIFICATE, &buf[..len], out)
+            .map_err(|_| ErrorCode::NoSpace.into())
+    }
+
     pub fn verify_chain_start(&self) -> CertVerifier {
         CertVerifier::new(self)
     }
@@ -815,10 +853,12 @@ pub trait CertConsumer {
 
 #[cfg(test)]
 mod tests {
+    use crate::crypto::EC_SIGNATURE_LEN_BYTES;
     use crate::tlv::{FromTLV, TLVElement, TLVWriter, TagType, ToTLV};
     use crate::utils::storage::WriteBuf;
 
-    use super::CertRef;
+    use super::der::{ecdsa_sig_from_der, DerReader, TAG_OID, TAG_SEQUENCE};
+    use super::{CertRef, OID_ECDSA_WITH_SHA256};
 
     #[test]
     fn test_asn1_encode_success() {
@@ -941,6 +981,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_x509_der_encode() {
+        let mut buf = [0; 1000];
+
+        let cert = CertRef::new(TLVElement::new(&test_vectors::CHIP_CERT_INPUT1));
+        let len = unwrap!(cert.as_x509_der(&mut buf));
+
+        let mut reader = DerReader::new(&buf[..len]);
+        let mut seq = unwrap!(reader.read_seq());
+        assert!(reader.is_empty());
+
+        // The TBS certificate is the same as the one produced by `as_asn1`
+        let tbs = unwrap!(seq.read(TAG_SEQUENCE));
+        assert_eq!(tbs.raw, &test_vectors::ASN1_OUTPUT1);
+
+        let mut algo = unwrap!(seq.read_seq());
+        assert_eq!(unwrap!(algo.read(TAG_OID)).content, &OID_ECDSA_WITH_SHA256);
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        unwrap!(ecdsa_sig_from_der(
+            unwrap!(seq.read_bitstr()),
+            &mut signature
+        ));
+        assert_eq!(&signature, unwrap!(cert.signature()));
+        assert!(seq.is_empty());
+    }
+
     #[test]
     fn test_unordered_extensions() {
         let mut buf = [0; 1000];
@@ -60,6 +60,13 @@ impl<'a> ASN1Writer<'a> {
         Err(ErrorCode::NoSpace.into())
     }
 
+    /// Append the provided, already DER-encoded data
+    pub fn append_der(&mut self, der: &[u8]) -> Result<(), Error> {
+        self.append_with(der.len(), |t| {
+            t.buf[t.offset..t.offset + der.len()].copy_from_slice(der)
+        })
+    }
+
     pub fn append_tlv<F>(&mut self, tag: u8, len: usize, f: F) -> Result<(), Error>
     where
         F: FnOnce(&mut Self),
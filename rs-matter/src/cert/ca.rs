@@ -0,0 +1,718 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! An operational Certificate Authority, issuing the Matter TLV certificates of a fabric:
+//! the root certificate (RCAC), an optional intermediate certificate (ICAC)
+//! and the Node Operational Certificates (NOCs) of the fabric nodes.
+
+use heapless::Vec;
+
+use crate::alloc;
+#[cfg(feature = "client")]
+use crate::commissioner::NocIssuer;
+use crate::crypto::{
+    KeyPair, Sha256, EC_POINT_LEN_BYTES, EC_SIGNATURE_LEN_BYTES, SHA256_HASH_LEN_BYTES,
+};
+use crate::error::{Error, ErrorCode};
+use crate::fabric::{Fabric, FabricMgr};
+use crate::tlv::{TLVElement, TLVTag, TLVWrite, ToTLV};
+use crate::transport::session::MAX_CAT_IDS_PER_NOC;
+use crate::utils::epoch::{Epoch, MATTER_EPOCH_SECS};
+use crate::utils::rand::Rand;
+use crate::utils::storage::WriteBuf;
+
+use super::der::{self, DerReader, TAG_INTEGER, TAG_OID, TAG_SEQUENCE};
+use super::{
+    CertRef, CertTag, DNTag, EcCurveIdValue, PubKeyAlgoValue, SignAlgoValue, MAX_CERT_TLV_LEN,
+    OID_ECDSA_WITH_SHA256, OID_EC_TYPE_PRIME256V1, OID_PUB_KEY_ECPUBKEY,
+};
+
+/// The length of the serial numbers of the issued certificates
+pub(super) const SERIAL_NUM_LEN: usize = 8;
+
+/// The length of the subject and authority key identifiers of the issued certificates
+pub(super) const KEY_ID_LEN: usize = 20;
+
+/// The maximum length of the DER encoding of the TBS part of an issued certificate
+const MAX_TBS_LEN: usize = 800;
+
+// Key usage flags, as per RFC 5280
+const KEY_USAGE_DIGITAL_SIGNATURE: u16 = 0x0001;
+const KEY_USAGE_KEY_CERT_SIGN: u16 = 0x0020;
+const KEY_USAGE_CRL_SIGN: u16 = 0x0040;
+
+// Extended key usage values, as per the Matter TLV certificate encoding
+const EXT_KEY_USAGE_SERVER_AUTH: u8 = 1;
+const EXT_KEY_USAGE_CLIENT_AUTH: u8 = 2;
+
+// Extension tags, as per the Matter TLV certificate encoding
+const EXT_TAG_BASIC_CONSTRAINTS: u8 = 1;
+const EXT_TAG_KEY_USAGE: u8 = 2;
+const EXT_TAG_EXT_KEY_USAGE: u8 = 3;
+const EXT_TAG_SUBJECT_KEY_ID: u8 = 4;
+const EXT_TAG_AUTHORITY_KEY_ID: u8 = 5;
+
+/// The largest operational node ID, as per the Matter spec
+const MAX_OPERATIONAL_NODE_ID: u64 = 0xFFFF_FFEF_FFFF_FFFF;
+
+/// The subject of an issued certificate: the DN attributes
+/// (Root CA ID, ICA ID or Node ID, Fabric ID and NOC CATs)
+type Subject = Vec<(DNTag, u64), { 2 + MAX_CAT_IDS_PER_NOC }>;
+
+/// A CA key pair, together with its certificate
+struct CaCert {
+    key: KeyPair,
+    cert: Vec<u8, MAX_CERT_TLV_LEN>,
+}
+
+impl CaCert {
+    fn new(key: KeyPair, cert: &[u8]) -> Result<Self, Error> {
+        let this = Self {
+            key,
+            cert: Vec::from_slice(cert).map_err(|_| ErrorCode::NoSpace)?,
+        };
+
+        // The key pair must be the one certified by the certificate
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        let len = this.key.get_public_key(&mut pubkey)?;
+
+        if this.cert_ref().pubkey()? != &pubkey[..len] {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(this)
+    }
+
+    fn cert_ref(&self) -> CertRef<'_> {
+        CertRef::new(TLVElement::new(&self.cert))
+    }
+}
+
+/// An operational Certificate Authority of a fabric.
+///
+/// The CA generates the root (and optionally, an intermediate) key pair of the fabric
+/// and issues the Matter TLV certificates of the fabric:
+/// - The self-signed root certificate (RCAC)
+/// - The intermediate certificate (ICAC), signed by the root
+/// - The Node Operational Certificates (NOCs) of the fabric nodes, signed by the intermediate
+///   (if there is one) or by the root
+///
+/// All certificates are issued as valid from the moment of their issuance, with no expiry.
+pub struct OperationalCa {
+    fabric_id: u64,
+    root: CaCert,
+    ica: Option<CaCert>,
+    epoch: Epoch,
+    rand: Rand,
+}
+
+impl OperationalCa {
+    /// Create a new CA for the provided fabric, generating a new root key pair
+    /// and a self-signed RCAC for it.
+    ///
+    /// # Arguments
+    /// - `fabric_id`: The ID of the fabric
+    /// - `rcac_id`: The Root CA ID to put in the subject of the RCAC
+    /// - `epoch`: The source of the current time
+    /// - `rand`: The source of randomness, used for the key pairs and the serial numbers
+    pub fn new(fabric_id: u64, rcac_id: u64, epoch: Epoch, rand: Rand) -> Result<Self, Error> {
+        let key = KeyPair::new(rand)?;
+
+        let mut subject = Subject::new();
+        unwrap!(subject.push((DNTag::RootCaId, rcac_id)));
+
+        let mut rcac = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+        unwrap!(rcac.resize_default(MAX_CERT_TLV_LEN));
+
+        let len = Self::issue(
+            &key,
+            None,
+            &subject,
+            &Self::pubkey(&key)?,
+            true,
+            epoch,
+            rand,
+            &mut rcac,
+        )?;
+
+        Self::new_from_root(fabric_id, key, &rcac[..len], epoch, rand)
+    }
+
+    /// Create a CA for the provided fabric from an existing root key pair and RCAC
+    /// (e.g. ones loaded from persistent storage).
+    ///
+    /// # Arguments
+    /// - `fabric_id`: The ID of the fabric
+    /// - `root_key`: The root key pair
+    /// - `rcac`: The RCAC (Matter TLV) certifying the root key pair
+    /// - `epoch`: The source of the current time
+    /// - `rand`: The source of randomness, used for the key pairs and the serial numbers
+    pub fn new_from_root(
+        fabric_id: u64,
+        root_key: KeyPair,
+        rcac: &[u8],
+        epoch: Epoch,
+        rand: Rand,
+    ) -> Result<Self, Error> {
+        if fabric_id == 0 {
+            Err(ErrorCode::Invalid)?;
+        }
+
+        let root = CaCert::new(root_key, rcac)?;
+
+        // The RCAC must be self-signed
+        let rcac = root.cert_ref();
+        rcac.verify_chain_start().finalise(&mut [0; MAX_TBS_LEN])?;
+
+        Ok(Self {
+            fabric_id,
+            root,
+            ica: None,
+            epoch,
+            rand,
+        })
+    }
+
+    /// Generate a new intermediate key pair and an ICAC for it, signed by the root.
+    ///
+    /// From then on, NOCs are signed by the intermediate key pair.
+    ///
+    /// # Arguments
+    /// - `icac_id`: The ICA ID to put in the subject of the ICAC
+    pub fn generate_ica(&mut self, icac_id: u64) -> Result<(), Error> {
+        let key = KeyPair::new(self.rand)?;
+
+        let mut subject = Subject::new();
+        unwrap!(subject.push((DNTag::IcaId, icac_id)));
+        unwrap!(subject.push((DNTag::FabricId, self.fabric_id)));
+
+        let mut icac = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+        unwrap!(icac.resize_default(MAX_CERT_TLV_LEN));
+
+        let len = Self::issue(
+            &self.root.key,
+            Some(&self.root.cert_ref()),
+            &subject,
+            &Self::pubkey(&key)?,
+            true,
+            self.epoch,
+            self.rand,
+            &mut icac,
+        )?;
+
+        self.ica = Some(CaCert::new(key, &icac[..len])?);
+
+        Ok(())
+    }
+
+    /// Use an existing intermediate key pair and ICAC (e.g. ones loaded from persistent storage).
+    ///
+    /// The ICAC must be signed by the root of the CA.
+    ///
+    /// From then on, NOCs are signed by the intermediate key pair.
+    pub fn set_ica(&mut self, ica_key: KeyPair, icac: &[u8]) -> Result<(), Error> {
+        let ica = CaCert::new(ica_key, icac)?;
+
+        let rcac = self.root.cert_ref();
+        let icac = ica.cert_ref();
+
+        let mut buf = [0; MAX_TBS_LEN];
+        icac.verify_chain_start()
+            .add_cert(&rcac, &mut buf)?
+            .finalise(&mut buf)?;
+
+        self.ica = Some(ica);
+
+        Ok(())
+    }
+
+    /// Return the ID of the fabric of the CA
+    pub fn fabric_id(&self) -> u64 {
+        self.fabric_id
+    }
+
+    /// Return the root key pair
+    pub fn root_key(&self) -> &KeyPair {
+        &self.root.key
+    }
+
+    /// Return the RCAC (Matter TLV)
+    pub fn rcac(&self) -> &[u8] {
+        &self.root.cert
+    }
+
+    /// Return the intermediate key pair, if the CA has one
+    pub fn ica_key(&self) -> Option<&KeyPair> {
+        self.ica.as_ref().map(|ica| &ica.key)
+    }
+
+    /// Return the ICAC (Matter TLV), if the CA has one
+    pub fn icac(&self) -> Option<&[u8]> {
+        self.ica.as_ref().map(|ica| ica.cert.as_slice())
+    }
+
+    /// Issue a NOC (Matter TLV) for the provided public key.
+    ///
+    /// # Arguments
+    /// - `pubkey`: The public key of the node (uncompressed P-256 point)
+    /// - `node_id`: The operational node ID of the node
+    /// - `cat_ids`: The CASE Authenticated Tags of the node, if any
+    /// - `noc`: The buffer to write the NOC into
+    ///
+    /// Return the length of the NOC.
+    pub fn issue_noc_for_pubkey(
+        &self,
+        pubkey: &[u8],
+        node_id: u64,
+        cat_ids: &[u32],
+        noc: &mut [u8],
+    ) -> Result<usize, Error> {
+        if pubkey.len() != EC_POINT_LEN_BYTES {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        if node_id == 0 || node_id > MAX_OPERATIONAL_NODE_ID {
+            Err(ErrorCode::Invalid)?;
+        }
+
+        if cat_ids.len() > MAX_CAT_IDS_PER_NOC {
+            Err(ErrorCode::NoSpace)?;
+        }
+
+        let mut subject = Subject::new();
+        unwrap!(subject.push((DNTag::NodeId, node_id)));
+        unwrap!(subject.push((DNTag::FabricId, self.fabric_id)));
+
+        for cat_id in cat_ids {
+            // The version part of a CAT is never 0
+            if *cat_id & 0xffff == 0 {
+                Err(ErrorCode::Invalid)?;
+            }
+
+            unwrap!(subject.push((DNTag::NocCat, *cat_id as u64)));
+        }
+
+        let signer = self.ica.as_ref().unwrap_or(&self.root);
+
+        Self::issue(
+            &signer.key,
+            Some(&signer.cert_ref()),
+            &subject,
+            pubkey,
+            false,
+            self.epoch,
+            self.rand,
+            noc,
+        )
+    }
+
+    /// Verify the provided CSR (PKCS#10 DER), e.g. one received with the `CSRResponse`
+    /// of a commissionee, and issue a NOC (Matter TLV) for its public key.
+    ///
+    /// # Arguments
+    /// - `csr`: The CSR
+    /// - `node_id`: The operational node ID of the node
+    /// - `cat_ids`: The CASE Authenticated Tags of the node, if any
+    /// - `noc`: The buffer to write the NOC into
+    ///
+    /// Return the length of the NOC.
+    pub fn sign_csr(
+        &self,
+        csr: &[u8],
+        node_id: u64,
+        cat_ids: &[u32],
+        noc: &mut [u8],
+    ) -> Result<usize, Error> {
+        let pubkey = Self::csr_pubkey(csr)?;
+
+        self.issue_noc_for_pubkey(pubkey, node_id, cat_ids, noc)
+    }
+
+    /// Add a fabric for a local node to the provided fabric manager,
+    /// generating the node key pair and issuing its NOC.
+    ///
+    /// This is how a commissioner joins the fabric of its own CA.
+    ///
+    /// # Arguments
+    /// - `fabric_mgr`: The fabric manager to add the fabric to
+    /// - `node_id`: The operational node ID of the local node; also used as the CASE admin subject
+    /// - `cat_ids`: The CASE Authenticated Tags of the local node, if any
+    /// - `ipk`: The Identity Protection Key (epoch key) of the fabric
+    /// - `vendor_id`: The vendor ID of the fabric admin
+    /// - `mdns_notif`: A callback invoked when the mDNS services need to be updated
+    pub fn add_fabric<'a>(
+        &self,
+        fabric_mgr: &'a mut FabricMgr,
+        node_id: u64,
+        cat_ids: &[u32],
+        ipk: &[u8],
+        vendor_id: u16,
+        mdns_notif: &mut dyn FnMut(),
+    ) -> Result<&'a mut Fabric, Error> {
+        let key = KeyPair::new(self.rand)?;
+
+        let mut noc = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+        unwrap!(noc.resize_default(MAX_CERT_TLV_LEN));
+
+        let len = self.issue_noc_for_pubkey(&Self::pubkey(&key)?, node_id, cat_ids, &mut noc)?;
+
+        fabric_mgr.add(
+            key,
+            self.rcac(),
+            &noc[..len],
+            self.icac().unwrap_or(&[]),
+            ipk,
+            vendor_id,
+            node_id,
+            mdns_notif,
+        )
+    }
+
+    /// Issue a certificate (Matter TLV) with the provided subject and public key.
+    ///
+    /// If `issuer` is `None`, the certificate is self-signed and `signer` must be
+    /// the key pair of the provided public key.
+    #[allow(clippy::too_many_arguments)]
+    fn issue(
+        signer: &KeyPair,
+        issuer: Option<&CertRef>,
+        subject: &[(DNTag, u64)],
+        pubkey: &[u8],
+        is_ca: bool,
+        epoch: Epoch,
+        rand: Rand,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut subject_key_id = [0; KEY_ID_LEN];
+        Self::key_id(pubkey, &mut subject_key_id)?;
+
+        let authority_key_id = if let Some(issuer) = issuer {
+            issuer.get_subject_key_id()?
+        } else {
+            &subject_key_id[..]
+        };
+
+        let mut serial_num = [0; SERIAL_NUM_LEN];
+        rand(&mut serial_num);
+        // The serial number is encoded as a positive ASN.1 integer, without leading zeroes
+        serial_num[0] = (serial_num[0] & 0x7f).max(1);
+
+        let not_before = epoch()
+            .as_secs()
+            .saturating_sub(MATTER_EPOCH_SECS)
+            .min(u32::MAX as u64) as u32;
+
+        let key_usage = if is_ca {
+            KEY_USAGE_KEY_CERT_SIGN | KEY_USAGE_CRL_SIGN
+        } else {
+            KEY_USAGE_DIGITAL_SIGNATURE
+        };
+
+        let len = {
+            let mut wb = WriteBuf::new(buf);
+
+            wb.start_struct(&TLVTag::Anonymous)?;
+            wb.str(&Self::tag(CertTag::SerialNum), &serial_num)?;
+            wb.u8(
+                &Self::tag(CertTag::SignAlgo),
+                SignAlgoValue::ECDSAWithSHA256 as _,
+            )?;
+
+            if let Some(issuer) = issuer {
+                // The issuer of the certificate is the subject of the issuer certificate
+                issuer
+                    .0
+                    .structure()?
+                    .find_ctx(CertTag::Subject as _)?
+                    .to_tlv(&Self::tag(CertTag::Issuer), &mut wb)?;
+            } else {
+                Self::write_dn(&mut wb, CertTag::Issuer, subject)?;
+            }
+
+            wb.u32(&Self::tag(CertTag::NotBefore), not_before)?;
+            // A Not-After value of 0 means that the certificate does not expire
+            wb.u32(&Self::tag(CertTag::NotAfter), 0)?;
+            Self::write_dn(&mut wb, CertTag::Subject, subject)?;
+            wb.u8(
+                &Self::tag(CertTag::PubKeyAlgo),
+                PubKeyAlgoValue::EcPubKey as _,
+            )?;
+            wb.u8(
+                &Self::tag(CertTag::EcCurveId),
+                EcCurveIdValue::Prime256V1 as _,
+            )?;
+            wb.str(&Self::tag(CertTag::EcPubKey), pubkey)?;
+
+            wb.start_list(&Self::tag(CertTag::Extensions))?;
+            wb.start_struct(&TLVTag::Context(EXT_TAG_BASIC_CONSTRAINTS))?;
+            wb.bool(&TLVTag::Context(1), is_ca)?;
+            wb.end_container()?;
+            wb.u16(&TLVTag::Context(EXT_TAG_KEY_USAGE), key_usage)?;
+            if !is_ca {
+                wb.start_array(&TLVTag::Context(EXT_TAG_EXT_KEY_USAGE))?;
+                wb.u8(&TLVTag::Anonymous, EXT_KEY_USAGE_CLIENT_AUTH)?;
+                wb.u8(&TLVTag::Anonymous, EXT_KEY_USAGE_SERVER_AUTH)?;
+                wb.end_container()?;
+            }
+            wb.str(&TLVTag::Context(EXT_TAG_SUBJECT_KEY_ID), &subject_key_id)?;
+            wb.str(&TLVTag::Context(EXT_TAG_AUTHORITY_KEY_ID), authority_key_id)?;
+            wb.end_container()?;
+
+            // A placeholder for the signature, which is computed over the DER encoding of all of the above
+            wb.str(&Self::tag(CertTag::Signature), &[0; EC_SIGNATURE_LEN_BYTES])?;
+            wb.end_container()?;
+
+            wb.get_tail()
+        };
+
+        let mut tbs = alloc!([0; MAX_TBS_LEN]); // TODO LARGE BUFFER
+        let tbs = &mut tbs[..];
+
+        let tbs_len = CertRef::new(TLVElement::new(&buf[..len])).as_asn1(tbs)?;
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        signer.sign_msg(&tbs[..tbs_len], &mut signature)?;
+
+        // The signature is the last element of the certificate structure,
+        // i.e. it is followed only by the end-of-container marker
+        buf[len - 1 - EC_SIGNATURE_LEN_BYTES..len - 1].copy_from_slice(&signature);
+
+        Ok(len)
+    }
+
+    fn write_dn<W: TLVWrite>(
+        mut tw: W,
+        tag: CertTag,
+        subject: &[(DNTag, u64)],
+    ) -> Result<(), Error> {
+        tw.start_list(&Self::tag(tag))?;
+
+        for (dn_tag, value) in subject {
+            tw.u64(&TLVTag::Context(*dn_tag as _), *value)?;
+        }
+
+        tw.end_container()
+    }
+
+    const fn tag(tag: CertTag) -> TLVTag {
+        TLVTag::Context(tag as _)
+    }
+
+    /// Compute the key identifier of the provided public key.
+    ///
+    /// As per RFC 7093 (method 1), this is the leftmost 160 bits of the SHA-256 hash of the key.
+    pub(super) fn key_id(pubkey: &[u8], key_id: &mut [u8; KEY_ID_LEN]) -> Result<(), Error> {
+        let mut hash = [0; SHA256_HASH_LEN_BYTES];
+
+        let mut sha256 = Sha256::new()?;
+        sha256.update(pubkey)?;
+        sha256.finish(&mut hash)?;
+
+        key_id.copy_from_slice(&hash[..KEY_ID_LEN]);
+
+        Ok(())
+    }
+
+    fn pubkey(key: &KeyPair) -> Result<[u8; EC_POINT_LEN_BYTES], Error> {
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        key.get_public_key(&mut pubkey)?;
+
+        Ok(pubkey)
+    }
+
+    /// Parse the provided CSR (PKCS#10 DER), verify its signature and return its public key
+    fn csr_pubkey(csr: &[u8]) -> Result<&[u8], Error> {
+        let mut reader = DerReader::new(csr);
+        let mut request = reader.read_seq()?;
+
+        let info = request.read(TAG_SEQUENCE)?;
+        let mut sign_algo = request.read_seq()?;
+        let signature = request.read_bitstr()?;
+
+        if sign_algo.read(TAG_OID)?.content != OID_ECDSA_WITH_SHA256 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let mut info_reader = DerReader::new(info.content);
+        // Version
+        info_reader.read(TAG_INTEGER)?;
+        // Subject, not used in Matter
+        info_reader.read(TAG_SEQUENCE)?;
+
+        let mut pubkey_info = info_reader.read_seq()?;
+        let mut pubkey_algo = pubkey_info.read_seq()?;
+
+        if pubkey_algo.read(TAG_OID)?.content != OID_PUB_KEY_ECPUBKEY
+            || pubkey_algo.read(TAG_OID)?.content != OID_EC_TYPE_PRIME256V1
+        {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let pubkey = pubkey_info.read_bitstr()?;
+        if pubkey.len() != EC_POINT_LEN_BYTES {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let mut raw_signature = [0; EC_SIGNATURE_LEN_BYTES];
+        der::ecdsa_sig_from_der(signature, &mut raw_signature)?;
+
+        KeyPair::new_from_public(pubkey)?.verify_msg(info.raw, &raw_signature)?;
+
+        Ok(pubkey)
+    }
+}
+
+#[cfg(feature = "client")]
+impl NocIssuer for OperationalCa {
+    fn issue_noc(&self, csr: &[u8], node_id: u64, noc: &mut [u8]) -> Result<usize, Error> {
+        self.sign_csr(csr, node_id, &[], noc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cert::{CertRef, MAX_CERT_TLV_LEN};
+    use crate::crypto::KeyPair;
+    use crate::tlv::TLVElement;
+    use crate::utils::epoch::sys_epoch;
+    use crate::utils::rand::sys_rand;
+
+    use super::OperationalCa;
+
+    const FABRIC_ID: u64 = 0xFAB000000000001D;
+    const NODE_ID: u64 = 0xDEDEDEDE00010001;
+    const CAT_ID: u32 = 0xABCD0002;
+
+    fn issue_and_verify(ca: &OperationalCa) {
+        let key = unwrap!(KeyPair::new(sys_rand));
+
+        let mut csr = [0; 300];
+        let csr = unwrap!(key.get_csr(&mut csr));
+
+        let mut noc = [0; MAX_CERT_TLV_LEN];
+        let len = unwrap!(ca.sign_csr(csr, NODE_ID, &[CAT_ID], &mut noc));
+
+        let noc = CertRef::new(TLVElement::new(&noc[..len]));
+        assert_eq!(unwrap!(noc.get_node_id()), NODE_ID);
+        assert_eq!(unwrap!(noc.get_fabric_id()), FABRIC_ID);
+
+        let mut cat_ids = [0; 3];
+        unwrap!(noc.get_cat_ids(&mut cat_ids));
+        assert_eq!(cat_ids, [CAT_ID, 0, 0]);
+
+        let rcac = CertRef::new(TLVElement::new(ca.rcac()));
+
+        let mut buf = [0; 800];
+        let mut verifier = noc.verify_chain_start();
+
+        let icac = ca.icac().map(|icac| CertRef::new(TLVElement::new(icac)));
+        if let Some(icac) = icac.as_ref() {
+            assert_eq!(unwrap!(icac.get_fabric_id()), FABRIC_ID);
+            verifier = unwrap!(verifier.add_cert(icac, &mut buf));
+        }
+
+        unwrap!(unwrap!(verifier.add_cert(&rcac, &mut buf)).finalise(&mut buf));
+    }
+
+    #[test]
+    fn test_issue_root_only() {
+        let ca = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+        assert!(ca.icac().is_none());
+
+        issue_and_verify(&ca);
+    }
+
+    #[test]
+    fn test_issue_with_ica() {
+        let mut ca = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+        unwrap!(ca.generate_ica(2));
+
+        issue_and_verify(&ca);
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut ca = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+        unwrap!(ca.generate_ica(2));
+
+        let mut pub_key = [0; 65];
+        let mut priv_key = [0; 32];
+
+        let root_key = ca.root_key();
+        unwrap!(root_key.get_public_key(&mut pub_key));
+        unwrap!(root_key.get_private_key(&mut priv_key));
+        let root_key = unwrap!(KeyPair::new_from_components(&pub_key, &priv_key));
+
+        let mut restored = unwrap!(OperationalCa::new_from_root(
+            FABRIC_ID,
+            root_key,
+            ca.rcac(),
+            sys_epoch,
+            sys_rand
+        ));
+
+        let ica_key = unwrap!(ca.ica_key());
+        unwrap!(ica_key.get_public_key(&mut pub_key));
+        unwrap!(ica_key.get_private_key(&mut priv_key));
+        let ica_key = unwrap!(KeyPair::new_from_components(&pub_key, &priv_key));
+
+        unwrap!(restored.set_ica(ica_key, unwrap!(ca.icac())));
+
+        issue_and_verify(&restored);
+
+        // An ICAC of another CA is rejected
+        let mut other = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+        unwrap!(other.generate_ica(2));
+
+        let ica_key = unwrap!(other.ica_key());
+        unwrap!(ica_key.get_public_key(&mut pub_key));
+        unwrap!(ica_key.get_private_key(&mut priv_key));
+        let ica_key = unwrap!(KeyPair::new_from_components(&pub_key, &priv_key));
+
+        assert!(restored.set_ica(ica_key, unwrap!(other.icac())).is_err());
+    }
+
+    #[test]
+    fn test_invalid_csr() {
+        let ca = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+
+        let key = unwrap!(KeyPair::new(sys_rand));
+
+        let mut csr = [0; 300];
+        let len = unwrap!(key.get_csr(&mut csr)).len();
+
+        // Corrupt the signed part of the CSR
+        csr[len / 2] ^= 0xff;
+
+        let mut noc = [0; MAX_CERT_TLV_LEN];
+        assert!(ca.sign_csr(&csr[..len], NODE_ID, &[], &mut noc).is_err());
+    }
+
+    #[test]
+    fn test_pem_export() {
+        let ca = unwrap!(OperationalCa::new(FABRIC_ID, 1, sys_epoch, sys_rand));
+
+        let rcac = CertRef::new(TLVElement::new(ca.rcac()));
+
+        let mut buf = [0; 800];
+        let mut pem = heapless::String::<1200>::new();
+        unwrap!(rcac.write_pem(&mut buf, &mut pem));
+
+        assert!(pem.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert!(pem.ends_with("-----END CERTIFICATE-----\n"));
+    }
+}
@@ -0,0 +1,391 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! A reader and a writer for the Certification Declarations (CDs) of the devices, as per
+//! section 6.3 "Certification Declaration" of the Matter spec.
+//!
+//! A CD is a TLV structure, enveloped in a CMS `SignedData` message (RFC 5652)
+//! which is signed by one of the CD signing keys of the CSA.
+
+use crate::alloc;
+use crate::crypto::{KeyPair, EC_SIGNATURE_LEN_BYTES};
+use crate::error::{Error, ErrorCode};
+use crate::tlv::{FromTLV, OctetStr, TLVArray, TLVElement, TLVTag, ToTLV, UtfStr};
+use crate::utils::storage::WriteBuf;
+
+use super::der::{
+    self, DerReader, MAX_DER_SIGNATURE_LEN, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SET,
+};
+use super::{ASN1Writer, CertConsumer, OID_ECDSA_WITH_SHA256};
+
+// As per https://datatracker.ietf.org/doc/html/rfc5652
+
+const OID_PKCS7_DATA: [u8; 9] = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01];
+const OID_PKCS7_SIGNED_DATA: [u8; 9] = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+const OID_SHA256: [u8; 9] = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+
+const TAG_CTX_CONTENT: u8 = 0xA0;
+const TAG_CTX_SUBJECT_KEY_ID: u8 = 0x80;
+
+/// The only CMS version allowed by the Matter spec for the CD envelope,
+/// as the signer is identified by its subject key ID
+const CMS_VERSION: u8 = 3;
+
+/// The only CD format version defined by the Matter spec
+pub const CD_FORMAT_VERSION: u16 = 1;
+
+/// The max length of the TLV encoding of a CD
+const MAX_CD_CONTENT_LEN: usize = 600;
+
+/// The contents of a Certification Declaration
+#[derive(Debug, Clone, FromTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct CertDeclaration<'a> {
+    pub format_version: u16,
+    pub vendor_id: u16,
+    pub product_id_array: TLVArray<'a, u16>,
+    pub device_type_id: u32,
+    pub certificate_id: UtfStr<'a>,
+    pub security_level: u8,
+    pub security_information: u16,
+    pub version_number: u16,
+    pub certification_type: u8,
+    pub dac_origin_vendor_id: Option<u16>,
+    pub dac_origin_product_id: Option<u16>,
+    pub authorized_paa_list: Option<TLVArray<'a, OctetStr<'a>>>,
+}
+
+impl CertDeclaration<'_> {
+    /// Return `true` if the CD covers the provided Product ID
+    pub fn has_product_id(&self, product_id: u16) -> Result<bool, Error> {
+        for pid in self.product_id_array.iter() {
+            if pid? == product_id {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Return `true` if the CD allows the PAA with the provided subject key ID,
+    /// i.e. if the CD has no authorized PAA list, or if the PAA is in that list
+    pub fn is_paa_authorized(&self, paa_key_id: &[u8]) -> Result<bool, Error> {
+        let Some(paas) = &self.authorized_paa_list else {
+            return Ok(true);
+        };
+
+        for paa in paas.iter() {
+            if paa?.0 == paa_key_id {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// The contents of a Certification Declaration to be generated.
+///
+/// Unlike `CertDeclaration`, which borrows its contents from a TLV encoding,
+/// this type borrows its contents from native Rust types.
+#[derive(Debug, Clone, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct CertDeclarationContent<'a> {
+    pub format_version: u16,
+    pub vendor_id: u16,
+    pub product_id_array: &'a [u16],
+    pub device_type_id: u32,
+    pub certificate_id: UtfStr<'a>,
+    pub security_level: u8,
+    pub security_information: u16,
+    pub version_number: u16,
+    pub certification_type: u8,
+    pub dac_origin_vendor_id: Option<u16>,
+    pub dac_origin_product_id: Option<u16>,
+    pub authorized_paa_list: Option<&'a [OctetStr<'a>]>,
+}
+
+impl CertDeclarationContent<'_> {
+    /// Encode the Certification Declaration and envelope it in a CMS `SignedData` message
+    /// (DER), signed with the provided key.
+    ///
+    /// # Arguments
+    /// - `signer_key_id`: The subject key ID of the signing key, identifying it in the envelope
+    /// - `signer`: The signing key
+    /// - `buf`: The buffer to write the enveloped Certification Declaration to
+    ///
+    /// Return the length of the enveloped Certification Declaration.
+    pub fn sign(
+        &self,
+        signer_key_id: &[u8],
+        signer: &KeyPair,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut content = alloc!([0; MAX_CD_CONTENT_LEN]); // TODO LARGE BUFFER
+        let content = &mut content[..];
+
+        let content_len = {
+            let mut wb = WriteBuf::new(content);
+            self.to_tlv(&TLVTag::Anonymous, &mut wb)?;
+
+            wb.get_tail()
+        };
+        let content = &content[..content_len];
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        signer.sign_msg(content, &mut signature)?;
+
+        let mut der_signature = [0; MAX_DER_SIGNATURE_LEN];
+        let der_signature_len = der::ecdsa_sig_to_der(&signature, &mut der_signature)?;
+
+        let mut w = ASN1Writer::new(buf);
+
+        w.start_seq("")?;
+        w.oid("", &OID_PKCS7_SIGNED_DATA)?;
+        w.start_ctx("", 0)?;
+        w.start_seq("")?;
+        w.integer("", &[CMS_VERSION])?;
+        w.start_set("")?;
+        Self::write_digest_algo(&mut w)?;
+        w.end_set()?;
+
+        w.start_seq("")?;
+        w.oid("", &OID_PKCS7_DATA)?;
+        w.start_ctx("", 0)?;
+        w.ostr("", content)?;
+        w.end_ctx()?;
+        w.end_seq()?;
+
+        w.start_set("")?;
+        w.start_seq("")?;
+        w.integer("", &[CMS_VERSION])?;
+        w.ctx("", 0, signer_key_id)?;
+        Self::write_digest_algo(&mut w)?;
+        w.start_seq("")?;
+        w.oid("", &OID_ECDSA_WITH_SHA256)?;
+        w.end_seq()?;
+        w.ostr("", &der_signature[..der_signature_len])?;
+        w.end_seq()?;
+        w.end_set()?;
+
+        w.end_seq()?;
+        w.end_ctx()?;
+        w.end_seq()?;
+
+        Ok(w.as_slice().len())
+    }
+
+    fn write_digest_algo(w: &mut dyn CertConsumer) -> Result<(), Error> {
+        w.start_seq("")?;
+        w.oid("", &OID_SHA256)?;
+        w.end_seq()
+    }
+}
+
+/// A reference to a CMS-enveloped Certification Declaration (DER).
+///
+/// Only envelopes with a single signer, identified by its subject key ID,
+/// and with no signed attributes are supported, as mandated by the Matter spec.
+#[derive(Debug, Clone)]
+pub struct CertDeclarationRef<'a> {
+    content: &'a [u8],
+    signer_key_id: &'a [u8],
+    signature: [u8; EC_SIGNATURE_LEN_BYTES],
+}
+
+impl<'a> CertDeclarationRef<'a> {
+    /// Parse the provided CMS-enveloped Certification Declaration (DER)
+    pub fn new(cms: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = DerReader::new(cms);
+        let mut content_info = reader.read_seq()?;
+
+        if !reader.is_empty() || content_info.read(TAG_OID)?.content != OID_PKCS7_SIGNED_DATA {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let mut signed_data =
+            DerReader::new(content_info.read(TAG_CTX_CONTENT)?.content).read_seq()?;
+
+        Self::check_version(&mut signed_data)?;
+        Self::check_digest_algo(&mut DerReader::new(signed_data.read(TAG_SET)?.content))?;
+
+        let mut encap_content_info = signed_data.read_seq()?;
+        if encap_content_info.read(TAG_OID)?.content != OID_PKCS7_DATA {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let content = DerReader::new(encap_content_info.read(TAG_CTX_CONTENT)?.content)
+            .read(TAG_OCTET_STRING)?
+            .content;
+
+        // Neither certificates, nor CRLs are expected in the envelope,
+        // so the next element are the signer infos
+        let mut signer_infos = DerReader::new(signed_data.read(TAG_SET)?.content);
+        let mut signer_info = signer_infos.read_seq()?;
+
+        if !signer_infos.is_empty() {
+            error!("Certification Declaration has more than one signer");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Self::check_version(&mut signer_info)?;
+
+        let signer_key_id = signer_info.read(TAG_CTX_SUBJECT_KEY_ID)?.content;
+
+        Self::check_digest_algo(&mut signer_info)?;
+
+        // No signed attributes are allowed, so the next element is the signature algorithm
+        if signer_info.read_seq()?.read(TAG_OID)?.content != OID_ECDSA_WITH_SHA256 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        der::ecdsa_sig_from_der(signer_info.read(TAG_OCTET_STRING)?.content, &mut signature)?;
+
+        Ok(Self {
+            content,
+            signer_key_id,
+            signature,
+        })
+    }
+
+    /// Return the enveloped Certification Declaration (TLV)
+    pub fn content(&self) -> &'a [u8] {
+        self.content
+    }
+
+    /// Return the subject key identifier of the signer of the Certification Declaration
+    pub fn signer_key_id(&self) -> &'a [u8] {
+        self.signer_key_id
+    }
+
+    /// Decode the enveloped Certification Declaration
+    pub fn decode(&self) -> Result<CertDeclaration<'a>, Error> {
+        CertDeclaration::from_tlv(&TLVElement::new(self.content))
+    }
+
+    /// Verify that the Certification Declaration is signed by the provided public key
+    pub fn verify_signed_by(&self, pubkey: &[u8]) -> Result<(), Error> {
+        KeyPair::new_from_public(pubkey)?.verify_msg(self.content, &self.signature)
+    }
+
+    fn check_version(reader: &mut DerReader<'_>) -> Result<(), Error> {
+        if reader.read(TAG_INTEGER)?.content != [CMS_VERSION] {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_digest_algo(reader: &mut DerReader<'_>) -> Result<(), Error> {
+        if reader.read_seq()?.read(TAG_OID)?.content != OID_SHA256 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dm::clusters::dev_att::{DataType, DevAttDataFetcher};
+    use crate::dm::devices::test::TEST_DEV_ATT;
+
+    use crate::crypto::{KeyPair, EC_POINT_LEN_BYTES};
+    use crate::tlv::OctetStr;
+    use crate::utils::rand::dummy_rand;
+
+    use super::{CertDeclarationContent, CertDeclarationRef, CD_FORMAT_VERSION};
+
+    #[test]
+    fn test_parse_cert_declaration() {
+        let mut buf = [0; 600];
+        let len = unwrap!(TEST_DEV_ATT.get_devatt_data(DataType::CertDeclaration, &mut buf));
+
+        let cms = unwrap!(CertDeclarationRef::new(&buf[..len]));
+
+        assert_eq!(
+            cms.signer_key_id(),
+            &[
+                0x62, 0xFA, 0x82, 0x33, 0x59, 0xAC, 0xFA, 0xA9, 0x96, 0x3E, 0x1C, 0xFA, 0x14, 0x0A,
+                0xDD, 0xF5, 0x04, 0xF3, 0x71, 0x60
+            ]
+        );
+
+        let cd = unwrap!(cms.decode());
+
+        assert_eq!(cd.format_version, CD_FORMAT_VERSION);
+        assert_eq!(cd.vendor_id, 0xFFF1);
+        assert_eq!(cd.device_type_id, 0x16);
+        assert_eq!(cd.certificate_id, "ZIG20142ZB330003-24");
+        assert!(unwrap!(cd.has_product_id(0x8002)));
+        assert!(!unwrap!(cd.has_product_id(0x8064)));
+        assert!(cd.dac_origin_vendor_id.is_none());
+        assert!(unwrap!(cd.is_paa_authorized(&[0; 20])));
+
+        let test_cd_pubkey = [
+            0x04, 0x3c, 0x39, 0x89, 0x22, 0x45, 0x2b, 0x55, 0xca, 0xf3, 0x89, 0xc2, 0x5b, 0xd1,
+            0xbc, 0xa4, 0x65, 0x69, 0x52, 0xcc, 0xb9, 0x0e, 0x88, 0x69, 0x24, 0x9a, 0xd8, 0x47,
+            0x46, 0x53, 0x01, 0x4c, 0xbf, 0x95, 0xd6, 0x87, 0x96, 0x5e, 0x03, 0x6b, 0x52, 0x1c,
+            0x51, 0x03, 0x7e, 0x6b, 0x8c, 0xed, 0xef, 0xca, 0x1e, 0xb4, 0x40, 0x46, 0x69, 0x4f,
+            0xa0, 0x88, 0x82, 0xee, 0xd6, 0x51, 0x9d, 0xec, 0xba,
+        ];
+        unwrap!(cms.verify_signed_by(&test_cd_pubkey));
+    }
+
+    #[test]
+    fn test_sign_cert_declaration() {
+        let key = unwrap!(KeyPair::new(dummy_rand));
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        unwrap!(key.get_public_key(&mut pubkey));
+
+        let paa_key_id = [0x11; 20];
+
+        let content = CertDeclarationContent {
+            format_version: CD_FORMAT_VERSION,
+            vendor_id: 0xFFF2,
+            product_id_array: &[0x8001, 0x8002],
+            device_type_id: 0x100,
+            certificate_id: "CSA00000SWC00000-01",
+            security_level: 0,
+            security_information: 0,
+            version_number: 1,
+            certification_type: 0,
+            dac_origin_vendor_id: None,
+            dac_origin_product_id: None,
+            authorized_paa_list: Some(&[OctetStr::new(&paa_key_id)]),
+        };
+
+        let mut buf = [0; 600];
+        let len = unwrap!(content.sign(&[0x22; 20], &key, &mut buf));
+
+        let cms = unwrap!(CertDeclarationRef::new(&buf[..len]));
+        assert_eq!(cms.signer_key_id(), &[0x22; 20]);
+        unwrap!(cms.verify_signed_by(&pubkey));
+
+        let cd = unwrap!(cms.decode());
+        assert_eq!(cd.vendor_id, 0xFFF2);
+        assert!(unwrap!(cd.has_product_id(0x8001)));
+        assert!(unwrap!(cd.has_product_id(0x8002)));
+        assert!(!unwrap!(cd.has_product_id(0x8003)));
+        assert_eq!(cd.certificate_id, "CSA00000SWC00000-01");
+        assert!(unwrap!(cd.is_paa_authorized(&paa_key_id)));
+        assert!(!unwrap!(cd.is_paa_authorized(&[0x33; 20])));
+    }
+}
@@ -0,0 +1,231 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! A minimal DER reader, sufficient for walking X.509 certificates, PKCS#10 CSRs and
+//! CMS-signed Certification Declarations, as well as helpers for converting ECDSA
+//! signatures between their raw and DER forms.
+
+use crate::crypto::EC_SIGNATURE_LEN_BYTES;
+use crate::error::{Error, ErrorCode};
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+
+/// The maximum length of a DER-encoded ECDSA P-256 signature
+pub(crate) const MAX_DER_SIGNATURE_LEN: usize = EC_SIGNATURE_LEN_BYTES + 8;
+
+/// A DER-encoded element: its tag, its complete encoding and its content.
+#[derive(Debug, Clone)]
+pub(crate) struct DerElement<'a> {
+    pub tag: u8,
+    pub raw: &'a [u8],
+    pub content: &'a [u8],
+}
+
+/// A reader over a sequence of DER-encoded elements.
+#[derive(Debug, Clone)]
+pub(crate) struct DerReader<'a>(&'a [u8]);
+
+impl<'a> DerReader<'a> {
+    pub const fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Read the next element, whatever its tag is
+    pub fn next(&mut self) -> Result<DerElement<'a>, Error> {
+        let data = self.0;
+
+        if data.len() < 2 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let tag = data[0];
+
+        let (len, offset) = if data[1] & 0x80 == 0 {
+            (data[1] as usize, 2)
+        } else {
+            let len_bytes = (data[1] & 0x7f) as usize;
+            if len_bytes == 0 || len_bytes > 2 || data.len() < 2 + len_bytes {
+                Err(ErrorCode::InvalidData)?;
+            }
+
+            let len = data[2..2 + len_bytes]
+                .iter()
+                .fold(0, |len, byte| (len << 8) | *byte as usize);
+
+            (len, 2 + len_bytes)
+        };
+
+        if data.len() < offset + len {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        self.0 = &data[offset + len..];
+
+        Ok(DerElement {
+            tag,
+            raw: &data[..offset + len],
+            content: &data[offset..offset + len],
+        })
+    }
+
+    /// Read the next element, and check that it has the provided tag
+    pub fn read(&mut self, tag: u8) -> Result<DerElement<'a>, Error> {
+        let element = self.next()?;
+
+        if element.tag != tag {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(element)
+    }
+
+    /// Read the next element as a sequence, and return a reader over its content
+    pub fn read_seq(&mut self) -> Result<DerReader<'a>, Error> {
+        Ok(DerReader::new(self.read(TAG_SEQUENCE)?.content))
+    }
+
+    /// Read the next element as a bit string with no unused bits, and return its bytes
+    pub fn read_bitstr(&mut self) -> Result<&'a [u8], Error> {
+        let content = self.read(TAG_BIT_STRING)?.content;
+
+        match content.split_first() {
+            Some((0, bytes)) => Ok(bytes),
+            _ => Err(ErrorCode::InvalidData.into()),
+        }
+    }
+}
+
+/// Convert a DER-encoded ECDSA signature (`SEQUENCE { r INTEGER, s INTEGER }`)
+/// into its raw form (`r || s`, 32 bytes each).
+pub(crate) fn ecdsa_sig_from_der(
+    der: &[u8],
+    raw: &mut [u8; EC_SIGNATURE_LEN_BYTES],
+) -> Result<(), Error> {
+    let mut reader = DerReader::new(der);
+    let mut seq = reader.read_seq()?;
+
+    if !reader.is_empty() {
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    let half = EC_SIGNATURE_LEN_BYTES / 2;
+
+    for out in raw.chunks_mut(half) {
+        let int = seq.read(TAG_INTEGER)?.content;
+
+        // Strip the sign-padding zeroes, if any
+        let start = int.iter().position(|b| *b != 0).unwrap_or(int.len());
+        let int = &int[start..];
+
+        if int.len() > half {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        out.fill(0);
+        out[half - int.len()..].copy_from_slice(int);
+    }
+
+    if !seq.is_empty() {
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    Ok(())
+}
+
+/// Convert a raw ECDSA signature (`r || s`, 32 bytes each) into its DER encoding
+/// (`SEQUENCE { r INTEGER, s INTEGER }`).
+///
+/// Return the length of the DER encoding.
+pub(crate) fn ecdsa_sig_to_der(raw: &[u8], der: &mut [u8]) -> Result<usize, Error> {
+    if raw.len() != EC_SIGNATURE_LEN_BYTES {
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    let mut ints = [[0; EC_SIGNATURE_LEN_BYTES / 2 + 1]; 2];
+    let mut lens = [0; 2];
+
+    for ((int, out), len) in raw
+        .chunks(EC_SIGNATURE_LEN_BYTES / 2)
+        .zip(ints.iter_mut())
+        .zip(lens.iter_mut())
+    {
+        // DER integers are minimal and signed: strip the leading zeroes
+        // and prepend a zero if the highest bit is set
+        let start = int.iter().position(|b| *b != 0).unwrap_or(int.len() - 1);
+        let int = &int[start..];
+        let pad = (int[0] & 0x80 != 0) as usize;
+
+        out[pad..pad + int.len()].copy_from_slice(int);
+        *len = pad + int.len();
+    }
+
+    // The content is at most 70 bytes, so all lengths fit in a single byte
+    let content_len = lens.iter().map(|len| 2 + len).sum::<usize>();
+    if der.len() < 2 + content_len {
+        Err(ErrorCode::NoSpace)?;
+    }
+
+    der[0] = TAG_SEQUENCE;
+    der[1] = content_len as u8;
+
+    let mut offset = 2;
+    for (int, len) in ints.iter().zip(lens) {
+        der[offset] = TAG_INTEGER;
+        der[offset + 1] = len as u8;
+        der[offset + 2..offset + 2 + len].copy_from_slice(&int[..len]);
+        offset += 2 + len;
+    }
+
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::EC_SIGNATURE_LEN_BYTES;
+
+    use super::{ecdsa_sig_from_der, ecdsa_sig_to_der, MAX_DER_SIGNATURE_LEN};
+
+    #[test]
+    fn test_ecdsa_sig_der_roundtrip() {
+        let mut raw = [0; EC_SIGNATURE_LEN_BYTES];
+        // A high `r` requiring sign-padding, and a short `s` requiring stripping
+        raw[0] = 0x80;
+        raw[31] = 0x01;
+        raw[62] = 0x12;
+        raw[63] = 0x34;
+
+        let mut der = [0; MAX_DER_SIGNATURE_LEN];
+        let len = unwrap!(ecdsa_sig_to_der(&raw, &mut der));
+
+        assert_eq!(&der[..6], &[0x30, 39, 0x02, 33, 0x00, 0x80]);
+        assert_eq!(&der[len - 4..len], &[0x02, 2, 0x12, 0x34]);
+
+        let mut decoded = [0; EC_SIGNATURE_LEN_BYTES];
+        unwrap!(ecdsa_sig_from_der(&der[..len], &mut decoded));
+
+        assert_eq!(raw, decoded);
+    }
+}
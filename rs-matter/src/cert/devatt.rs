@@ -0,0 +1,474 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! A generator of device attestation credentials: the X.509 certificates of the
+//! device attestation hierarchy (PAA, PAI and DAC) and the CMS-signed Certification
+//! Declarations (CDs), as per section 6.2 "Device Attestation" of the Matter spec.
+//!
+//! The generated credentials are only meant for testing and for the lab provisioning
+//! of uncertified devices, as they are not rooted in any of the PAAs and CD signing keys
+//! trusted by the Matter ecosystems.
+
+use core::fmt::Write;
+
+use heapless::Vec;
+
+use crate::alloc;
+use crate::crypto::{KeyPair, EC_POINT_LEN_BYTES, EC_SIGNATURE_LEN_BYTES};
+use crate::error::{Error, ErrorCode};
+use crate::utils::epoch::{Epoch, MATTER_CERT_DOESNT_EXPIRE, MATTER_EPOCH_SECS};
+use crate::utils::rand::Rand;
+
+use super::ca::{OperationalCa, KEY_ID_LEN, SERIAL_NUM_LEN};
+use super::cd::CertDeclarationContent;
+use super::der::{self, MAX_DER_SIGNATURE_LEN};
+use super::x509::{
+    X509CertRef, OID_AUTHORITY_KEY_ID, OID_BASIC_CONSTRAINTS, OID_COMMON_NAME, OID_MATTER_PID,
+    OID_MATTER_VID, OID_SUBJECT_KEY_ID,
+};
+use super::{
+    ASN1Writer, CertConsumer, OID_ECDSA_WITH_SHA256, OID_EC_TYPE_PRIME256V1, OID_PUB_KEY_ECPUBKEY,
+};
+
+/// The max length of a device attestation certificate (X.509 DER), as per the Matter spec
+pub const MAX_X509_LEN: usize = 600;
+
+/// The max length of the DER encoding of a certificate while it is being written,
+/// as the ASN.1 writer reserves space for the length of each of its open structures
+const MAX_X509_WRITE_LEN: usize = 800;
+
+// As per https://datatracker.ietf.org/doc/html/rfc5280
+const OID_KEY_USAGE: [u8; 3] = [0x55, 0x1D, 0x0F];
+
+// Key usage bits, as per RFC 5280, in their DER bit string encoding
+const KEY_USAGE_DIGITAL_SIGNATURE: u8 = 0x80;
+const KEY_USAGE_KEY_CERT_SIGN_CRL_SIGN: u8 = 0x06;
+
+/// The profiles of the generated certificates, as per section 6.2.2 of the Matter spec
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Profile {
+    /// A self-signed PAA
+    Paa,
+    /// A PAI, issued by a PAA
+    Pai,
+    /// A DAC, issued by a PAI, or - for CD signing certificates - self-signed
+    Leaf,
+}
+
+/// A key pair, together with its device attestation certificate (X.509 DER)
+pub struct AttestationCert {
+    key: KeyPair,
+    cert: Vec<u8, MAX_X509_LEN>,
+}
+
+impl AttestationCert {
+    /// Create a new instance from the provided key pair and its certificate (X.509 DER)
+    pub fn new(key: KeyPair, cert: &[u8]) -> Result<Self, Error> {
+        let this = Self {
+            key,
+            cert: Vec::from_slice(cert).map_err(|_| ErrorCode::NoSpace)?,
+        };
+
+        // The key pair must be the one certified by the certificate
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        let len = this.key.get_public_key(&mut pubkey)?;
+
+        if this.cert_ref()?.pubkey() != &pubkey[..len] {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(this)
+    }
+
+    /// Return the key pair
+    pub fn key(&self) -> &KeyPair {
+        &self.key
+    }
+
+    /// Return the certificate (X.509 DER)
+    pub fn cert(&self) -> &[u8] {
+        &self.cert
+    }
+
+    /// Return a parsed view of the certificate
+    pub fn cert_ref(&self) -> Result<X509CertRef<'_>, Error> {
+        X509CertRef::new(&self.cert)
+    }
+
+    /// Return the subject key ID of the certificate
+    pub fn key_id(&self) -> Result<&[u8], Error> {
+        Ok(self
+            .cert_ref()?
+            .subject_key_id()?
+            .ok_or(ErrorCode::InvalidData)?)
+    }
+
+    /// Sign the provided Certification Declaration with the key pair, and envelope it
+    /// in a CMS `SignedData` message (DER), identified by the subject key ID of the certificate.
+    ///
+    /// Return the length of the enveloped Certification Declaration.
+    pub fn sign_cd(
+        &self,
+        content: &CertDeclarationContent<'_>,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        content.sign(self.key_id()?, &self.key, buf)
+    }
+}
+
+/// A generator of device attestation credentials.
+///
+/// All certificates are generated as valid from the moment of their generation,
+/// with no well-defined expiration date.
+pub struct DevAttGenerator {
+    epoch: Epoch,
+    rand: Rand,
+}
+
+impl DevAttGenerator {
+    /// Create a new generator
+    ///
+    /// # Arguments
+    /// - `epoch`: The source of the current time
+    /// - `rand`: The source of randomness, used for the key pairs and the serial numbers
+    pub const fn new(epoch: Epoch, rand: Rand) -> Self {
+        Self { epoch, rand }
+    }
+
+    /// Generate a new key pair and a self-signed PAA certificate for it
+    ///
+    /// # Arguments
+    /// - `common_name`: The common name of the subject of the PAA
+    /// - `vid`: The Vendor ID of the PAA, if it is a vendor-scoped one
+    pub fn generate_paa(
+        &self,
+        common_name: &str,
+        vid: Option<u16>,
+    ) -> Result<AttestationCert, Error> {
+        self.generate(None, Profile::Paa, common_name, vid, None)
+    }
+
+    /// Generate a new key pair and a PAI certificate for it, issued by the provided PAA
+    ///
+    /// # Arguments
+    /// - `paa`: The issuing PAA
+    /// - `common_name`: The common name of the subject of the PAI
+    /// - `vid`: The Vendor ID of the PAI
+    /// - `pid`: The Product ID of the PAI, if it is scoped to a single product
+    pub fn generate_pai(
+        &self,
+        paa: &AttestationCert,
+        common_name: &str,
+        vid: u16,
+        pid: Option<u16>,
+    ) -> Result<AttestationCert, Error> {
+        self.generate(Some(paa), Profile::Pai, common_name, Some(vid), pid)
+    }
+
+    /// Generate a new key pair and a DAC certificate for it, issued by the provided PAI
+    ///
+    /// # Arguments
+    /// - `pai`: The issuing PAI
+    /// - `common_name`: The common name of the subject of the DAC
+    /// - `vid`: The Vendor ID of the device
+    /// - `pid`: The Product ID of the device
+    pub fn generate_dac(
+        &self,
+        pai: &AttestationCert,
+        common_name: &str,
+        vid: u16,
+        pid: u16,
+    ) -> Result<AttestationCert, Error> {
+        self.generate(Some(pai), Profile::Leaf, common_name, Some(vid), Some(pid))
+    }
+
+    /// Generate a new key pair and a self-signed certificate for it, suitable for
+    /// signing Certification Declarations
+    ///
+    /// # Arguments
+    /// - `common_name`: The common name of the subject of the certificate
+    pub fn generate_cd_signer(&self, common_name: &str) -> Result<AttestationCert, Error> {
+        self.generate(None, Profile::Leaf, common_name, None, None)
+    }
+
+    fn generate(
+        &self,
+        issuer: Option<&AttestationCert>,
+        profile: Profile,
+        common_name: &str,
+        vid: Option<u16>,
+        pid: Option<u16>,
+    ) -> Result<AttestationCert, Error> {
+        let key = KeyPair::new(self.rand)?;
+
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        key.get_public_key(&mut pubkey)?;
+
+        let mut buf = alloc!([0; MAX_X509_WRITE_LEN]); // TODO LARGE BUFFER
+        let buf = &mut buf[..];
+
+        let len = self.issue(
+            issuer,
+            issuer.map(AttestationCert::key).unwrap_or(&key),
+            profile,
+            common_name,
+            vid,
+            pid,
+            &pubkey,
+            buf,
+        )?;
+
+        AttestationCert::new(key, &buf[..len])
+    }
+
+    /// Issue a certificate (X.509 DER) for the provided public key
+    ///
+    /// If `issuer` is `None`, the certificate is self-signed and `signer` must be
+    /// the key pair of the provided public key.
+    #[allow(clippy::too_many_arguments)]
+    fn issue(
+        &self,
+        issuer: Option<&AttestationCert>,
+        signer: &KeyPair,
+        profile: Profile,
+        common_name: &str,
+        vid: Option<u16>,
+        pid: Option<u16>,
+        pubkey: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut subject_key_id = [0; KEY_ID_LEN];
+        OperationalCa::key_id(pubkey, &mut subject_key_id)?;
+
+        let mut serial_num = [0; SERIAL_NUM_LEN];
+        (self.rand)(&mut serial_num);
+        // The serial number is encoded as a positive ASN.1 integer, without leading zeroes
+        serial_num[0] = (serial_num[0] & 0x7f).max(1);
+
+        let not_before = (self.epoch)().as_secs().saturating_sub(MATTER_EPOCH_SECS);
+
+        let mut tbs = alloc!([0; MAX_X509_WRITE_LEN]); // TODO LARGE BUFFER
+
+        let tbs_len = {
+            let mut w = ASN1Writer::new(&mut tbs[..]);
+
+            w.start_seq("")?;
+
+            w.start_ctx("", 0)?;
+            w.integer("", &[2])?;
+            w.end_ctx()?;
+
+            w.integer("", &serial_num)?;
+
+            w.start_seq("")?;
+            w.oid("", &OID_ECDSA_WITH_SHA256)?;
+            w.end_seq()?;
+
+            w.start_seq("")?;
+            if let Some(issuer) = issuer {
+                // The issuer of the certificate is the subject of the issuer certificate
+                w.append_der(issuer.cert_ref()?.subject())?;
+            } else {
+                Self::write_dn(&mut w, common_name, vid, pid)?;
+            }
+            w.end_seq()?;
+
+            w.start_seq("")?;
+            w.utctime("", not_before)?;
+            w.utctime("", MATTER_CERT_DOESNT_EXPIRE)?;
+            w.end_seq()?;
+
+            w.start_seq("")?;
+            Self::write_dn(&mut w, common_name, vid, pid)?;
+            w.end_seq()?;
+
+            w.start_seq("")?;
+            w.start_seq("")?;
+            w.oid("", &OID_PUB_KEY_ECPUBKEY)?;
+            w.oid("", &OID_EC_TYPE_PRIME256V1)?;
+            w.end_seq()?;
+            w.bitstr("", false, pubkey)?;
+            w.end_seq()?;
+
+            w.start_ctx("", 3)?;
+            w.start_seq("")?;
+            Self::write_extensions(&mut w, issuer, profile, &subject_key_id)?;
+            w.end_seq()?;
+            w.end_ctx()?;
+
+            w.end_seq()?;
+
+            w.as_slice().len()
+        };
+        let tbs = &tbs[..tbs_len];
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        signer.sign_msg(tbs, &mut signature)?;
+
+        let mut der_signature = [0; MAX_DER_SIGNATURE_LEN];
+        let der_signature_len = der::ecdsa_sig_to_der(&signature, &mut der_signature)?;
+
+        let mut w = ASN1Writer::new(buf);
+
+        w.start_seq("")?;
+        w.append_der(tbs)?;
+        w.start_seq("")?;
+        w.oid("", &OID_ECDSA_WITH_SHA256)?;
+        w.end_seq()?;
+        w.bitstr("", false, &der_signature[..der_signature_len])?;
+        w.end_seq()?;
+
+        Ok(w.as_slice().len())
+    }
+
+    /// Write the attributes of a DN: the common name, and the Matter Vendor ID
+    /// and Product ID, if any
+    fn write_dn(
+        w: &mut dyn CertConsumer,
+        common_name: &str,
+        vid: Option<u16>,
+        pid: Option<u16>,
+    ) -> Result<(), Error> {
+        Self::write_dn_attr(w, &OID_COMMON_NAME, common_name)?;
+
+        for (oid, id) in [(&OID_MATTER_VID, vid), (&OID_MATTER_PID, pid)] {
+            if let Some(id) = id {
+                // As per the Matter spec, the IDs are encoded as 4 uppercase hex digits
+                let mut value = heapless::String::<4>::new();
+                write_unwrap!(&mut value, "{:04X}", id);
+
+                Self::write_dn_attr(w, oid, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_dn_attr(w: &mut dyn CertConsumer, oid: &[u8], value: &str) -> Result<(), Error> {
+        w.start_set("")?;
+        w.start_seq("")?;
+        w.oid("", oid)?;
+        w.utf8str("", value)?;
+        w.end_seq()?;
+        w.end_set()
+    }
+
+    fn write_extensions(
+        w: &mut dyn CertConsumer,
+        issuer: Option<&AttestationCert>,
+        profile: Profile,
+        subject_key_id: &[u8],
+    ) -> Result<(), Error> {
+        // Basic constraints (critical), with a path length of 1 for PAAs and 0 for PAIs
+        Self::start_extension(w, &OID_BASIC_CONSTRAINTS, true)?;
+        w.start_seq("")?;
+        match profile {
+            Profile::Paa => {
+                w.bool("", true)?;
+                w.integer("", &[1])?;
+            }
+            Profile::Pai => {
+                w.bool("", true)?;
+                w.integer("", &[0])?;
+            }
+            Profile::Leaf => (),
+        }
+        w.end_seq()?;
+        Self::end_extension(w)?;
+
+        // Key usage (critical)
+        let key_usage = if profile == Profile::Leaf {
+            KEY_USAGE_DIGITAL_SIGNATURE
+        } else {
+            KEY_USAGE_KEY_CERT_SIGN_CRL_SIGN
+        };
+
+        Self::start_extension(w, &OID_KEY_USAGE, true)?;
+        w.bitstr("", true, &[key_usage])?;
+        Self::end_extension(w)?;
+
+        Self::start_extension(w, &OID_SUBJECT_KEY_ID, false)?;
+        w.ostr("", subject_key_id)?;
+        Self::end_extension(w)?;
+
+        let authority_key_id = if let Some(issuer) = issuer {
+            issuer.key_id()?
+        } else {
+            subject_key_id
+        };
+
+        Self::start_extension(w, &OID_AUTHORITY_KEY_ID, false)?;
+        w.start_seq("")?;
+        w.ctx("", 0, authority_key_id)?;
+        w.end_seq()?;
+        Self::end_extension(w)
+    }
+
+    fn start_extension(w: &mut dyn CertConsumer, oid: &[u8], critical: bool) -> Result<(), Error> {
+        w.start_seq("")?;
+        w.oid("", oid)?;
+        if critical {
+            w.bool("", true)?;
+        }
+        w.start_compound_ostr("")
+    }
+
+    fn end_extension(w: &mut dyn CertConsumer) -> Result<(), Error> {
+        w.end_compound_ostr()?;
+        w.end_seq()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::epoch::sys_epoch;
+    use crate::utils::rand::sys_rand;
+
+    use super::DevAttGenerator;
+
+    #[test]
+    fn test_generate_hierarchy() {
+        let generator = DevAttGenerator::new(sys_epoch, sys_rand);
+
+        let paa = unwrap!(generator.generate_paa("Test PAA", None));
+        let pai = unwrap!(generator.generate_pai(&paa, "Test PAI", 0xFFF2, None));
+        let dac = unwrap!(generator.generate_dac(&pai, "Test DAC", 0xFFF2, 0x8001));
+
+        let paa_cert = unwrap!(paa.cert_ref());
+        let pai_cert = unwrap!(pai.cert_ref());
+        let dac_cert = unwrap!(dac.cert_ref());
+
+        assert!(unwrap!(paa_cert.is_ca()));
+        assert!(unwrap!(pai_cert.is_ca()));
+        assert!(!unwrap!(dac_cert.is_ca()));
+
+        unwrap!(paa_cert.verify_issued_by(&paa_cert));
+        unwrap!(pai_cert.verify_issued_by(&paa_cert));
+        unwrap!(dac_cert.verify_issued_by(&pai_cert));
+        assert!(dac_cert.verify_issued_by(&paa_cert).is_err());
+
+        assert_eq!(unwrap!(paa_cert.vid()), None);
+        assert_eq!(unwrap!(pai_cert.vid()), Some(0xFFF2));
+        assert_eq!(unwrap!(pai_cert.pid()), None);
+        assert_eq!(unwrap!(dac_cert.vid()), Some(0xFFF2));
+        assert_eq!(unwrap!(dac_cert.pid()), Some(0x8001));
+
+        let now = sys_epoch().as_secs();
+        assert!(unwrap!(dac_cert.is_valid_at(now)));
+        assert!(!unwrap!(dac_cert.is_valid_at(now - 3600)));
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! PEM (RFC 7468) encoding and decoding of DER data.
+
+use core::fmt::{self, Write};
+
+use crate::error::{Error, ErrorCode};
+
+/// The PEM label of X.509 certificates
+pub const PEM_LABEL_CERTIFICATE: &str = "CERTIFICATE";
+/// The PEM label of SEC1 EC private keys
+pub const PEM_LABEL_EC_PRIVATE_KEY: &str = "EC PRIVATE KEY";
+/// The PEM label of PKCS#8 private keys
+pub const PEM_LABEL_PRIVATE_KEY: &str = "PRIVATE KEY";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// As per RFC 7468, the base64 text is wrapped at 64 characters, i.e. 48 bytes of data
+const BYTES_PER_LINE: usize = 48;
+
+/// Write the provided DER data in PEM format, using the provided label
+/// (e.g. `CERTIFICATE`).
+pub fn encode<W: Write>(label: &str, der: &[u8], out: &mut W) -> fmt::Result {
+    writeln!(out, "-----BEGIN {label}-----")?;
+
+    for line in der.chunks(BYTES_PER_LINE) {
+        for chunk in line.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+
+            let indices = [
+                b[0] >> 2,
+                ((b[0] & 0x03) << 4) | (b[1] >> 4),
+                ((b[1] & 0x0f) << 2) | (b[2] >> 6),
+                b[2] & 0x3f,
+            ];
+
+            for (index, value) in indices.iter().enumerate() {
+                if index <= chunk.len() {
+                    out.write_char(BASE64_ALPHABET[*value as usize] as char)?;
+                } else {
+                    out.write_char('=')?;
+                }
+            }
+        }
+
+        writeln!(out)?;
+    }
+
+    writeln!(out, "-----END {label}-----")
+}
+
+/// Decode the first block with the provided label (e.g. `CERTIFICATE`) in the
+/// provided PEM text into DER data.
+///
+/// Return the length of the DER data.
+pub fn decode(label: &str, pem: &str, der: &mut [u8]) -> Result<usize, Error> {
+    let mut lines = pem.lines().map(str::trim);
+
+    lines
+        .find(|line| Some(label) == boundary(line, "-----BEGIN "))
+        .ok_or(ErrorCode::NotFound)?;
+
+    let mut bits = 0_u32;
+    let mut bits_len = 0;
+    let mut len = 0;
+
+    for line in lines {
+        if let Some(end_label) = boundary(line, "-----END ") {
+            if end_label != label {
+                Err(ErrorCode::InvalidData)?;
+            }
+
+            return Ok(len);
+        }
+
+        for ch in line.bytes().take_while(|ch| *ch != b'=') {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|b| *b == ch)
+                .ok_or(ErrorCode::InvalidData)?;
+
+            bits = (bits << 6) | value as u32;
+            bits_len += 6;
+
+            if bits_len >= 8 {
+                bits_len -= 8;
+
+                *der.get_mut(len).ok_or(ErrorCode::NoSpace)? = (bits >> bits_len) as u8;
+                len += 1;
+            }
+        }
+    }
+
+    Err(ErrorCode::InvalidData.into())
+}
+
+/// Return the label of the provided line, if it is a boundary of the provided kind
+fn boundary<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.strip_prefix(kind)?.strip_suffix("-----")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    fn test_encode() {
+        let mut out = heapless::String::<128>::new();
+
+        unwrap!(encode("TEST", b"foobar", &mut out));
+        assert_eq!(out, "-----BEGIN TEST-----\nZm9vYmFy\n-----END TEST-----\n");
+
+        out.clear();
+        unwrap!(encode("TEST", b"fooba", &mut out));
+        assert_eq!(out, "-----BEGIN TEST-----\nZm9vYmE=\n-----END TEST-----\n");
+
+        out.clear();
+        unwrap!(encode("TEST", b"foob", &mut out));
+        assert_eq!(out, "-----BEGIN TEST-----\nZm9vYg==\n-----END TEST-----\n");
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut der = [0; 6];
+
+        let pem = "-----BEGIN TEST-----\nZm9vYmFy\n-----END TEST-----\n";
+        assert_eq!(unwrap!(decode("TEST", pem, &mut der)), 6);
+        assert_eq!(&der, b"foobar");
+
+        let pem = "garbage\n-----BEGIN TEST-----\r\nZm9v\r\nYg==\r\n-----END TEST-----\r\n";
+        assert_eq!(unwrap!(decode("TEST", pem, &mut der)), 4);
+        assert_eq!(&der[..4], b"foob");
+
+        let pem = "-----BEGIN OTHER-----\nZm9vYmFy\n-----END OTHER-----\n";
+        assert!(decode("TEST", pem, &mut der).is_err());
+
+        let pem = "-----BEGIN TEST-----\nZm9vYmFy\n";
+        assert!(decode("TEST", pem, &mut der).is_err());
+
+        let pem = "-----BEGIN TEST-----\nZm9vYmFyYmF6\n-----END TEST-----\n";
+        assert!(decode("TEST", pem, &mut der).is_err());
+    }
+}
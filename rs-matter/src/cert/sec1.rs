@@ -0,0 +1,174 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! DER encoding of P-256 key pairs, as SEC1 `ECPrivateKey` structures (RFC 5915).
+//!
+//! For interoperability with the usual tooling, key pairs wrapped in PKCS#8
+//! `PrivateKeyInfo` structures (RFC 5208) are accepted on decoding as well.
+
+use crate::crypto::{KeyPair, BIGNUM_LEN_BYTES, EC_POINT_LEN_BYTES};
+use crate::error::{Error, ErrorCode};
+
+use super::der::{DerReader, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID};
+use super::{ASN1Writer, CertConsumer, OID_EC_TYPE_PRIME256V1, OID_PUB_KEY_ECPUBKEY};
+
+/// The version of the SEC1 `ECPrivateKey` structure
+const SEC1_VERSION: u8 = 1;
+/// The version of the PKCS#8 `PrivateKeyInfo` structure
+const PKCS8_VERSION: u8 = 0;
+
+const TAG_CTX_PARAMETERS: u8 = 0xA0;
+const TAG_CTX_PUBLIC_KEY: u8 = 0xA1;
+
+/// The max length of the DER encoding of a P-256 key pair
+pub const MAX_KEY_PAIR_DER_LEN: usize = 128;
+
+/// Encode the provided key pair as a SEC1 `ECPrivateKey` structure (DER)
+///
+/// Return the length of the encoding.
+pub fn encode(key: &KeyPair, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut privkey = [0; BIGNUM_LEN_BYTES];
+    let privkey_len = key.get_private_key(&mut privkey)?;
+
+    let mut pubkey = [0; EC_POINT_LEN_BYTES];
+    let pubkey_len = key.get_public_key(&mut pubkey)?;
+
+    let mut w = ASN1Writer::new(buf);
+
+    w.start_seq("")?;
+    w.integer("", &[SEC1_VERSION])?;
+    w.ostr("", &privkey[..privkey_len])?;
+    w.start_ctx("", 0)?;
+    w.oid("", &OID_EC_TYPE_PRIME256V1)?;
+    w.end_ctx()?;
+    w.start_ctx("", 1)?;
+    w.bitstr("", false, &pubkey[..pubkey_len])?;
+    w.end_ctx()?;
+    w.end_seq()?;
+
+    Ok(w.as_slice().len())
+}
+
+/// Decode a key pair from the provided SEC1 `ECPrivateKey` or PKCS#8 `PrivateKeyInfo`
+/// structure (DER).
+///
+/// The SEC1 structure must contain the public key, as it is not derived from the private key.
+pub fn decode(der: &[u8]) -> Result<KeyPair, Error> {
+    let mut reader = DerReader::new(der);
+    let mut key = reader.read_seq()?;
+
+    if !reader.is_empty() {
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    match key.read(TAG_INTEGER)?.content {
+        [SEC1_VERSION] => decode_sec1(key),
+        [PKCS8_VERSION] => {
+            let mut algo = key.read_seq()?;
+            if algo.read(TAG_OID)?.content != OID_PUB_KEY_ECPUBKEY
+                || algo.read(TAG_OID)?.content != OID_EC_TYPE_PRIME256V1
+            {
+                Err(ErrorCode::InvalidData)?;
+            }
+
+            // The private key is a SEC1 structure, wrapped in an octet string
+            decode(key.read(TAG_OCTET_STRING)?.content)
+        }
+        _ => Err(ErrorCode::InvalidData.into()),
+    }
+}
+
+/// Decode the rest of a SEC1 `ECPrivateKey` structure, following its version
+fn decode_sec1(mut key: DerReader<'_>) -> Result<KeyPair, Error> {
+    let privkey = key.read(TAG_OCTET_STRING)?.content;
+    let mut pubkey = None;
+
+    while !key.is_empty() {
+        let element = key.next()?;
+
+        match element.tag {
+            TAG_CTX_PARAMETERS => {
+                if DerReader::new(element.content).read(TAG_OID)?.content != OID_EC_TYPE_PRIME256V1
+                {
+                    Err(ErrorCode::InvalidData)?;
+                }
+            }
+            TAG_CTX_PUBLIC_KEY => {
+                pubkey = Some(DerReader::new(element.content).read_bitstr()?);
+            }
+            _ => Err(ErrorCode::InvalidData)?,
+        }
+    }
+
+    let pubkey = pubkey.ok_or(ErrorCode::InvalidData)?;
+    if privkey.len() != BIGNUM_LEN_BYTES || pubkey.len() != EC_POINT_LEN_BYTES {
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    KeyPair::new_from_components(pubkey, privkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{KeyPair, EC_POINT_LEN_BYTES};
+    use crate::utils::rand::dummy_rand;
+
+    use super::{decode, encode, MAX_KEY_PAIR_DER_LEN};
+
+    #[test]
+    fn test_roundtrip() {
+        let key = unwrap!(KeyPair::new(dummy_rand));
+
+        let mut der = [0; MAX_KEY_PAIR_DER_LEN];
+        let len = unwrap!(encode(&key, &mut der));
+
+        let decoded = unwrap!(decode(&der[..len]));
+
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        let mut decoded_pubkey = [0; EC_POINT_LEN_BYTES];
+        unwrap!(key.get_public_key(&mut pubkey));
+        unwrap!(decoded.get_public_key(&mut decoded_pubkey));
+
+        assert_eq!(pubkey, decoded_pubkey);
+    }
+
+    #[test]
+    fn test_decode_pkcs8() {
+        // A PKCS#8 key, as generated by `openssl pkcs8 -topk8 -nocrypt`
+        let pkcs8 = [
+            0x30, 0x81, 0x87, 0x02, 0x01, 0x00, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce,
+            0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x04,
+            0x6d, 0x30, 0x6b, 0x02, 0x01, 0x01, 0x04, 0x20, 0x07, 0x4f, 0x1a, 0xfc, 0x02, 0x8f,
+            0x42, 0xf5, 0xd8, 0x8e, 0x9c, 0x6f, 0xf9, 0x7c, 0xa3, 0xdf, 0x3e, 0x83, 0x73, 0xdb,
+            0x68, 0xa4, 0x89, 0xe7, 0xe5, 0xd0, 0x9d, 0x7b, 0x39, 0x17, 0x79, 0xa7, 0xa1, 0x44,
+            0x03, 0x42, 0x00, 0x04, 0x92, 0x2b, 0x10, 0x15, 0x34, 0x6f, 0x05, 0xb9, 0xf3, 0x4b,
+            0x76, 0x89, 0x75, 0xb3, 0x5e, 0x44, 0xdd, 0xb3, 0x67, 0xc2, 0xa5, 0x01, 0xed, 0x2b,
+            0x58, 0x76, 0x77, 0x12, 0xa7, 0xb3, 0xfc, 0x8d, 0xf6, 0xdf, 0x4a, 0xf4, 0x97, 0x25,
+            0x1d, 0xff, 0x99, 0x7e, 0x9b, 0x50, 0x80, 0xa8, 0x0c, 0xb8, 0x19, 0x4b, 0x6f, 0xef,
+            0xbe, 0xc7, 0x87, 0xe4, 0x6c, 0xf3, 0xa8, 0x6c, 0xdf, 0xf1, 0x89, 0x67,
+        ];
+
+        let key = unwrap!(decode(&pkcs8));
+
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        unwrap!(key.get_public_key(&mut pubkey));
+        assert_eq!(&pubkey[..], &pkcs8[pkcs8.len() - EC_POINT_LEN_BYTES..]);
+
+        // Truncated keys are rejected
+        assert!(decode(&pkcs8[..40]).is_err());
+    }
+}
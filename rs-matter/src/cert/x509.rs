@@ -0,0 +1,402 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! A reader for the X.509 (DER) certificates of the device attestation hierarchy
+//! (PAA, PAI and DAC), as per section 6.2.2 "Device Attestation Certificate (DAC)"
+//! of the Matter spec.
+
+use time::{Date, Month, Time};
+
+use crate::crypto::{KeyPair, EC_POINT_LEN_BYTES, EC_SIGNATURE_LEN_BYTES};
+use crate::error::{Error, ErrorCode};
+
+use super::der::{self, DerReader, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE};
+use super::{OID_ECDSA_WITH_SHA256, OID_EC_TYPE_PRIME256V1, OID_PUB_KEY_ECPUBKEY};
+
+// As per https://datatracker.ietf.org/doc/html/rfc5280
+
+pub(super) const OID_COMMON_NAME: [u8; 3] = [0x55, 0x04, 0x03];
+pub(super) const OID_SUBJECT_KEY_ID: [u8; 3] = [0x55, 0x1D, 0x0E];
+pub(super) const OID_BASIC_CONSTRAINTS: [u8; 3] = [0x55, 0x1D, 0x13];
+pub(super) const OID_AUTHORITY_KEY_ID: [u8; 3] = [0x55, 0x1D, 0x23];
+
+// As per section 6.2.2.2 "Encoding of Vendor ID and Product ID in subject and issuer fields"
+// of the Matter spec
+
+pub(super) const OID_MATTER_VID: [u8; 10] =
+    [0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0xA2, 0x7C, 0x02, 0x01];
+pub(super) const OID_MATTER_PID: [u8; 10] =
+    [0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0xA2, 0x7C, 0x02, 0x02];
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_CTX_VERSION: u8 = 0xA0;
+const TAG_CTX_EXTENSIONS: u8 = 0xA3;
+const TAG_CTX_KEY_ID: u8 = 0x80;
+
+/// A reference to an X.509 certificate (DER) of the device attestation hierarchy.
+///
+/// Only certificates with P-256 keys and ECDSA-with-SHA256 signatures are supported,
+/// which is what the Matter spec mandates for the device attestation certificates.
+#[derive(Debug, Clone)]
+pub struct X509CertRef<'a> {
+    tbs: &'a [u8],
+    issuer: &'a [u8],
+    not_before: &'a [u8],
+    not_after: &'a [u8],
+    subject: &'a [u8],
+    pubkey: &'a [u8],
+    extensions: &'a [u8],
+    signature: [u8; EC_SIGNATURE_LEN_BYTES],
+}
+
+impl<'a> X509CertRef<'a> {
+    /// Parse the provided X.509 certificate (DER)
+    pub fn new(der: &'a [u8]) -> Result<Self, Error> {
+        let mut reader = DerReader::new(der);
+        let mut cert = reader.read_seq()?;
+
+        if !reader.is_empty() {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let tbs = cert.read(TAG_SEQUENCE)?;
+        Self::check_sign_algo(&mut cert.read_seq()?)?;
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        der::ecdsa_sig_from_der(cert.read_bitstr()?, &mut signature)?;
+
+        let mut tbs_reader = DerReader::new(tbs.content);
+
+        // Version (v3), serial number and signature algorithm
+        tbs_reader.read(TAG_CTX_VERSION)?;
+        tbs_reader.next()?;
+        Self::check_sign_algo(&mut tbs_reader.read_seq()?)?;
+
+        let issuer = tbs_reader.read(TAG_SEQUENCE)?.content;
+
+        let mut validity = tbs_reader.read_seq()?;
+        let not_before = validity.next()?.raw;
+        let not_after = validity.next()?.raw;
+
+        let subject = tbs_reader.read(TAG_SEQUENCE)?.content;
+
+        let mut pubkey_info = tbs_reader.read_seq()?;
+        let mut pubkey_algo = pubkey_info.read_seq()?;
+        if pubkey_algo.read(TAG_OID)?.content != OID_PUB_KEY_ECPUBKEY
+            || pubkey_algo.read(TAG_OID)?.content != OID_EC_TYPE_PRIME256V1
+        {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let pubkey = pubkey_info.read_bitstr()?;
+        if pubkey.len() != EC_POINT_LEN_BYTES {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        // The issuer and subject unique IDs are not used by Matter,
+        // so the next element - if any - are the extensions
+        let extensions = if tbs_reader.is_empty() {
+            &[]
+        } else {
+            DerReader::new(tbs_reader.read(TAG_CTX_EXTENSIONS)?.content)
+                .read(TAG_SEQUENCE)?
+                .content
+        };
+
+        Ok(Self {
+            tbs: tbs.raw,
+            issuer,
+            not_before,
+            not_after,
+            subject,
+            pubkey,
+            extensions,
+            signature,
+        })
+    }
+
+    /// Return the public key of the certificate (uncompressed P-256 point)
+    pub fn pubkey(&self) -> &'a [u8] {
+        self.pubkey
+    }
+
+    /// Return the encoded subject of the certificate
+    pub fn subject(&self) -> &'a [u8] {
+        self.subject
+    }
+
+    /// Return the encoded issuer of the certificate
+    pub fn issuer(&self) -> &'a [u8] {
+        self.issuer
+    }
+
+    /// Return the subject key identifier of the certificate, if present
+    pub fn subject_key_id(&self) -> Result<Option<&'a [u8]>, Error> {
+        let Some(value) = self.extension(&OID_SUBJECT_KEY_ID)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(DerReader::new(value).read(TAG_OCTET_STRING)?.content))
+    }
+
+    /// Return the authority key identifier of the certificate, if present
+    pub fn authority_key_id(&self) -> Result<Option<&'a [u8]>, Error> {
+        let Some(value) = self.extension(&OID_AUTHORITY_KEY_ID)? else {
+            return Ok(None);
+        };
+
+        let mut akid = DerReader::new(value).read_seq()?;
+
+        while !akid.is_empty() {
+            let element = akid.next()?;
+            if element.tag == TAG_CTX_KEY_ID {
+                return Ok(Some(element.content));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Return `true` if the certificate is a CA certificate, as per its basic constraints
+    pub fn is_ca(&self) -> Result<bool, Error> {
+        let Some(value) = self.extension(&OID_BASIC_CONSTRAINTS)? else {
+            return Ok(false);
+        };
+
+        let mut constraints = DerReader::new(value).read_seq()?;
+
+        if constraints.is_empty() {
+            return Ok(false);
+        }
+
+        let element = constraints.next()?;
+
+        Ok(element.tag == TAG_BOOLEAN && element.content.first().copied().unwrap_or(0) != 0)
+    }
+
+    /// Return the Vendor ID in the subject of the certificate, if present
+    pub fn vid(&self) -> Result<Option<u16>, Error> {
+        Self::dn_id(self.subject, &OID_MATTER_VID, "Mvid:")
+    }
+
+    /// Return the Product ID in the subject of the certificate, if present
+    pub fn pid(&self) -> Result<Option<u16>, Error> {
+        Self::dn_id(self.subject, &OID_MATTER_PID, "Mpid:")
+    }
+
+    /// Return `true` if the certificate is valid at the provided time (seconds since the UNIX epoch)
+    pub fn is_valid_at(&self, unix_secs: u64) -> Result<bool, Error> {
+        let not_before = Self::time(self.not_before)?;
+        let not_after = Self::time(self.not_after)?;
+
+        Ok(not_before <= unix_secs as i64 && unix_secs as i64 <= not_after)
+    }
+
+    /// Verify that the certificate is signed by the provided public key
+    pub fn verify_signed_by(&self, pubkey: &[u8]) -> Result<(), Error> {
+        KeyPair::new_from_public(pubkey)?.verify_msg(self.tbs, &self.signature)
+    }
+
+    /// Verify that the certificate is issued by the provided certificate, i.e. that
+    /// its issuer and authority key ID match the subject and the subject key ID
+    /// of the provided certificate, and that it is signed by its key
+    pub fn verify_issued_by(&self, issuer: &X509CertRef<'_>) -> Result<(), Error> {
+        if self.issuer != issuer.subject {
+            error!("Certificate issuer does not match the subject of the issuer certificate");
+            Err(ErrorCode::InvalidAuthKey)?;
+        }
+
+        if self.authority_key_id()?.is_none()
+            || self.authority_key_id()? != issuer.subject_key_id()?
+        {
+            error!("Certificate authority key ID does not match the issuer certificate");
+            Err(ErrorCode::InvalidAuthKey)?;
+        }
+
+        self.verify_signed_by(issuer.pubkey)
+    }
+
+    /// Find the value of the extension with the provided OID
+    fn extension(&self, oid: &[u8]) -> Result<Option<&'a [u8]>, Error> {
+        let mut extensions = DerReader::new(self.extensions);
+
+        while !extensions.is_empty() {
+            let mut extension = extensions.read_seq()?;
+
+            if extension.read(TAG_OID)?.content == oid {
+                let mut value = extension.next()?;
+                if value.tag == TAG_BOOLEAN {
+                    // Skip the "critical" flag
+                    value = extension.next()?;
+                }
+
+                if value.tag != TAG_OCTET_STRING {
+                    Err(ErrorCode::InvalidData)?;
+                }
+
+                return Ok(Some(value.content));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Find the Vendor ID or the Product ID in the provided DN.
+    ///
+    /// The ID is either encoded as a dedicated Matter attribute, or - for legacy
+    /// certificates - as a `Mvid:XXXX` / `Mpid:XXXX` substring of the common name.
+    fn dn_id(dn: &[u8], oid: &[u8], legacy_prefix: &str) -> Result<Option<u16>, Error> {
+        let mut rdns = DerReader::new(dn);
+        let mut legacy = None;
+
+        while !rdns.is_empty() {
+            let mut rdn = DerReader::new(rdns.next()?.content);
+
+            while !rdn.is_empty() {
+                let mut attr = rdn.read_seq()?;
+                let attr_oid = attr.read(TAG_OID)?.content;
+                let value = attr.next()?.content;
+
+                if attr_oid == oid {
+                    return Self::hex_id(value).map(Some);
+                } else if attr_oid == OID_COMMON_NAME {
+                    let value = core::str::from_utf8(value).map_err(|_| ErrorCode::InvalidData)?;
+
+                    if let Some(pos) = value.find(legacy_prefix) {
+                        let start = pos + legacy_prefix.len();
+                        let id = value.get(start..start + 4).ok_or(ErrorCode::InvalidData)?;
+
+                        legacy = Some(Self::hex_id(id.as_bytes())?);
+                    }
+                }
+            }
+        }
+
+        Ok(legacy)
+    }
+
+    /// Parse a Vendor ID or a Product ID, encoded as 4 uppercase hex digits
+    fn hex_id(value: &[u8]) -> Result<u16, Error> {
+        if value.len() != 4 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        value.iter().try_fold(0, |id, digit| {
+            let nibble = match digit {
+                b'0'..=b'9' => digit - b'0',
+                b'A'..=b'F' => digit - b'A' + 10,
+                _ => Err(ErrorCode::InvalidData)?,
+            };
+
+            Ok((id << 4) | nibble as u16)
+        })
+    }
+
+    /// Parse an UTCTime or a GeneralizedTime into seconds since the UNIX epoch
+    fn time(raw: &[u8]) -> Result<i64, Error> {
+        let element = DerReader::new(raw).next()?;
+        let value = element.content;
+
+        let (year, rest) = match element.tag {
+            TAG_UTC_TIME if value.len() == 13 => {
+                let year = Self::digits(&value[..2])? as i32;
+                // As per RFC 5280, years 50..99 are 1950..1999
+                (if year >= 50 { 1900 } else { 2000 } + year, &value[2..])
+            }
+            TAG_GENERALIZED_TIME if value.len() == 15 => {
+                (Self::digits(&value[..4])? as i32, &value[4..])
+            }
+            _ => Err(ErrorCode::InvalidData)?,
+        };
+
+        if rest[10] != b'Z' {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let month = Month::try_from(Self::digits(&rest[0..2])? as u8)
+            .map_err(|_| ErrorCode::InvalidData)?;
+        let date = Date::from_calendar_date(year, month, Self::digits(&rest[2..4])? as u8)
+            .map_err(|_| ErrorCode::InvalidData)?;
+        let time = Time::from_hms(
+            Self::digits(&rest[4..6])? as u8,
+            Self::digits(&rest[6..8])? as u8,
+            Self::digits(&rest[8..10])? as u8,
+        )
+        .map_err(|_| ErrorCode::InvalidData)?;
+
+        Ok(date.with_time(time).assume_utc().unix_timestamp())
+    }
+
+    fn digits(value: &[u8]) -> Result<u32, Error> {
+        value.iter().try_fold(0, |acc, digit| {
+            if digit.is_ascii_digit() {
+                Ok(acc * 10 + (digit - b'0') as u32)
+            } else {
+                Err(ErrorCode::InvalidData.into())
+            }
+        })
+    }
+
+    fn check_sign_algo(algo: &mut DerReader<'_>) -> Result<(), Error> {
+        if algo.read(TAG_OID)?.content != OID_ECDSA_WITH_SHA256 {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dm::clusters::dev_att::{DataType, DevAttDataFetcher};
+    use crate::dm::devices::test::TEST_DEV_ATT;
+
+    use super::X509CertRef;
+
+    fn fetch(data_type: DataType, buf: &mut [u8]) -> usize {
+        unwrap!(TEST_DEV_ATT.get_devatt_data(data_type, buf))
+    }
+
+    #[test]
+    fn test_parse_attestation_certs() {
+        let mut dac = [0; 600];
+        let dac_len = fetch(DataType::DAC, &mut dac);
+        let dac = unwrap!(X509CertRef::new(&dac[..dac_len]));
+
+        let mut pai = [0; 600];
+        let pai_len = fetch(DataType::PAI, &mut pai);
+        let pai = unwrap!(X509CertRef::new(&pai[..pai_len]));
+
+        assert!(!unwrap!(dac.is_ca()));
+        assert!(unwrap!(pai.is_ca()));
+
+        assert_eq!(unwrap!(dac.vid()), Some(0xFFF1));
+        assert_eq!(unwrap!(dac.pid()), Some(0x8002));
+        assert_eq!(unwrap!(pai.vid()), Some(0xFFF1));
+        assert_eq!(unwrap!(pai.pid()), None);
+
+        unwrap!(dac.verify_issued_by(&pai));
+        assert!(pai.verify_issued_by(&dac).is_err());
+
+        // The DAC is valid from 2022-02-05 on, with no well-defined expiration date
+        assert!(!unwrap!(dac.is_valid_at(1640995200)));
+        assert!(unwrap!(dac.is_valid_at(1672531200)));
+        assert!(unwrap!(dac.is_valid_at(4102444800)));
+    }
+}
@@ -0,0 +1,694 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! This module contains the commissioner role, i.e. the logic for commissioning a device
+//! into one of the fabrics of the local Matter instance.
+//!
+//! The commissioning is driven over a PASE session to the device, and completed over
+//! a CASE session on the fabric the device is commissioned into, as per the Matter spec:
+//! - The fail-safe of the device is armed and its regulatory config is set
+//! - The device attestation information is fetched and verified
+//! - A NOC is issued for the CSR of the device, and installed on the device together
+//!   with the root certificate and the IPK of the fabric
+//! - The device is configured with the credentials of the operational network, if necessary
+//! - The device is discovered on the operational network and the commissioning is completed
+//!   over a CASE session.
+
+use core::mem::MaybeUninit;
+use core::num::NonZeroU8;
+
+use crate::cert::MAX_CERT_TLV_LEN;
+use crate::crypto::{EC_SIGNATURE_LEN_BYTES, SYMM_KEY_LEN_BYTES};
+use crate::dm::clusters::decl::general_commissioning::{
+    self, CommissioningErrorEnum, RegulatoryLocationTypeEnum,
+};
+use crate::dm::clusters::decl::network_commissioning::{self, NetworkCommissioningStatusEnum};
+use crate::dm::clusters::decl::operational_credentials::{
+    self, CertificateChainTypeEnum, NodeOperationalCertStatusEnum,
+};
+use crate::dm::networks::wireless::Thread;
+use crate::dm::EndptId;
+use crate::error::{Error, ErrorCode};
+use crate::sc::pake::Pake;
+use crate::sc::spake2p::Spake2P;
+use crate::tlv::{FromTLV, OctetStr, TLVElement};
+use crate::transport::exchange::Exchange;
+use crate::transport::network::mdns::MdnsResolver;
+use crate::transport::network::Address;
+use crate::utils::init::InitMaybeUninit;
+use crate::utils::storage::Vec;
+use crate::Matter;
+
+pub mod attestation;
+
+/// The endpoint of the commissioning-related clusters of a device
+const ROOT_ENDPOINT_ID: EndptId = 0;
+
+/// The max length of a DAC or a PAI certificate (X.509 DER), as per the Matter spec
+pub const MAX_DAC_LEN: usize = 600;
+/// The max length of the attestation elements, as per the Matter spec
+pub const MAX_ATTESTATION_ELEMENTS_LEN: usize = 900;
+/// The max length of the NOCSR elements
+pub const MAX_NOCSR_ELEMENTS_LEN: usize = 900;
+
+/// The length of the attestation and the CSR nonces
+const NONCE_LEN: usize = 32;
+
+/// The device attestation information, as fetched from a device during the commissioning
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AttestationInfo<'a> {
+    /// The Device Attestation Certificate (X.509 DER)
+    pub dac: &'a [u8],
+    /// The Product Attestation Intermediate certificate (X.509 DER)
+    pub pai: &'a [u8],
+    /// The attestation elements (TLV), as returned in the `AttestationResponse`
+    pub attestation_elements: &'a [u8],
+    /// The signature of the attestation elements and the attestation challenge by the DAC key
+    pub attestation_signature: &'a [u8],
+    /// The attestation nonce, as sent in the `AttestationRequest`
+    pub attestation_nonce: &'a [u8],
+    /// The attestation challenge of the PASE session with the device
+    pub attestation_challenge: &'a [u8],
+}
+
+/// The CSR information, as fetched from a device during the commissioning
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CsrInfo<'a> {
+    /// The Device Attestation Certificate (X.509 DER) of the device
+    pub dac: &'a [u8],
+    /// The NOCSR elements (TLV), as returned in the `CSRResponse`
+    pub nocsr_elements: &'a [u8],
+    /// The signature of the NOCSR elements and the attestation challenge by the DAC key
+    pub attestation_signature: &'a [u8],
+    /// The attestation challenge of the PASE session with the device
+    pub attestation_challenge: &'a [u8],
+}
+
+/// A trait for verifying the attestation information of the devices being commissioned.
+///
+/// See `attestation::DefaultAttestationVerifier` for an implementation verifying
+/// the information against a store of trusted PAAs and CD signing keys.
+pub trait AttestationVerifier {
+    /// Verify the attestation information of a device.
+    ///
+    /// The commissioning is aborted if the verification fails.
+    fn verify_attestation(&self, info: &AttestationInfo<'_>) -> Result<(), Error>;
+
+    /// Verify that the NOCSR elements of a device are signed with its DAC key.
+    ///
+    /// The commissioning is aborted if the verification fails.
+    fn verify_csr(&self, info: &CsrInfo<'_>) -> Result<(), Error>;
+}
+
+impl<T> AttestationVerifier for &T
+where
+    T: AttestationVerifier,
+{
+    fn verify_attestation(&self, info: &AttestationInfo<'_>) -> Result<(), Error> {
+        (**self).verify_attestation(info)
+    }
+
+    fn verify_csr(&self, info: &CsrInfo<'_>) -> Result<(), Error> {
+        (**self).verify_csr(info)
+    }
+}
+
+/// A dummy implementation of the `AttestationVerifier` trait, which accepts any device.
+///
+/// Only useful for testing, as it defeats the purpose of the device attestation.
+impl AttestationVerifier for () {
+    fn verify_attestation(&self, _info: &AttestationInfo<'_>) -> Result<(), Error> {
+        warn!("Skipping the verification of the device attestation");
+
+        Ok(())
+    }
+
+    fn verify_csr(&self, _info: &CsrInfo<'_>) -> Result<(), Error> {
+        warn!("Skipping the verification of the NOCSR signature");
+
+        Ok(())
+    }
+}
+
+/// A trait for issuing the Node Operational Certificates of the devices being commissioned.
+///
+/// Implemented by the operational CA of the fabric the devices are commissioned into,
+/// i.e. the NOCs must chain up to the root (and the intermediate, if any) certificate
+/// of the fabric of the commissioner.
+pub trait NocIssuer {
+    /// Issue a NOC (Matter TLV) for the public key in the provided CSR (PKCS#10 DER)
+    /// and the provided node ID, writing it into the provided buffer.
+    ///
+    /// Return the length of the NOC.
+    fn issue_noc(&self, csr: &[u8], node_id: u64, noc: &mut [u8]) -> Result<usize, Error>;
+}
+
+impl<T> NocIssuer for &T
+where
+    T: NocIssuer,
+{
+    fn issue_noc(&self, csr: &[u8], node_id: u64, noc: &mut [u8]) -> Result<usize, Error> {
+        (**self).issue_noc(csr, node_id, noc)
+    }
+}
+
+/// The credentials of the operational network of a device
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NetworkCredentials<'a> {
+    /// A Wifi network
+    Wifi {
+        /// The SSID of the network
+        ssid: &'a [u8],
+        /// The password of the network
+        password: &'a [u8],
+    },
+    /// A Thread network
+    Thread {
+        /// The operational dataset of the network (TLV)
+        dataset: &'a [u8],
+    },
+}
+
+/// The parameters of the commissioning of a device
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CommissioningParams<'a> {
+    /// The node ID to assign to the device
+    pub node_id: u64,
+    /// The expiry length of the fail-safe of the device, in seconds
+    pub fail_safe_expiry_secs: u16,
+    /// The regulatory location and the country code to set on the device, if any
+    pub regulatory_config: Option<(RegulatoryLocationTypeEnum, &'a str)>,
+    /// The credentials of the operational network of the device,
+    /// if the device is not on the operational network already (e.g. when commissioned over BLE)
+    pub network: Option<NetworkCredentials<'a>>,
+}
+
+impl<'a> CommissioningParams<'a> {
+    /// Create new commissioning parameters for the provided node ID,
+    /// with the default fail-safe expiry length and no regulatory config or network credentials
+    pub const fn new(node_id: u64) -> Self {
+        Self {
+            node_id,
+            fail_safe_expiry_secs: 60,
+            regulatory_config: None,
+            network: None,
+        }
+    }
+}
+
+/// The commissioner role.
+///
+/// Commissions devices into a fabric of the local Matter instance.
+pub struct Commissioner<'a, R> {
+    matter: &'a Matter<'a>,
+    fab_idx: NonZeroU8,
+    resolver: R,
+    noc_issuer: &'a dyn NocIssuer,
+    verifier: &'a dyn AttestationVerifier,
+}
+
+impl<'a, R> Commissioner<'a, R>
+where
+    R: MdnsResolver,
+{
+    /// Create a new commissioner.
+    ///
+    /// # Arguments
+    /// - `matter`: The local Matter instance
+    /// - `fab_idx`: The index of the fabric in the local Matter instance the devices are commissioned into
+    /// - `resolver`: The mDNS resolver used for discovering the devices on the operational network
+    /// - `noc_issuer`: The issuer of the NOCs of the devices, i.e. the operational CA of the fabric
+    /// - `verifier`: The verifier of the attestation information of the devices
+    pub const fn new(
+        matter: &'a Matter<'a>,
+        fab_idx: NonZeroU8,
+        resolver: R,
+        noc_issuer: &'a dyn NocIssuer,
+        verifier: &'a dyn AttestationVerifier,
+    ) -> Self {
+        Self {
+            matter,
+            fab_idx,
+            resolver,
+            noc_issuer,
+            verifier,
+        }
+    }
+
+    /// Commission the commissionable device at the provided address, using the provided passcode.
+    ///
+    /// A PASE session is first established with the device, and then the commissioning
+    /// is performed over it, as per `commission`.
+    ///
+    /// # Arguments
+    /// - `peer_addr`: The address of the device
+    /// - `passcode`: The setup passcode of the device
+    /// - `params`: The commissioning parameters
+    pub async fn commission_with_passcode(
+        &mut self,
+        peer_addr: Address,
+        passcode: u32,
+        params: &CommissioningParams<'_>,
+    ) -> Result<(), Error> {
+        let pase_session_id = self.establish_pase(peer_addr, passcode).await?;
+
+        self.commission(pase_session_id, params).await
+    }
+
+    /// Establish a PASE session with the commissionable device at the provided address,
+    /// using the provided passcode.
+    ///
+    /// Return the ID of the PASE session.
+    pub async fn establish_pase(&self, peer_addr: Address, passcode: u32) -> Result<u32, Error> {
+        debug!("Establishing a PASE session with {}", peer_addr);
+
+        let mut exchange = Exchange::initiate_unsecured(self.matter, peer_addr).await?;
+
+        let mut spake2p = MaybeUninit::uninit(); // TODO LARGE BUFFER
+        let spake2p = spake2p.init_with(Spake2P::init());
+
+        Pake::new().initiate(&mut exchange, spake2p, passcode).await
+    }
+
+    /// Commission the device on the other side of the provided PASE session.
+    ///
+    /// # Arguments
+    /// - `pase_session_id`: The ID of the PASE session with the device
+    /// - `params`: The commissioning parameters
+    pub async fn commission(
+        &mut self,
+        pase_session_id: u32,
+        params: &CommissioningParams<'_>,
+    ) -> Result<(), Error> {
+        info!(
+            "Commissioning node {:x} into fabric {}",
+            params.node_id, self.fab_idx
+        );
+
+        self.arm_fail_safe(pase_session_id, params.fail_safe_expiry_secs)
+            .await?;
+
+        if let Some((location, country_code)) = params.regulatory_config {
+            self.set_regulatory_config(pase_session_id, location, country_code)
+                .await?;
+        }
+
+        let mut dac = Vec::<u8, MAX_DAC_LEN>::new();
+
+        self.verify_attestation(pase_session_id, &mut dac).await?;
+
+        let mut noc = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+
+        self.issue_noc(pase_session_id, &dac, params.node_id, &mut noc)
+            .await?;
+        self.add_noc(pase_session_id, &noc).await?;
+
+        if let Some(network) = params.network {
+            self.configure_network(pase_session_id, &network).await?;
+        }
+
+        self.complete(params.node_id).await?;
+
+        info!(
+            "Commissioned node {:x} into fabric {}",
+            params.node_id, self.fab_idx
+        );
+
+        Ok(())
+    }
+
+    /// Arm the fail-safe of the device
+    async fn arm_fail_safe(&self, pase_session_id: u32, expiry_secs: u16) -> Result<(), Error> {
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let status = general_commissioning::ClusterClient::new()
+            .arm_fail_safe(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| req.expiry_length_seconds(expiry_secs)?.breadcrumb(1)?.end(),
+                |resp| resp.error_code(),
+            )
+            .await?;
+
+        Self::check_commissioning_status("ArmFailSafe", status)
+    }
+
+    /// Set the regulatory config of the device
+    async fn set_regulatory_config(
+        &self,
+        pase_session_id: u32,
+        location: RegulatoryLocationTypeEnum,
+        country_code: &str,
+    ) -> Result<(), Error> {
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let status = general_commissioning::ClusterClient::new()
+            .set_regulatory_config(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| {
+                    req.new_regulatory_config(location)?
+                        .country_code(country_code)?
+                        .breadcrumb(2)?
+                        .end()
+                },
+                |resp| resp.error_code(),
+            )
+            .await?;
+
+        Self::check_commissioning_status("SetRegulatoryConfig", status)
+    }
+
+    /// Fetch the attestation information of the device and verify it
+    ///
+    /// The DAC of the device is returned in the provided buffer, as it is
+    /// necessary for the verification of the NOCSR elements later on.
+    async fn verify_attestation(
+        &self,
+        pase_session_id: u32,
+        dac: &mut Vec<u8, MAX_DAC_LEN>,
+    ) -> Result<(), Error> {
+        let mut pai = Vec::<u8, MAX_DAC_LEN>::new();
+
+        self.fetch_cert(
+            pase_session_id,
+            CertificateChainTypeEnum::DACCertificate,
+            dac,
+        )
+        .await?;
+        self.fetch_cert(
+            pase_session_id,
+            CertificateChainTypeEnum::PAICertificate,
+            &mut pai,
+        )
+        .await?;
+
+        let mut nonce = [0; NONCE_LEN];
+        (self.matter.rand())(&mut nonce);
+
+        let mut elements = Vec::<u8, MAX_ATTESTATION_ELEMENTS_LEN>::new();
+        let mut signature = Vec::<u8, EC_SIGNATURE_LEN_BYTES>::new();
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let challenge = Self::attestation_challenge(&exchange)?;
+
+        operational_credentials::ClusterClient::new()
+            .attestation_request(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| req.attestation_nonce(OctetStr::new(&nonce))?.end(),
+                |resp| {
+                    Self::copy(resp.attestation_elements()?.0, &mut elements)?;
+                    Self::copy(resp.attestation_signature()?.0, &mut signature)
+                },
+            )
+            .await?;
+
+        self.verifier.verify_attestation(&AttestationInfo {
+            dac,
+            pai: &pai,
+            attestation_elements: &elements,
+            attestation_signature: &signature,
+            attestation_nonce: &nonce,
+            attestation_challenge: &challenge,
+        })
+    }
+
+    /// Fetch the DAC or the PAI certificate of the device
+    async fn fetch_cert(
+        &self,
+        pase_session_id: u32,
+        cert_type: CertificateChainTypeEnum,
+        cert: &mut Vec<u8, MAX_DAC_LEN>,
+    ) -> Result<(), Error> {
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        operational_credentials::ClusterClient::new()
+            .certificate_chain_request(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| req.certificate_type(cert_type)?.end(),
+                |resp| Self::copy(resp.certificate()?.0, cert),
+            )
+            .await
+    }
+
+    /// Request a CSR from the device, verify it and issue a NOC for it
+    async fn issue_noc(
+        &self,
+        pase_session_id: u32,
+        dac: &[u8],
+        node_id: u64,
+        noc: &mut Vec<u8, MAX_CERT_TLV_LEN>,
+    ) -> Result<(), Error> {
+        let mut nonce = [0; NONCE_LEN];
+        (self.matter.rand())(&mut nonce);
+
+        let mut elements = Vec::<u8, MAX_NOCSR_ELEMENTS_LEN>::new();
+        let mut signature = Vec::<u8, EC_SIGNATURE_LEN_BYTES>::new();
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let challenge = Self::attestation_challenge(&exchange)?;
+
+        operational_credentials::ClusterClient::new()
+            .csr_request(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| {
+                    req.csr_nonce(OctetStr::new(&nonce))?
+                        .is_for_update_noc(Some(false))?
+                        .end()
+                },
+                |resp| {
+                    Self::copy(resp.nocsr_elements()?.0, &mut elements)?;
+                    Self::copy(resp.attestation_signature()?.0, &mut signature)
+                },
+            )
+            .await?;
+
+        self.verifier.verify_csr(&CsrInfo {
+            dac,
+            nocsr_elements: &elements,
+            attestation_signature: &signature,
+            attestation_challenge: &challenge,
+        })?;
+
+        let elements = NocsrElements::from_tlv(&TLVElement::new(&elements))?;
+
+        if elements.csr_nonce.0 != nonce {
+            error!("CSR nonce mismatch");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        unwrap!(noc.resize_default(MAX_CERT_TLV_LEN));
+
+        let len = self.noc_issuer.issue_noc(elements.csr.0, node_id, noc)?;
+        noc.truncate(len);
+
+        Ok(())
+    }
+
+    /// Install the root certificate of the fabric and the provided NOC on the device
+    async fn add_noc(&self, pase_session_id: u32, noc: &[u8]) -> Result<(), Error> {
+        // Copy the fabric credentials out, as the fabric manager must not stay borrowed across the awaits below
+        let mut root_ca = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+        let mut icac = Vec::<u8, MAX_CERT_TLV_LEN>::new();
+        let mut ipk = [0; SYMM_KEY_LEN_BYTES];
+
+        let (node_id, vendor_id) = {
+            let fabric_mgr = self.matter.fabric_mgr.borrow();
+            let fabric = fabric_mgr.get(self.fab_idx).ok_or(ErrorCode::NoFabricId)?;
+
+            unwrap!(root_ca.extend_from_slice(fabric.root_ca()));
+            unwrap!(icac.extend_from_slice(fabric.icac()));
+            ipk.copy_from_slice(fabric.ipk().epoch_key());
+
+            (fabric.node_id(), fabric.vendor_id())
+        };
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        operational_credentials::ClusterClient::new()
+            .add_trusted_root_certificate(&mut exchange, ROOT_ENDPOINT_ID, |req| {
+                req.root_ca_certificate(OctetStr::new(&root_ca))?.end()
+            })
+            .await?;
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let status = operational_credentials::ClusterClient::new()
+            .add_noc(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| {
+                    req.noc_value(OctetStr::new(noc))?
+                        .icac_value((!icac.is_empty()).then(|| OctetStr::new(&icac)))?
+                        .ipk_value(OctetStr::new(&ipk))?
+                        .case_admin_subject(node_id)?
+                        .admin_vendor_id(vendor_id)?
+                        .end()
+                },
+                |resp| resp.status_code(),
+            )
+            .await?;
+
+        if status != NodeOperationalCertStatusEnum::OK {
+            error!("AddNOC failed with status {:?}", status);
+            Err(ErrorCode::Failure)?;
+        }
+
+        Ok(())
+    }
+
+    /// Configure the device with the credentials of the operational network and connect it to the network
+    async fn configure_network(
+        &self,
+        pase_session_id: u32,
+        network: &NetworkCredentials<'_>,
+    ) -> Result<(), Error> {
+        let client = network_commissioning::ClusterClient::new();
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let (network_id, status) = match network {
+            NetworkCredentials::Wifi { ssid, password } => {
+                let status = client
+                    .add_or_update_wi_fi_network(
+                        &mut exchange,
+                        ROOT_ENDPOINT_ID,
+                        |req| {
+                            req.ssid(OctetStr::new(ssid))?
+                                .credentials(OctetStr::new(password))?
+                                .breadcrumb(Some(3))?
+                                .network_identity(None)?
+                                .client_identifier(None)?
+                                .possession_nonce(None)?
+                                .end()
+                        },
+                        |resp| resp.networking_status(),
+                    )
+                    .await?;
+
+                (*ssid, status)
+            }
+            NetworkCredentials::Thread { dataset } => {
+                let status = client
+                    .add_or_update_thread_network(
+                        &mut exchange,
+                        ROOT_ENDPOINT_ID,
+                        |req| {
+                            req.operational_dataset(OctetStr::new(dataset))?
+                                .breadcrumb(Some(3))?
+                                .end()
+                        },
+                        |resp| resp.networking_status(),
+                    )
+                    .await?;
+
+                (Thread::dataset_ext_pan_id(dataset)?, status)
+            }
+        };
+
+        Self::check_network_status("AddOrUpdateNetwork", status)?;
+
+        let mut exchange = Exchange::initiate_for_session(self.matter, pase_session_id)?;
+
+        let status = client
+            .connect_network(
+                &mut exchange,
+                ROOT_ENDPOINT_ID,
+                |req| {
+                    req.network_id(OctetStr::new(network_id))?
+                        .breadcrumb(Some(4))?
+                        .end()
+                },
+                |resp| resp.networking_status(),
+            )
+            .await?;
+
+        Self::check_network_status("ConnectNetwork", status)
+    }
+
+    /// Discover the device on the operational network and complete the commissioning over CASE
+    async fn complete(&mut self, node_id: u64) -> Result<(), Error> {
+        let mut exchange = Exchange::initiate_with(
+            self.matter,
+            &mut self.resolver,
+            self.fab_idx.get(),
+            node_id,
+            true,
+        )
+        .await?;
+
+        let status = general_commissioning::ClusterClient::new()
+            .commissioning_complete(&mut exchange, ROOT_ENDPOINT_ID, |resp| resp.error_code())
+            .await?;
+
+        Self::check_commissioning_status("CommissioningComplete", status)
+    }
+
+    /// Return the attestation challenge of the session of the provided exchange
+    fn attestation_challenge(exchange: &Exchange<'_>) -> Result<[u8; 16], Error> {
+        exchange.with_session(|sess| {
+            let mut challenge = [0; 16];
+            challenge.copy_from_slice(sess.get_att_challenge());
+
+            Ok(challenge)
+        })
+    }
+
+    fn copy<const N: usize>(data: &[u8], buf: &mut Vec<u8, N>) -> Result<(), Error> {
+        buf.clear();
+        buf.extend_from_slice(data)
+            .map_err(|_| ErrorCode::NoSpace.into())
+    }
+
+    fn check_commissioning_status(cmd: &str, status: CommissioningErrorEnum) -> Result<(), Error> {
+        if status != CommissioningErrorEnum::OK {
+            error!("{} failed with status {:?}", cmd, status);
+            Err(ErrorCode::Failure)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_network_status(
+        cmd: &str,
+        status: NetworkCommissioningStatusEnum,
+    ) -> Result<(), Error> {
+        if status != NetworkCommissioningStatusEnum::Success {
+            error!("{} failed with status {:?}", cmd, status);
+            Err(ErrorCode::Failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The NOCSR elements, as returned by the device in the `CSRResponse`
+#[derive(FromTLV)]
+#[tlvargs(lifetime = "'a", start = 1)]
+struct NocsrElements<'a> {
+    csr: OctetStr<'a>,
+    csr_nonce: OctetStr<'a>,
+}
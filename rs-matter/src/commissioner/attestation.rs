@@ -0,0 +1,751 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! The verification of the device attestation information by the commissioner,
+//! as per section 6.2.3 "Device Attestation Procedure" of the Matter spec:
+//! - The DAC -> PAI -> PAA chain of the device is verified against a store of trusted PAAs
+//! - The attestation signature over the attestation elements and the attestation challenge
+//!   is verified with the DAC key
+//! - The CMS signature of the Certification Declaration (CD) is verified against a store
+//!   of trusted CD signing keys
+//! - The Vendor ID and the Product ID are matched across the DAC, the PAI and the CD
+
+use crate::alloc;
+use crate::cert::cd::{CertDeclaration, CertDeclarationRef, CD_FORMAT_VERSION};
+use crate::cert::x509::X509CertRef;
+use crate::crypto::KeyPair;
+use crate::error::{Error, ErrorCode};
+use crate::fmt::Bytes;
+use crate::tlv::{FromTLV, OctetStr, TLVElement};
+use crate::utils::epoch::Epoch;
+
+use super::{
+    AttestationInfo, AttestationVerifier, CsrInfo, MAX_ATTESTATION_ELEMENTS_LEN,
+    MAX_NOCSR_ELEMENTS_LEN,
+};
+
+#[cfg(feature = "std")]
+pub use fileio::*;
+
+/// The subject key ID of the CD signing key used by the CSA for test devices
+pub const TEST_CD_SIGNING_KEY_ID: [u8; 20] = [
+    0x62, 0xFA, 0x82, 0x33, 0x59, 0xAC, 0xFA, 0xA9, 0x96, 0x3E, 0x1C, 0xFA, 0x14, 0x0A, 0xDD, 0xF5,
+    0x04, 0xF3, 0x71, 0x60,
+];
+
+/// The public key of the CD signing key used by the CSA for test devices
+pub const TEST_CD_SIGNING_PUBKEY: [u8; 65] = [
+    0x04, 0x3C, 0x39, 0x89, 0x22, 0x45, 0x2B, 0x55, 0xCA, 0xF3, 0x89, 0xC2, 0x5B, 0xD1, 0xBC, 0xA4,
+    0x65, 0x69, 0x52, 0xCC, 0xB9, 0x0E, 0x88, 0x69, 0x24, 0x9A, 0xD8, 0x47, 0x46, 0x53, 0x01, 0x4C,
+    0xBF, 0x95, 0xD6, 0x87, 0x96, 0x5E, 0x03, 0x6B, 0x52, 0x1C, 0x51, 0x03, 0x7E, 0x6B, 0x8C, 0xED,
+    0xEF, 0xCA, 0x1E, 0xB4, 0x40, 0x46, 0x69, 0x4F, 0xA0, 0x88, 0x82, 0xEE, 0xD6, 0x51, 0x9D, 0xEC,
+    0xBA,
+];
+
+/// The CD signing keys used by the CSA for test devices, in a form suitable
+/// for `StaticTrustStore`
+pub const TEST_CD_SIGNING_KEYS: &[(&[u8], &[u8])] =
+    &[(&TEST_CD_SIGNING_KEY_ID, &TEST_CD_SIGNING_PUBKEY)];
+
+/// The max length of the attestation challenge of a session
+const MAX_ATTESTATION_CHALLENGE_LEN: usize = 16;
+
+/// A store of the trust anchors of the device attestation, i.e. the PAA certificates
+/// and the CD signing keys.
+pub trait AttestationTrustStore {
+    /// Return the trusted PAA certificate (X.509 DER) with the provided subject key ID, if any
+    fn paa(&self, key_id: &[u8]) -> Option<&[u8]>;
+
+    /// Return the trusted CD signing public key with the provided subject key ID, if any
+    fn cd_signing_key(&self, key_id: &[u8]) -> Option<&[u8]>;
+}
+
+impl<T> AttestationTrustStore for &T
+where
+    T: AttestationTrustStore,
+{
+    fn paa(&self, key_id: &[u8]) -> Option<&[u8]> {
+        (**self).paa(key_id)
+    }
+
+    fn cd_signing_key(&self, key_id: &[u8]) -> Option<&[u8]> {
+        (**self).cd_signing_key(key_id)
+    }
+}
+
+/// An `AttestationTrustStore` implementation over static PAA certificates and CD signing keys.
+#[derive(Debug, Clone)]
+pub struct StaticTrustStore<'a> {
+    paas: &'a [&'a [u8]],
+    cd_signing_keys: &'a [(&'a [u8], &'a [u8])],
+}
+
+impl<'a> StaticTrustStore<'a> {
+    /// Create a new trust store
+    ///
+    /// Parameters:
+    /// - `paas`: The trusted PAA certificates (X.509 DER)
+    /// - `cd_signing_keys`: The trusted CD signing keys, as (subject key ID, public key) pairs
+    pub const fn new(paas: &'a [&'a [u8]], cd_signing_keys: &'a [(&'a [u8], &'a [u8])]) -> Self {
+        Self {
+            paas,
+            cd_signing_keys,
+        }
+    }
+}
+
+impl AttestationTrustStore for StaticTrustStore<'_> {
+    fn paa(&self, key_id: &[u8]) -> Option<&[u8]> {
+        find_paa(self.paas.iter().copied(), key_id)
+    }
+
+    fn cd_signing_key(&self, key_id: &[u8]) -> Option<&[u8]> {
+        find_cd_signing_key(self.cd_signing_keys.iter().copied(), key_id)
+    }
+}
+
+/// An `AttestationVerifier` implementation, which verifies the attestation information
+/// of the devices against the provided `AttestationTrustStore`.
+pub struct DefaultAttestationVerifier<T> {
+    trust_store: T,
+    epoch: Epoch,
+}
+
+impl<T> DefaultAttestationVerifier<T>
+where
+    T: AttestationTrustStore,
+{
+    /// Create a new verifier
+    ///
+    /// Parameters:
+    /// - `trust_store`: The store of the trusted PAAs and CD signing keys
+    /// - `epoch`: The current time, used for checking the validity of the certificates.
+    ///   The validity is not checked if the current time is not known (i.e. is zero)
+    pub const fn new(trust_store: T, epoch: Epoch) -> Self {
+        Self { trust_store, epoch }
+    }
+
+    /// Verify the DAC -> PAI -> PAA chain, and return the PAA
+    fn verify_chain<'a>(
+        &'a self,
+        dac: &X509CertRef<'_>,
+        pai: &X509CertRef<'_>,
+    ) -> Result<X509CertRef<'a>, Error> {
+        if dac.is_ca()? || !pai.is_ca()? {
+            error!("DAC must not be a CA certificate, while PAI must be");
+            Err(ErrorCode::InvalidAuthKey)?;
+        }
+
+        let paa_key_id = pai.authority_key_id()?.ok_or(ErrorCode::InvalidAuthKey)?;
+        let Some(paa) = self.trust_store.paa(paa_key_id) else {
+            error!("PAA {} is not trusted", Bytes(paa_key_id));
+            Err(ErrorCode::NotFound)?
+        };
+
+        let paa = X509CertRef::new(paa)?;
+        if !paa.is_ca()? {
+            error!("PAA is not a CA certificate");
+            Err(ErrorCode::InvalidAuthKey)?;
+        }
+
+        dac.verify_issued_by(pai)?;
+        pai.verify_issued_by(&paa)?;
+
+        let now = (self.epoch)().as_secs();
+        if now != 0 {
+            for cert in [dac, pai, &paa] {
+                if !cert.is_valid_at(now)? {
+                    error!("Device attestation certificate is not valid at {}", now);
+                    Err(ErrorCode::InvalidTime)?;
+                }
+            }
+        }
+
+        Ok(paa)
+    }
+
+    /// Verify the Vendor ID and the Product ID of the DAC against the ones of the PAI
+    /// and the PAA, and return them
+    fn verify_ids(
+        dac: &X509CertRef<'_>,
+        pai: &X509CertRef<'_>,
+        paa: &X509CertRef<'_>,
+    ) -> Result<(u16, u16), Error> {
+        let (Some(vid), Some(pid)) = (dac.vid()?, dac.pid()?) else {
+            error!("DAC does not have a Vendor ID and a Product ID");
+            Err(ErrorCode::InvalidData)?
+        };
+
+        if pai.vid()?.is_some_and(|pai_vid| pai_vid != vid)
+            || paa.vid()?.is_some_and(|paa_vid| paa_vid != vid)
+        {
+            error!("DAC Vendor ID does not match the PAI or the PAA Vendor ID");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        if pai.pid()?.is_some_and(|pai_pid| pai_pid != pid) {
+            error!("DAC Product ID does not match the PAI Product ID");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok((vid, pid))
+    }
+
+    /// Verify the signature of the CD and its contents against the Vendor ID and
+    /// the Product ID of the DAC, as well as against the PAA
+    fn verify_cd(&self, cd: &[u8], vid: u16, pid: u16, paa: &X509CertRef<'_>) -> Result<(), Error> {
+        let cms = CertDeclarationRef::new(cd)?;
+
+        let Some(signing_key) = self.trust_store.cd_signing_key(cms.signer_key_id()) else {
+            error!(
+                "CD signing key {} is not trusted",
+                Bytes(cms.signer_key_id())
+            );
+            Err(ErrorCode::NotFound)?
+        };
+
+        cms.verify_signed_by(signing_key)?;
+
+        let cd = cms.decode()?;
+
+        if cd.format_version != CD_FORMAT_VERSION {
+            error!("Unsupported CD format version {}", cd.format_version);
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        if !Self::cd_matches(&cd, vid, pid)? {
+            error!("CD does not match the DAC Vendor ID and Product ID");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let paa_key_id = paa.subject_key_id()?.ok_or(ErrorCode::InvalidAuthKey)?;
+        if !cd.is_paa_authorized(paa_key_id)? {
+            error!("PAA is not authorized by the CD");
+            Err(ErrorCode::InvalidAuthKey)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return `true` if the CD covers the provided Vendor ID and Product ID of the DAC,
+    /// either directly, or via its DAC origin IDs
+    fn cd_matches(cd: &CertDeclaration<'_>, vid: u16, pid: u16) -> Result<bool, Error> {
+        match (cd.dac_origin_vendor_id, cd.dac_origin_product_id) {
+            (Some(origin_vid), Some(origin_pid)) => Ok(origin_vid == vid && origin_pid == pid),
+            (None, None) => Ok(cd.vendor_id == vid && cd.has_product_id(pid)?),
+            _ => Ok(false),
+        }
+    }
+}
+
+impl<T> AttestationVerifier for DefaultAttestationVerifier<T>
+where
+    T: AttestationTrustStore,
+{
+    fn verify_attestation(&self, info: &AttestationInfo<'_>) -> Result<(), Error> {
+        let dac = X509CertRef::new(info.dac)?;
+        let pai = X509CertRef::new(info.pai)?;
+
+        let paa = self.verify_chain(&dac, &pai)?;
+        let (vid, pid) = Self::verify_ids(&dac, &pai, &paa)?;
+
+        verify_dac_signature::<{ MAX_ATTESTATION_ELEMENTS_LEN + MAX_ATTESTATION_CHALLENGE_LEN }>(
+            &dac,
+            info.attestation_elements,
+            info.attestation_challenge,
+            info.attestation_signature,
+        )?;
+
+        let elements = AttestationElements::from_tlv(&TLVElement::new(info.attestation_elements))?;
+
+        if elements.attestation_nonce.0 != info.attestation_nonce {
+            error!("Attestation nonce mismatch");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        self.verify_cd(elements.certification_declaration.0, vid, pid, &paa)?;
+
+        info!(
+            "Device attestation verified: VID {:04x}, PID {:04x}",
+            vid, pid
+        );
+
+        Ok(())
+    }
+
+    fn verify_csr(&self, info: &CsrInfo<'_>) -> Result<(), Error> {
+        let dac = X509CertRef::new(info.dac)?;
+
+        verify_dac_signature::<{ MAX_NOCSR_ELEMENTS_LEN + MAX_ATTESTATION_CHALLENGE_LEN }>(
+            &dac,
+            info.nocsr_elements,
+            info.attestation_challenge,
+            info.attestation_signature,
+        )
+    }
+}
+
+/// Verify the signature of the provided elements (attestation or NOCSR) and the
+/// attestation challenge by the DAC key
+fn verify_dac_signature<const N: usize>(
+    dac: &X509CertRef<'_>,
+    elements: &[u8],
+    challenge: &[u8],
+    signature: &[u8],
+) -> Result<(), Error> {
+    let mut msg = alloc!([0; N]); // TODO LARGE BUFFER
+
+    let len = elements.len() + challenge.len();
+    if len > msg.len() {
+        Err(ErrorCode::NoSpace)?;
+    }
+
+    msg[..elements.len()].copy_from_slice(elements);
+    msg[elements.len()..len].copy_from_slice(challenge);
+
+    KeyPair::new_from_public(dac.pubkey())?
+        .verify_msg(&msg[..len], signature)
+        .inspect_err(|_| error!("Invalid attestation signature"))
+}
+
+/// Find the PAA certificate with the provided subject key ID among the provided ones
+fn find_paa<'a>(mut paas: impl Iterator<Item = &'a [u8]>, key_id: &[u8]) -> Option<&'a [u8]> {
+    paas.find(|paa| {
+        X509CertRef::new(paa)
+            .and_then(|paa| paa.subject_key_id())
+            .ok()
+            .flatten()
+            == Some(key_id)
+    })
+}
+
+/// Find the CD signing key with the provided subject key ID among the provided ones
+fn find_cd_signing_key<'a>(
+    mut keys: impl Iterator<Item = (&'a [u8], &'a [u8])>,
+    key_id: &[u8],
+) -> Option<&'a [u8]> {
+    keys.find(|(id, _)| *id == key_id).map(|(_, pubkey)| pubkey)
+}
+
+/// The attestation elements, as returned by the device in the `AttestationResponse`
+#[derive(FromTLV)]
+#[tlvargs(lifetime = "'a", start = 1)]
+struct AttestationElements<'a> {
+    certification_declaration: OctetStr<'a>,
+    attestation_nonce: OctetStr<'a>,
+    #[allow(unused)]
+    timestamp: u32,
+    #[allow(unused)]
+    firmware_information: Option<OctetStr<'a>>,
+}
+
+#[cfg(feature = "std")]
+pub mod fileio {
+    use std::fs;
+    use std::path::Path;
+    use std::vec::Vec;
+
+    use crate::cert::pem::{self, PEM_LABEL_CERTIFICATE};
+    use crate::cert::x509::X509CertRef;
+    use crate::error::Error;
+
+    use super::{find_cd_signing_key, find_paa, AttestationTrustStore};
+
+    /// The max length of a PAA certificate (X.509 DER)
+    const MAX_PAA_LEN: usize = 600;
+
+    /// An `AttestationTrustStore` implementation, which loads the trusted PAA certificates
+    /// from the DER (`.der`) and PEM (`.pem`) files of a directory.
+    #[derive(Debug, Clone, Default)]
+    pub struct DirTrustStore {
+        paas: Vec<Vec<u8>>,
+        cd_signing_keys: Vec<(Vec<u8>, Vec<u8>)>,
+    }
+
+    impl DirTrustStore {
+        /// Create a new, empty trust store
+        pub const fn new() -> Self {
+            Self {
+                paas: Vec::new(),
+                cd_signing_keys: Vec::new(),
+            }
+        }
+
+        /// Load the PAA certificates from the DER and PEM files of the provided directory.
+        ///
+        /// Files which are not valid PAA certificates are skipped.
+        pub fn load(&mut self, dir: &Path) -> Result<(), Error> {
+            for entry in fs::read_dir(dir)? {
+                let path = entry?.path();
+
+                let der = match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("der") => fs::read(&path)?,
+                    Some("pem") => {
+                        let mut der = [0; MAX_PAA_LEN];
+                        let Ok(len) = pem::decode(
+                            PEM_LABEL_CERTIFICATE,
+                            &fs::read_to_string(&path)?,
+                            &mut der,
+                        ) else {
+                            warn!("Skipping {:?}: not a PEM certificate", path);
+                            continue;
+                        };
+
+                        der[..len].to_vec()
+                    }
+                    _ => continue,
+                };
+
+                if !X509CertRef::new(&der)
+                    .and_then(|paa| paa.is_ca())
+                    .unwrap_or(false)
+                {
+                    warn!("Skipping {:?}: not a PAA certificate", path);
+                    continue;
+                }
+
+                debug!("Loaded PAA {:?}", path);
+
+                self.paas.push(der);
+            }
+
+            Ok(())
+        }
+
+        /// Add a trusted PAA certificate (X.509 DER)
+        pub fn add_paa(&mut self, paa: &[u8]) {
+            self.paas.push(paa.to_vec());
+        }
+
+        /// Add a trusted CD signing key
+        pub fn add_cd_signing_key(&mut self, key_id: &[u8], pubkey: &[u8]) {
+            self.cd_signing_keys
+                .push((key_id.to_vec(), pubkey.to_vec()));
+        }
+    }
+
+    impl AttestationTrustStore for DirTrustStore {
+        fn paa(&self, key_id: &[u8]) -> Option<&[u8]> {
+            find_paa(self.paas.iter().map(Vec::as_slice), key_id)
+        }
+
+        fn cd_signing_key(&self, key_id: &[u8]) -> Option<&[u8]> {
+            find_cd_signing_key(
+                self.cd_signing_keys
+                    .iter()
+                    .map(|(id, pubkey)| (id.as_slice(), pubkey.as_slice())),
+                key_id,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cert::cd::CertDeclarationRef;
+    use crate::cert::x509::X509CertRef;
+    use crate::commissioner::{AttestationInfo, AttestationVerifier, CsrInfo};
+    use crate::crypto::{KeyPair, EC_SIGNATURE_LEN_BYTES};
+    use crate::dm::clusters::dev_att::{DataType, DevAttDataFetcher};
+    use crate::dm::devices::test::TEST_DEV_ATT;
+    use crate::error::ErrorCode;
+    use crate::tlv::{TLVTag, TLVWrite};
+    use crate::utils::epoch::dummy_epoch;
+    use crate::utils::storage::WriteBuf;
+
+    use super::{DefaultAttestationVerifier, StaticTrustStore, TEST_CD_SIGNING_KEYS};
+
+    const CHALLENGE: [u8; 16] = [0x55; 16];
+    const NONCE: [u8; 32] = [0xAA; 32];
+
+    fn fetch(data_type: DataType, buf: &mut [u8]) -> usize {
+        unwrap!(TEST_DEV_ATT.get_devatt_data(data_type, buf))
+    }
+
+    /// Sign the provided elements and the attestation challenge with the test DAC key
+    fn sign(elements: &[u8], signature: &mut [u8; EC_SIGNATURE_LEN_BYTES]) {
+        let mut pubkey = [0; 65];
+        let mut privkey = [0; 32];
+        let pubkey_len = fetch(DataType::DACPubKey, &mut pubkey);
+        let privkey_len = fetch(DataType::DACPrivKey, &mut privkey);
+
+        let key = unwrap!(KeyPair::new_from_components(
+            &pubkey[..pubkey_len],
+            &privkey[..privkey_len]
+        ));
+
+        let mut msg = [0; 1024];
+        msg[..elements.len()].copy_from_slice(elements);
+        msg[elements.len()..elements.len() + CHALLENGE.len()].copy_from_slice(&CHALLENGE);
+
+        unwrap!(key.sign_msg(&msg[..elements.len() + CHALLENGE.len()], signature));
+    }
+
+    #[test]
+    fn test_untrusted_paa() {
+        let mut dac = [0; 600];
+        let dac_len = fetch(DataType::DAC, &mut dac);
+        let mut pai = [0; 600];
+        let pai_len = fetch(DataType::PAI, &mut pai);
+        let mut cd = [0; 600];
+        let cd_len = fetch(DataType::CertDeclaration, &mut cd);
+
+        // The attestation elements, as built by the device
+        let mut elements = [0; 900];
+        let mut wb = WriteBuf::new(&mut elements);
+        unwrap!(wb.start_struct(&TLVTag::Anonymous));
+        unwrap!(wb.str(&TLVTag::Context(1), &cd[..cd_len]));
+        unwrap!(wb.str(&TLVTag::Context(2), &NONCE));
+        unwrap!(wb.u32(&TLVTag::Context(3), 0));
+        unwrap!(wb.end_container());
+        let elements_len = wb.get_tail();
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        sign(&elements[..elements_len], &mut signature);
+
+        let info = AttestationInfo {
+            dac: &dac[..dac_len],
+            pai: &pai[..pai_len],
+            attestation_elements: &elements[..elements_len],
+            attestation_signature: &signature,
+            attestation_nonce: &NONCE,
+            attestation_challenge: &CHALLENGE,
+        };
+
+        // The test PAA is not part of the trust store, even though the PAI is
+        let paas: &[&[u8]] = &[&pai[..pai_len]];
+        let verifier = DefaultAttestationVerifier::new(
+            StaticTrustStore::new(paas, TEST_CD_SIGNING_KEYS),
+            dummy_epoch,
+        );
+
+        let err = unwrap!(verifier.verify_attestation(&info).err());
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        // A DAC in place of the PAI is rejected regardless of the trust store
+        let err = unwrap!(verifier
+            .verify_attestation(&AttestationInfo {
+                pai: &dac[..dac_len],
+                ..info
+            })
+            .err());
+        assert_eq!(err.code(), ErrorCode::InvalidAuthKey);
+    }
+
+    #[test]
+    fn test_cd_matches() {
+        let mut cd = [0; 600];
+        let cd_len = fetch(DataType::CertDeclaration, &mut cd);
+
+        let cd = unwrap!(unwrap!(CertDeclarationRef::new(&cd[..cd_len])).decode());
+
+        type Verifier = DefaultAttestationVerifier<StaticTrustStore<'static>>;
+
+        assert!(unwrap!(Verifier::cd_matches(&cd, 0xFFF1, 0x8002)));
+        assert!(!unwrap!(Verifier::cd_matches(&cd, 0xFFF2, 0x8002)));
+        assert!(!unwrap!(Verifier::cd_matches(&cd, 0xFFF1, 0x8064)));
+    }
+
+    #[test]
+    fn test_verify_csr() {
+        let mut dac = [0; 600];
+        let dac_len = fetch(DataType::DAC, &mut dac);
+
+        let nocsr_elements = [0x15, 0x30, 0x01, 0x02, 0xAB, 0xCD, 0x18];
+
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        sign(&nocsr_elements, &mut signature);
+
+        let verifier =
+            DefaultAttestationVerifier::new(StaticTrustStore::new(&[], &[]), dummy_epoch);
+
+        let info = CsrInfo {
+            dac: &dac[..dac_len],
+            nocsr_elements: &nocsr_elements,
+            attestation_signature: &signature,
+            attestation_challenge: &CHALLENGE,
+        };
+
+        unwrap!(verifier.verify_csr(&info));
+
+        let err = unwrap!(verifier
+            .verify_csr(&CsrInfo {
+                attestation_challenge: &[0; 16],
+                ..info
+            })
+            .err());
+        assert_eq!(err.code(), ErrorCode::InvalidSignature);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_generated_attestation() {
+        use crate::cert::cd::{CertDeclarationContent, CD_FORMAT_VERSION};
+        use crate::cert::devatt::{AttestationCert, DevAttGenerator};
+        use crate::utils::epoch::sys_epoch;
+        use crate::utils::rand::sys_rand;
+
+        /// Build the attestation elements with the provided CD, and sign them with the DAC key
+        fn attest(
+            dac: &AttestationCert,
+            cd: &[u8],
+            elements: &mut [u8],
+            signature: &mut [u8; EC_SIGNATURE_LEN_BYTES],
+        ) -> usize {
+            let mut wb = WriteBuf::new(elements);
+            unwrap!(wb.start_struct(&TLVTag::Anonymous));
+            unwrap!(wb.str(&TLVTag::Context(1), cd));
+            unwrap!(wb.str(&TLVTag::Context(2), &NONCE));
+            unwrap!(wb.u32(&TLVTag::Context(3), 0));
+            unwrap!(wb.end_container());
+            let len = wb.get_tail();
+
+            let mut msg = [0; 1024];
+            msg[..len].copy_from_slice(&elements[..len]);
+            msg[len..len + CHALLENGE.len()].copy_from_slice(&CHALLENGE);
+            unwrap!(dac.key().sign_msg(&msg[..len + CHALLENGE.len()], signature));
+
+            len
+        }
+
+        let generator = DevAttGenerator::new(sys_epoch, sys_rand);
+
+        let paa = unwrap!(generator.generate_paa("Test PAA", Some(0xFFF2)));
+        let pai = unwrap!(generator.generate_pai(&paa, "Test PAI", 0xFFF2, None));
+        let dac = unwrap!(generator.generate_dac(&pai, "Test DAC", 0xFFF2, 0x8001));
+        let other_dac = unwrap!(generator.generate_dac(&pai, "Test DAC", 0xFFF2, 0x8002));
+        let signer = unwrap!(generator.generate_cd_signer("Test CD Signer"));
+
+        let mut cd = [0; 600];
+        let cd_len = unwrap!(signer.sign_cd(
+            &CertDeclarationContent {
+                format_version: CD_FORMAT_VERSION,
+                vendor_id: 0xFFF2,
+                product_id_array: &[0x8000, 0x8001],
+                device_type_id: 0x16,
+                certificate_id: "CSA00000SWC00000-00",
+                security_level: 0,
+                security_information: 0,
+                version_number: 1,
+                certification_type: 0,
+                dac_origin_vendor_id: None,
+                dac_origin_product_id: None,
+                authorized_paa_list: None,
+            },
+            &mut cd
+        ));
+        let cd = &cd[..cd_len];
+
+        let mut signer_pubkey = [0; 65];
+        unwrap!(signer.key().get_public_key(&mut signer_pubkey));
+
+        let paas: &[&[u8]] = &[paa.cert()];
+        let cd_signing_keys: &[(&[u8], &[u8])] = &[(unwrap!(signer.key_id()), &signer_pubkey)];
+        let verifier = DefaultAttestationVerifier::new(
+            StaticTrustStore::new(paas, cd_signing_keys),
+            sys_epoch,
+        );
+
+        let mut elements = [0; 900];
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        let elements_len = attest(&dac, cd, &mut elements, &mut signature);
+
+        let info = AttestationInfo {
+            dac: dac.cert(),
+            pai: pai.cert(),
+            attestation_elements: &elements[..elements_len],
+            attestation_signature: &signature,
+            attestation_nonce: &NONCE,
+            attestation_challenge: &CHALLENGE,
+        };
+
+        unwrap!(verifier.verify_attestation(&info));
+
+        // The nonce of the attestation elements must be the one of the request
+        let err = unwrap!(verifier
+            .verify_attestation(&AttestationInfo {
+                attestation_nonce: &[0; 32],
+                ..info
+            })
+            .err());
+        assert_eq!(err.code(), ErrorCode::InvalidData);
+
+        // The CD signing key must be trusted
+        let err = unwrap!(DefaultAttestationVerifier::new(
+            StaticTrustStore::new(paas, TEST_CD_SIGNING_KEYS),
+            sys_epoch,
+        )
+        .verify_attestation(&info)
+        .err());
+        assert_eq!(err.code(), ErrorCode::NotFound);
+
+        // The PID of the DAC must be covered by the CD
+        let mut elements = [0; 900];
+        let mut signature = [0; EC_SIGNATURE_LEN_BYTES];
+        let elements_len = attest(&other_dac, cd, &mut elements, &mut signature);
+
+        let err = unwrap!(verifier
+            .verify_attestation(&AttestationInfo {
+                dac: other_dac.cert(),
+                pai: pai.cert(),
+                attestation_elements: &elements[..elements_len],
+                attestation_signature: &signature,
+                attestation_nonce: &NONCE,
+                attestation_challenge: &CHALLENGE,
+            })
+            .err());
+        assert_eq!(err.code(), ErrorCode::InvalidData);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dir_trust_store() {
+        use crate::cert::pem::{self, PEM_LABEL_CERTIFICATE};
+
+        use super::{AttestationTrustStore, DirTrustStore};
+
+        let mut dac = [0; 600];
+        let dac_len = fetch(DataType::DAC, &mut dac);
+        let mut pai = [0; 600];
+        let pai_len = fetch(DataType::PAI, &mut pai);
+
+        let dir = std::env::temp_dir().join(format!("rs-matter-paas-{}", std::process::id()));
+        unwrap!(std::fs::create_dir_all(&dir));
+
+        // Stand-ins for PAAs: a CA certificate in PEM and a non-CA one in DER
+        let mut pem = std::string::String::new();
+        unwrap!(pem::encode(
+            PEM_LABEL_CERTIFICATE,
+            &pai[..pai_len],
+            &mut pem
+        ));
+        unwrap!(std::fs::write(dir.join("pai.pem"), pem));
+        unwrap!(std::fs::write(dir.join("dac.der"), &dac[..dac_len]));
+
+        let mut store = DirTrustStore::new();
+        let result = store.load(&dir);
+
+        unwrap!(std::fs::remove_dir_all(&dir));
+        unwrap!(result);
+
+        let pai_key_id = unwrap!(unwrap!(X509CertRef::new(&pai[..pai_len])).subject_key_id());
+        let dac_key_id = unwrap!(unwrap!(X509CertRef::new(&dac[..dac_len])).subject_key_id());
+
+        assert_eq!(store.paa(unwrap!(pai_key_id)), Some(&pai[..pai_len]));
+        assert_eq!(store.paa(unwrap!(dac_key_id)), None);
+    }
+}
@@ -174,8 +174,11 @@ impl KeyPair {
 
     pub fn new_from_public(pub_key: &[u8]) -> Result<Self, Error> {
         let encoded_point = EncodedPoint::from_bytes(pub_key)?;
+        let public_key = Option::from(PublicKey::from_encoded_point(&encoded_point))
+            .ok_or(ErrorCode::InvalidData)?;
+
         Ok(Self {
-            key: KeyType::Public(PublicKey::from_encoded_point(&encoded_point).unwrap()), // TODO: defmt
+            key: KeyType::Public(public_key),
         })
     }
 
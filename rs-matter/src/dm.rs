@@ -21,29 +21,38 @@ use core::num::NonZeroU8;
 use core::pin::pin;
 use core::time::Duration;
 
-use embassy_futures::select::select3;
+use embassy_futures::select::select4;
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
 use embassy_time::{Instant, Timer};
 
+use crate::acl::Accessor;
 use crate::error::*;
+use crate::im::client::{ImClient, ReportHandler};
 use crate::im::{
-    AttrStatus, IMStatusCode, InvReqRef, InvRespTag, OpCode, ReadReqRef, ReportDataReq,
-    ReportDataTag, StatusResp, SubscribeReqRef, SubscribeResp, TimedReq, WriteReqRef, WriteRespTag,
-    PROTO_ID_INTERACTION_MODEL,
+    AttrStatus, EventPath, EventResp, EventStatus, IMStatusCode, InvReqRef, InvRespTag, OpCode,
+    ReadReqRef, ReportDataMsg, ReportDataReq, ReportDataTag, StatusResp, SubscribeReqRef,
+    SubscribeResp, TimedReq, WriteReqRef, WriteRespTag, PROTO_ID_INTERACTION_MODEL,
 };
 use crate::respond::ExchangeHandler;
-use crate::tlv::{get_root_node_struct, FromTLV, TLVElement, TLVTag, TLVWrite, TLVWriter};
+use crate::tlv::{
+    get_root_node_struct, FromTLV, OctetStr, TLVArray, TLVElement, TLVTag, TLVWrite, TLVWriter,
+    TagType, ToTLV,
+};
 use crate::transport::exchange::{Exchange, MAX_EXCHANGE_RX_BUF_SIZE, MAX_EXCHANGE_TX_BUF_SIZE};
 use crate::utils::storage::pooled::BufferAccess;
 use crate::utils::storage::WriteBuf;
+use crate::utils::sync::Notification;
 use crate::Matter;
 
-use subscriptions::Subscriptions;
+use events::{EventEntry, EventSource};
+use subscriptions::{MaxIntPolicy, ReportedDatavers, Subscriptions};
 
 pub use types::*;
 
 pub mod clusters;
 pub mod devices;
 pub mod endpoints;
+pub mod events;
 pub mod networks;
 pub mod subscriptions;
 
@@ -61,9 +70,55 @@ struct SubscriptionBuffer<B> {
     fabric_idx: NonZeroU8,
     peer_node_id: u64,
     subscription_id: u32,
+    event_number: u64,
+    datavers: ReportedDatavers,
     buffer: B,
 }
 
+/// A subscription, as persisted by `DataModel::store_subscriptions`.
+#[derive(FromTLV, ToTLV, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+struct PersistedSubscription<'a> {
+    fabric_idx: u8,
+    peer_node_id: u64,
+    id: u32,
+    min_int_secs: u16,
+    max_int_secs: u16,
+    /// The original subscribe request, which contains the subscribed paths and filters
+    request: OctetStr<'a>,
+}
+
+/// The outcome of reporting data on a subscription.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ReportOutcome {
+    /// The report had been sent to and accepted by the subscriber
+    Sent,
+    /// Nothing had changed since the last report and no keep-alive report was due, so nothing was sent
+    Skipped,
+    /// The report could not be sent or was rejected by the subscriber
+    Aborted,
+}
+
+/// The state of reporting events in a (potentially chunked) report data response.
+struct EventsReport<'a> {
+    events: Option<&'a dyn EventSource>,
+    /// The number of event path statuses reported so far
+    statuses: usize,
+    /// The number of the next event to be considered for reporting
+    event_number: u64,
+}
+
+impl<'a> EventsReport<'a> {
+    const fn new(events: Option<&'a dyn EventSource>, event_number: u64) -> Self {
+        Self {
+            events,
+            statuses: 0,
+            event_number,
+        }
+    }
+}
+
 /// An `ExchangeHandler` implementation capable of handling responder exchanges for the Interaction Model protocol.
 /// The implementation needs a `DataModelHandler` instance to interact with the underlying clusters of the data model.
 pub struct DataModel<'a, const N: usize, B, T>
@@ -74,6 +129,14 @@ where
     subscriptions: &'a Subscriptions<N>,
     subscriptions_buffers: RefCell<heapless::Vec<SubscriptionBuffer<B::Buffer<'a>>, N>>,
     buffers: &'a B,
+    events: Option<&'a dyn EventSource>,
+    report_handler: Option<&'a dyn ReportHandler>,
+    max_int_policy: MaxIntPolicy,
+    /// Whether the set of established subscriptions had changed since they were last persisted
+    subscriptions_changed: Cell<bool>,
+    /// Whether a subscription is being reported on, in which case it is temporarily missing from `subscriptions_buffers`
+    reporting: Cell<bool>,
+    persist_notification: Notification<NoopRawMutex>,
 }
 
 impl<'a, const N: usize, B, T> DataModel<'a, N, B, T>
@@ -97,7 +160,176 @@ where
             subscriptions,
             subscriptions_buffers: RefCell::new(heapless::Vec::new()),
             buffers,
+            events: None,
+            report_handler: None,
+            max_int_policy: MaxIntPolicy::new(),
+            subscriptions_changed: Cell::new(false),
+            reporting: Cell::new(false),
+            persist_notification: Notification::new(),
+        }
+    }
+
+    /// Return the data model with event reporting enabled.
+    ///
+    /// The events emitted into the provided `EventSource` (typically an `Events` instance)
+    /// will be reported in response to the Read and Subscribe requests which contain event paths.
+    /// Without an event source, these requests are answered with no events.
+    pub const fn with_events(mut self, events: &'a dyn EventSource) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Return the data model with a handler for the reports of the subscriptions established by this node as a client
+    /// (see `ImClient::subscribe`).
+    ///
+    /// Without a report handler, all incoming reports are answered with an `InvalidSubscription` status.
+    pub const fn with_report_handler(mut self, report_handler: &'a dyn ReportHandler) -> Self {
+        self.report_handler = Some(report_handler);
+        self
+    }
+
+    /// Return the data model with the provided policy for computing the max interval of the accepted subscriptions.
+    ///
+    /// By default, the max interval ceiling requested by the subscriber is used as-is.
+    pub const fn with_max_int_policy(mut self, max_int_policy: MaxIntPolicy) -> Self {
+        self.max_int_policy = max_int_policy;
+        self
+    }
+
+    /// Load the subscriptions persisted with `store_subscriptions` and schedule their resumption.
+    ///
+    /// Persisting subscriptions is opt-in: unless this method is called (typically on startup, after the fabrics had
+    /// been loaded), subscriptions do not survive a restart of the device.
+    ///
+    /// Loaded subscriptions are resumed by `process_subscriptions`, by sending a priming report to the subscriber
+    /// over a new exchange, as per the subscription resumption procedure of the Matter spec.
+    /// As there are no sessions after a restart, resuming requires an mDNS resolver to be run with
+    /// `Matter::run_resolver`, so that CASE sessions with the subscribers can be established.
+    pub async fn load_subscriptions(&self, data: &[u8]) -> Result<(), Error> {
+        for entry in TLVElement::new(data).array()?.iter() {
+            let sub = PersistedSubscription::from_tlv(&entry?)?;
+            let fabric_idx = NonZeroU8::new(sub.fabric_idx).ok_or(ErrorCode::Invalid)?;
+
+            let Some(mut buffer) = self.buffers.get().await else {
+                warn!(
+                    "No buffer available for resuming subscription [F:{:x},P:{:x}]::{}, dropping it",
+                    fabric_idx, sub.peer_node_id, sub.id
+                );
+                continue;
+            };
+
+            buffer.clear();
+            buffer
+                .extend_from_slice(sub.request.0)
+                .map_err(|_| ErrorCode::NoSpace)?;
+
+            let event_number = {
+                let req = SubscribeReqRef::new(TLVElement::new(&buffer));
+                ReportDataReq::Subscribe(&req).event_min()?
+            };
+
+            if !self.subscriptions.add_resumed(
+                fabric_idx,
+                sub.peer_node_id,
+                sub.id,
+                sub.min_int_secs,
+                sub.max_int_secs,
+            ) {
+                warn!(
+                    "No space for resuming subscription [F:{:x},P:{:x}]::{}, dropping it",
+                    fabric_idx, sub.peer_node_id, sub.id
+                );
+                continue;
+            }
+
+            // Cannot fail, as there is a buffer slot for each subscription `Subscriptions` can track
+            let _ = self
+                .subscriptions_buffers
+                .borrow_mut()
+                .push(SubscriptionBuffer {
+                    fabric_idx,
+                    peer_node_id: sub.peer_node_id,
+                    subscription_id: sub.id,
+                    event_number,
+                    datavers: ReportedDatavers::new(),
+                    buffer,
+                });
+
+            debug!(
+                "Subscription [F:{:x},P:{:x}]::{} loaded, about to resume it",
+                fabric_idx, sub.peer_node_id, sub.id
+            );
         }
+
+        self.subscriptions_changed.set(false);
+        self.subscriptions.notification.notify();
+
+        Ok(())
+    }
+
+    /// Store the established subscriptions into the provided buffer as TLV data.
+    ///
+    /// If the subscriptions have not changed since the last store operation, the
+    /// function returns `None` and does not store the subscriptions.
+    pub fn store_subscriptions<'b>(&self, buf: &'b mut [u8]) -> Result<Option<&'b [u8]>, Error> {
+        if !self.subscriptions_changed.get() {
+            return Ok(None);
+        }
+
+        let mut wb = WriteBuf::new(buf);
+
+        wb.start_array(&TLVTag::Anonymous)?;
+
+        for sb in self.subscriptions_buffers.borrow().iter() {
+            let Some((min_int_secs, max_int_secs)) =
+                self.subscriptions.intervals(sb.subscription_id)
+            else {
+                continue;
+            };
+
+            PersistedSubscription {
+                fabric_idx: sb.fabric_idx.get(),
+                peer_node_id: sb.peer_node_id,
+                id: sb.subscription_id,
+                min_int_secs,
+                max_int_secs,
+                request: OctetStr::new(&sb.buffer),
+            }
+            .to_tlv(&TagType::Anonymous, &mut wb)
+            .map_err(|_| ErrorCode::NoSpace)?;
+        }
+
+        wb.end_container()?;
+
+        // The subscription being reported on is not part of the stored data,
+        // so the subscriptions need to be stored again once the report is done
+        self.subscriptions_changed.set(self.reporting.get());
+
+        let len = wb.get_tail();
+
+        Ok(Some(&buf[..len]))
+    }
+
+    /// Return `true` if the established subscriptions had changed since they were last stored.
+    pub fn subscriptions_changed(&self) -> bool {
+        self.subscriptions_changed.get()
+    }
+
+    /// Wait for the established subscriptions to change in a way that requires persisting them.
+    pub async fn wait_persist_subscriptions(&self) {
+        loop {
+            if self.subscriptions_changed.get() && !self.reporting.get() {
+                break;
+            }
+
+            self.persist_notification.wait().await;
+        }
+    }
+
+    /// Mark the established subscriptions as changed, so that they get persisted.
+    fn notify_subscriptions_changed(&self) {
+        self.subscriptions_changed.set(true);
+        self.persist_notification.notify();
     }
 
     /// Answer a responding exchange using the `DataModelHandler` instance wrapped by this exchange handler.
@@ -123,6 +355,7 @@ where
                 }
                 OpCode::InvokeRequest => self.invoke(exchange, timeout_instant.take()).await?,
                 OpCode::SubscribeRequest => self.subscribe(exchange).await?,
+                OpCode::ReportData => self.report(exchange).await?,
                 OpCode::TimedRequest => {
                     timeout_instant = Some(self.timed(exchange).await?);
                     repeat = true;
@@ -144,6 +377,16 @@ where
         Ok(())
     }
 
+    async fn report(&self, exchange: &mut Exchange<'_>) -> Result<(), Error> {
+        if let Some(report_handler) = self.report_handler {
+            ImClient::handle_report(exchange, report_handler).await
+        } else {
+            warn!("Got a subscription report, but no report handler is configured");
+
+            Self::send_status(exchange, IMStatusCode::InvalidSubscription).await
+        }
+    }
+
     async fn read(&self, exchange: &mut Exchange<'_>) -> Result<(), Error> {
         let Some(mut tx) = self.tx_buffer(exchange).await? else {
             return Ok(());
@@ -179,9 +422,20 @@ where
 
             let node = metadata.node();
             let mut attrs = node.read(&req, &accessor)?.peekable();
+            let mut events = EventsReport::new(self.events, req.event_min()?);
 
             if !req
-                .respond(&self.handler, exchange, None, &mut attrs, &mut wb, true)
+                .respond(
+                    &self.handler,
+                    exchange,
+                    None,
+                    &node,
+                    &mut attrs,
+                    &mut events,
+                    None,
+                    &mut wb,
+                    true,
+                )
                 .await?
             {
                 drop(attrs);
@@ -219,10 +473,21 @@ where
 
         let node = metadata.node();
         let mut attrs = node.read(&req, &accessor)?.peekable();
+        let mut events = EventsReport::new(self.events, req.event_min()?);
 
         loop {
             let more_chunks = req
-                .respond(&self.handler, exchange, None, &mut attrs, &mut wb, true)
+                .respond(
+                    &self.handler,
+                    exchange,
+                    None,
+                    &node,
+                    &mut attrs,
+                    &mut events,
+                    None,
+                    &mut wb,
+                    true,
+                )
                 .await?;
 
             exchange.send(OpCode::ReportData, wb.as_slice()).await?;
@@ -421,6 +686,7 @@ where
             self.subscriptions_buffers
                 .borrow_mut()
                 .retain(|sb| sb.fabric_idx != fabric_idx || sb.peer_node_id != peer_node_id);
+            self.notify_subscriptions_changed();
 
             debug!(
                 "All subscriptions for [F:{:x},P:{:x}] removed",
@@ -428,8 +694,10 @@ where
             );
         }
 
-        let max_int_secs = core::cmp::max(req.max_int_ceil()?, 40); // Say we need at least 4 secs for potential latencies
         let min_int_secs = req.min_int_floor()?;
+        let max_int_secs = self
+            .max_int_policy
+            .max_int_secs(min_int_secs, req.max_int_ceil()?);
 
         let Some(id) = self.subscriptions.add(
             fabric_idx,
@@ -449,7 +717,10 @@ where
             }
         });
 
-        let primed = self
+        let mut event_number = ReportDataReq::Subscribe(&req).event_min()?;
+        let mut datavers = ReportedDatavers::new();
+
+        let outcome = self
             .report_data(
                 id,
                 fabric_idx.get(),
@@ -458,10 +729,13 @@ where
                 &mut tx,
                 exchange,
                 true,
+                true,
+                &mut event_number,
+                &mut datavers,
             )
             .await?;
 
-        if primed {
+        if outcome == ReportOutcome::Sent {
             exchange
                 .send_with(|_, wb| {
                     SubscribeResp::write(wb, id, max_int_secs)?;
@@ -482,10 +756,16 @@ where
                         fabric_idx,
                         peer_node_id,
                         subscription_id: id,
+                        event_number,
+                        datavers,
                         buffer: rx,
                     });
 
                 subscribed.set(true);
+
+                // Have the subscriptions' processing re-schedule its next wakeup
+                self.subscriptions.notification.notify();
+                self.notify_subscriptions_changed();
             }
         }
 
@@ -494,12 +774,35 @@ where
 
     pub async fn process_subscriptions(&self, matter: &Matter<'_>) -> Result<(), Error> {
         loop {
-            // TODO: Un-hardcode these 4 seconds of waiting when the more precise change detection logic is implemented
-            let mut timeout = pin!(Timer::after(embassy_time::Duration::from_secs(4)));
+            let next_due = self.subscriptions.next_due();
+
+            let mut timeout = pin!(async {
+                if let Some(next_due) = next_due {
+                    Timer::at(next_due).await
+                } else {
+                    core::future::pending().await
+                }
+            });
             let mut notification = pin!(self.subscriptions.notification.wait());
             let mut session_removed = pin!(matter.transport_mgr.session_removed.wait());
+            let mut events_emitted = pin!(async {
+                if let Some(events) = self.events {
+                    events.notification().wait().await
+                } else {
+                    core::future::pending().await
+                }
+            });
+
+            select4(
+                &mut notification,
+                &mut timeout,
+                &mut session_removed,
+                &mut events_emitted,
+            )
+            .await;
 
-            select3(&mut notification, &mut timeout, &mut session_removed).await;
+            self.mark_events_changed()?;
+            self.mark_attrs_changed()?;
 
             while let Some((fabric_idx, peer_node_id, session_id, id)) =
                 self.subscriptions.find_removed_session(|session_id| {
@@ -515,6 +818,7 @@ where
                 self.subscriptions_buffers
                     .borrow_mut()
                     .retain(|sb| sb.subscription_id != id);
+                self.notify_subscriptions_changed();
 
                 debug!(
                     "Subscription [F:{:x},P:{:x}]::{} removed since its session ({}) had been removed too",
@@ -533,6 +837,7 @@ where
                 self.subscriptions_buffers
                     .borrow_mut()
                     .retain(|sb| sb.subscription_id != id);
+                self.notify_subscriptions_changed();
 
                 warn!(
                     "Subscription [F:{:x},P:{:x}]::{} removed due to inactivity",
@@ -543,7 +848,7 @@ where
             loop {
                 let sub = self.subscriptions.find_report_due(now);
 
-                if let Some((fabric_idx, peer_node_id, session_id, id)) = sub {
+                if let Some((fabric_idx, peer_node_id, session_id, id, keep_alive)) = sub {
                     debug!(
                         "About to report data for subscription [F:{:x},P:{:x}]::{}",
                         fabric_idx, peer_node_id, id
@@ -551,42 +856,79 @@ where
 
                     let subscribed = Cell::new(false);
 
+                    self.reporting.set(true);
+
                     let _guard = scopeguard::guard((), |_| {
+                        self.reporting.set(false);
+
                         if !subscribed.get() {
                             self.subscriptions.remove(None, None, Some(id));
+                            self.subscriptions_changed.set(true);
                         }
-                    });
 
-                    // TODO: Do a more sophisticated check whether something had actually changed w.r.t. this subscription
+                        if self.subscriptions_changed.get() {
+                            self.persist_notification.notify();
+                        }
+                    });
 
                     let index = unwrap!(self
                         .subscriptions_buffers
                         .borrow()
                         .iter()
                         .position(|sb| sb.subscription_id == id));
-                    let rx = self.subscriptions_buffers.borrow_mut().remove(index).buffer;
+                    let SubscriptionBuffer {
+                        mut event_number,
+                        mut datavers,
+                        buffer: rx,
+                        ..
+                    } = self.subscriptions_buffers.borrow_mut().remove(index);
 
                     let result = self
-                        .process_subscription(matter, fabric_idx, peer_node_id, session_id, id, &rx)
+                        .process_subscription(
+                            matter,
+                            fabric_idx,
+                            peer_node_id,
+                            session_id,
+                            id,
+                            &rx,
+                            keep_alive,
+                            &mut event_number,
+                            &mut datavers,
+                        )
                         .await;
 
-                    match result {
-                        Ok(primed) => {
-                            if primed && self.subscriptions.mark_reported(id) {
-                                let _ = self.subscriptions_buffers.borrow_mut().push(
-                                    SubscriptionBuffer {
-                                        fabric_idx,
-                                        peer_node_id,
-                                        subscription_id: id,
-                                        buffer: rx,
-                                    },
-                                );
-                                subscribed.set(true);
-                            }
+                    let subscription_exists = match result {
+                        Ok(ReportOutcome::Sent) => self.subscriptions.mark_reported(id),
+                        Ok(ReportOutcome::Skipped) => true,
+                        Ok(ReportOutcome::Aborted) => false,
+                        Err(e) if session_id.is_none() => {
+                            warn!(
+                                "Resuming subscription [F:{:x},P:{:x}]::{} failed: {:?}",
+                                fabric_idx, peer_node_id, id, e
+                            );
+
+                            self.subscriptions.resume_failed(id)
                         }
                         Err(e) => {
                             error!("Error while processing subscription: {:?}", e);
+
+                            false
                         }
+                    };
+
+                    if subscription_exists {
+                        let _ = self
+                            .subscriptions_buffers
+                            .borrow_mut()
+                            .push(SubscriptionBuffer {
+                                fabric_idx,
+                                peer_node_id,
+                                subscription_id: id,
+                                event_number,
+                                datavers,
+                                buffer: rx,
+                            });
+                        subscribed.set(true);
                     }
                 } else {
                     break;
@@ -595,6 +937,65 @@ where
         }
     }
 
+    /// Mark as changed all subscriptions which are interested in events
+    /// and for which new events had been emitted since they were last reported.
+    fn mark_events_changed(&self) -> Result<(), Error> {
+        let Some(events) = self.events else {
+            return Ok(());
+        };
+
+        let next_event_number = events.next_event_number();
+
+        for sb in self.subscriptions_buffers.borrow().iter() {
+            if sb.event_number < next_event_number {
+                let req = SubscribeReqRef::new(TLVElement::new(&sb.buffer));
+
+                if req.event_requests()?.is_some() {
+                    self.subscriptions.mark_changed(sb.subscription_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark as changed all subscriptions whose attribute paths cover one of the attributes
+    /// notified as changed via `Subscriptions::notify_attr_changed`.
+    fn mark_attrs_changed(&self) -> Result<(), Error> {
+        let changed_attrs = self.subscriptions.take_changed_attrs();
+        if changed_attrs.is_empty() {
+            return Ok(());
+        }
+
+        for sb in self.subscriptions_buffers.borrow().iter() {
+            let req = SubscribeReqRef::new(TLVElement::new(&sb.buffer));
+
+            let Some(attr_requests) = req.attr_requests()? else {
+                continue;
+            };
+
+            for path in attr_requests {
+                let path = path?;
+
+                let covered = changed_attrs
+                    .iter()
+                    .any(|(endpoint_id, cluster_id, attr_id)| {
+                        path.endpoint.map(|id| id == *endpoint_id).unwrap_or(true)
+                            && path.cluster.map(|id| id == *cluster_id).unwrap_or(true)
+                            && path.attr.map(|id| id == *attr_id).unwrap_or(true)
+                    });
+
+                if covered {
+                    self.subscriptions.mark_changed(sb.subscription_id);
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn process_subscription(
         &self,
         matter: &Matter<'_>,
@@ -603,21 +1004,27 @@ where
         session_id: Option<u32>,
         id: u32,
         rx: &[u8],
-    ) -> Result<bool, Error> {
+        keep_alive: bool,
+        event_number: &mut u64,
+        datavers: &mut ReportedDatavers,
+    ) -> Result<ReportOutcome, Error> {
         let mut exchange = if let Some(session_id) = session_id {
             Exchange::initiate_for_session(matter, session_id)?
         } else {
-            // Commented out as we have issues on HomeKit with that:
-            // https://github.com/ivmarkov/esp-idf-matter/issues/3
-            // Exchange::initiate(matter, fabric_idx, peer_node_id, true).await?
-            Err(ErrorCode::NoSession)?
+            // Only subscriptions loaded from persistent storage have no session, and these are resumed
+            // over a (new) CASE session with the subscriber, which `Exchange::initiate` establishes
+            // with the help of the resolver run with `Matter::run_resolver`.
+            //
+            // Subscriptions whose session is gone are removed rather than re-established over a new session,
+            // as the latter has issues on HomeKit: https://github.com/ivmarkov/esp-idf-matter/issues/3
+            Exchange::initiate(matter, fabric_idx.get(), peer_node_id, true).await?
         };
 
         if let Some(mut tx) = self.buffers.get().await {
             // Always safe as `IMBuffer` is defined to be `MAX_EXCHANGE_RX_BUF_SIZE`, which is bigger than `MAX_EXCHANGE_TX_BUF_SIZE`
             unwrap!(tx.resize_default(MAX_EXCHANGE_TX_BUF_SIZE));
 
-            let primed = self
+            let outcome = self
                 .report_data(
                     id,
                     fabric_idx.get(),
@@ -625,20 +1032,34 @@ where
                     rx,
                     &mut tx,
                     &mut exchange,
-                    false,
+                    // Resuming a subscription is done with a priming report
+                    session_id.is_none(),
+                    keep_alive,
+                    event_number,
+                    datavers,
                 )
                 .await?;
 
+            if session_id.is_none() && outcome == ReportOutcome::Sent {
+                self.subscriptions
+                    .mark_resumed(id, exchange.id().session_id());
+
+                debug!(
+                    "Subscription [F:{:x},P:{:x}]::{} resumed",
+                    fabric_idx, peer_node_id, id
+                );
+            }
+
             exchange.acknowledge().await?;
 
-            Ok(primed)
+            Ok(outcome)
         } else {
             error!(
                 "No TX buffer available for processing subscription [F:{:x},P:{:x}]::{}",
                 fabric_idx, peer_node_id, id
             );
 
-            Ok(false)
+            Ok(ReportOutcome::Aborted)
         }
     }
 
@@ -681,6 +1102,13 @@ where
         }
     }
 
+    /// Report data on a subscription, either as the priming report (`with_dataver = true`)
+    /// or as a subsequent report.
+    ///
+    /// Subsequent reports only contain the clusters whose data version had changed since they were
+    /// last reported, as tracked by `datavers`, and the events emitted since `event_number`.
+    /// If there is nothing to report, the report is skipped altogether, unless `keep_alive` is `true`,
+    /// in which case an empty report is sent.
     #[allow(clippy::too_many_arguments)]
     async fn report_data(
         &self,
@@ -691,7 +1119,10 @@ where
         tx: &mut [u8],
         exchange: &mut Exchange<'_>,
         with_dataver: bool,
-    ) -> Result<bool, Error>
+        keep_alive: bool,
+        event_number: &mut u64,
+        datavers: &mut ReportedDatavers,
+    ) -> Result<ReportOutcome, Error>
     where
         T: DataModelHandler,
     {
@@ -711,6 +1142,8 @@ where
         {
             let node = metadata.node();
             let mut attrs = node.read(&req, &accessor)?.peekable();
+            let mut events = EventsReport::new(self.events, *event_number);
+            let mut first_chunk = true;
 
             loop {
                 let more_chunks = req
@@ -718,12 +1151,35 @@ where
                         &self.handler,
                         exchange,
                         Some(id),
+                        &node,
                         &mut attrs,
+                        &mut events,
+                        Some(datavers),
                         &mut wb,
                         false,
                     )
                     .await?;
 
+                *event_number = events.event_number;
+
+                if first_chunk
+                    && !more_chunks
+                    && !with_dataver
+                    && !keep_alive
+                    && Self::report_empty(wb.as_slice())?
+                {
+                    debug!(
+                        "Nothing changed for subscription [F:{:x},P:{:x}]::{}, skipping report",
+                        fabric_idx, peer_node_id, id
+                    );
+
+                    datavers.commit();
+
+                    return Ok(ReportOutcome::Skipped);
+                }
+
+                first_chunk = false;
+
                 exchange.send(OpCode::ReportData, wb.as_slice()).await?;
 
                 if !Self::recv_status_success(exchange).await? {
@@ -731,7 +1187,7 @@ where
                         "Subscription [F:{:x},P:{:x}]::{} removed during reporting",
                         fabric_idx, peer_node_id, id
                     );
-                    return Ok(false);
+                    return Ok(ReportOutcome::Aborted);
                 }
 
                 if !more_chunks {
@@ -740,7 +1196,25 @@ where
             }
         }
 
-        Ok(true)
+        datavers.commit();
+
+        Ok(ReportOutcome::Sent)
+    }
+
+    /// Check whether the provided report data message contains neither attribute nor event reports.
+    fn report_empty(report: &[u8]) -> Result<bool, Error> {
+        let report = ReportDataMsg::from_tlv(&get_root_node_struct(report)?)?;
+
+        let attrs_empty = report
+            .attr_reports
+            .map(|reports| reports.iter().next().is_none())
+            .unwrap_or(true);
+        let events_empty = report
+            .event_reports
+            .map(|reports| reports.iter().next().is_none())
+            .unwrap_or(true);
+
+        Ok(attrs_empty && events_empty)
     }
 
     async fn rx_buffer(&self, exchange: &mut Exchange<'_>) -> Result<Option<B::Buffer<'a>>, Error> {
@@ -852,12 +1326,16 @@ impl<'a> ReportDataReq<'a> {
     // the end of long reads.
     const LONG_READS_TLV_RESERVE_SIZE: usize = 24;
 
-    pub(crate) async fn respond<T, I>(
+    #[allow(clippy::too_many_arguments)]
+    async fn respond<T, I>(
         &self,
         handler: T,
         exchange: &Exchange<'_>,
         subscription_id: Option<u32>,
+        node: &Node<'_>,
         attrs: &mut Peekable<I>,
+        events: &mut EventsReport<'_>,
+        mut datavers: Option<&mut ReportedDatavers>,
         wb: &mut WriteBuf<'_>,
         suppress_resp: bool,
     ) -> Result<bool, Error>
@@ -891,11 +1369,47 @@ impl<'a> ReportDataReq<'a> {
             tw.start_array(&TLVTag::Context(ReportDataTag::AttributeReports as u8))?;
         }
 
+        let mut attrs_reported = false;
+
         while let Some(item) = attrs.peek() {
             match item {
                 Ok(item) => {
-                    if AttrDataEncoder::handle_read(exchange, item, &handler, &mut tw).await? {
+                    // In subscription reports, filter out the clusters whose data version
+                    // had not changed since they were last reported
+                    let filtered_item;
+                    let item = match (&datavers, item) {
+                        (Some(datavers), Ok(attr))
+                            if matches!(self, ReportDataReq::SubscribeReport(_)) =>
+                        {
+                            filtered_item = Ok(AttrDetails {
+                                dataver: datavers.reported(attr.endpoint_id, attr.cluster_id),
+                                ..attr.clone()
+                            });
+
+                            &filtered_item
+                        }
+                        _ => item,
+                    };
+
+                    let observed_dataver = Cell::new(None);
+
+                    if AttrDataEncoder::handle_read(
+                        exchange,
+                        item,
+                        &handler,
+                        &mut tw,
+                        Some(&observed_dataver),
+                    )
+                    .await?
+                    {
+                        if let (Some(datavers), Ok(attr), Some(dataver)) =
+                            (datavers.as_deref_mut(), item, observed_dataver.get())
+                        {
+                            datavers.observe(attr.endpoint_id, attr.cluster_id, dataver);
+                        }
+
                         attrs.next();
+                        attrs_reported = true;
                     } else {
                         break;
                     }
@@ -907,13 +1421,36 @@ impl<'a> ReportDataReq<'a> {
         }
 
         wb.expand(Self::LONG_READS_TLV_RESERVE_SIZE)?;
-        let tw = wb;
 
         if has_requests {
-            tw.end_container()?;
+            wb.end_container()?;
+        }
+
+        let mut more_chunks = attrs.peek().is_some();
+
+        // Events are reported only once all attributes are reported
+        if !more_chunks && self.event_requests()?.is_some() {
+            if wb.shrink(Self::LONG_READS_TLV_RESERVE_SIZE).is_ok() {
+                let mut tw = TLVWriter::new(wb);
+
+                tw.start_array(&TLVTag::Context(ReportDataTag::EventReports as u8))?;
+
+                more_chunks = !self.respond_events(
+                    node,
+                    &exchange.accessor()?,
+                    events,
+                    !attrs_reported,
+                    &mut tw,
+                )?;
+
+                wb.expand(Self::LONG_READS_TLV_RESERVE_SIZE)?;
+                wb.end_container()?;
+            } else {
+                more_chunks = true;
+            }
         }
 
-        let more_chunks = attrs.peek().is_some();
+        let tw = wb;
 
         if more_chunks {
             tw.bool(&TLVTag::Context(ReportDataTag::MoreChunkedMsgs as u8), true)?;
@@ -927,6 +1464,142 @@ impl<'a> ReportDataReq<'a> {
 
         Ok(more_chunks)
     }
+
+    /// Report the event path statuses and the events matching the request, starting from where
+    /// the previous chunk (if any) had stopped.
+    ///
+    /// Returns `true` if everything had been reported, or `false` if there is no more space
+    /// in the current chunk.
+    fn respond_events<W: TLVWrite>(
+        &self,
+        node: &Node<'_>,
+        accessor: &Accessor<'_>,
+        report: &mut EventsReport<'_>,
+        mut empty: bool,
+        mut tw: W,
+    ) -> Result<bool, Error> {
+        let Some(paths) = self.event_requests()? else {
+            return Ok(true);
+        };
+
+        // First, report the statuses of the non-wildcard paths which are not valid or accessible
+
+        for path in paths.iter().skip(report.statuses) {
+            let path = path?;
+
+            let status = match (path.endpoint, path.cluster, path.event) {
+                (Some(endpoint_id), Some(cluster_id), Some(event_id)) => node
+                    .check_event_access(accessor, endpoint_id, cluster_id, event_id)
+                    .err(),
+                _ => None,
+            };
+
+            if let Some(status) = status {
+                let anchor = tw.get_tail();
+
+                let resp = EventResp::Status(EventStatus::new(&path.to_gp(), status, 0));
+
+                if let Err(err) = resp.to_tlv(&TLVTag::Anonymous, &mut tw) {
+                    tw.rewind_to(anchor);
+
+                    if err.code() != ErrorCode::NoSpace {
+                        return Err(err);
+                    }
+
+                    if !empty {
+                        // Report the status in the next chunk
+                        return Ok(false);
+                    }
+
+                    // The status would not fit even in an empty chunk
+                    warn!("Event path status too large to be reported, skipping");
+                } else {
+                    empty = false;
+                }
+            }
+
+            report.statuses += 1;
+        }
+
+        // Then, report the events themselves
+
+        let Some(events) = report.events else {
+            return Ok(true);
+        };
+
+        let mut done = true;
+
+        events.visit(report.event_number, &mut |entry| {
+            if Self::event_reportable(&paths, node, accessor, entry)? {
+                let anchor = tw.get_tail();
+
+                let resp = EventResp::Data(entry.event_data());
+
+                if let Err(err) = resp.to_tlv(&TLVTag::Anonymous, &mut tw) {
+                    tw.rewind_to(anchor);
+
+                    if err.code() != ErrorCode::NoSpace {
+                        return Err(err);
+                    }
+
+                    if !empty {
+                        // Report the event in the next chunk
+                        done = false;
+                        return Ok(false);
+                    }
+
+                    // The event would not fit even in an empty chunk
+                    warn!("Event {} too large to be reported, skipping", entry.number);
+                } else {
+                    empty = false;
+                }
+            }
+
+            report.event_number = entry.number + 1;
+
+            Ok(true)
+        })?;
+
+        Ok(done)
+    }
+
+    /// Check whether the provided event matches any of the requested paths
+    /// and is accessible by the accessor.
+    fn event_reportable(
+        paths: &TLVArray<'_, EventPath>,
+        node: &Node<'_>,
+        accessor: &Accessor<'_>,
+        entry: &EventEntry,
+    ) -> Result<bool, Error> {
+        if entry
+            .fab_idx
+            .map(|fab_idx| fab_idx.get() != accessor.fab_idx)
+            .unwrap_or(false)
+        {
+            // Fabric-sensitive events are only reported to their own fabric
+            return Ok(false);
+        }
+
+        for path in paths.iter() {
+            let path = path?;
+
+            if path.endpoint.unwrap_or(entry.endpoint_id) == entry.endpoint_id
+                && path.cluster.unwrap_or(entry.cluster_id) == entry.cluster_id
+                && path.event.unwrap_or(entry.event_id) == entry.event_id
+            {
+                return Ok(node
+                    .check_event_access(
+                        accessor,
+                        entry.endpoint_id,
+                        entry.cluster_id,
+                        entry.event_id,
+                    )
+                    .is_ok());
+            }
+        }
+
+        Ok(false)
+    }
 }
 
 impl WriteReqRef<'_> {
@@ -29,30 +29,49 @@ pub mod dev_att;
 pub mod eth_diag;
 pub mod gen_comm;
 pub mod gen_diag;
+pub mod groups;
 pub mod grp_key_mgmt;
+pub mod identify;
 pub mod net_comm;
 pub mod noc;
 pub mod on_off;
+pub mod scenes;
 pub mod thread_diag;
 pub mod unit_testing;
 pub mod wifi_diag;
 
 /// This module imports all system clusters that are used by the `rs-matter` itself.
+///
+/// With the `client` feature enabled, typed clients for these clusters are generated as well.
 pub mod decl {
-    crate::import!(
-        AdministratorCommissioning,
-        AccessControl,
-        BasicInformation,
-        Descriptor,
-        EthernetNetworkDiagnostics,
-        GeneralDiagnostics,
-        GeneralCommissioning,
-        GroupKeyManagement,
-        NetworkCommissioning,
-        OnOff,
-        OperationalCredentials,
-        ThreadNetworkDiagnostics,
-        UnitTesting,
-        WiFiNetworkDiagnostics,
-    );
+    macro_rules! import_system_clusters {
+        ($($options:tt)*) => {
+            crate::import!(
+                AdministratorCommissioning,
+                AccessControl,
+                BasicInformation,
+                Descriptor,
+                EthernetNetworkDiagnostics,
+                GeneralDiagnostics,
+                GeneralCommissioning,
+                GroupKeyManagement,
+                Groups,
+                Identify,
+                NetworkCommissioning,
+                OnOff,
+                OperationalCredentials,
+                ScenesManagement,
+                ThreadNetworkDiagnostics,
+                UnitTesting,
+                WiFiNetworkDiagnostics
+                $($options)*
+            );
+        };
+    }
+
+    #[cfg(feature = "client")]
+    import_system_clusters!(; client);
+
+    #[cfg(not(feature = "client"))]
+    import_system_clusters!();
 }
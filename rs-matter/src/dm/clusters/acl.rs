@@ -138,7 +138,8 @@ impl ClusterHandler for AclHandler {
     const CLUSTER: Cluster<'static> = FULL_CLUSTER
         .with_revision(1)
         .with_attrs(with!(required))
-        .with_cmds(with!());
+        .with_cmds(with!())
+        .with_events(with!());
 
     fn dataver(&self) -> u32 {
         self.dataver.get()
@@ -19,6 +19,8 @@
 
 use core::str::FromStr;
 
+use crate::dm::endpoints::ROOT_ENDPOINT_ID;
+use crate::dm::events::Events;
 use crate::dm::{Cluster, Dataver, InvokeContext, ReadContext, WriteContext};
 use crate::error::{Error, ErrorCode};
 use crate::tlv::{FromTLV, TLVBuilderParent, TLVElement, TLVTag, ToTLV, Utf8StrBuilder};
@@ -27,6 +29,7 @@ use crate::utils::cell::RefCell;
 use crate::utils::init::{init, Init};
 use crate::utils::storage::WriteBuf;
 use crate::with;
+use crate::Matter;
 
 pub use crate::dm::clusters::decl::basic_information::*;
 
@@ -34,7 +37,7 @@ const SUPPORTED_MATTER_SPEC_VERSION: u32 = 0x01000000;
 
 /// Basic infomration which is immutable
 /// (i.e. valid for the lifetime of the device firmware)
-#[derive(Default, Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub struct BasicInfoConfig<'a> {
     pub vid: u16,
     pub pid: u16,
@@ -53,6 +56,43 @@ pub struct BasicInfoConfig<'a> {
     /// Session Idle Interval in ms
     /// If not specified, defaults to 5000
     pub sii: Option<u16>,
+    /// Whether the device supports Matter over TCP, both as a client and as a server
+    /// If set, the device advertises `T=6` in its mDNS TXT records
+    ///
+    /// NOTE: This field was added after the others, so configurations created with a struct literal
+    /// need to set it too, or - better - be based on `BasicInfoConfig::new()`
+    pub tcp: bool,
+}
+
+impl BasicInfoConfig<'_> {
+    /// Create a new `BasicInfoConfig` with all fields set to their defaults.
+    ///
+    /// Unlike `Default::default()`, this can be used in `const` contexts, as in
+    /// `BasicInfoConfig { vid: 0xfff1, pid: 0x8000, ..BasicInfoConfig::new() }`,
+    /// which keeps compiling as new optional fields get added to the configuration.
+    pub const fn new() -> Self {
+        Self {
+            vid: 0,
+            pid: 0,
+            hw_ver: 0,
+            hw_ver_str: "",
+            sw_ver: 0,
+            sw_ver_str: "",
+            serial_no: "",
+            device_name: "",
+            vendor_name: "",
+            product_name: "",
+            sai: None,
+            sii: None,
+            tcp: false,
+        }
+    }
+}
+
+impl Default for BasicInfoConfig<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Mutable basic information
@@ -143,6 +183,21 @@ impl BasicInfoHandler {
         HandlerAdaptor(self)
     }
 
+    /// Emit the `StartUp` event on the root endpoint, with the software version of the provided Matter stack.
+    ///
+    /// Should be called by the application exactly once on node start, before the node
+    /// starts processing interactions. Return the number assigned to the event.
+    pub fn emit_start_up<const D: usize, const I: usize, const C: usize>(
+        events: &Events<D, I, C>,
+        matter: &Matter,
+    ) -> Result<u64, Error> {
+        let sw_ver = matter.dev_det().sw_ver;
+
+        HandlerAdaptor(()).emit_start_up(events, ROOT_ENDPOINT_ID, |builder| {
+            builder.software_version(sw_ver)?.end()
+        })
+    }
+
     fn config<'a>(exchange: &'a Exchange) -> &'a BasicInfoConfig<'a> {
         exchange.matter().dev_det()
     }
@@ -156,7 +211,8 @@ impl ClusterHandler for BasicInfoHandler {
     const CLUSTER: Cluster<'static> = FULL_CLUSTER
         .with_revision(1)
         .with_attrs(with!(required; AttributeId::SerialNumber))
-        .with_cmds(with!());
+        .with_cmds(with!())
+        .with_events(with!(EventId::StartUp));
 
     fn dataver(&self) -> u32 {
         self.0.get()
@@ -50,3 +50,237 @@ where
         (*self).get_devatt_data(data_type, buf)
     }
 }
+
+#[cfg(feature = "std")]
+pub use fileio::*;
+
+#[cfg(feature = "std")]
+pub mod fileio {
+    use std::fs;
+    use std::path::Path;
+    use std::vec::Vec;
+
+    use crate::cert::pem::{
+        self, PEM_LABEL_CERTIFICATE, PEM_LABEL_EC_PRIVATE_KEY, PEM_LABEL_PRIVATE_KEY,
+    };
+    use crate::cert::sec1;
+    use crate::cert::x509::X509CertRef;
+    use crate::crypto::{BIGNUM_LEN_BYTES, EC_POINT_LEN_BYTES};
+    use crate::error::{Error, ErrorCode};
+
+    use super::{DataType, DevAttDataFetcher};
+
+    const PEM_BOUNDARY: &[u8] = b"-----BEGIN";
+
+    /// A `DevAttDataFetcher` implementation, which serves the device attestation
+    /// credentials loaded from files.
+    ///
+    /// The certificates and the DAC key pair can be either in DER or in PEM format.
+    /// The DAC key pair can be either a SEC1 or a PKCS#8 structure.
+    /// The Certification Declaration is always in DER format, as generated by the
+    /// CSA tooling and `cert::devatt`.
+    #[derive(Debug, Clone)]
+    pub struct FileDevAtt {
+        cd: Vec<u8>,
+        pai: Vec<u8>,
+        dac: Vec<u8>,
+        dac_pubkey: [u8; EC_POINT_LEN_BYTES],
+        dac_privkey: [u8; BIGNUM_LEN_BYTES],
+    }
+
+    impl FileDevAtt {
+        /// Load the device attestation credentials from the provided files
+        ///
+        /// # Arguments
+        /// - `cd`: The Certification Declaration
+        /// - `pai`: The PAI certificate
+        /// - `dac`: The DAC certificate
+        /// - `dac_key`: The key pair of the DAC
+        pub fn load(cd: &Path, pai: &Path, dac: &Path, dac_key: &Path) -> Result<Self, Error> {
+            let cd = fs::read(cd)?;
+            let pai = Self::read(pai, &[PEM_LABEL_CERTIFICATE])?;
+            let dac = Self::read(dac, &[PEM_LABEL_CERTIFICATE])?;
+            let dac_key = Self::read(dac_key, &[PEM_LABEL_EC_PRIVATE_KEY, PEM_LABEL_PRIVATE_KEY])?;
+
+            // Fail early on malformed certificates
+            X509CertRef::new(&pai)?;
+            let dac_cert = X509CertRef::new(&dac)?;
+
+            let dac_key = sec1::decode(&dac_key)?;
+
+            let mut dac_pubkey = [0; EC_POINT_LEN_BYTES];
+            dac_key.get_public_key(&mut dac_pubkey)?;
+
+            let mut dac_privkey = [0; BIGNUM_LEN_BYTES];
+            dac_key.get_private_key(&mut dac_privkey)?;
+
+            if dac_cert.pubkey() != dac_pubkey {
+                error!("The DAC key pair does not match the DAC certificate");
+                Err(ErrorCode::InvalidData)?;
+            }
+
+            Ok(Self {
+                cd,
+                pai,
+                dac,
+                dac_pubkey,
+                dac_privkey,
+            })
+        }
+
+        /// Read the DER contents of the provided file, decoding it first if it is in PEM format
+        fn read(path: &Path, pem_labels: &[&str]) -> Result<Vec<u8>, Error> {
+            let data = fs::read(path)?;
+
+            if !data.starts_with(PEM_BOUNDARY) {
+                return Ok(data);
+            }
+
+            let pem = core::str::from_utf8(&data).map_err(|_| ErrorCode::InvalidData)?;
+
+            // The DER contents are always shorter than their PEM encoding
+            let mut der = std::vec![0; data.len()];
+
+            let len = pem_labels
+                .iter()
+                .find_map(|label| pem::decode(label, pem, &mut der).ok())
+                .ok_or(ErrorCode::InvalidData)?;
+
+            der.truncate(len);
+
+            Ok(der)
+        }
+    }
+
+    impl DevAttDataFetcher for FileDevAtt {
+        fn get_devatt_data(&self, data_type: DataType, data: &mut [u8]) -> Result<usize, Error> {
+            let src = match data_type {
+                DataType::CertDeclaration => &self.cd[..],
+                DataType::PAI => &self.pai[..],
+                DataType::DAC => &self.dac[..],
+                DataType::DACPubKey => &self.dac_pubkey[..],
+                DataType::DACPrivKey => &self.dac_privkey[..],
+            };
+
+            let data = data.get_mut(..src.len()).ok_or(ErrorCode::NoSpace)?;
+            data.copy_from_slice(src);
+
+            Ok(src.len())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fs;
+    use std::string::String;
+
+    use crate::cert::cd::CertDeclarationContent;
+    use crate::cert::devatt::DevAttGenerator;
+    use crate::cert::pem::{self, PEM_LABEL_CERTIFICATE, PEM_LABEL_EC_PRIVATE_KEY};
+    use crate::cert::sec1::{self, MAX_KEY_PAIR_DER_LEN};
+    use crate::crypto::{BIGNUM_LEN_BYTES, EC_POINT_LEN_BYTES};
+    use crate::error::ErrorCode;
+    use crate::utils::epoch::sys_epoch;
+    use crate::utils::rand::sys_rand;
+
+    use super::{DataType, DevAttDataFetcher, FileDevAtt};
+
+    #[test]
+    fn test_file_dev_att() {
+        let generator = DevAttGenerator::new(sys_epoch, sys_rand);
+
+        let paa = unwrap!(generator.generate_paa("Test PAA", None));
+        let pai = unwrap!(generator.generate_pai(&paa, "Test PAI", 0xFFF2, None));
+        let dac = unwrap!(generator.generate_dac(&pai, "Test DAC", 0xFFF2, 0x8001));
+        let signer = unwrap!(generator.generate_cd_signer("Test CD Signer"));
+
+        let mut cd = [0; 600];
+        let cd_len = unwrap!(signer.sign_cd(
+            &CertDeclarationContent {
+                format_version: 1,
+                vendor_id: 0xFFF2,
+                product_id_array: &[0x8001],
+                device_type_id: 0x16,
+                certificate_id: "CSA00000SWC00000-00",
+                security_level: 0,
+                security_information: 0,
+                version_number: 1,
+                certification_type: 0,
+                dac_origin_vendor_id: None,
+                dac_origin_product_id: None,
+                authorized_paa_list: None,
+            },
+            &mut cd
+        ));
+
+        let mut dac_key = [0; MAX_KEY_PAIR_DER_LEN];
+        let dac_key_len = unwrap!(sec1::encode(dac.key(), &mut dac_key));
+
+        let mut pai_key = [0; MAX_KEY_PAIR_DER_LEN];
+        let pai_key_len = unwrap!(sec1::encode(pai.key(), &mut pai_key));
+
+        let mut dac_pem = String::new();
+        unwrap!(pem::encode(PEM_LABEL_CERTIFICATE, dac.cert(), &mut dac_pem));
+        let mut dac_key_pem = String::new();
+        unwrap!(pem::encode(
+            PEM_LABEL_EC_PRIVATE_KEY,
+            &dac_key[..dac_key_len],
+            &mut dac_key_pem
+        ));
+
+        let dir = std::env::temp_dir().join(format!("rs-matter-devatt-{}", std::process::id()));
+        unwrap!(fs::create_dir_all(&dir));
+
+        // A mix of DER and PEM files
+        unwrap!(fs::write(dir.join("cd.der"), &cd[..cd_len]));
+        unwrap!(fs::write(dir.join("pai.der"), pai.cert()));
+        unwrap!(fs::write(dir.join("dac.pem"), dac_pem));
+        unwrap!(fs::write(dir.join("dac-key.pem"), dac_key_pem));
+        unwrap!(fs::write(dir.join("pai-key.der"), &pai_key[..pai_key_len]));
+
+        let dev_att = FileDevAtt::load(
+            &dir.join("cd.der"),
+            &dir.join("pai.der"),
+            &dir.join("dac.pem"),
+            &dir.join("dac-key.pem"),
+        );
+        let mismatched = FileDevAtt::load(
+            &dir.join("cd.der"),
+            &dir.join("pai.der"),
+            &dir.join("dac.pem"),
+            &dir.join("pai-key.der"),
+        );
+
+        unwrap!(fs::remove_dir_all(&dir));
+
+        let dev_att = unwrap!(dev_att);
+        assert!(mismatched.is_err());
+
+        let mut buf = [0; 600];
+
+        let len = unwrap!(dev_att.get_devatt_data(DataType::CertDeclaration, &mut buf));
+        assert_eq!(&buf[..len], &cd[..cd_len]);
+        let len = unwrap!(dev_att.get_devatt_data(DataType::PAI, &mut buf));
+        assert_eq!(&buf[..len], pai.cert());
+        let len = unwrap!(dev_att.get_devatt_data(DataType::DAC, &mut buf));
+        assert_eq!(&buf[..len], dac.cert());
+
+        let mut pubkey = [0; EC_POINT_LEN_BYTES];
+        unwrap!(dac.key().get_public_key(&mut pubkey));
+        let len = unwrap!(dev_att.get_devatt_data(DataType::DACPubKey, &mut buf));
+        assert_eq!(&buf[..len], pubkey);
+
+        let mut privkey = [0; BIGNUM_LEN_BYTES];
+        unwrap!(dac.key().get_private_key(&mut privkey));
+        let len = unwrap!(dev_att.get_devatt_data(DataType::DACPrivKey, &mut buf));
+        assert_eq!(&buf[..len], privkey);
+
+        assert_eq!(
+            dev_att
+                .get_devatt_data(DataType::DAC, &mut buf[..10])
+                .map_err(|e| e.code()),
+            Err(ErrorCode::NoSpace)
+        );
+    }
+}
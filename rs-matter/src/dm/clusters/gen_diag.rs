@@ -199,7 +199,8 @@ impl<'a> GenDiagHandler<'a> {
 impl ClusterHandler for GenDiagHandler<'_> {
     const CLUSTER: Cluster<'static> = FULL_CLUSTER
         .with_attrs(with!(required))
-        .with_cmds(with!(CommandId::TestEventTrigger));
+        .with_cmds(with!(CommandId::TestEventTrigger))
+        .with_events(with!());
 
     fn dataver(&self) -> u32 {
         self.dataver.get()
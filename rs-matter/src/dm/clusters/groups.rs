@@ -0,0 +1,445 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! This module contains the implementation of the Groups cluster and its handler.
+//!
+//! The group memberships of the endpoints are stored in the group table of each fabric,
+//! which is also reported by the `GroupTable` attribute of the Group Key Management cluster.
+
+use core::fmt;
+use core::num::NonZeroU8;
+
+use crate::dm::clusters::grp_key_mgmt;
+use crate::dm::endpoints::ROOT_ENDPOINT_ID;
+use crate::dm::{Cluster, Dataver, EndptId, InvokeContext, ReadContext};
+use crate::error::{Error, ErrorCode};
+use crate::fabric::FabricMgr;
+use crate::group_keys::MAX_GROUPS_PER_FABRIC;
+use crate::im::IMStatusCode;
+use crate::tlv::{Nullable, TLVBuilderParent};
+use crate::with;
+
+pub use crate::dm::clusters::decl::groups::*;
+
+/// A trait to which the system implementation of the Groups Matter cluster
+/// delegates for checking whether an endpoint is currently identifying itself,
+/// i.e. whether the `IdentifyTime` attribute of its Identify cluster is non-zero.
+///
+/// Necessary for the `AddGroupIfIdentifying` command. Implemented by the `IdentifyHandler`.
+pub trait IdentifyState {
+    /// Return `true` if the provided endpoint is currently identifying itself.
+    fn is_identifying(&self, endpoint_id: EndptId) -> bool;
+}
+
+impl<T> IdentifyState for &T
+where
+    T: IdentifyState,
+{
+    fn is_identifying(&self, endpoint_id: EndptId) -> bool {
+        (**self).is_identifying(endpoint_id)
+    }
+}
+
+/// A dummy implementation of the `IdentifyState` trait, where no endpoint is ever identifying.
+impl IdentifyState for () {
+    fn is_identifying(&self, _endpoint_id: EndptId) -> bool {
+        false
+    }
+}
+
+/// The system implementation of a handler for the Groups Matter cluster.
+///
+/// One instance of the handler is supposed to be registered for each endpoint
+/// which supports group membership.
+#[derive(Clone)]
+pub struct GroupsHandler<'a> {
+    dataver: Dataver,
+    identify: &'a dyn IdentifyState,
+}
+
+impl fmt::Debug for GroupsHandler<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupsHandler")
+            .field("dataver", &self.dataver)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GroupsHandler<'_> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "GroupsHandler {{ dataver: {:?}, .. }}", self.dataver)
+    }
+}
+
+impl<'a> GroupsHandler<'a> {
+    /// Create a new instance of `GroupsHandler` with the given `Dataver`
+    /// and `IdentifyState` implementation.
+    pub const fn new(dataver: Dataver, identify: &'a dyn IdentifyState) -> Self {
+        Self { dataver, identify }
+    }
+
+    /// Adapt the handler instance to the generic `rs-matter` `Handler` trait
+    pub const fn adapt(self) -> HandlerAdaptor<Self> {
+        HandlerAdaptor(self)
+    }
+
+    /// For unit-testing
+    /// Add the endpoint to the group in the fabric manager
+    fn add_group(
+        &self,
+        fabric_mgr: &mut FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+        name: &str,
+    ) -> Result<(), Error> {
+        let fabric = fabric_mgr.get(fab_idx).ok_or(ErrorCode::NotFound)?;
+
+        if group_id == 0 {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        // Only groups which have a key set assigned can be added
+        if !fabric
+            .group_key_map_iter()
+            .any(|entry| entry.group_id == group_id)
+        {
+            Err(ErrorCode::UnsupportedAccess)?;
+        }
+
+        fabric_mgr.group_add(fab_idx, group_id, endpoint_id, name)
+    }
+
+    /// For unit-testing
+    /// Remove the endpoint from the group in the fabric manager
+    fn remove_group(
+        &self,
+        fabric_mgr: &mut FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+    ) -> Result<(), Error> {
+        if group_id == 0 {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        fabric_mgr.group_remove(fab_idx, group_id, endpoint_id)
+    }
+
+    /// Return the fabric index of the accessing session of the command
+    fn fab_idx(ctx: &InvokeContext<'_>) -> Result<NonZeroU8, Error> {
+        ctx.exchange().with_session(|sess| {
+            NonZeroU8::new(sess.get_local_fabric_idx()).ok_or(ErrorCode::UnsupportedAccess.into())
+        })
+    }
+
+    /// Notify that the `GroupTable` attribute of the Group Key Management cluster has changed
+    ///
+    /// The dataver of that cluster is bumped by its handler, based on the version of the group table.
+    fn notify_group_table_changed(ctx: &InvokeContext<'_>) {
+        ctx.notify_endpoint_cluster_changed(ROOT_ENDPOINT_ID, grp_key_mgmt::FULL_CLUSTER.id);
+    }
+
+    /// Map the result of a group table operation to the status reported in the command responses
+    fn status(result: Result<(), Error>) -> Result<u8, Error> {
+        let status = match result {
+            Ok(()) => IMStatusCode::Success,
+            Err(e) => match e.code() {
+                ErrorCode::NotFound => IMStatusCode::NotFound,
+                ErrorCode::ConstraintError => IMStatusCode::ConstraintError,
+                ErrorCode::UnsupportedAccess => IMStatusCode::UnsupportedAccess,
+                ErrorCode::ResourceExhausted => IMStatusCode::ResourceExhausted,
+                _ => Err(e)?,
+            },
+        };
+
+        Ok(status as _)
+    }
+}
+
+impl ClusterHandler for GroupsHandler<'_> {
+    const CLUSTER: Cluster<'static> = FULL_CLUSTER
+        .with_revision(4)
+        .with_features(Feature::GROUP_NAMES.bits())
+        .with_attrs(with!(required))
+        .with_cmds(with!(
+            CommandId::AddGroup
+                | CommandId::ViewGroup
+                | CommandId::GetGroupMembership
+                | CommandId::RemoveGroup
+                | CommandId::RemoveAllGroups
+                | CommandId::AddGroupIfIdentifying
+        ));
+
+    fn dataver(&self) -> u32 {
+        self.dataver.get()
+    }
+
+    fn dataver_changed(&self) {
+        self.dataver.changed();
+    }
+
+    fn name_support(&self, _ctx: &ReadContext<'_>) -> Result<NameSupportBitmap, Error> {
+        Ok(NameSupportBitmap::GROUP_NAMES)
+    }
+
+    fn handle_add_group<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: AddGroupRequest<'_>,
+        response: AddGroupResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+
+        let status = Self::status(self.add_group(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            group_id,
+            request.group_name()?,
+        ))?;
+
+        if status == IMStatusCode::Success as u8 {
+            Self::notify_group_table_changed(ctx);
+        }
+
+        response.status(status)?.group_id(group_id)?.end()
+    }
+
+    fn handle_view_group<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: ViewGroupRequest<'_>,
+        response: ViewGroupResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+        let fabric = fabric_mgr.get(fab_idx).ok_or(ErrorCode::NotFound)?;
+
+        let entry = fabric
+            .group(group_id)
+            .filter(|entry| entry.endpoints.contains(&ctx.cmd().endpoint_id));
+
+        let status = if group_id == 0 {
+            IMStatusCode::ConstraintError
+        } else if entry.is_none() {
+            IMStatusCode::NotFound
+        } else {
+            IMStatusCode::Success
+        };
+
+        response
+            .status(status as _)?
+            .group_id(group_id)?
+            .group_name(entry.map(|entry| entry.name.as_str()).unwrap_or(""))?
+            .end()
+    }
+
+    fn handle_get_group_membership<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: GetGroupMembershipRequest<'_>,
+        response: GetGroupMembershipResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let endpoint_id = ctx.cmd().endpoint_id;
+
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+        let fabric = fabric_mgr.get(fab_idx).ok_or(ErrorCode::NotFound)?;
+
+        let capacity = MAX_GROUPS_PER_FABRIC - fabric.groups_iter().count();
+
+        let group_list = request.group_list()?;
+        let all = group_list.iter().next().is_none();
+
+        let mut builder = response
+            .capacity(Nullable::some(capacity as _))?
+            .group_list()?;
+
+        for entry in fabric
+            .groups_iter()
+            .filter(|entry| entry.endpoints.contains(&endpoint_id))
+        {
+            // An empty request list means all groups of the endpoint
+            let requested = all
+                || group_list
+                    .iter()
+                    .any(|group_id| group_id.ok() == Some(entry.group_id));
+
+            if requested {
+                builder = builder.push(&entry.group_id)?;
+            }
+        }
+
+        builder.end()?.end()
+    }
+
+    fn handle_remove_group<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: RemoveGroupRequest<'_>,
+        response: RemoveGroupResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+
+        let status = Self::status(self.remove_group(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            group_id,
+        ))?;
+
+        if status == IMStatusCode::Success as u8 {
+            Self::notify_group_table_changed(ctx);
+        }
+
+        response.status(status)?.group_id(group_id)?.end()
+    }
+
+    fn handle_remove_all_groups(&self, ctx: &InvokeContext<'_>) -> Result<(), Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+
+        ctx.exchange()
+            .matter()
+            .fabric_mgr
+            .borrow_mut()
+            .group_remove_all(fab_idx, ctx.cmd().endpoint_id)?;
+
+        Self::notify_group_table_changed(ctx);
+
+        Ok(())
+    }
+
+    fn handle_add_group_if_identifying(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: AddGroupIfIdentifyingRequest<'_>,
+    ) -> Result<(), Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let endpoint_id = ctx.cmd().endpoint_id;
+
+        if !self.identify.is_identifying(endpoint_id) {
+            // Nothing to do, yet the command is still considered successful
+            return Ok(());
+        }
+
+        self.add_group(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            endpoint_id,
+            request.group_id()?,
+            request.group_name()?,
+        )?;
+
+        Self::notify_group_table_changed(ctx);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::acl::tests::{FAB_1, FAB_2};
+    use crate::crypto::KeyPair;
+    use crate::error::ErrorCode;
+    use crate::fabric::FabricMgr;
+    use crate::group_keys::MAX_ENDPOINTS_PER_GROUP;
+    use crate::utils::rand::dummy_rand;
+
+    use super::{Dataver, GroupsHandler};
+
+    #[test]
+    fn add_remove_group() {
+        let mut fab_mgr = FabricMgr::new();
+
+        // Add fabrics with IDs 1 and 2
+        for _ in 0..2 {
+            unwrap!(fab_mgr.add_with_post_init(unwrap!(KeyPair::new(dummy_rand)), |_| Ok(())));
+        }
+
+        for fab_idx in [FAB_1, FAB_2] {
+            unwrap!(fab_mgr.group_key_set_add(fab_idx, 1, &[(&[0x55; 16], 1)]));
+            unwrap!(fab_mgr.group_key_map_add(fab_idx, 1, 1));
+        }
+
+        let handler = GroupsHandler::new(Dataver::new(0), &());
+
+        // Group ID 0 is invalid
+        assert_eq!(
+            unwrap!(handler.add_group(&mut fab_mgr, FAB_1, 1, 0, "").err()).code(),
+            ErrorCode::ConstraintError
+        );
+        // Groups without a key set are not allowed
+        assert_eq!(
+            unwrap!(handler.add_group(&mut fab_mgr, FAB_1, 1, 2, "").err()).code(),
+            ErrorCode::UnsupportedAccess
+        );
+
+        unwrap!(handler.add_group(&mut fab_mgr, FAB_1, 1, 1, "Kitchen"));
+        unwrap!(handler.add_group(&mut fab_mgr, FAB_1, 2, 1, "Kitchen Lights"));
+        unwrap!(handler.add_group(&mut fab_mgr, FAB_2, 1, 1, ""));
+
+        let fabric = unwrap!(fab_mgr.get(FAB_1));
+        let entry = unwrap!(fabric.group(1));
+        assert_eq!(entry.endpoints.as_slice(), &[1, 2]);
+        assert_eq!(entry.name.as_str(), "Kitchen Lights");
+        assert!(fabric.is_group_member(1, 2));
+        assert!(!fabric.is_group_member(1, 3));
+
+        // Groups have a limited number of member endpoints
+        for endpoint_id in 3..=MAX_ENDPOINTS_PER_GROUP as u16 {
+            unwrap!(handler.add_group(&mut fab_mgr, FAB_1, endpoint_id, 1, ""));
+        }
+        assert_eq!(
+            unwrap!(handler
+                .add_group(
+                    &mut fab_mgr,
+                    FAB_1,
+                    MAX_ENDPOINTS_PER_GROUP as u16 + 1,
+                    1,
+                    ""
+                )
+                .err())
+            .code(),
+            ErrorCode::ResourceExhausted
+        );
+        for endpoint_id in 3..=MAX_ENDPOINTS_PER_GROUP as u16 {
+            unwrap!(handler.remove_group(&mut fab_mgr, FAB_1, endpoint_id, 1));
+        }
+
+        // Groups are removed once they have no member endpoints left
+        unwrap!(handler.remove_group(&mut fab_mgr, FAB_1, 1, 1));
+        assert!(unwrap!(fab_mgr.get(FAB_1)).group(1).is_some());
+        assert_eq!(
+            unwrap!(handler.remove_group(&mut fab_mgr, FAB_1, 1, 1).err()).code(),
+            ErrorCode::NotFound
+        );
+        unwrap!(handler.remove_group(&mut fab_mgr, FAB_1, 2, 1));
+        assert!(unwrap!(fab_mgr.get(FAB_1)).group(1).is_none());
+
+        unwrap!(fab_mgr.group_remove_all(FAB_1, 1));
+        assert_eq!(unwrap!(fab_mgr.get(FAB_1)).groups_iter().count(), 0);
+
+        // The groups of the other fabric are not affected
+        assert!(unwrap!(fab_mgr.get(FAB_2)).is_group_member(1, 1));
+    }
+}
@@ -16,34 +16,283 @@
  */
 
 //! This module contains the implementation of the Group Key Management cluster and its handler.
-//! Note that the implementation is currently stubbed out, as `rs-matter` does not support Groups yet.
 
+use core::cell::Cell;
+use core::num::NonZeroU8;
+
+use crate::crypto::SYMM_KEY_LEN_BYTES;
 use crate::dm::{
-    ArrayAttributeRead, ArrayAttributeWrite, Cluster, Dataver, InvokeContext, ReadContext,
+    ArrayAttributeRead, ArrayAttributeWrite, AttrDataEncoder, AttrDetails, Cluster, CmdDataEncoder,
+    Dataver, Handler, InvokeContext, NonBlockingHandler, ReadContext, WriteContext,
 };
 use crate::error::{Error, ErrorCode};
-use crate::tlv::TLVBuilderParent;
+use crate::fabric::{Fabric, FabricMgr};
+use crate::group_keys::{
+    GroupEntry, GroupEpochKey, GroupKeyMapEntry, GroupKeySet, IPK_KEY_SET_ID, MAX_EPOCH_KEYS,
+    MAX_GROUPS_PER_FABRIC, MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC, MAX_GROUP_KEY_SETS_PER_FABRIC,
+};
+use crate::tlv::{Nullable, OctetStr, TLVArray, TLVBuilderParent};
+use crate::utils::storage::Vec;
 use crate::with;
 
 pub use crate::dm::clusters::decl::group_key_management::*;
 
 /// The system implementation of a handler for the Group Key Management Matter cluster.
-/// This is a stub implementation, as `rs-matter` does not support Groups yet.
+///
+/// The group key sets, the group key map and the group table are stored per fabric
+/// in the fabric manager, and are thus persisted together with the fabrics.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GrpKeyMgmtHandler {
     dataver: Dataver,
+    /// The version of the group table of the fabric manager, as of the last operation of the handler
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    group_table_ver: Cell<u32>,
 }
 
 impl GrpKeyMgmtHandler {
     /// Creates a new instance of the `GrpKeyMgmtHandler` with the given `Dataver`.
     pub const fn new(dataver: Dataver) -> Self {
-        Self { dataver }
+        Self {
+            dataver,
+            group_table_ver: Cell::new(0),
+        }
     }
 
     /// Adapt the handler instance to the generic `rs-matter` `Handler` trait
-    pub const fn adapt(self) -> HandlerAdaptor<Self> {
-        HandlerAdaptor(self)
+    pub const fn adapt(self) -> GrpKeyMgmtHandlerAdaptor {
+        GrpKeyMgmtHandlerAdaptor(HandlerAdaptor(self))
+    }
+
+    /// Bump the dataver of the cluster if the group table had changed since the last operation of the handler
+    fn sync_group_table(&self, fabric_mgr: &FabricMgr) {
+        let group_table_ver = fabric_mgr.group_table_ver();
+
+        if self.group_table_ver.replace(group_table_ver) != group_table_ver {
+            self.dataver_changed();
+        }
+    }
+
+    /// For unit-testing
+    /// Read the group key map entries from the fabric manager and write them into the builder
+    fn group_key_map<P: TLVBuilderParent>(
+        &self,
+        fabric_mgr: &FabricMgr,
+        attr: &AttrDetails<'_>,
+        builder: ArrayAttributeRead<GroupKeyMapStructArrayBuilder<P>, GroupKeyMapStructBuilder<P>>,
+    ) -> Result<P, Error> {
+        let mut entries = fabric_mgr
+            .iter()
+            .filter(|fabric| !attr.fab_filter || fabric.fab_idx().get() == attr.fab_idx)
+            .flat_map(|fabric| {
+                fabric
+                    .group_key_map_iter()
+                    .map(|entry| (fabric.fab_idx(), entry))
+            });
+
+        match builder {
+            ArrayAttributeRead::ReadAll(mut builder) => {
+                for (fab_idx, entry) in entries {
+                    builder = entry.read_into(fab_idx, builder.push()?)?;
+                }
+
+                builder.end()
+            }
+            ArrayAttributeRead::ReadOne(index, builder) => {
+                let Some((fab_idx, entry)) = entries.nth(index as usize) else {
+                    return Err(ErrorCode::ConstraintError.into());
+                };
+
+                entry.read_into(fab_idx, builder)
+            }
+        }
+    }
+
+    /// For unit-testing
+    /// Set the group key map entries in the fabric manager
+    fn set_group_key_map(
+        &self,
+        fabric_mgr: &mut FabricMgr,
+        fab_idx: NonZeroU8,
+        value: ArrayAttributeWrite<TLVArray<'_, GroupKeyMapStruct<'_>>, GroupKeyMapStruct<'_>>,
+    ) -> Result<(), Error> {
+        match value {
+            ArrayAttributeWrite::Replace(list) => {
+                // Check the well-formedness of the list first
+                for (index, entry) in list.iter().enumerate() {
+                    let entry = entry?;
+                    let group_id = entry.group_id()?;
+
+                    let duplicate = list.iter().take(index).any(|other| {
+                        other.and_then(|other| other.group_id()).ok() == Some(group_id)
+                    });
+
+                    if group_id == 0 || entry.group_key_set_id()? == IPK_KEY_SET_ID || duplicate {
+                        Err(ErrorCode::ConstraintError)?;
+                    }
+                }
+                if list.iter().count() > MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC {
+                    Err(ErrorCode::ResourceExhausted)?;
+                }
+
+                // Now add everything
+                fabric_mgr.group_key_map_remove_all(fab_idx)?;
+                for entry in list {
+                    let entry = entry?;
+                    fabric_mgr.group_key_map_add(
+                        fab_idx,
+                        entry.group_id()?,
+                        entry.group_key_set_id()?,
+                    )?;
+                }
+            }
+            ArrayAttributeWrite::Add(entry) => {
+                fabric_mgr.group_key_map_add(
+                    fab_idx,
+                    entry.group_id()?,
+                    entry.group_key_set_id()?,
+                )?;
+            }
+            ArrayAttributeWrite::Update(index, entry) => {
+                fabric_mgr.group_key_map_update(
+                    fab_idx,
+                    index as _,
+                    entry.group_id()?,
+                    entry.group_key_set_id()?,
+                )?;
+            }
+            ArrayAttributeWrite::Remove(index) => {
+                fabric_mgr.group_key_map_remove(fab_idx, index as _)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// For unit-testing
+    /// Read the group table entries from the fabric manager and write them into the builder
+    fn group_table<P: TLVBuilderParent>(
+        &self,
+        fabric_mgr: &FabricMgr,
+        attr: &AttrDetails<'_>,
+        builder: ArrayAttributeRead<
+            GroupInfoMapStructArrayBuilder<P>,
+            GroupInfoMapStructBuilder<P>,
+        >,
+    ) -> Result<P, Error> {
+        let mut entries = fabric_mgr
+            .iter()
+            .filter(|fabric| !attr.fab_filter || fabric.fab_idx().get() == attr.fab_idx)
+            .flat_map(|fabric| fabric.groups_iter().map(|entry| (fabric.fab_idx(), entry)));
+
+        match builder {
+            ArrayAttributeRead::ReadAll(mut builder) => {
+                for (fab_idx, entry) in entries {
+                    builder = entry.read_into(fab_idx, builder.push()?)?;
+                }
+
+                builder.end()
+            }
+            ArrayAttributeRead::ReadOne(index, builder) => {
+                let Some((fab_idx, entry)) = entries.nth(index as usize) else {
+                    return Err(ErrorCode::ConstraintError.into());
+                };
+
+                entry.read_into(fab_idx, builder)
+            }
+        }
+    }
+
+    /// For unit-testing
+    /// Validate the provided group key set and store it in the fabric manager
+    fn key_set_write(
+        &self,
+        fabric_mgr: &mut FabricMgr,
+        fab_idx: NonZeroU8,
+        key_set: &GroupKeySetStruct<'_>,
+    ) -> Result<(), Error> {
+        let key_set_id = key_set.group_key_set_id()?;
+        if key_set_id == IPK_KEY_SET_ID {
+            // The IPK key set can only be modified via the Operational Credentials cluster
+            Err(ErrorCode::InvalidCommand)?;
+        }
+
+        if key_set.group_key_security_policy()? != GroupKeySecurityPolicyEnum::TrustFirst {
+            // `CacheAndSync` is only allowed when the feature is supported, which it is not
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        let epoch_keys = [
+            (key_set.epoch_key_0()?, key_set.epoch_start_time_0()?),
+            (key_set.epoch_key_1()?, key_set.epoch_start_time_1()?),
+            (key_set.epoch_key_2()?, key_set.epoch_start_time_2()?),
+        ];
+
+        let mut keys: [(&[u8], u64); MAX_EPOCH_KEYS] = [(&[], 0); MAX_EPOCH_KEYS];
+        let mut len = 0;
+
+        for (index, (epoch_key, start_time)) in epoch_keys.iter().enumerate() {
+            let (epoch_key, start_time) = match (epoch_key.as_opt_ref(), start_time.as_opt_ref()) {
+                (Some(epoch_key), Some(start_time)) => (epoch_key.0, *start_time),
+                // Epoch key 0 is mandatory, and the following epoch keys must be provided
+                // together with their start times, and without gaps
+                (None, None) if index > 0 => break,
+                _ => Err(ErrorCode::InvalidCommand)?,
+            };
+
+            if epoch_key.len() != SYMM_KEY_LEN_BYTES {
+                Err(ErrorCode::ConstraintError)?;
+            }
+
+            // The start time of the first epoch key cannot be 0, and the start times
+            // of the following epoch keys must be strictly increasing
+            if start_time == 0 || len > 0 && start_time <= keys[len - 1].1 {
+                Err(ErrorCode::InvalidCommand)?;
+            }
+
+            keys[len] = (epoch_key, start_time);
+            len += 1;
+        }
+
+        // Make sure the remaining epoch keys are not provided either if a previous one was missing
+        if epoch_keys[len..]
+            .iter()
+            .any(|(epoch_key, start_time)| epoch_key.is_some() || start_time.is_some())
+        {
+            Err(ErrorCode::InvalidCommand)?;
+        }
+
+        fabric_mgr.group_key_set_add(fab_idx, key_set_id, &keys[..len])
+    }
+
+    /// For unit-testing
+    /// Remove the group key set with the provided ID from the fabric manager
+    fn key_set_remove(
+        &self,
+        fabric_mgr: &mut FabricMgr,
+        fab_idx: NonZeroU8,
+        key_set_id: u16,
+    ) -> Result<(), Error> {
+        if key_set_id == IPK_KEY_SET_ID {
+            // The IPK key set cannot be removed
+            Err(ErrorCode::InvalidCommand)?;
+        }
+
+        fabric_mgr.group_key_set_remove(fab_idx, key_set_id)
+    }
+
+    /// Return the fabric index of the accessing session of the command
+    fn fab_idx(ctx: &InvokeContext<'_>) -> Result<NonZeroU8, Error> {
+        ctx.exchange().with_session(|sess| {
+            NonZeroU8::new(sess.get_local_fabric_idx()).ok_or(ErrorCode::UnsupportedAccess.into())
+        })
+    }
+
+    /// Return the fabric of the accessing session of the command
+    fn fabric<'a>(fabric_mgr: &'a FabricMgr, ctx: &InvokeContext<'_>) -> Result<&'a Fabric, Error> {
+        fabric_mgr
+            .get(Self::fab_idx(ctx)?)
+            .ok_or(ErrorCode::NotFound.into())
     }
 }
 
@@ -60,78 +309,551 @@ impl ClusterHandler for GrpKeyMgmtHandler {
 
     fn group_key_map<P: TLVBuilderParent>(
         &self,
-        _ctx: &ReadContext<'_>,
+        ctx: &ReadContext<'_>,
         builder: ArrayAttributeRead<GroupKeyMapStructArrayBuilder<P>, GroupKeyMapStructBuilder<P>>,
     ) -> Result<P, Error> {
-        match builder {
-            ArrayAttributeRead::ReadAll(builder) => builder.end(),
-            ArrayAttributeRead::ReadOne(_, _) => Err(ErrorCode::InvalidAction.into()), // TODO
-        }
+        self.group_key_map(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            ctx.attr(),
+            builder,
+        )
     }
 
     fn group_table<P: TLVBuilderParent>(
         &self,
-        _ctx: &ReadContext<'_>,
+        ctx: &ReadContext<'_>,
         builder: ArrayAttributeRead<
             GroupInfoMapStructArrayBuilder<P>,
             GroupInfoMapStructBuilder<P>,
         >,
     ) -> Result<P, Error> {
-        match builder {
-            ArrayAttributeRead::ReadAll(builder) => builder.end(),
-            ArrayAttributeRead::ReadOne(_, _) => Err(ErrorCode::InvalidAction.into()), // TODO
-        }
+        self.group_table(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            ctx.attr(),
+            builder,
+        )
     }
 
     fn max_groups_per_fabric(&self, _ctx: &ReadContext<'_>) -> Result<u16, Error> {
-        Ok(1)
+        Ok(MAX_GROUPS_PER_FABRIC as _)
     }
 
     fn max_group_keys_per_fabric(&self, _ctx: &ReadContext<'_>) -> Result<u16, Error> {
-        Ok(1)
+        // The IPK key set is counted too
+        Ok((MAX_GROUP_KEY_SETS_PER_FABRIC + 1) as _)
     }
 
     fn set_group_key_map(
         &self,
-        _ctx: &crate::dm::WriteContext<'_>,
-        _value: ArrayAttributeWrite<
-            crate::tlv::TLVArray<'_, GroupKeyMapStruct<'_>>,
-            GroupKeyMapStruct<'_>,
-        >,
+        ctx: &WriteContext<'_>,
+        value: ArrayAttributeWrite<TLVArray<'_, GroupKeyMapStruct<'_>>, GroupKeyMapStruct<'_>>,
     ) -> Result<(), Error> {
-        Ok(())
+        let fab_idx = NonZeroU8::new(ctx.attr().fab_idx).ok_or(ErrorCode::Invalid)?;
+        self.set_group_key_map(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            value,
+        )
     }
 
     fn handle_key_set_write(
         &self,
-        _ctx: &InvokeContext<'_>,
-        _request: KeySetWriteRequest<'_>,
+        ctx: &InvokeContext<'_>,
+        request: KeySetWriteRequest<'_>,
     ) -> Result<(), Error> {
-        Ok(())
+        let fab_idx = Self::fab_idx(ctx)?;
+
+        self.key_set_write(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            &request.group_key_set()?,
+        )
     }
 
     fn handle_key_set_read<P: TLVBuilderParent>(
         &self,
-        _ctx: &InvokeContext<'_>,
-        _request: KeySetReadRequest<'_>,
-        _response: KeySetReadResponseBuilder<P>,
+        ctx: &InvokeContext<'_>,
+        request: KeySetReadRequest<'_>,
+        response: KeySetReadResponseBuilder<P>,
     ) -> Result<P, Error> {
-        Err(ErrorCode::NotFound.into())
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+        let fabric = Self::fabric(&fabric_mgr, ctx)?;
+
+        let key_set_id = request.group_key_set_id()?;
+
+        let builder = response.group_key_set()?;
+
+        let builder = if key_set_id == IPK_KEY_SET_ID {
+            GroupKeySet::ipk(fabric)?.read_into(builder)?
+        } else {
+            fabric
+                .group_key_set(key_set_id)
+                .ok_or(ErrorCode::NotFound)?
+                .read_into(builder)?
+        };
+
+        builder.end()
     }
 
     fn handle_key_set_remove(
         &self,
-        _ctx: &InvokeContext<'_>,
-        _request: KeySetRemoveRequest<'_>,
+        ctx: &InvokeContext<'_>,
+        request: KeySetRemoveRequest<'_>,
     ) -> Result<(), Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+
+        self.key_set_remove(
+            &mut ctx.exchange().matter().fabric_mgr.borrow_mut(),
+            fab_idx,
+            request.group_key_set_id()?,
+        )?;
+
+        // Removing a key set also removes its group key map entries
+        self.dataver_changed();
+
         Ok(())
     }
 
     fn handle_key_set_read_all_indices<P: TLVBuilderParent>(
         &self,
-        _ctx: &InvokeContext<'_>,
+        ctx: &InvokeContext<'_>,
         response: KeySetReadAllIndicesResponseBuilder<P>,
     ) -> Result<P, Error> {
-        response.group_key_set_i_ds()?.end()?.end()
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+        let fabric = Self::fabric(&fabric_mgr, ctx)?;
+
+        let mut builder = response.group_key_set_i_ds()?.push(&IPK_KEY_SET_ID)?;
+
+        for key_set in fabric.group_key_sets_iter() {
+            builder = builder.push(&key_set.id)?;
+        }
+
+        builder.end()?.end()
+    }
+}
+
+/// The adaptor of `GrpKeyMgmtHandler` to the generic `rs-matter` `Handler` trait
+///
+/// The group table is also changed outside of the cluster - by the Groups clusters of the other endpoints,
+/// and by the removal of fabrics - so the dataver of the cluster is synced with the group table before each operation.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GrpKeyMgmtHandlerAdaptor(pub HandlerAdaptor<GrpKeyMgmtHandler>);
+
+impl Handler for GrpKeyMgmtHandlerAdaptor {
+    fn read(&self, ctx: &ReadContext<'_>, encoder: AttrDataEncoder) -> Result<(), Error> {
+        self.0
+             .0
+            .sync_group_table(&ctx.exchange().matter().fabric_mgr.borrow());
+
+        self.0.read(ctx, encoder)
+    }
+
+    fn write(&self, ctx: &WriteContext<'_>) -> Result<(), Error> {
+        self.0
+             .0
+            .sync_group_table(&ctx.exchange().matter().fabric_mgr.borrow());
+
+        self.0.write(ctx)
+    }
+
+    fn invoke(&self, ctx: &InvokeContext<'_>, encoder: CmdDataEncoder) -> Result<(), Error> {
+        self.0
+             .0
+            .sync_group_table(&ctx.exchange().matter().fabric_mgr.borrow());
+
+        self.0.invoke(ctx, encoder)
+    }
+}
+
+impl NonBlockingHandler for GrpKeyMgmtHandlerAdaptor {}
+
+impl GroupKeyMapEntry {
+    /// Write the group key map entry into the provided builder
+    fn read_into<P: TLVBuilderParent>(
+        &self,
+        fab_idx: NonZeroU8,
+        builder: GroupKeyMapStructBuilder<P>,
+    ) -> Result<P, Error> {
+        builder
+            .group_id(self.group_id)?
+            .group_key_set_id(self.key_set_id)?
+            .fabric_index(fab_idx.get())?
+            .end()
+    }
+}
+
+impl GroupEntry {
+    /// Write the group table entry into the provided builder
+    fn read_into<P: TLVBuilderParent>(
+        &self,
+        fab_idx: NonZeroU8,
+        builder: GroupInfoMapStructBuilder<P>,
+    ) -> Result<P, Error> {
+        let mut endpoints = builder.group_id(self.group_id)?.endpoints()?;
+
+        for endpoint_id in &self.endpoints {
+            endpoints = endpoints.push(endpoint_id)?;
+        }
+
+        endpoints
+            .end()?
+            .group_name(Some(self.name.as_str()))?
+            .fabric_index(fab_idx.get())?
+            .end()
+    }
+}
+
+impl GroupKeySet {
+    /// Write the group key set into the provided builder
+    ///
+    /// As per the Matter spec, the epoch keys themselves are never reported,
+    /// only their start times.
+    fn read_into<P: TLVBuilderParent>(
+        &self,
+        builder: GroupKeySetStructBuilder<P>,
+    ) -> Result<P, Error> {
+        let start_time =
+            |index: usize| Nullable::new(self.epoch_keys.get(index).map(|key| key.start_time));
+
+        builder
+            .group_key_set_id(self.id)?
+            .group_key_security_policy(GroupKeySecurityPolicyEnum::TrustFirst)?
+            .epoch_key_0(Nullable::<OctetStr>::none())?
+            .epoch_start_time_0(start_time(0))?
+            .epoch_key_1(Nullable::none())?
+            .epoch_start_time_1(start_time(1))?
+            .epoch_key_2(Nullable::none())?
+            .epoch_start_time_2(start_time(2))?
+            .end()
+    }
+
+    /// Return the IPK key set of the provided fabric
+    ///
+    /// The IPK key set has a single epoch key - the IPK of the fabric - which is
+    /// provisioned with the fabric's NOC, and hence always has a start time of 0.
+    fn ipk(fabric: &Fabric) -> Result<Self, Error> {
+        let mut key_set = Self {
+            id: IPK_KEY_SET_ID,
+            epoch_keys: Vec::new(),
+        };
+
+        key_set
+            .epoch_keys
+            .push(GroupEpochKey::new(
+                fabric.ipk().epoch_key(),
+                0,
+                fabric.compressed_fabric_id(),
+            )?)
+            .map_err(|_| ErrorCode::NoSpace)?;
+
+        Ok(key_set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::KeyPair;
+    use crate::dm::ArrayAttributeWrite;
+    use crate::error::{Error, ErrorCode};
+    use crate::fabric::FabricMgr;
+    use crate::group_keys::{GroupKeyMapEntry, GroupKeySet, IPK_KEY_SET_ID};
+    use crate::tlv::{get_root_node_struct, TLVTag, TLVWrite, TLVWriter};
+    use crate::utils::rand::dummy_rand;
+    use crate::utils::storage::WriteBuf;
+
+    use crate::acl::tests::{FAB_1, FAB_2};
+
+    use super::{ClusterHandler, Dataver, GroupKeyMapStruct, GroupKeySetStruct, GrpKeyMgmtHandler};
+
+    const EPOCH_KEY: &[u8] = &[0x55; 16];
+
+    #[test]
+    fn key_set_write() {
+        let mut fab_mgr = fab_mgr();
+        let handler = GrpKeyMgmtHandler::new(Dataver::new(0));
+
+        let mut write = |id, policy, keys: &[(Option<&[u8]>, Option<u64>)]| {
+            let mut buf = [0; 200];
+            let mut writebuf = WriteBuf::new(&mut buf);
+            write_key_set(&mut writebuf, id, policy, keys);
+
+            let data = unwrap!(get_root_node_struct(writebuf.as_slice()));
+            handler.key_set_write(&mut fab_mgr, FAB_1, &GroupKeySetStruct::new(data))
+        };
+
+        // The IPK key set cannot be written
+        assert_code(
+            write(0, 0, &[(Some(EPOCH_KEY), Some(1))]),
+            ErrorCode::InvalidCommand,
+        );
+        // CacheAndSync is not supported
+        assert_code(
+            write(1, 1, &[(Some(EPOCH_KEY), Some(1))]),
+            ErrorCode::ConstraintError,
+        );
+        // Epoch key 0 is mandatory
+        assert_code(
+            write(1, 0, &[(None, None), (Some(EPOCH_KEY), Some(2))]),
+            ErrorCode::InvalidCommand,
+        );
+        // Epoch start time 0 cannot be 0
+        assert_code(
+            write(1, 0, &[(Some(EPOCH_KEY), Some(0))]),
+            ErrorCode::InvalidCommand,
+        );
+        // Epoch keys need their start times
+        assert_code(
+            write(1, 0, &[(Some(EPOCH_KEY), Some(1)), (Some(EPOCH_KEY), None)]),
+            ErrorCode::InvalidCommand,
+        );
+        // Start times must be increasing
+        assert_code(
+            write(
+                1,
+                0,
+                &[(Some(EPOCH_KEY), Some(2)), (Some(EPOCH_KEY), Some(2))],
+            ),
+            ErrorCode::InvalidCommand,
+        );
+        // No gaps are allowed between epoch keys
+        assert_code(
+            write(
+                1,
+                0,
+                &[
+                    (Some(EPOCH_KEY), Some(1)),
+                    (None, None),
+                    (Some(EPOCH_KEY), Some(3)),
+                ],
+            ),
+            ErrorCode::InvalidCommand,
+        );
+        // Epoch keys must be 16 bytes long
+        assert_code(
+            write(1, 0, &[(Some(&EPOCH_KEY[1..]), Some(1))]),
+            ErrorCode::ConstraintError,
+        );
+
+        unwrap!(write(
+            1,
+            0,
+            &[(Some(EPOCH_KEY), Some(1)), (Some(EPOCH_KEY), Some(2))]
+        ));
+        unwrap!(write(2, 0, &[(Some(EPOCH_KEY), Some(1))]));
+        unwrap!(write(3, 0, &[(Some(EPOCH_KEY), Some(1))]));
+        // Overwrite an existing key set
+        unwrap!(write(1, 0, &[(Some(EPOCH_KEY), Some(5))]));
+
+        // No more space for new key sets
+        assert_code(
+            write(4, 0, &[(Some(EPOCH_KEY), Some(1))]),
+            ErrorCode::ResourceExhausted,
+        );
+
+        let fabric = unwrap!(fab_mgr.get(FAB_1));
+        assert_eq!(fabric.group_key_sets_iter().count(), 3);
+
+        let key_set = unwrap!(fabric.group_key_set(1));
+        assert_eq!(key_set.epoch_keys.len(), 1);
+        assert_eq!(key_set.epoch_keys[0].start_time, 5);
+
+        assert!(unwrap!(fab_mgr.get(FAB_2)).group_key_set(1).is_none());
+    }
+
+    #[test]
+    fn key_set_remove() {
+        let mut fab_mgr = fab_mgr();
+        let handler = GrpKeyMgmtHandler::new(Dataver::new(0));
+
+        unwrap!(fab_mgr.group_key_set_add(FAB_1, 1, &[(EPOCH_KEY, 1)]));
+        unwrap!(fab_mgr.group_key_set_add(FAB_1, 2, &[(EPOCH_KEY, 1)]));
+        unwrap!(fab_mgr.group_key_map_add(FAB_1, 0x101, 1));
+        unwrap!(fab_mgr.group_key_map_add(FAB_1, 0x102, 2));
+
+        // The IPK key set cannot be removed
+        assert_code(
+            handler.key_set_remove(&mut fab_mgr, FAB_1, 0),
+            ErrorCode::InvalidCommand,
+        );
+        // Key sets of other fabrics are not visible
+        assert_code(
+            handler.key_set_remove(&mut fab_mgr, FAB_2, 1),
+            ErrorCode::NotFound,
+        );
+
+        unwrap!(handler.key_set_remove(&mut fab_mgr, FAB_1, 1));
+
+        // The group key map entries of the removed key set are removed too
+        let fabric = unwrap!(fab_mgr.get(FAB_1));
+        assert!(fabric.group_key_set(1).is_none());
+        assert_eq!(
+            fabric.group_key_map_iter().collect::<heapless::Vec<_, 4>>(),
+            [&GroupKeyMapEntry {
+                group_id: 0x102,
+                key_set_id: 2
+            }]
+        );
+
+        assert_code(
+            handler.key_set_remove(&mut fab_mgr, FAB_1, 1),
+            ErrorCode::NotFound,
+        );
+    }
+
+    #[test]
+    fn group_table_dataver() {
+        let mut fab_mgr = fab_mgr();
+        let handler = GrpKeyMgmtHandler::new(Dataver::new(0));
+
+        handler.sync_group_table(&fab_mgr);
+        assert_eq!(handler.dataver(), 0);
+
+        // Changes of the group table outside of the cluster bump its dataver
+        unwrap!(fab_mgr.group_add(FAB_1, 1, 1, ""));
+        handler.sync_group_table(&fab_mgr);
+        assert_eq!(handler.dataver(), 1);
+
+        handler.sync_group_table(&fab_mgr);
+        assert_eq!(handler.dataver(), 1);
+
+        unwrap!(fab_mgr.remove(FAB_1, &mut || ()));
+        handler.sync_group_table(&fab_mgr);
+        assert_eq!(handler.dataver(), 2);
+    }
+
+    #[test]
+    fn ipk_key_set() {
+        let fab_mgr = fab_mgr();
+        let fabric = unwrap!(fab_mgr.get(FAB_1));
+
+        let key_set = unwrap!(GroupKeySet::ipk(fabric));
+
+        assert_eq!(key_set.id, IPK_KEY_SET_ID);
+        assert_eq!(key_set.epoch_keys.len(), 1);
+        assert_eq!(key_set.epoch_keys[0].start_time, 0);
+        assert_eq!(
+            key_set.epoch_keys[0].keys.epoch_key(),
+            fabric.ipk().epoch_key()
+        );
+    }
+
+    #[test]
+    fn group_key_map_write() {
+        let mut fab_mgr = fab_mgr();
+        let handler = GrpKeyMgmtHandler::new(Dataver::new(0));
+
+        let mut buf = [0; 200];
+
+        let mut add = |fab_mgr: &mut FabricMgr, fab_idx, group_id, key_set_id| {
+            let mut writebuf = WriteBuf::new(&mut buf);
+            write_key_map_entry(&mut writebuf, group_id, key_set_id);
+
+            let data = unwrap!(get_root_node_struct(writebuf.as_slice()));
+            handler.set_group_key_map(
+                fab_mgr,
+                fab_idx,
+                ArrayAttributeWrite::Add(GroupKeyMapStruct::new(data)),
+            )
+        };
+
+        unwrap!(add(&mut fab_mgr, FAB_2, 0x101, 1));
+        unwrap!(add(&mut fab_mgr, FAB_1, 0x101, 1));
+        unwrap!(add(&mut fab_mgr, FAB_1, 0x102, 2));
+
+        // The IPK key set cannot be mapped
+        assert_code(
+            add(&mut fab_mgr, FAB_1, 0x103, 0),
+            ErrorCode::ConstraintError,
+        );
+        // A group can only be mapped once per fabric
+        assert_code(
+            add(&mut fab_mgr, FAB_1, 0x101, 2),
+            ErrorCode::ConstraintError,
+        );
+
+        // Indices are relative to the fabric
+        unwrap!(handler.set_group_key_map(&mut fab_mgr, FAB_1, ArrayAttributeWrite::Remove(0)));
+
+        assert_eq!(
+            unwrap!(fab_mgr.get(FAB_1))
+                .group_key_map_iter()
+                .collect::<heapless::Vec<_, 4>>(),
+            [&GroupKeyMapEntry {
+                group_id: 0x102,
+                key_set_id: 2
+            }]
+        );
+        assert_eq!(
+            unwrap!(fab_mgr.get(FAB_2))
+                .group_key_map_iter()
+                .collect::<heapless::Vec<_, 4>>(),
+            [&GroupKeyMapEntry {
+                group_id: 0x101,
+                key_set_id: 1
+            }]
+        );
+
+        assert_code(
+            handler.set_group_key_map(&mut fab_mgr, FAB_1, ArrayAttributeWrite::Remove(1)),
+            ErrorCode::NotFound,
+        );
+    }
+
+    fn fab_mgr() -> FabricMgr {
+        let mut fab_mgr = FabricMgr::new();
+
+        // Add fabrics with IDs 1 and 2
+        for _ in 0..2 {
+            unwrap!(fab_mgr.add_with_post_init(unwrap!(KeyPair::new(dummy_rand)), |_| Ok(())));
+        }
+
+        fab_mgr
+    }
+
+    fn assert_code(result: Result<(), Error>, code: ErrorCode) {
+        assert_eq!(unwrap!(result.err()).code(), code);
+    }
+
+    fn write_key_set(
+        writebuf: &mut WriteBuf<'_>,
+        id: u16,
+        policy: u8,
+        keys: &[(Option<&[u8]>, Option<u64>)],
+    ) {
+        let mut tw = TLVWriter::new(writebuf);
+
+        unwrap!(tw.start_struct(&TLVTag::Anonymous));
+        unwrap!(tw.u16(&TLVTag::Context(0), id));
+        unwrap!(tw.u8(&TLVTag::Context(1), policy));
+
+        for index in 0..3 {
+            let (epoch_key, start_time) = keys.get(index).copied().unwrap_or((None, None));
+            let key_tag = TLVTag::Context(2 + index as u8 * 2);
+            let start_time_tag = TLVTag::Context(3 + index as u8 * 2);
+
+            if let Some(epoch_key) = epoch_key {
+                unwrap!(tw.str(&key_tag, epoch_key));
+            } else {
+                unwrap!(tw.null(&key_tag));
+            }
+
+            if let Some(start_time) = start_time {
+                unwrap!(tw.u64(&start_time_tag, start_time));
+            } else {
+                unwrap!(tw.null(&start_time_tag));
+            }
+        }
+
+        unwrap!(tw.end_container());
+    }
+
+    fn write_key_map_entry(writebuf: &mut WriteBuf<'_>, group_id: u16, key_set_id: u16) {
+        let mut tw = TLVWriter::new(writebuf);
+
+        unwrap!(tw.start_struct(&TLVTag::Anonymous));
+        unwrap!(tw.u16(&TLVTag::Context(1), group_id));
+        unwrap!(tw.u16(&TLVTag::Context(2), key_set_id));
+        unwrap!(tw.end_container());
     }
 }
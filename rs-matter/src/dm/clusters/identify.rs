@@ -0,0 +1,308 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! This module contains the implementation of the Identify cluster and its handler.
+//!
+//! The handler counts down the `IdentifyTime` attribute in its `run` method, and notifies
+//! the application of the identification state changes and of the requested effects,
+//! so that the application can e.g. blink a LED.
+
+use core::cell::Cell;
+use core::fmt;
+
+use embassy_futures::select::{select, Either};
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_time::{Duration, Timer};
+
+use crate::dm::clusters::groups::IdentifyState;
+use crate::dm::subscriptions::Subscriptions;
+use crate::dm::{Cluster, Dataver, EndptId, InvokeContext, ReadContext, WriteContext};
+use crate::error::Error;
+use crate::utils::sync::{Notification, Signal};
+use crate::with;
+
+pub use crate::dm::clusters::decl::identify::*;
+
+/// An identification event the application is supposed to render
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IdentifyEvent {
+    /// The endpoint started identifying itself
+    Started,
+    /// The endpoint stopped identifying itself
+    Stopped,
+    /// An effect was requested with the `TriggerEffect` command
+    Effect {
+        /// The requested effect
+        effect: EffectIdentifierEnum,
+        /// The variant of the requested effect
+        variant: EffectVariantEnum,
+    },
+}
+
+/// The system implementation of a handler for the Identify Matter cluster.
+///
+/// One instance of the handler is supposed to be registered for each endpoint
+/// which needs to support identification.
+///
+/// For the `IdentifyTime` attribute to count down, the `run` method of the handler needs to be
+/// polled, and the application is supposed to render the events returned by `wait_event`.
+pub struct IdentifyHandler {
+    dataver: Dataver,
+    endpoint_id: EndptId,
+    identify_type: IdentifyTypeEnum,
+    identify_time: Cell<u16>,
+    time_changed: Notification<NoopRawMutex>,
+    event: Signal<NoopRawMutex, Option<IdentifyEvent>>,
+}
+
+impl fmt::Debug for IdentifyHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentifyHandler")
+            .field("dataver", &self.dataver)
+            .field("endpoint_id", &self.endpoint_id)
+            .field("identify_type", &self.identify_type)
+            .field("identify_time", &self.identify_time.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for IdentifyHandler {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "IdentifyHandler {{ dataver: {:?}, endpoint_id: {}, identify_type: {:?}, identify_time: {}, .. }}",
+            self.dataver,
+            self.endpoint_id,
+            self.identify_type,
+            self.identify_time.get(),
+        )
+    }
+}
+
+impl IdentifyHandler {
+    /// Create a new instance of `IdentifyHandler` with the given `Dataver`,
+    /// the ID of the endpoint it is registered on and the type of the identification.
+    pub const fn new(
+        dataver: Dataver,
+        endpoint_id: EndptId,
+        identify_type: IdentifyTypeEnum,
+    ) -> Self {
+        Self {
+            dataver,
+            endpoint_id,
+            identify_type,
+            identify_time: Cell::new(0),
+            time_changed: Notification::new(),
+            event: Signal::new(None),
+        }
+    }
+
+    /// Adapt the handler instance to the generic `rs-matter` `Handler` trait
+    pub const fn adapt(self) -> HandlerAdaptor<Self> {
+        HandlerAdaptor(self)
+    }
+
+    /// Return the remaining identification time in seconds, with 0 meaning that
+    /// the endpoint is not identifying itself.
+    pub fn identify_time(&self) -> u16 {
+        self.identify_time.get()
+    }
+
+    /// Identify the endpoint for the provided number of seconds, with 0 stopping the identification.
+    ///
+    /// Note that subscribers are not notified by this method.
+    pub fn identify_for(&self, time: u16) {
+        self.update_identify_time(time);
+
+        // Restart the countdown
+        self.time_changed.notify();
+    }
+
+    /// Wait for the next identification event to render.
+    ///
+    /// Only the latest event is kept, so events which are not awaited in time
+    /// are superseded by the newer ones.
+    pub async fn wait_event(&self) -> IdentifyEvent {
+        self.event.wait(|event| event.take()).await
+    }
+
+    /// Run the countdown of the `IdentifyTime` attribute, notifying the provided
+    /// subscriptions each second that the attribute has changed so that it is reported.
+    ///
+    /// The method never returns, unless an error occurs.
+    pub async fn run<const N: usize>(&self, subscriptions: &Subscriptions<N>) -> Result<(), Error> {
+        loop {
+            if self.identify_time.get() == 0 {
+                self.time_changed.wait().await;
+                continue;
+            }
+
+            let tick = Timer::after(Duration::from_secs(1));
+
+            if let Either::Second(_) = select(self.time_changed.wait(), tick).await {
+                self.update_identify_time(self.identify_time.get() - 1);
+                subscriptions.notify_attr_changed(
+                    self.endpoint_id,
+                    Self::CLUSTER.id,
+                    AttributeId::IdentifyTime as _,
+                );
+            }
+        }
+    }
+
+    /// Update the remaining identification time and signal the identification state changes
+    fn update_identify_time(&self, time: u16) {
+        let prev = self.identify_time.replace(time);
+
+        if prev != time {
+            self.dataver.changed();
+        }
+
+        if prev == 0 && time > 0 {
+            self.signal(IdentifyEvent::Started);
+        } else if prev > 0 && time == 0 {
+            self.signal(IdentifyEvent::Stopped);
+        }
+    }
+
+    /// For unit-testing
+    /// Process the requested effect
+    fn trigger_effect(&self, effect: EffectIdentifierEnum, variant: EffectVariantEnum) {
+        match effect {
+            // Stop the identification after the current effect cycle
+            EffectIdentifierEnum::FinishEffect if self.identify_time.get() > 0 => {
+                self.identify_for(1)
+            }
+            EffectIdentifierEnum::StopEffect if self.identify_time.get() > 0 => {
+                self.identify_for(0)
+            }
+            _ => self.signal(IdentifyEvent::Effect { effect, variant }),
+        }
+    }
+
+    fn signal(&self, event: IdentifyEvent) {
+        self.event.modify(|state| {
+            *state = Some(event);
+            (true, ())
+        });
+    }
+}
+
+impl IdentifyState for IdentifyHandler {
+    fn is_identifying(&self, endpoint_id: EndptId) -> bool {
+        self.endpoint_id == endpoint_id && self.identify_time.get() > 0
+    }
+}
+
+impl ClusterHandler for IdentifyHandler {
+    const CLUSTER: Cluster<'static> = FULL_CLUSTER
+        .with_revision(4)
+        .with_attrs(with!(required))
+        .with_cmds(with!(CommandId::Identify | CommandId::TriggerEffect));
+
+    fn dataver(&self) -> u32 {
+        self.dataver.get()
+    }
+
+    fn dataver_changed(&self) {
+        self.dataver.changed();
+    }
+
+    fn identify_time(&self, _ctx: &ReadContext<'_>) -> Result<u16, Error> {
+        Ok(self.identify_time.get())
+    }
+
+    fn identify_type(&self, _ctx: &ReadContext<'_>) -> Result<IdentifyTypeEnum, Error> {
+        Ok(self.identify_type)
+    }
+
+    fn set_identify_time(&self, ctx: &WriteContext<'_>, value: u16) -> Result<(), Error> {
+        self.identify_for(value);
+        ctx.notify_changed();
+
+        Ok(())
+    }
+
+    fn handle_identify(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: IdentifyRequest<'_>,
+    ) -> Result<(), Error> {
+        self.identify_for(request.identify_time()?);
+        ctx.notify_changed();
+
+        Ok(())
+    }
+
+    fn handle_trigger_effect(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: TriggerEffectRequest<'_>,
+    ) -> Result<(), Error> {
+        self.trigger_effect(request.effect_identifier()?, request.effect_variant()?);
+        ctx.notify_changed();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embassy_futures::block_on;
+
+    use crate::dm::clusters::groups::IdentifyState;
+
+    use super::{
+        Dataver, EffectIdentifierEnum, EffectVariantEnum, IdentifyEvent, IdentifyHandler,
+        IdentifyTypeEnum,
+    };
+
+    #[test]
+    fn identify() {
+        let handler = IdentifyHandler::new(Dataver::new(0), 1, IdentifyTypeEnum::LightOutput);
+
+        assert!(!handler.is_identifying(1));
+
+        handler.identify_for(10);
+        assert!(handler.is_identifying(1));
+        assert!(!handler.is_identifying(2));
+        assert_eq!(block_on(handler.wait_event()), IdentifyEvent::Started);
+
+        handler.trigger_effect(EffectIdentifierEnum::Blink, EffectVariantEnum::Default);
+        assert_eq!(
+            block_on(handler.wait_event()),
+            IdentifyEvent::Effect {
+                effect: EffectIdentifierEnum::Blink,
+                variant: EffectVariantEnum::Default
+            }
+        );
+
+        // Finishing the effect leaves one second of identification
+        handler.trigger_effect(
+            EffectIdentifierEnum::FinishEffect,
+            EffectVariantEnum::Default,
+        );
+        assert_eq!(handler.identify_time(), 1);
+
+        handler.trigger_effect(EffectIdentifierEnum::StopEffect, EffectVariantEnum::Default);
+        assert_eq!(handler.identify_time(), 0);
+        assert!(!handler.is_identifying(1));
+        assert_eq!(block_on(handler.wait_event()), IdentifyEvent::Stopped);
+    }
+}
@@ -22,7 +22,8 @@
 
 use core::cell::Cell;
 
-use crate::dm::{Cluster, Dataver, InvokeContext, ReadContext};
+use crate::dm::clusters::scenes::{SceneAttr, SceneAttrValue, SceneCluster};
+use crate::dm::{AttrId, Cluster, ClusterId, Dataver, InvokeContext, ReadContext};
 use crate::error::{Error, ErrorCode};
 use crate::with;
 
@@ -129,3 +130,32 @@ impl ClusterHandler for OnOffHandler {
         Err(ErrorCode::InvalidCommand.into())
     }
 }
+
+impl SceneCluster for OnOffHandler {
+    fn cluster_id(&self) -> ClusterId {
+        FULL_CLUSTER.id
+    }
+
+    fn export_scene(&self, f: &mut dyn FnMut(SceneAttr) -> Result<(), Error>) -> Result<(), Error> {
+        f(SceneAttr::new(
+            AttributeId::OnOff as _,
+            SceneAttrValue::U8(self.on.get() as _),
+        ))
+    }
+
+    fn apply_scene(&self, attrs: &[SceneAttr], _transition_time_ms: u32) -> Result<bool, Error> {
+        let mut changed = false;
+
+        // Transitions are not supported, so the values are applied immediately
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.attr_id == AttributeId::OnOff as AttrId)
+        {
+            let on = attr.value.as_unsigned().ok_or(ErrorCode::InvalidData)? != 0;
+
+            changed |= self.set(on);
+        }
+
+        Ok(changed)
+    }
+}
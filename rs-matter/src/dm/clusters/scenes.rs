@@ -0,0 +1,1648 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! This module contains the implementation of the Scenes Management cluster and its handler.
+//!
+//! The scene table of an endpoint is kept by the handler registered on that endpoint.
+//! The values of the scene-capable attributes of the other clusters on the endpoint
+//! are captured and applied via the `SceneCluster` trait, which the handlers of these
+//! clusters (e.g. `OnOffHandler`) implement.
+//!
+//! The scene table survives reboots by being persisted with `ScenesHandler::store`
+//! and restored with `ScenesHandler::load` (see also `Psm::store_scenes` and `Psm::load_scenes`).
+
+use core::fmt;
+use core::num::NonZeroU8;
+
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+use heapless::String;
+
+use crate::dm::{
+    ArrayAttributeRead, AttrDetails, AttrId, Cluster, ClusterId, Dataver, EndptId, InvokeContext,
+    ReadContext,
+};
+use crate::error::{Error, ErrorCode};
+use crate::fabric::{FabricMgr, MAX_SUPPORTED_FABRICS};
+use crate::im::IMStatusCode;
+use crate::tlv::{FromTLV, Nullable, TLVBuilderParent, TLVElement, TLVTag, TLVWrite, ToTLV, TLV};
+use crate::utils::cell::RefCell;
+use crate::utils::storage::{Vec, WriteBuf};
+use crate::utils::sync::Notification;
+use crate::with;
+
+pub use crate::dm::clusters::decl::scenes_management::*;
+
+/// The default number of entries in the scene table of an endpoint,
+/// which is also the minimum required by the Matter spec
+pub const DEFAULT_SCENE_TABLE_SIZE: usize = 16;
+/// Max length of a scene name, as per the Matter spec
+pub const MAX_SCENE_NAME_LEN: usize = 16;
+/// Max number of clusters whose attributes can be stored in a scene
+pub const MAX_SCENE_CLUSTERS: usize = 4;
+/// Max number of attributes of a cluster which can be stored in a scene
+pub const MAX_SCENE_ATTRS_PER_CLUSTER: usize = 4;
+
+/// Max transition time of a scene in milliseconds, as per the Matter spec
+const MAX_TRANSITION_TIME_MS: u32 = 60_000_000;
+
+/// The value of a scene-capable attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SceneAttrValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+}
+
+impl SceneAttrValue {
+    /// Return the value as an unsigned integer, if it is unsigned
+    pub const fn as_unsigned(&self) -> Option<u64> {
+        match *self {
+            Self::U8(v) => Some(v as _),
+            Self::U16(v) => Some(v as _),
+            Self::U32(v) => Some(v as _),
+            Self::U64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Return the value as a signed integer, if it is signed
+    pub const fn as_signed(&self) -> Option<i64> {
+        match *self {
+            Self::I8(v) => Some(v as _),
+            Self::I16(v) => Some(v as _),
+            Self::I32(v) => Some(v as _),
+            Self::I64(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// A scene-capable attribute and its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SceneAttr {
+    /// The ID of the attribute
+    pub attr_id: AttrId,
+    /// The value of the attribute
+    pub value: SceneAttrValue,
+}
+
+impl SceneAttr {
+    /// Create a new scene attribute with the given ID and value
+    pub const fn new(attr_id: AttrId, value: SceneAttrValue) -> Self {
+        Self { attr_id, value }
+    }
+}
+
+/// Same layout as the `AttributeValuePairStruct` of the cluster:
+/// the attribute ID with tag 0, and the value with the tag of its type (1 to 8)
+impl<'a> FromTLV<'a> for SceneAttr {
+    fn from_tlv(element: &TLVElement<'a>) -> Result<Self, Error> {
+        let mut attr_id = None;
+        let mut value = None;
+
+        for field in element.structure()?.iter() {
+            let field = field?;
+
+            match field.ctx()? {
+                0 => attr_id = Some(field.u32()?),
+                1 => value = Some(SceneAttrValue::U8(field.u8()?)),
+                2 => value = Some(SceneAttrValue::I8(field.i8()?)),
+                3 => value = Some(SceneAttrValue::U16(field.u16()?)),
+                4 => value = Some(SceneAttrValue::I16(field.i16()?)),
+                5 => value = Some(SceneAttrValue::U32(field.u32()?)),
+                6 => value = Some(SceneAttrValue::I32(field.i32()?)),
+                7 => value = Some(SceneAttrValue::U64(field.u64()?)),
+                8 => value = Some(SceneAttrValue::I64(field.i64()?)),
+                _ => (),
+            }
+        }
+
+        Ok(Self::new(
+            attr_id.ok_or(ErrorCode::Invalid)?,
+            value.ok_or(ErrorCode::Invalid)?,
+        ))
+    }
+}
+
+impl ToTLV for SceneAttr {
+    fn to_tlv<W: TLVWrite>(&self, tag: &TLVTag, mut tw: W) -> Result<(), Error> {
+        tw.start_struct(tag)?;
+
+        tw.u32(&TLVTag::Context(0), self.attr_id)?;
+
+        match self.value {
+            SceneAttrValue::U8(v) => tw.u8(&TLVTag::Context(1), v),
+            SceneAttrValue::I8(v) => tw.i8(&TLVTag::Context(2), v),
+            SceneAttrValue::U16(v) => tw.u16(&TLVTag::Context(3), v),
+            SceneAttrValue::I16(v) => tw.i16(&TLVTag::Context(4), v),
+            SceneAttrValue::U32(v) => tw.u32(&TLVTag::Context(5), v),
+            SceneAttrValue::I32(v) => tw.i32(&TLVTag::Context(6), v),
+            SceneAttrValue::U64(v) => tw.u64(&TLVTag::Context(7), v),
+            SceneAttrValue::I64(v) => tw.i64(&TLVTag::Context(8), v),
+        }?;
+
+        tw.end_container()
+    }
+
+    fn tlv_iter(&self, _tag: TLVTag) -> impl Iterator<Item = Result<TLV<'_>, Error>> {
+        unimplemented!("Not implemented for `SceneAttr`");
+
+        #[allow(unreachable_code)]
+        core::iter::empty()
+    }
+}
+
+/// A trait implemented by the handlers of clusters having scene-capable attributes.
+///
+/// The system implementation of the Scenes Management Matter cluster delegates to
+/// the implementations of this trait on its endpoint when storing and recalling scenes.
+pub trait SceneCluster {
+    /// Return the ID of the cluster
+    fn cluster_id(&self) -> ClusterId;
+
+    /// Export the current values of the scene-capable attributes of the cluster
+    /// by calling `f` for each of them.
+    fn export_scene(&self, f: &mut dyn FnMut(SceneAttr) -> Result<(), Error>) -> Result<(), Error>;
+
+    /// Apply the provided values of scene-capable attributes to the cluster.
+    ///
+    /// Clusters which support transitions should reach the values over the
+    /// provided transition time (in milliseconds). Attributes which are not
+    /// scene-capable should be ignored.
+    ///
+    /// Return `true` if any of the attributes of the cluster was changed.
+    fn apply_scene(&self, attrs: &[SceneAttr], transition_time_ms: u32) -> Result<bool, Error>;
+}
+
+impl<T> SceneCluster for &T
+where
+    T: SceneCluster,
+{
+    fn cluster_id(&self) -> ClusterId {
+        (**self).cluster_id()
+    }
+
+    fn export_scene(&self, f: &mut dyn FnMut(SceneAttr) -> Result<(), Error>) -> Result<(), Error> {
+        (**self).export_scene(f)
+    }
+
+    fn apply_scene(&self, attrs: &[SceneAttr], transition_time_ms: u32) -> Result<bool, Error> {
+        (**self).apply_scene(attrs, transition_time_ms)
+    }
+}
+
+/// The stored attribute values of one cluster in a scene
+#[derive(Debug, Clone, PartialEq, Eq, FromTLV, ToTLV)]
+struct SceneFieldSet {
+    cluster_id: ClusterId,
+    attrs: Vec<SceneAttr, MAX_SCENE_ATTRS_PER_CLUSTER>,
+}
+
+/// An entry in the scene table
+#[derive(Debug, Clone, PartialEq, Eq, FromTLV, ToTLV)]
+struct SceneEntry {
+    fab_idx: NonZeroU8,
+    group_id: u16,
+    scene_id: u8,
+    name: String<MAX_SCENE_NAME_LEN>,
+    transition_time_ms: u32,
+    field_sets: Vec<SceneFieldSet, MAX_SCENE_CLUSTERS>,
+}
+
+impl SceneEntry {
+    /// Create a new, empty scene entry
+    fn new(fab_idx: NonZeroU8, group_id: u16, scene_id: u8) -> Self {
+        Self {
+            fab_idx,
+            group_id,
+            scene_id,
+            name: String::new(),
+            transition_time_ms: 0,
+            field_sets: Vec::new(),
+        }
+    }
+
+    /// Return `true` if the entry has the provided fabric, group and scene IDs
+    fn is(&self, fab_idx: NonZeroU8, group_id: u16, scene_id: u8) -> bool {
+        self.fab_idx == fab_idx && self.group_id == group_id && self.scene_id == scene_id
+    }
+
+    /// Write the extension field sets of the scene into the provided builder
+    fn read_field_sets_into<P: TLVBuilderParent>(
+        &self,
+        mut builder: ExtensionFieldSetArrayBuilder<P>,
+    ) -> Result<P, Error> {
+        for field_set in &self.field_sets {
+            let mut attrs = builder
+                .push()?
+                .cluster_id(field_set.cluster_id)?
+                .attribute_value_list()?;
+
+            for attr in &field_set.attrs {
+                let value = attr.value;
+
+                attrs = attrs
+                    .push()?
+                    .attribute_id(attr.attr_id)?
+                    .value_unsigned_8(match value {
+                        SceneAttrValue::U8(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_signed_8(match value {
+                        SceneAttrValue::I8(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_unsigned_16(match value {
+                        SceneAttrValue::U16(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_signed_16(match value {
+                        SceneAttrValue::I16(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_unsigned_32(match value {
+                        SceneAttrValue::U32(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_signed_32(match value {
+                        SceneAttrValue::I32(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_unsigned_64(match value {
+                        SceneAttrValue::U64(v) => Some(v),
+                        _ => None,
+                    })?
+                    .value_signed_64(match value {
+                        SceneAttrValue::I64(v) => Some(v),
+                        _ => None,
+                    })?
+                    .end()?;
+            }
+
+            builder = attrs.end()?.end()?;
+        }
+
+        builder.end()
+    }
+}
+
+/// The scene which was last stored or recalled by a fabric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromTLV, ToTLV)]
+struct CurrentScene {
+    fab_idx: NonZeroU8,
+    group_id: u16,
+    scene_id: u8,
+}
+
+/// The scene table of an endpoint
+#[derive(Debug)]
+struct SceneTable<const N: usize> {
+    scenes: Vec<SceneEntry, N>,
+    current: Vec<CurrentScene, MAX_SUPPORTED_FABRICS>,
+    last_configured_by: Option<u64>,
+    changed: bool,
+}
+
+impl<const N: usize> SceneTable<N> {
+    /// The max number of scenes a single fabric can have in the table,
+    /// so that the other fabrics are guaranteed some room too
+    const MAX_PER_FABRIC: usize = (N - 1) / 2;
+
+    const fn new() -> Self {
+        Self {
+            scenes: Vec::new(),
+            current: Vec::new(),
+            last_configured_by: None,
+            changed: false,
+        }
+    }
+
+    /// Load the scene table from the provided TLV data
+    fn load(&mut self, data: &[u8]) -> Result<(), Error> {
+        let root = TLVElement::new(data).structure()?;
+
+        self.scenes.clear();
+        self.current.clear();
+
+        for entry in root.ctx(0)?.array()?.iter() {
+            self.scenes
+                .push(SceneEntry::from_tlv(&entry?)?)
+                .map_err(|_| ErrorCode::NoSpace)?;
+        }
+
+        for current in root.ctx(1)?.array()?.iter() {
+            self.current
+                .push(CurrentScene::from_tlv(&current?)?)
+                .map_err(|_| ErrorCode::NoSpace)?;
+        }
+
+        self.last_configured_by = Option::from_tlv(&root.find_ctx(2)?)?;
+        self.changed = false;
+
+        Ok(())
+    }
+
+    /// Store the scene table into the provided buffer as TLV data
+    ///
+    /// If the scene table has not changed since the last store operation, the
+    /// function returns `None` and does not store the scene table.
+    fn store<'a>(&mut self, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>, Error> {
+        if !self.changed {
+            return Ok(None);
+        }
+
+        let mut wb = WriteBuf::new(buf);
+
+        wb.start_struct(&TLVTag::Anonymous)?;
+        self.scenes.to_tlv(&TLVTag::Context(0), &mut wb)?;
+        self.current.to_tlv(&TLVTag::Context(1), &mut wb)?;
+        self.last_configured_by
+            .to_tlv(&TLVTag::Context(2), &mut wb)?;
+        wb.end_container()?;
+
+        self.changed = false;
+
+        let len = wb.get_tail();
+
+        Ok(Some(&buf[..len]))
+    }
+
+    /// Remove the scenes of fabrics which no longer exist, as well as the scenes
+    /// of groups the endpoint is no longer a member of
+    fn purge(&mut self, fabric_mgr: &FabricMgr, endpoint_id: EndptId) {
+        let valid = |fab_idx: NonZeroU8, group_id: u16| {
+            fabric_mgr.get(fab_idx).is_some_and(|fabric| {
+                group_id == 0 || fabric.is_group_member(group_id, endpoint_id)
+            })
+        };
+
+        let len = self.scenes.len() + self.current.len();
+
+        self.scenes
+            .retain(|entry| valid(entry.fab_idx, entry.group_id));
+        self.current
+            .retain(|current| valid(current.fab_idx, current.group_id));
+
+        if self.scenes.len() + self.current.len() != len {
+            self.changed = true;
+        }
+    }
+
+    fn get(&self, fab_idx: NonZeroU8, group_id: u16, scene_id: u8) -> Option<&SceneEntry> {
+        self.scenes
+            .iter()
+            .find(|entry| entry.is(fab_idx, group_id, scene_id))
+    }
+
+    fn count(&self, fab_idx: NonZeroU8) -> usize {
+        self.scenes
+            .iter()
+            .filter(|entry| entry.fab_idx == fab_idx)
+            .count()
+    }
+
+    /// Return the number of scenes the fabric can still add to the table
+    fn remaining_capacity(&self, fab_idx: NonZeroU8) -> usize {
+        Self::MAX_PER_FABRIC
+            .saturating_sub(self.count(fab_idx))
+            .min(N - self.scenes.len())
+    }
+
+    /// Add the scene to the table, replacing the existing scene with the same IDs, if any
+    fn insert(&mut self, entry: SceneEntry) -> Result<(), Error> {
+        if let Some(existing) = self
+            .scenes
+            .iter_mut()
+            .find(|existing| existing.is(entry.fab_idx, entry.group_id, entry.scene_id))
+        {
+            *existing = entry;
+        } else {
+            if self.remaining_capacity(entry.fab_idx) == 0 {
+                Err(ErrorCode::ResourceExhausted)?;
+            }
+
+            // Cannot fail, as the remaining capacity accounts for the free entries
+            unwrap!(self.scenes.push(entry).map_err(|_| ErrorCode::NoSpace));
+        }
+
+        self.changed = true;
+
+        Ok(())
+    }
+
+    fn remove(&mut self, fab_idx: NonZeroU8, group_id: u16, scene_id: u8) -> Result<(), Error> {
+        let index = self
+            .scenes
+            .iter()
+            .position(|entry| entry.is(fab_idx, group_id, scene_id))
+            .ok_or(ErrorCode::NotFound)?;
+
+        self.scenes.remove(index);
+        self.current.retain(|current| {
+            *current
+                != CurrentScene {
+                    fab_idx,
+                    group_id,
+                    scene_id,
+                }
+        });
+
+        self.changed = true;
+
+        Ok(())
+    }
+
+    fn remove_all(&mut self, fab_idx: NonZeroU8, group_id: u16) {
+        self.scenes
+            .retain(|entry| entry.fab_idx != fab_idx || entry.group_id != group_id);
+        self.current
+            .retain(|current| current.fab_idx != fab_idx || current.group_id != group_id);
+
+        self.changed = true;
+    }
+
+    fn current(&self, fab_idx: NonZeroU8) -> Option<&CurrentScene> {
+        self.current
+            .iter()
+            .find(|current| current.fab_idx == fab_idx)
+    }
+
+    fn set_current(&mut self, fab_idx: NonZeroU8, group_id: u16, scene_id: u8) {
+        let current = CurrentScene {
+            fab_idx,
+            group_id,
+            scene_id,
+        };
+
+        if let Some(existing) = self
+            .current
+            .iter_mut()
+            .find(|existing| existing.fab_idx == fab_idx)
+        {
+            *existing = current;
+        } else {
+            // There is always room, as there is at most one entry per fabric
+            unwrap!(self.current.push(current).map_err(|_| ErrorCode::NoSpace));
+        }
+
+        self.changed = true;
+    }
+}
+
+/// The system implementation of a handler for the Scenes Management Matter cluster.
+///
+/// One instance of the handler is supposed to be registered for each endpoint
+/// which supports scenes, with the scene-capable clusters of that endpoint.
+///
+/// The scene table of the endpoint has `N` entries. User code is supposed to persist it
+/// with `store` each time `wait_persist` signals a change, and to restore it with `load` on startup.
+pub struct ScenesHandler<'a, const N: usize = DEFAULT_SCENE_TABLE_SIZE> {
+    dataver: Dataver,
+    clusters: &'a [&'a dyn SceneCluster],
+    table: RefCell<SceneTable<N>>,
+    persist_notification: Notification<NoopRawMutex>,
+}
+
+impl<const N: usize> fmt::Debug for ScenesHandler<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScenesHandler")
+            .field("dataver", &self.dataver)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for ScenesHandler<'_, N> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "ScenesHandler {{ dataver: {:?}, .. }}", self.dataver)
+    }
+}
+
+impl<'a, const N: usize> ScenesHandler<'a, N> {
+    /// Create a new instance of `ScenesHandler` with the given `Dataver`
+    /// and the scene-capable clusters of the endpoint.
+    pub const fn new(dataver: Dataver, clusters: &'a [&'a dyn SceneCluster]) -> Self {
+        Self {
+            dataver,
+            clusters,
+            table: RefCell::new(SceneTable::new()),
+            persist_notification: Notification::new(),
+        }
+    }
+
+    /// Adapt the handler instance to the generic `rs-matter` `Handler` trait
+    pub const fn adapt(self) -> HandlerAdaptor<Self> {
+        HandlerAdaptor(self)
+    }
+
+    /// Load the scene table from the provided TLV data
+    pub fn load(&self, data: &[u8]) -> Result<(), Error> {
+        self.table.borrow_mut().load(data)
+    }
+
+    /// Store the scene table into the provided buffer as TLV data
+    ///
+    /// If the scene table has not changed since the last store operation, the
+    /// function returns `None` and does not store the scene table.
+    pub fn store<'b>(&self, buf: &'b mut [u8]) -> Result<Option<&'b [u8]>, Error> {
+        self.table.borrow_mut().store(buf)
+    }
+
+    /// Return `true` if the scene table has changed since the last store operation
+    pub fn changed(&self) -> bool {
+        self.table.borrow().changed
+    }
+
+    /// Wait for the scene table to change in a way that requires persisting it
+    pub async fn wait_persist(&self) {
+        loop {
+            if self.changed() {
+                break;
+            }
+
+            self.persist_notification.wait().await;
+        }
+    }
+
+    /// Notify the persistence code, if the scene table has changed
+    fn notify_persist(&self) {
+        if self.changed() {
+            self.persist_notification.notify();
+        }
+    }
+
+    fn cluster(&self, cluster_id: ClusterId) -> Option<&dyn SceneCluster> {
+        self.clusters
+            .iter()
+            .find(|cluster| cluster.cluster_id() == cluster_id)
+            .copied()
+    }
+
+    /// Check that the endpoint is a member of the group, with group ID 0 meaning no group
+    fn check_group(
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+    ) -> Result<(), Error> {
+        let fabric = fabric_mgr.get(fab_idx).ok_or(ErrorCode::NotFound)?;
+
+        if group_id != 0 && !fabric.is_group_member(group_id, endpoint_id) {
+            Err(ErrorCode::InvalidCommand)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return `true` if the current values of the attributes of the
+    /// scene-capable clusters match the values stored in the scene
+    fn is_valid(&self, entry: &SceneEntry) -> Result<bool, Error> {
+        for field_set in &entry.field_sets {
+            let Some(cluster) = self.cluster(field_set.cluster_id) else {
+                continue;
+            };
+
+            let mut valid = true;
+
+            cluster.export_scene(&mut |attr| {
+                if field_set
+                    .attrs
+                    .iter()
+                    .any(|stored| stored.attr_id == attr.attr_id && stored.value != attr.value)
+                {
+                    valid = false;
+                }
+
+                Ok(())
+            })?;
+
+            if !valid {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Capture the current values of the attributes of the scene-capable clusters
+    fn export(&self) -> Result<Vec<SceneFieldSet, MAX_SCENE_CLUSTERS>, Error> {
+        let mut field_sets = Vec::new();
+
+        for cluster in self.clusters {
+            let mut field_set = SceneFieldSet {
+                cluster_id: cluster.cluster_id(),
+                attrs: Vec::new(),
+            };
+
+            cluster.export_scene(&mut |attr| {
+                field_set
+                    .attrs
+                    .push(attr)
+                    .map_err(|_| ErrorCode::ResourceExhausted.into())
+            })?;
+
+            field_sets
+                .push(field_set)
+                .map_err(|_| ErrorCode::ResourceExhausted)?;
+        }
+
+        Ok(field_sets)
+    }
+
+    /// For unit-testing
+    /// Add the scene to the scene table of the endpoint
+    fn add_scene(
+        &self,
+        fabric_mgr: &FabricMgr,
+        endpoint_id: EndptId,
+        mut entry: SceneEntry,
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, entry.fab_idx, endpoint_id, entry.group_id)?;
+
+        if entry.transition_time_ms > MAX_TRANSITION_TIME_MS {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        // Attribute values of clusters which are not scene-capable on this endpoint are ignored
+        entry
+            .field_sets
+            .retain(|field_set| self.cluster(field_set.cluster_id).is_some());
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+        table.insert(entry)
+    }
+
+    /// For unit-testing
+    /// Remove the scene from the scene table of the endpoint
+    fn remove_scene(
+        &self,
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+        scene_id: u8,
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id)?;
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+        table.remove(fab_idx, group_id, scene_id)
+    }
+
+    /// For unit-testing
+    /// Remove all scenes of the group from the scene table of the endpoint
+    fn remove_all_scenes(
+        &self,
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id)?;
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+        table.remove_all(fab_idx, group_id);
+
+        Ok(())
+    }
+
+    /// For unit-testing
+    /// Store the current values of the attributes of the scene-capable clusters into the scene
+    ///
+    /// The name and the transition time of an existing scene are preserved.
+    fn store_scene(
+        &self,
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+        scene_id: u8,
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id)?;
+
+        let field_sets = self.export()?;
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+
+        let mut entry = table
+            .get(fab_idx, group_id, scene_id)
+            .cloned()
+            .unwrap_or_else(|| SceneEntry::new(fab_idx, group_id, scene_id));
+        entry.field_sets = field_sets;
+
+        table.insert(entry)?;
+        table.set_current(fab_idx, group_id, scene_id);
+
+        Ok(())
+    }
+
+    /// For unit-testing
+    /// Apply the values stored in the scene to the scene-capable clusters
+    ///
+    /// `notify` is called with the ID of each cluster which changed as a result.
+    #[allow(clippy::too_many_arguments)]
+    fn recall_scene(
+        &self,
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        group_id: u16,
+        scene_id: u8,
+        transition_time_ms: Option<u32>,
+        mut notify: impl FnMut(ClusterId),
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id)?;
+
+        if transition_time_ms.is_some_and(|time| time > MAX_TRANSITION_TIME_MS) {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+
+        let entry = table
+            .get(fab_idx, group_id, scene_id)
+            .ok_or(ErrorCode::NotFound)?;
+
+        // The transition time in the request, if provided, overrides the one of the scene
+        let transition_time_ms = transition_time_ms.unwrap_or(entry.transition_time_ms);
+
+        for field_set in &entry.field_sets {
+            if let Some(cluster) = self.cluster(field_set.cluster_id) {
+                if cluster.apply_scene(&field_set.attrs, transition_time_ms)? {
+                    notify(field_set.cluster_id);
+                }
+            }
+        }
+
+        table.set_current(fab_idx, group_id, scene_id);
+
+        Ok(())
+    }
+
+    /// For unit-testing
+    /// Copy one or all scenes of a group to another group
+    ///
+    /// When all scenes are copied, the scene IDs are ignored and the scenes keep their IDs.
+    #[allow(clippy::too_many_arguments)]
+    fn copy_scene(
+        &self,
+        fabric_mgr: &FabricMgr,
+        fab_idx: NonZeroU8,
+        endpoint_id: EndptId,
+        all: bool,
+        group_id_from: u16,
+        scene_id_from: u8,
+        group_id_to: u16,
+        scene_id_to: u8,
+    ) -> Result<(), Error> {
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id_from)?;
+        Self::check_group(fabric_mgr, fab_idx, endpoint_id, group_id_to)?;
+
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, endpoint_id);
+
+        if all {
+            let scenes = table
+                .scenes
+                .iter()
+                .filter(|entry| entry.fab_idx == fab_idx && entry.group_id == group_id_from)
+                .cloned()
+                .collect::<Vec<_, N>>();
+
+            // Check the capacity upfront, so that either all scenes are copied, or none
+            let new = scenes
+                .iter()
+                .filter(|entry| table.get(fab_idx, group_id_to, entry.scene_id).is_none())
+                .count();
+            if new > table.remaining_capacity(fab_idx) {
+                Err(ErrorCode::ResourceExhausted)?;
+            }
+
+            for mut entry in scenes {
+                entry.group_id = group_id_to;
+                table.insert(entry)?;
+            }
+        } else {
+            let mut entry = table
+                .get(fab_idx, group_id_from, scene_id_from)
+                .cloned()
+                .ok_or(ErrorCode::NotFound)?;
+
+            entry.group_id = group_id_to;
+            entry.scene_id = scene_id_to;
+
+            table.insert(entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// For unit-testing
+    /// Read the scene info of each fabric
+    fn fabric_scene_info<P: TLVBuilderParent>(
+        &self,
+        fabric_mgr: &FabricMgr,
+        attr: &AttrDetails<'_>,
+        builder: ArrayAttributeRead<SceneInfoStructArrayBuilder<P>, SceneInfoStructBuilder<P>>,
+    ) -> Result<P, Error> {
+        let mut table = self.table.borrow_mut();
+
+        table.purge(fabric_mgr, attr.endpoint_id);
+
+        let mut fabrics = fabric_mgr
+            .iter()
+            .map(|fabric| fabric.fab_idx())
+            .filter(|fab_idx| !attr.fab_filter || fab_idx.get() == attr.fab_idx);
+
+        match builder {
+            ArrayAttributeRead::ReadAll(mut builder) => {
+                for fab_idx in fabrics {
+                    builder = self.read_scene_info_into(&table, fab_idx, builder.push()?)?;
+                }
+
+                builder.end()
+            }
+            ArrayAttributeRead::ReadOne(index, builder) => {
+                let Some(fab_idx) = fabrics.nth(index as usize) else {
+                    return Err(ErrorCode::ConstraintError.into());
+                };
+
+                self.read_scene_info_into(&table, fab_idx, builder)
+            }
+        }
+    }
+
+    /// Write the scene info of the fabric into the provided builder
+    fn read_scene_info_into<P: TLVBuilderParent>(
+        &self,
+        table: &SceneTable<N>,
+        fab_idx: NonZeroU8,
+        builder: SceneInfoStructBuilder<P>,
+    ) -> Result<P, Error> {
+        let current = table.current(fab_idx);
+        let valid = match current {
+            Some(current) => match table.get(fab_idx, current.group_id, current.scene_id) {
+                Some(entry) => self.is_valid(entry)?,
+                None => false,
+            },
+            None => false,
+        };
+
+        builder
+            .scene_count(table.count(fab_idx) as _)?
+            .current_scene(current.map(|current| current.scene_id).unwrap_or(0))?
+            .current_group(current.map(|current| current.group_id).unwrap_or(0))?
+            .scene_valid(valid)?
+            .remaining_capacity(table.remaining_capacity(fab_idx) as _)?
+            .fabric_index(fab_idx.get())?
+            .end()
+    }
+
+    /// Record the node which last changed the scene table
+    fn configured_by(&self, ctx: &InvokeContext<'_>) {
+        let node_id = ctx
+            .exchange()
+            .with_session(|sess| Ok(sess.get_peer_node_id()));
+
+        let mut table = self.table.borrow_mut();
+
+        table.last_configured_by = node_id.ok().flatten();
+        table.changed = true;
+    }
+
+    /// Return the fabric index of the accessing session of the command
+    fn fab_idx(ctx: &InvokeContext<'_>) -> Result<NonZeroU8, Error> {
+        ctx.exchange().with_session(|sess| {
+            NonZeroU8::new(sess.get_local_fabric_idx()).ok_or(ErrorCode::UnsupportedAccess.into())
+        })
+    }
+
+    /// Map the result of a scene table operation to the status reported in the command responses
+    fn status(result: Result<(), Error>) -> Result<u8, Error> {
+        let status = match result {
+            Ok(()) => IMStatusCode::Success,
+            Err(e) => match e.code() {
+                ErrorCode::NotFound => IMStatusCode::NotFound,
+                ErrorCode::InvalidCommand => IMStatusCode::InvalidCommand,
+                ErrorCode::ConstraintError => IMStatusCode::ConstraintError,
+                ErrorCode::ResourceExhausted => IMStatusCode::ResourceExhausted,
+                _ => Err(e)?,
+            },
+        };
+
+        Ok(status as _)
+    }
+
+    /// Parse the scene in an `AddScene` request
+    fn parse_scene(fab_idx: NonZeroU8, request: &AddSceneRequest<'_>) -> Result<SceneEntry, Error> {
+        let mut entry = SceneEntry::new(fab_idx, request.group_id()?, request.scene_id()?);
+
+        entry.transition_time_ms = request.transition_time()?;
+
+        // Names are optional as per the spec, so longer names are truncated rather than rejected
+        let name = request.scene_name()?;
+        let mut end = name.len().min(MAX_SCENE_NAME_LEN);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        unwrap!(entry.name.push_str(&name[..end]));
+
+        for field_set in request.extension_field_sets()? {
+            let field_set = field_set?;
+
+            let mut attrs = Vec::new();
+
+            for attr in field_set.attribute_value_list()? {
+                let attr = attr?;
+
+                let values = [
+                    attr.value_unsigned_8()?.map(SceneAttrValue::U8),
+                    attr.value_signed_8()?.map(SceneAttrValue::I8),
+                    attr.value_unsigned_16()?.map(SceneAttrValue::U16),
+                    attr.value_signed_16()?.map(SceneAttrValue::I16),
+                    attr.value_unsigned_32()?.map(SceneAttrValue::U32),
+                    attr.value_signed_32()?.map(SceneAttrValue::I32),
+                    attr.value_unsigned_64()?.map(SceneAttrValue::U64),
+                    attr.value_signed_64()?.map(SceneAttrValue::I64),
+                ];
+
+                // Exactly one value must be provided for each attribute
+                let mut values = values.into_iter().flatten();
+                let (Some(value), None) = (values.next(), values.next()) else {
+                    return Err(ErrorCode::InvalidCommand.into());
+                };
+
+                attrs
+                    .push(SceneAttr::new(attr.attribute_id()?, value))
+                    .map_err(|_| ErrorCode::ResourceExhausted)?;
+            }
+
+            entry
+                .field_sets
+                .push(SceneFieldSet {
+                    cluster_id: field_set.cluster_id()?,
+                    attrs,
+                })
+                .map_err(|_| ErrorCode::ResourceExhausted)?;
+        }
+
+        Ok(entry)
+    }
+}
+
+impl<const N: usize> ClusterHandler for ScenesHandler<'_, N> {
+    const CLUSTER: Cluster<'static> = FULL_CLUSTER
+        .with_revision(1)
+        .with_features(Feature::SCENE_NAMES.bits())
+        .with_attrs(with!(required; AttributeId::LastConfiguredBy))
+        .with_cmds(with!(
+            CommandId::AddScene
+                | CommandId::ViewScene
+                | CommandId::RemoveScene
+                | CommandId::RemoveAllScenes
+                | CommandId::StoreScene
+                | CommandId::RecallScene
+                | CommandId::GetSceneMembership
+                | CommandId::CopyScene
+        ));
+
+    fn dataver(&self) -> u32 {
+        self.dataver.get()
+    }
+
+    fn dataver_changed(&self) {
+        self.dataver.changed();
+    }
+
+    fn last_configured_by(&self, _ctx: &ReadContext<'_>) -> Result<Nullable<u64>, Error> {
+        Ok(Nullable::new(self.table.borrow().last_configured_by))
+    }
+
+    fn scene_table_size(&self, _ctx: &ReadContext<'_>) -> Result<u16, Error> {
+        Ok(N as _)
+    }
+
+    fn fabric_scene_info<P: TLVBuilderParent>(
+        &self,
+        ctx: &ReadContext<'_>,
+        builder: ArrayAttributeRead<SceneInfoStructArrayBuilder<P>, SceneInfoStructBuilder<P>>,
+    ) -> Result<P, Error> {
+        self.fabric_scene_info(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            ctx.attr(),
+            builder,
+        )
+    }
+
+    fn handle_add_scene<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: AddSceneRequest<'_>,
+        response: AddSceneResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+        let scene_id = request.scene_id()?;
+
+        let result = Self::parse_scene(fab_idx, &request).and_then(|entry| {
+            self.add_scene(
+                &ctx.exchange().matter().fabric_mgr.borrow(),
+                ctx.cmd().endpoint_id,
+                entry,
+            )
+        });
+
+        if result.is_ok() {
+            self.configured_by(ctx);
+            self.dataver_changed();
+            ctx.notify_changed();
+        }
+
+        self.notify_persist();
+
+        response
+            .status(Self::status(result)?)?
+            .group_id(group_id)?
+            .scene_id(scene_id)?
+            .end()
+    }
+
+    fn handle_view_scene<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: ViewSceneRequest<'_>,
+        response: ViewSceneResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let endpoint_id = ctx.cmd().endpoint_id;
+        let group_id = request.group_id()?;
+        let scene_id = request.scene_id()?;
+
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+
+        let mut table = self.table.borrow_mut();
+        table.purge(&fabric_mgr, endpoint_id);
+
+        let result =
+            Self::check_group(&fabric_mgr, fab_idx, endpoint_id, group_id).and_then(|_| {
+                table
+                    .get(fab_idx, group_id, scene_id)
+                    .ok_or(ErrorCode::NotFound.into())
+            });
+
+        let (status, entry) = match result {
+            Ok(entry) => (IMStatusCode::Success as u8, Some(entry)),
+            Err(e) => (Self::status(Err(e))?, None),
+        };
+
+        let response = response
+            .status(status)?
+            .group_id(group_id)?
+            .scene_id(scene_id)?
+            .transition_time(entry.map(|entry| entry.transition_time_ms))?
+            .scene_name(entry.map(|entry| entry.name.as_str()))?
+            .extension_field_sets()?;
+
+        if let Some(entry) = entry {
+            entry.read_field_sets_into(response.some()?)?.end()
+        } else {
+            response.none().end()
+        }
+    }
+
+    fn handle_remove_scene<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: RemoveSceneRequest<'_>,
+        response: RemoveSceneResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+        let scene_id = request.scene_id()?;
+
+        let result = self.remove_scene(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            group_id,
+            scene_id,
+        );
+
+        if result.is_ok() {
+            self.configured_by(ctx);
+            self.dataver_changed();
+            ctx.notify_changed();
+        }
+
+        self.notify_persist();
+
+        response
+            .status(Self::status(result)?)?
+            .group_id(group_id)?
+            .scene_id(scene_id)?
+            .end()
+    }
+
+    fn handle_remove_all_scenes<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: RemoveAllScenesRequest<'_>,
+        response: RemoveAllScenesResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+
+        let result = self.remove_all_scenes(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            group_id,
+        );
+
+        if result.is_ok() {
+            self.configured_by(ctx);
+            self.dataver_changed();
+            ctx.notify_changed();
+        }
+
+        self.notify_persist();
+
+        response
+            .status(Self::status(result)?)?
+            .group_id(group_id)?
+            .end()
+    }
+
+    fn handle_store_scene<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: StoreSceneRequest<'_>,
+        response: StoreSceneResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id = request.group_id()?;
+        let scene_id = request.scene_id()?;
+
+        let result = self.store_scene(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            group_id,
+            scene_id,
+        );
+
+        if result.is_ok() {
+            self.configured_by(ctx);
+            self.dataver_changed();
+            ctx.notify_changed();
+        }
+
+        self.notify_persist();
+
+        response
+            .status(Self::status(result)?)?
+            .group_id(group_id)?
+            .scene_id(scene_id)?
+            .end()
+    }
+
+    fn handle_recall_scene(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: RecallSceneRequest<'_>,
+    ) -> Result<(), Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+
+        let transition_time_ms = request
+            .transition_time()?
+            .and_then(|transition_time| transition_time.into_option());
+
+        self.recall_scene(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            request.group_id()?,
+            request.scene_id()?,
+            transition_time_ms,
+            |cluster_id| ctx.notify_cluster_changed(cluster_id),
+        )?;
+
+        // The current scene of the fabric has changed
+        self.dataver_changed();
+        ctx.notify_changed();
+        self.notify_persist();
+
+        Ok(())
+    }
+
+    fn handle_get_scene_membership<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: GetSceneMembershipRequest<'_>,
+        response: GetSceneMembershipResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let endpoint_id = ctx.cmd().endpoint_id;
+        let group_id = request.group_id()?;
+
+        let fabric_mgr = ctx.exchange().matter().fabric_mgr.borrow();
+
+        let mut table = self.table.borrow_mut();
+        table.purge(&fabric_mgr, endpoint_id);
+
+        let capacity = table.remaining_capacity(fab_idx);
+
+        match Self::check_group(&fabric_mgr, fab_idx, endpoint_id, group_id) {
+            Ok(()) => {
+                let mut builder = response
+                    .status(IMStatusCode::Success as _)?
+                    .capacity(Nullable::some(capacity as _))?
+                    .group_id(group_id)?
+                    .scene_list()?
+                    .some()?;
+
+                for entry in table
+                    .scenes
+                    .iter()
+                    .filter(|entry| entry.fab_idx == fab_idx && entry.group_id == group_id)
+                {
+                    builder = builder.push(&entry.scene_id)?;
+                }
+
+                builder.end()?.end()
+            }
+            Err(e) => response
+                .status(Self::status(Err(e))?)?
+                .capacity(Nullable::some(capacity as _))?
+                .group_id(group_id)?
+                .scene_list()?
+                .none()
+                .end(),
+        }
+    }
+
+    fn handle_copy_scene<P: TLVBuilderParent>(
+        &self,
+        ctx: &InvokeContext<'_>,
+        request: CopySceneRequest<'_>,
+        response: CopySceneResponseBuilder<P>,
+    ) -> Result<P, Error> {
+        let fab_idx = Self::fab_idx(ctx)?;
+        let group_id_from = request.group_identifier_from()?;
+        let scene_id_from = request.scene_identifier_from()?;
+
+        let result = self.copy_scene(
+            &ctx.exchange().matter().fabric_mgr.borrow(),
+            fab_idx,
+            ctx.cmd().endpoint_id,
+            request.mode()?.contains(CopyModeBitmap::COPY_ALL_SCENES),
+            group_id_from,
+            scene_id_from,
+            request.group_identifier_to()?,
+            request.scene_identifier_to()?,
+        );
+
+        if result.is_ok() {
+            self.configured_by(ctx);
+            self.dataver_changed();
+            ctx.notify_changed();
+        }
+
+        self.notify_persist();
+
+        response
+            .status(Self::status(result)?)?
+            .group_identifier_from(group_id_from)?
+            .scene_identifier_from(scene_id_from)?
+            .end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::acl::tests::{FAB_1, FAB_2};
+    use crate::crypto::KeyPair;
+    use crate::dm::clusters::on_off::{self, OnOffHandler};
+    use crate::error::ErrorCode;
+    use crate::fabric::FabricMgr;
+    use crate::utils::rand::dummy_rand;
+    use crate::utils::storage::Vec;
+
+    use super::{
+        Dataver, SceneAttr, SceneAttrValue, SceneCluster, SceneEntry, SceneFieldSet, ScenesHandler,
+        MAX_TRANSITION_TIME_MS,
+    };
+
+    const ENDPOINT: u16 = 1;
+
+    fn fab_mgr() -> FabricMgr {
+        let mut fab_mgr = FabricMgr::new();
+
+        // Add fabrics with IDs 1 and 2
+        for _ in 0..2 {
+            unwrap!(fab_mgr.add_with_post_init(unwrap!(KeyPair::new(dummy_rand)), |_| Ok(())));
+        }
+
+        // Only fabric 1 has the endpoint in group 1
+        unwrap!(fab_mgr.group_key_set_add(FAB_1, 1, &[(&[0x55; 16], 1)]));
+        unwrap!(fab_mgr.group_key_map_add(FAB_1, 1, 1));
+        unwrap!(fab_mgr.group_add(FAB_1, 1, ENDPOINT, ""));
+
+        fab_mgr
+    }
+
+    #[test]
+    fn store_recall_scene() {
+        let fab_mgr = fab_mgr();
+
+        let on_off = OnOffHandler::new(Dataver::new(0));
+        let clusters: &[&dyn SceneCluster] = &[&on_off];
+        let handler = ScenesHandler::<16>::new(Dataver::new(0), clusters);
+
+        on_off.set(true);
+        unwrap!(handler.store_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 1));
+        on_off.set(false);
+        unwrap!(handler.store_scene(&fab_mgr, FAB_1, ENDPOINT, 1, 2));
+
+        let table = handler.table.borrow();
+        let entry = unwrap!(table.get(FAB_1, 0, 1));
+        assert_eq!(entry.field_sets.len(), 1);
+        assert_eq!(
+            entry.field_sets[0].attrs.as_slice(),
+            &[SceneAttr::new(
+                on_off::AttributeId::OnOff as _,
+                SceneAttrValue::U8(1)
+            )]
+        );
+        assert!(!unwrap!(handler.is_valid(entry)));
+        assert!(unwrap!(handler.is_valid(unwrap!(table.get(FAB_1, 1, 2)))));
+        drop(table);
+
+        // Recalling a scene applies the stored values and notifies the changed clusters
+        let mut changed = None;
+        unwrap!(
+            handler.recall_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 1, None, |cluster_id| {
+                changed = Some(cluster_id)
+            })
+        );
+        assert!(on_off.get());
+        assert_eq!(changed, Some(on_off.cluster_id()));
+        assert_eq!(
+            handler
+                .table
+                .borrow()
+                .current(FAB_1)
+                .map(|current| current.scene_id),
+            Some(1)
+        );
+
+        // Nothing changes when recalling a scene matching the current state
+        let mut changed = None;
+        unwrap!(
+            handler.recall_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 1, None, |cluster_id| {
+                changed = Some(cluster_id)
+            })
+        );
+        assert_eq!(changed, None);
+
+        assert_eq!(
+            unwrap!(handler
+                .recall_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 3, None, |_| ())
+                .err())
+            .code(),
+            ErrorCode::NotFound
+        );
+        assert_eq!(
+            unwrap!(handler
+                .recall_scene(
+                    &fab_mgr,
+                    FAB_1,
+                    ENDPOINT,
+                    0,
+                    1,
+                    Some(MAX_TRANSITION_TIME_MS + 1),
+                    |_| ()
+                )
+                .err())
+            .code(),
+            ErrorCode::ConstraintError
+        );
+
+        // The endpoint is not a member of group 1 in fabric 2
+        assert_eq!(
+            unwrap!(handler.store_scene(&fab_mgr, FAB_2, ENDPOINT, 1, 1).err()).code(),
+            ErrorCode::InvalidCommand
+        );
+        // Scenes of one fabric are not visible to the other fabrics
+        assert_eq!(
+            unwrap!(handler
+                .recall_scene(&fab_mgr, FAB_2, ENDPOINT, 0, 1, None, |_| ())
+                .err())
+            .code(),
+            ErrorCode::NotFound
+        );
+    }
+
+    #[test]
+    fn add_copy_remove_scene() {
+        let mut fab_mgr = fab_mgr();
+
+        let on_off = OnOffHandler::new(Dataver::new(0));
+        let clusters: &[&dyn SceneCluster] = &[&on_off];
+        let handler = ScenesHandler::<16>::new(Dataver::new(0), clusters);
+
+        let scene = |fab_idx, group_id, scene_id| {
+            let mut entry = SceneEntry::new(fab_idx, group_id, scene_id);
+            entry.transition_time_ms = 1000;
+            unwrap!(entry.name.push_str("Evening"));
+            unwrap!(entry
+                .field_sets
+                .push(SceneFieldSet {
+                    cluster_id: on_off.cluster_id(),
+                    attrs: unwrap!([SceneAttr::new(0, SceneAttrValue::U8(1))]
+                        .as_slice()
+                        .try_into()),
+                })
+                .map_err(|_| ()));
+            // Not a scene-capable cluster on this endpoint
+            unwrap!(entry
+                .field_sets
+                .push(SceneFieldSet {
+                    cluster_id: 0x0300,
+                    attrs: unwrap!([SceneAttr::new(0, SceneAttrValue::U16(1))]
+                        .as_slice()
+                        .try_into()),
+                })
+                .map_err(|_| ()));
+
+            entry
+        };
+
+        let mut entry = scene(FAB_1, 0, 1);
+        entry.transition_time_ms = MAX_TRANSITION_TIME_MS + 1;
+        assert_eq!(
+            unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, entry).err()).code(),
+            ErrorCode::ConstraintError
+        );
+
+        unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, scene(FAB_1, 0, 1)));
+        {
+            let table = handler.table.borrow();
+            let entry = unwrap!(table.get(FAB_1, 0, 1));
+            assert_eq!(entry.name.as_str(), "Evening");
+            assert_eq!(entry.field_sets.len(), 1);
+        }
+
+        // Storing a scene keeps its name and transition time
+        unwrap!(handler.store_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 1));
+        {
+            let table = handler.table.borrow();
+            let entry = unwrap!(table.get(FAB_1, 0, 1));
+            assert_eq!(entry.name.as_str(), "Evening");
+            assert_eq!(entry.transition_time_ms, 1000);
+            assert_eq!(
+                entry.field_sets[0].attrs.as_slice(),
+                &[SceneAttr::new(0, SceneAttrValue::U8(0))]
+            );
+        }
+
+        // Each fabric can use at most (16 - 1) / 2 = 7 entries of the table
+        for scene_id in 2..=7 {
+            unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, scene(FAB_1, 0, scene_id)));
+        }
+        assert_eq!(handler.table.borrow().remaining_capacity(FAB_1), 0);
+        assert_eq!(
+            unwrap!(handler
+                .add_scene(&fab_mgr, ENDPOINT, scene(FAB_1, 0, 8))
+                .err())
+            .code(),
+            ErrorCode::ResourceExhausted
+        );
+        // Existing scenes can still be replaced
+        unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, scene(FAB_1, 0, 7)));
+        assert_eq!(
+            unwrap!(handler
+                .copy_scene(&fab_mgr, FAB_1, ENDPOINT, true, 0, 0, 1, 0)
+                .err())
+            .code(),
+            ErrorCode::ResourceExhausted
+        );
+
+        for scene_id in 3..=7 {
+            unwrap!(handler.remove_scene(&fab_mgr, FAB_1, ENDPOINT, 0, scene_id));
+        }
+        assert_eq!(
+            unwrap!(handler.remove_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 3).err()).code(),
+            ErrorCode::NotFound
+        );
+
+        unwrap!(handler.copy_scene(&fab_mgr, FAB_1, ENDPOINT, false, 0, 1, 0, 10));
+        unwrap!(handler.copy_scene(&fab_mgr, FAB_1, ENDPOINT, true, 0, 0, 1, 0));
+        {
+            let table = handler.table.borrow();
+            assert_eq!(table.count(FAB_1), 6);
+            assert_eq!(unwrap!(table.get(FAB_1, 1, 10)).name.as_str(), "Evening");
+            assert!(table.get(FAB_1, 1, 2).is_some());
+        }
+        assert_eq!(
+            unwrap!(handler
+                .copy_scene(&fab_mgr, FAB_1, ENDPOINT, false, 0, 5, 0, 6)
+                .err())
+            .code(),
+            ErrorCode::NotFound
+        );
+
+        unwrap!(handler.remove_all_scenes(&fab_mgr, FAB_1, ENDPOINT, 1));
+        assert_eq!(handler.table.borrow().count(FAB_1), 3);
+
+        // Scenes of removed groups and fabrics are purged
+        unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, scene(FAB_1, 1, 1)));
+        unwrap!(handler.add_scene(&fab_mgr, ENDPOINT, scene(FAB_2, 0, 1)));
+        unwrap!(fab_mgr.group_remove(FAB_1, 1, ENDPOINT));
+        unwrap!(fab_mgr.remove(FAB_2, &mut || ()));
+        unwrap!(handler.remove_scene(&fab_mgr, FAB_1, ENDPOINT, 0, 10));
+
+        let table = handler.table.borrow();
+        assert_eq!(table.scenes.len(), 2);
+        assert_eq!(table.count(FAB_2), 0);
+    }
+
+    #[test]
+    fn load_store_scenes() {
+        let fab_mgr = fab_mgr();
+
+        let on_off = OnOffHandler::new(Dataver::new(0));
+        let clusters: &[&dyn SceneCluster] = &[&on_off];
+        let handler = ScenesHandler::<16>::new(Dataver::new(0), clusters);
+
+        let mut buf = [0; 1024];
+
+        // Nothing to store for an unchanged scene table
+        assert!(unwrap!(handler.store(&mut buf)).is_none());
+
+        on_off.set(true);
+        unwrap!(handler.store_scene(&fab_mgr, FAB_1, ENDPOINT, 1, 2));
+        unwrap!(handler.add_scene(
+            &fab_mgr,
+            ENDPOINT,
+            SceneEntry {
+                transition_time_ms: 500,
+                field_sets: unwrap!(Vec::from_slice(&[SceneFieldSet {
+                    cluster_id: on_off.cluster_id(),
+                    attrs: unwrap!(Vec::from_slice(&[SceneAttr::new(
+                        on_off::AttributeId::OnOff as _,
+                        SceneAttrValue::U8(0)
+                    )])),
+                }])),
+                ..SceneEntry::new(FAB_2, 0, 3)
+            }
+        ));
+        assert!(handler.changed());
+
+        let data = unwrap!(unwrap!(handler.store(&mut buf)));
+        assert!(!handler.changed());
+
+        // The scene table survives a reboot
+        let reloaded = ScenesHandler::<16>::new(Dataver::new(0), clusters);
+        unwrap!(reloaded.load(data));
+
+        let table = handler.table.borrow();
+        let reloaded_table = reloaded.table.borrow();
+        assert_eq!(reloaded_table.scenes, table.scenes);
+        assert_eq!(reloaded_table.current, table.current);
+        assert_eq!(reloaded_table.last_configured_by, table.last_configured_by);
+        assert!(!reloaded.changed());
+    }
+}
@@ -379,7 +379,8 @@ impl ClusterHandler for ThreadDiagHandler<'_> {
     const CLUSTER: crate::dm::Cluster<'static> = FULL_CLUSTER
         .with_revision(1)
         .with_attrs(with!(required))
-        .with_cmds(with!());
+        .with_cmds(with!())
+        .with_events(with!());
 
     fn dataver(&self) -> u32 {
         self.dataver.get()
@@ -50,8 +50,7 @@ pub const TEST_DEV_DET: BasicInfoConfig = BasicInfoConfig {
     device_name: "MyTest",
     product_name: "ACME Test",
     vendor_name: "ACME",
-    sai: None,
-    sii: None,
+    ..BasicInfoConfig::new()
 };
 
 #[derive(Debug, Clone)]
@@ -83,7 +83,7 @@ pub type SysHandler<'a, H> = handler_chain_type!(
     EpClMatcher => Async<adm_comm::HandlerAdaptor<AdminCommHandler>>,
     EpClMatcher => Async<noc::HandlerAdaptor<NocHandler>>,
     EpClMatcher => Async<acl::HandlerAdaptor<acl::AclHandler>>,
-    EpClMatcher => Async<grp_key_mgmt::HandlerAdaptor<GrpKeyMgmtHandler>>
+    EpClMatcher => Async<grp_key_mgmt::GrpKeyMgmtHandlerAdaptor>
     | H
 );
 
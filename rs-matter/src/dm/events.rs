@@ -0,0 +1,584 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::num::NonZeroU8;
+
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_time::Instant;
+
+use crate::error::{Error, ErrorCode};
+use crate::im::{EventData, EventPath};
+use crate::tlv::TLVElement;
+use crate::utils::cell::RefCell;
+use crate::utils::init::{init, Init};
+use crate::utils::storage::WriteBuf;
+use crate::utils::sync::Notification;
+
+use super::{ClusterId, EndptId, EventId, EventPriority};
+
+/// The default size (in bytes) of the buffer for `Debug` priority events
+pub const DEFAULT_DEBUG_EVENTS_BUF_SIZE: usize = 512;
+/// The default size (in bytes) of the buffer for `Info` priority events
+pub const DEFAULT_INFO_EVENTS_BUF_SIZE: usize = 1024;
+/// The default size (in bytes) of the buffer for `Critical` priority events
+pub const DEFAULT_CRITICAL_EVENTS_BUF_SIZE: usize = 512;
+
+// The layout of a stored event record is as follows (all numbers are little-endian):
+// - event number: u64
+// - system timestamp in milliseconds: u64
+// - endpoint ID: u16
+// - cluster ID: u32
+// - event ID: u32
+// - fabric index (0 if the event is not fabric-sensitive): u8
+// - length of the event payload: u16
+// - event payload: an anonymous TLV element
+const RECORD_HEADER_LEN: usize = 8 + 8 + 2 + 4 + 4 + 1 + 2;
+
+/// An event, as stored in the event log.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventEntry<'a> {
+    /// The (monotonically increasing) number of the event
+    pub number: u64,
+    /// The priority of the event
+    pub priority: EventPriority,
+    /// The system timestamp of the event, in milliseconds since boot
+    pub system_timestamp_ms: u64,
+    /// The endpoint which emitted the event
+    pub endpoint_id: EndptId,
+    /// The cluster which emitted the event
+    pub cluster_id: ClusterId,
+    /// The ID of the event
+    pub event_id: EventId,
+    /// The index of the fabric the event is associated with, if the event is fabric-sensitive
+    pub fab_idx: Option<NonZeroU8>,
+    /// The TLV payload of the event
+    pub data: &'a [u8],
+}
+
+impl<'a> EventEntry<'a> {
+    /// Return the TLV payload of the event
+    pub fn data(&self) -> TLVElement<'a> {
+        TLVElement::new(self.data)
+    }
+
+    /// Convert the event into an Event Data IB, suitable for reporting
+    pub fn event_data(&self) -> EventData<'a> {
+        EventData {
+            path: EventPath {
+                node: None,
+                endpoint: Some(self.endpoint_id),
+                cluster: Some(self.cluster_id),
+                event: Some(self.event_id),
+                is_urgent: None,
+            },
+            event_number: self.number,
+            priority: self.priority,
+            epoch_timestamp: None,
+            system_timestamp: Some(self.system_timestamp_ms),
+            delta_epoch_timestamp: None,
+            delta_system_timestamp: None,
+            data: self.data(),
+        }
+    }
+
+    fn parse(priority: EventPriority, buf: &'a [u8]) -> (Self, usize) {
+        let u16_at = |offset: usize| u16::from_le_bytes([buf[offset], buf[offset + 1]]);
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(unwrap!(buf[offset..offset + 4].try_into()));
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(unwrap!(buf[offset..offset + 8].try_into()));
+
+        let len = u16_at(27) as usize;
+
+        let entry = Self {
+            number: u64_at(0),
+            priority,
+            system_timestamp_ms: u64_at(8),
+            endpoint_id: u16_at(16),
+            cluster_id: u32_at(18),
+            event_id: u32_at(22),
+            fab_idx: NonZeroU8::new(buf[26]),
+            data: &buf[RECORD_HEADER_LEN..RECORD_HEADER_LEN + len],
+        };
+
+        (entry, RECORD_HEADER_LEN + len)
+    }
+}
+
+/// A trait for accessing the event log from the data model.
+///
+/// Implemented by `Events`, but could be implemented by a user-provided event log as well
+/// (e.g. one which keeps the events in persistent storage).
+pub trait EventSource {
+    /// Return the number which will be assigned to the next emitted event.
+    fn next_event_number(&self) -> u64;
+
+    /// Visit all events with event numbers equal to or greater than `from`, in increasing event number order.
+    ///
+    /// The visitor should return `Ok(false)` to stop the visiting.
+    fn visit(
+        &self,
+        from: u64,
+        f: &mut dyn FnMut(&EventEntry) -> Result<bool, Error>,
+    ) -> Result<(), Error>;
+
+    /// Return a notification which is triggered each time a new event is emitted.
+    fn notification(&self) -> &Notification<NoopRawMutex>;
+}
+
+impl<T> EventSource for &T
+where
+    T: EventSource,
+{
+    fn next_event_number(&self) -> u64 {
+        (*self).next_event_number()
+    }
+
+    fn visit(
+        &self,
+        from: u64,
+        f: &mut dyn FnMut(&EventEntry) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        (*self).visit(from, f)
+    }
+
+    fn notification(&self) -> &Notification<NoopRawMutex> {
+        (*self).notification()
+    }
+}
+
+/// A buffer of event records of a single priority.
+///
+/// When there is no space for a new record, the oldest records are evicted.
+struct EventBuf<const N: usize> {
+    priority: EventPriority,
+    data: crate::utils::storage::Vec<u8, N>,
+}
+
+impl<const N: usize> EventBuf<N> {
+    const fn new(priority: EventPriority) -> Self {
+        Self {
+            priority,
+            data: crate::utils::storage::Vec::new(),
+        }
+    }
+
+    fn init(priority: EventPriority) -> impl Init<Self> {
+        init!(Self {
+            priority,
+            data <- crate::utils::storage::Vec::init(),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push<F>(
+        &mut self,
+        number: u64,
+        system_timestamp_ms: u64,
+        endpoint_id: EndptId,
+        cluster_id: ClusterId,
+        event_id: EventId,
+        fab_idx: Option<NonZeroU8>,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&mut WriteBuf) -> Result<(), Error>,
+    {
+        loop {
+            let start = self.data.len();
+
+            // Always safe as we are resizing to the capacity of the vector
+            unwrap!(self.data.resize_default(N));
+
+            let result = (|| -> Result<u16, Error> {
+                let mut wb = WriteBuf::new(&mut self.data[start..]);
+
+                wb.le_u64(number)?;
+                wb.le_u64(system_timestamp_ms)?;
+                wb.le_u16(endpoint_id)?;
+                wb.le_u32(cluster_id)?;
+                wb.le_u32(event_id)?;
+                wb.le_u8(fab_idx.map(NonZeroU8::get).unwrap_or(0))?;
+                wb.le_u16(0)?;
+
+                f(&mut wb)?;
+
+                let len = wb.get_tail() - RECORD_HEADER_LEN;
+
+                Ok(u16::try_from(len).map_err(|_| ErrorCode::NoSpace)?)
+            })();
+
+            match result {
+                Ok(len) => {
+                    self.data[start + RECORD_HEADER_LEN - 2..start + RECORD_HEADER_LEN]
+                        .copy_from_slice(&len.to_le_bytes());
+                    self.data.truncate(start + RECORD_HEADER_LEN + len as usize);
+
+                    break Ok(());
+                }
+                Err(err) => {
+                    self.data.truncate(start);
+
+                    if err.code() != ErrorCode::NoSpace || start == 0 {
+                        break Err(err);
+                    }
+
+                    // Make room by evicting the oldest record and try again
+                    self.evict_oldest();
+                }
+            }
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if !self.data.is_empty() {
+            let (_, len) = EventEntry::parse(self.priority, &self.data);
+
+            self.data.copy_within(len.., 0);
+            self.data.truncate(self.data.len() - len);
+        }
+    }
+
+    fn iter(&self, from: u64) -> impl Iterator<Item = EventEntry<'_>> + '_ {
+        let mut offset = 0;
+
+        core::iter::from_fn(move || {
+            if offset < self.data.len() {
+                let (entry, len) = EventEntry::parse(self.priority, &self.data[offset..]);
+                offset += len;
+
+                Some(entry)
+            } else {
+                None
+            }
+        })
+        .filter(move |entry| entry.number >= from)
+    }
+}
+
+struct EventsState<const D: usize, const I: usize, const C: usize> {
+    next_number: u64,
+    debug: EventBuf<D>,
+    info: EventBuf<I>,
+    critical: EventBuf<C>,
+}
+
+/// An in-memory event log.
+///
+/// The log keeps the events of each priority in a separate buffer of a fixed size
+/// (`D` bytes for `Debug`, `I` bytes for `Info` and `C` bytes for `Critical` events),
+/// so that a burst of lower-priority events cannot evict higher-priority ones.
+/// Once a buffer is full, its oldest events are evicted to make room for the new ones.
+pub struct Events<
+    const D: usize = DEFAULT_DEBUG_EVENTS_BUF_SIZE,
+    const I: usize = DEFAULT_INFO_EVENTS_BUF_SIZE,
+    const C: usize = DEFAULT_CRITICAL_EVENTS_BUF_SIZE,
+> {
+    state: RefCell<EventsState<D, I, C>>,
+    notification: Notification<NoopRawMutex>,
+}
+
+impl<const D: usize, const I: usize, const C: usize> Events<D, I, C> {
+    /// Create the instance.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            state: RefCell::new(EventsState {
+                next_number: 0,
+                debug: EventBuf::new(EventPriority::Debug),
+                info: EventBuf::new(EventPriority::Info),
+                critical: EventBuf::new(EventPriority::Critical),
+            }),
+            notification: Notification::new(),
+        }
+    }
+
+    /// Create an in-place initializer for the instance.
+    pub fn init() -> impl Init<Self> {
+        init!(Self {
+            state <- RefCell::init(init!(EventsState {
+                next_number: 0,
+                debug <- EventBuf::init(EventPriority::Debug),
+                info <- EventBuf::init(EventPriority::Info),
+                critical <- EventBuf::init(EventPriority::Critical),
+            })),
+            notification: Notification::new(),
+        })
+    }
+
+    /// Set the number which will be assigned to the next emitted event.
+    ///
+    /// The Matter spec requires event numbers to be monotonically increasing across reboots,
+    /// so applications which persist the last event number should use this method on startup
+    /// to resume the numbering.
+    pub fn set_next_event_number(&self, number: u64) {
+        self.state.borrow_mut().next_number = number;
+    }
+
+    /// Emit an event.
+    ///
+    /// The parameters are as follows:
+    /// - `endpoint_id`, `cluster_id` and `event_id` - the path of the event
+    /// - `priority` - the priority of the event
+    /// - `fab_idx` - the index of the fabric the event is associated with, for fabric-sensitive events
+    /// - `f` - a closure which writes the event payload as an anonymous TLV element into the provided buffer;
+    ///   the closure might be called more than once, if older events need to be evicted to make room for the new one
+    ///
+    /// Returns the number assigned to the event.
+    pub fn emit<F>(
+        &self,
+        endpoint_id: EndptId,
+        cluster_id: ClusterId,
+        event_id: EventId,
+        priority: EventPriority,
+        fab_idx: Option<NonZeroU8>,
+        f: F,
+    ) -> Result<u64, Error>
+    where
+        F: FnMut(&mut WriteBuf) -> Result<(), Error>,
+    {
+        let mut state = self.state.borrow_mut();
+
+        let number = state.next_number;
+        let system_timestamp_ms = Instant::now().as_millis();
+
+        match priority {
+            EventPriority::Debug => state.debug.push(
+                number,
+                system_timestamp_ms,
+                endpoint_id,
+                cluster_id,
+                event_id,
+                fab_idx,
+                f,
+            ),
+            EventPriority::Info => state.info.push(
+                number,
+                system_timestamp_ms,
+                endpoint_id,
+                cluster_id,
+                event_id,
+                fab_idx,
+                f,
+            ),
+            EventPriority::Critical => state.critical.push(
+                number,
+                system_timestamp_ms,
+                endpoint_id,
+                cluster_id,
+                event_id,
+                fab_idx,
+                f,
+            ),
+        }?;
+
+        state.next_number += 1;
+
+        debug!(
+            "Event {} emitted: Endpt(0x{:02x})::Cluster(0x{:04x})::Event(0x{:02x}), priority {:?}",
+            number, endpoint_id, cluster_id, event_id, priority
+        );
+
+        self.notification.notify();
+
+        Ok(number)
+    }
+
+    /// Remove all events from the log.
+    ///
+    /// Event numbering is not reset.
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+
+        state.debug.data.clear();
+        state.info.data.clear();
+        state.critical.data.clear();
+    }
+}
+
+impl<const D: usize, const I: usize, const C: usize> EventSource for Events<D, I, C> {
+    fn next_event_number(&self) -> u64 {
+        self.state.borrow().next_number
+    }
+
+    fn visit(
+        &self,
+        from: u64,
+        f: &mut dyn FnMut(&EventEntry) -> Result<bool, Error>,
+    ) -> Result<(), Error> {
+        let state = self.state.borrow();
+
+        let mut debug = state.debug.iter(from).peekable();
+        let mut info = state.info.iter(from).peekable();
+        let mut critical = state.critical.iter(from).peekable();
+
+        // Each buffer is sorted by event number, so just merge the three
+        loop {
+            let numbers = [
+                debug.peek().map(|entry| entry.number),
+                info.peek().map(|entry| entry.number),
+                critical.peek().map(|entry| entry.number),
+            ];
+
+            let Some(min) = numbers.iter().flatten().min().copied() else {
+                break;
+            };
+
+            let entry = if numbers[0] == Some(min) {
+                debug.next()
+            } else if numbers[1] == Some(min) {
+                info.next()
+            } else {
+                critical.next()
+            };
+
+            if !f(&unwrap!(entry))? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn notification(&self) -> &Notification<NoopRawMutex> {
+        &self.notification
+    }
+}
+
+impl<const D: usize, const I: usize, const C: usize> Default for Events<D, I, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dm::clusters::decl::basic_information::{self, HandlerAdaptor, StartUp};
+    use crate::dm::EventPriority;
+    use crate::tlv::{TLVTag, TLVWrite};
+
+    use super::{EventSource, Events};
+
+    fn numbers<const D: usize, const I: usize, const C: usize>(
+        events: &Events<D, I, C>,
+        from: u64,
+    ) -> alloc::vec::Vec<u64> {
+        let mut numbers = alloc::vec::Vec::new();
+
+        events
+            .visit(from, &mut |entry| {
+                numbers.push(entry.number);
+                Ok(true)
+            })
+            .unwrap();
+
+        numbers
+    }
+
+    #[test]
+    fn test_emit_and_visit_in_order() {
+        let events = Events::<256, 256, 256>::new();
+
+        for (index, priority) in [
+            EventPriority::Info,
+            EventPriority::Critical,
+            EventPriority::Debug,
+            EventPriority::Info,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let number = events
+                .emit(1, 6, 0, priority, None, |wb| {
+                    wb.u32(&TLVTag::Anonymous, index as u32)
+                })
+                .unwrap();
+            assert_eq!(number, index as u64);
+        }
+
+        assert_eq!(events.next_event_number(), 4);
+        assert_eq!(numbers(&events, 0), [0, 1, 2, 3]);
+        assert_eq!(numbers(&events, 2), [2, 3]);
+
+        let mut payloads = alloc::vec::Vec::new();
+        events
+            .visit(0, &mut |entry| {
+                assert_eq!(entry.endpoint_id, 1);
+                assert_eq!(entry.cluster_id, 6);
+                payloads.push(entry.data().u32()?);
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(payloads, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_eviction_per_priority() {
+        // Room for exactly two records of 36 bytes in the debug buffer
+        let events = Events::<72, 256, 256>::new();
+
+        events
+            .emit(0, 1, 0, EventPriority::Critical, None, |wb| {
+                wb.u8(&TLVTag::Anonymous, 0)
+            })
+            .unwrap();
+
+        for _ in 0..5 {
+            events
+                .emit(0, 1, 1, EventPriority::Debug, None, |wb| {
+                    wb.utf8(&TLVTag::Anonymous, "debug")
+                })
+                .unwrap();
+        }
+
+        // The critical event is still there, only the last two debug events are
+        assert_eq!(numbers(&events, 0), [0, 4, 5]);
+    }
+
+    #[test]
+    fn test_too_large() {
+        let events = Events::<32, 32, 32>::new();
+
+        assert!(events
+            .emit(0, 1, 0, EventPriority::Info, None, |wb| {
+                wb.utf8(&TLVTag::Anonymous, "a payload which does not fit")
+            })
+            .is_err());
+        assert_eq!(events.next_event_number(), 0);
+    }
+
+    #[test]
+    fn test_emit_generated() {
+        let events = Events::<256, 256, 256>::new();
+
+        let number = HandlerAdaptor(())
+            .emit_start_up(&events, 0, |builder| builder.software_version(3)?.end())
+            .unwrap();
+        assert_eq!(number, 0);
+
+        events
+            .visit(0, &mut |entry| {
+                assert_eq!(entry.priority, EventPriority::Critical);
+                assert_eq!(entry.cluster_id, basic_information::FULL_CLUSTER.id);
+                assert_eq!(entry.event_id, basic_information::EventId::StartUp as u32);
+                assert_eq!(StartUp::new(entry.data()).software_version()?, 3);
+                Ok(true)
+            })
+            .unwrap();
+    }
+}
@@ -18,7 +18,7 @@
 use core::num::NonZeroU8;
 
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
-use embassy_time::Instant;
+use embassy_time::{Duration, Instant};
 
 use portable_atomic::{AtomicU32, Ordering};
 
@@ -26,6 +26,74 @@ use crate::utils::cell::RefCell;
 use crate::utils::init::{init, Init};
 use crate::utils::sync::Notification;
 
+use super::{AttrId, ClusterId, EndptId};
+
+/// The maximum number of (endpoint, cluster) data versions tracked per subscription.
+///
+/// Clusters beyond that number are not tracked and are therefore reported in full in every report.
+pub(crate) const MAX_SUBSCRIPTION_DATAVERS: usize = 32;
+
+/// The largest max interval (in seconds) a publisher is allowed to negotiate, unless the subscriber
+/// requested an even larger max interval ceiling (`SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT` in the Matter spec).
+pub const SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT_SECS: u16 = 60 * 60;
+
+/// The maximum number of changed attributes which can be pending evaluation against the subscriptions.
+///
+/// When more attributes are changed before the pending ones are evaluated,
+/// all subscriptions are considered as changed instead.
+const MAX_CHANGED_ATTRS: usize = 8;
+
+/// The delay (in seconds) before retrying a failed resumption of a persisted subscription.
+/// The delay is doubled with every subsequent failed attempt.
+const RESUME_RETRY_DELAY_SECS: u16 = 10;
+
+/// The number of failed resumption attempts after which a persisted subscription is dropped.
+const MAX_RESUME_ATTEMPTS: u8 = 5;
+
+/// The policy used by the publisher for computing the max interval of a subscription,
+/// out of the min interval floor and the max interval ceiling requested by the subscriber.
+///
+/// Regardless of the policy, the computed max interval always stays within the range allowed by the Matter spec, i.e.
+/// `min_int_floor <= max_int <= max(SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT_SECS, max_int_ceil)`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MaxIntPolicy {
+    /// The smallest max interval (in seconds) the publisher would negotiate.
+    ///
+    /// Max interval ceilings below that value are raised to it, which trades a less frequent
+    /// liveness detection on the subscriber side for less keep-alive reports.
+    pub min_max_int_secs: u16,
+}
+
+impl MaxIntPolicy {
+    /// Create a policy which honors the max interval ceiling requested by the subscriber as-is.
+    pub const fn new() -> Self {
+        Self::min(0)
+    }
+
+    /// Create a policy which raises the max interval ceiling requested by the subscriber to at least `min_max_int_secs`.
+    pub const fn min(min_max_int_secs: u16) -> Self {
+        Self { min_max_int_secs }
+    }
+
+    /// Compute the max interval (in seconds) of a subscription with the provided
+    /// min interval floor and max interval ceiling.
+    pub fn max_int_secs(&self, min_int_floor: u16, max_int_ceil: u16) -> u16 {
+        let limit = max_int_ceil.max(SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT_SECS);
+
+        max_int_ceil
+            .max(self.min_max_int_secs)
+            .max(min_int_floor)
+            .min(limit)
+    }
+}
+
+impl Default for MaxIntPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 struct Subscription {
     fabric_idx: NonZeroU8,
     peer_node_id: u64,
@@ -35,29 +103,74 @@ struct Subscription {
     min_int_secs: u16,
     // Ditto
     max_int_secs: u16,
-    // TODO: Change to `Option<Instant>` to avoid using `Instant::MAX` as a sentinel value
-    reported_at: Instant,
+    // `None` until the priming report is sent
+    reported_at: Option<Instant>,
     changed: bool,
+    // Set for subscriptions loaded from persistent storage which are yet to be resumed,
+    // i.e. re-primed over a new session with the subscriber
+    resume_at: Option<Instant>,
+    resume_attempts: u8,
 }
 
 impl Subscription {
     pub fn report_due(&self, now: Instant) -> bool {
-        // Either the data for the subscription had changed and therefore we need to report,
+        // Either the subscription is to be resumed,
+        // or the data for the subscription had changed and therefore we need to report,
         // or the data for the subscription had not changed yet, however the report interval is due
-        self.changed && self.expired(self.min_int_secs, now)
-            || self.expired(self.min_int_secs.max(self.max_int_secs / 2), now)
+        self.resume_at.map(|at| at <= now).unwrap_or(false)
+            || self.changed && self.expired(self.min_int_secs, now)
+            || self.keep_alive_due(now)
+    }
+
+    pub fn keep_alive_due(&self, now: Instant) -> bool {
+        self.expired(self.keep_alive_secs(), now)
     }
 
     pub fn is_expired(&self, now: Instant) -> bool {
-        self.expired(self.max_int_secs, now)
+        self.expired(self.expiry_secs(), now)
+    }
+
+    /// Return the instant when the subscription would next need to be reported on (or resumed), or would expire,
+    /// or `None` if the subscription is not primed yet.
+    pub fn next_due(&self) -> Option<Instant> {
+        if self.resume_at.is_some() {
+            return self.resume_at;
+        }
+
+        let keep_alive = self.deadline(self.keep_alive_secs())?;
+        let expiry = self.deadline(self.expiry_secs())?;
+
+        let due = if self.changed {
+            keep_alive.min(self.deadline(self.min_int_secs)?)
+        } else {
+            keep_alive
+        };
+
+        Some(due.min(expiry))
+    }
+
+    fn keep_alive_secs(&self) -> u16 {
+        // Leave room for latencies by sending the keep-alive report well before the max interval elapses,
+        // but never earlier than the min interval, and never in a tight loop
+        self.min_int_secs.max(self.max_int_secs / 2).max(1)
+    }
+
+    fn expiry_secs(&self) -> u16 {
+        // Give the keep-alive report a chance to be sent before expiring the subscription
+        self.max_int_secs
+            .max(self.keep_alive_secs().saturating_add(1))
     }
 
     fn expired(&self, secs: u16, now: Instant) -> bool {
-        self.reported_at
-            .checked_add(embassy_time::Duration::from_secs(secs as _))
-            .map(|expiry| expiry <= now)
+        self.deadline(secs)
+            .map(|deadline| deadline <= now)
             .unwrap_or(false)
     }
+
+    fn deadline(&self, secs: u16) -> Option<Instant> {
+        self.reported_at?
+            .checked_add(Duration::from_secs(secs as _))
+    }
 }
 
 /// A utility for tracking subscriptions accepted by the data model.
@@ -67,6 +180,7 @@ impl Subscription {
 pub struct Subscriptions<const N: usize> {
     next_subscription_id: AtomicU32,
     subscriptions: RefCell<crate::utils::storage::Vec<Subscription, N>>,
+    changed_attrs: RefCell<heapless::Vec<(EndptId, ClusterId, AttrId), MAX_CHANGED_ATTRS>>,
     pub(crate) notification: Notification<NoopRawMutex>,
 }
 
@@ -77,6 +191,7 @@ impl<const N: usize> Subscriptions<N> {
         Self {
             next_subscription_id: AtomicU32::new(1),
             subscriptions: RefCell::new(crate::utils::storage::Vec::new()),
+            changed_attrs: RefCell::new(heapless::Vec::new()),
             notification: Notification::new(),
         }
     }
@@ -86,6 +201,7 @@ impl<const N: usize> Subscriptions<N> {
         init!(Self {
             next_subscription_id: AtomicU32::new(1),
             subscriptions <- RefCell::init(crate::utils::storage::Vec::init()),
+            changed_attrs: RefCell::new(heapless::Vec::new()),
             notification: Notification::new(),
         })
     }
@@ -102,6 +218,51 @@ impl<const N: usize> Subscriptions<N> {
         self.notification.notify();
     }
 
+    /// Notify the instance that the attribute with the given path has changed, so that it should report
+    /// only on the subscriptions whose attribute paths cover that attribute.
+    ///
+    /// This method is a cheaper alternative to `notify_changed` for attributes which change often.
+    pub fn notify_attr_changed(
+        &self,
+        endpoint_id: EndptId,
+        cluster_id: ClusterId,
+        attr_id: AttrId,
+    ) {
+        let path = (endpoint_id, cluster_id, attr_id);
+
+        let tracked = {
+            let mut changed_attrs = self.changed_attrs.borrow_mut();
+
+            changed_attrs.contains(&path) || changed_attrs.push(path).is_ok()
+        };
+
+        if tracked {
+            self.notification.notify();
+        } else {
+            // No room to track the attribute, so re-evaluate all subscriptions instead
+            self.notify_changed();
+        }
+    }
+
+    /// Take the attributes changed since the last call, as notified with `notify_attr_changed`.
+    pub(crate) fn take_changed_attrs(
+        &self,
+    ) -> heapless::Vec<(EndptId, ClusterId, AttrId), MAX_CHANGED_ATTRS> {
+        core::mem::take(&mut *self.changed_attrs.borrow_mut())
+    }
+
+    /// Mark the subscription with the given ID as having changed data to report.
+    pub(crate) fn mark_changed(&self, id: u32) {
+        if let Some(sub) = self
+            .subscriptions
+            .borrow_mut()
+            .iter_mut()
+            .find(|sub| sub.id == id)
+        {
+            sub.changed = true;
+        }
+    }
+
     pub(crate) fn add(
         &self,
         fabric_idx: NonZeroU8,
@@ -121,13 +282,97 @@ impl<const N: usize> Subscriptions<N> {
                 id,
                 min_int_secs,
                 max_int_secs,
-                reported_at: Instant::MAX,
+                reported_at: None,
                 changed: false,
+                resume_at: None,
+                resume_attempts: 0,
             })
             .map(|_| id)
             .ok()
     }
 
+    /// Add a subscription loaded from persistent storage.
+    ///
+    /// The subscription has no session yet, and is scheduled for resumption right away.
+    /// Returns `false` if there is no space for the subscription.
+    pub(crate) fn add_resumed(
+        &self,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        id: u32,
+        min_int_secs: u16,
+        max_int_secs: u16,
+    ) -> bool {
+        // Make sure the IDs of new subscriptions do not clash with the ID of the loaded one
+        self.next_subscription_id
+            .fetch_max(id.wrapping_add(1), Ordering::SeqCst);
+
+        self.subscriptions
+            .borrow_mut()
+            .push(Subscription {
+                fabric_idx,
+                peer_node_id,
+                session_id: None,
+                id,
+                min_int_secs,
+                max_int_secs,
+                reported_at: None,
+                changed: false,
+                resume_at: Some(Instant::now()),
+                resume_attempts: 0,
+            })
+            .is_ok()
+    }
+
+    /// Mark the subscription with the given ID as resumed over the session with the given ID.
+    pub(crate) fn mark_resumed(&self, id: u32, session_id: u32) {
+        if let Some(sub) = self
+            .subscriptions
+            .borrow_mut()
+            .iter_mut()
+            .find(|sub| sub.id == id)
+        {
+            sub.session_id = Some(session_id);
+            sub.resume_at = None;
+            sub.resume_attempts = 0;
+        }
+    }
+
+    /// Schedule another resumption attempt for the subscription with the given ID after a failed one.
+    ///
+    /// Returns `false` if the subscription does no longer exist, or if it should be given up on,
+    /// as too many resumption attempts had failed already.
+    pub(crate) fn resume_failed(&self, id: u32) -> bool {
+        let mut subscriptions = self.subscriptions.borrow_mut();
+
+        let Some(sub) = subscriptions
+            .iter_mut()
+            .find(|sub| sub.id == id && sub.resume_at.is_some())
+        else {
+            return false;
+        };
+
+        sub.resume_attempts += 1;
+
+        if sub.resume_attempts >= MAX_RESUME_ATTEMPTS {
+            return false;
+        }
+
+        let delay_secs = (RESUME_RETRY_DELAY_SECS as u64) << (sub.resume_attempts - 1);
+        sub.resume_at = Some(Instant::now() + Duration::from_secs(delay_secs));
+
+        true
+    }
+
+    /// Return the min and max intervals (in seconds) of the subscription with the given ID.
+    pub(crate) fn intervals(&self, id: u32) -> Option<(u16, u16)> {
+        self.subscriptions
+            .borrow()
+            .iter()
+            .find(|sub| sub.id == id)
+            .map(|sub| (sub.min_int_secs, sub.max_int_secs))
+    }
+
     /// Mark the subscription with the given ID as reported.
     ///
     /// Will return `false` if the subscription with the given ID does no longer exist, as it might be
@@ -136,8 +381,7 @@ impl<const N: usize> Subscriptions<N> {
         let mut subscriptions = self.subscriptions.borrow_mut();
 
         if let Some(sub) = subscriptions.iter_mut().find(|sub| sub.id == id) {
-            sub.reported_at = Instant::now();
-            sub.changed = false;
+            sub.reported_at = Some(Instant::now());
 
             true
         } else {
@@ -169,18 +413,28 @@ impl<const N: usize> Subscriptions<N> {
         F: Fn(u32) -> bool,
     {
         self.subscriptions.borrow().iter().find_map(|sub| {
-            sub.session_id
-                .map(&session_removed)
-                .unwrap_or(false)
-                .then_some((
-                    sub.fabric_idx,
-                    sub.peer_node_id,
-                    unwrap!(sub.session_id),
-                    sub.id,
-                ))
+            // Subscriptions which are yet to be resumed have no session
+            let session_id = sub.session_id?;
+
+            session_removed(session_id).then_some((
+                sub.fabric_idx,
+                sub.peer_node_id,
+                session_id,
+                sub.id,
+            ))
         })
     }
 
+    /// Return the earliest instant when any of the subscriptions would need to be reported on,
+    /// or would expire, or `None` if there are no primed subscriptions.
+    pub(crate) fn next_due(&self) -> Option<Instant> {
+        self.subscriptions
+            .borrow()
+            .iter()
+            .filter_map(Subscription::next_due)
+            .min()
+    }
+
     pub(crate) fn find_expired(&self, now: Instant) -> Option<(NonZeroU8, u64, Option<u32>, u32)> {
         self.subscriptions.borrow().iter().find_map(|sub| {
             sub.is_expired(now).then_some((
@@ -192,19 +446,31 @@ impl<const N: usize> Subscriptions<N> {
         })
     }
 
+    /// Find a subscription which is due for a report.
+    ///
+    /// The last element of the returned tuple indicates whether a keep-alive report is due,
+    /// i.e. whether the subscription needs to be reported even if nothing had changed.
+    ///
     /// Note that this method has a side effect:
-    /// it updates the `reported_at` field of the subscription that is returned.
+    /// it clears the `changed` flag of the subscription that is returned, so that changes
+    /// happening while the subscription is being reported on are not lost.
     pub(crate) fn find_report_due(
         &self,
         now: Instant,
-    ) -> Option<(NonZeroU8, u64, Option<u32>, u32)> {
+    ) -> Option<(NonZeroU8, u64, Option<u32>, u32, bool)> {
         self.subscriptions
             .borrow_mut()
             .iter_mut()
             .find(|sub| sub.report_due(now))
             .map(|sub| {
-                sub.reported_at = now;
-                (sub.fabric_idx, sub.peer_node_id, sub.session_id, sub.id)
+                sub.changed = false;
+                (
+                    sub.fabric_idx,
+                    sub.peer_node_id,
+                    sub.session_id,
+                    sub.id,
+                    sub.keep_alive_due(now),
+                )
             })
     }
 }
@@ -214,3 +480,302 @@ impl<const N: usize> Default for Subscriptions<N> {
         Self::new()
     }
 }
+
+/// The data version of a concrete (endpoint, cluster) pair, as tracked by `ReportedDatavers`.
+struct ClusterDataver {
+    endpoint_id: EndptId,
+    cluster_id: ClusterId,
+    /// The data version as of the last report delivered to the subscriber, if any
+    reported: Option<u32>,
+    /// The data version observed while generating the report in progress, if any
+    current: Option<u32>,
+}
+
+/// Tracks - for a single subscription - the data versions of the clusters reported to the subscriber,
+/// so that subsequent reports only contain the clusters whose data version had changed since.
+pub(crate) struct ReportedDatavers {
+    datavers: crate::utils::storage::Vec<ClusterDataver, MAX_SUBSCRIPTION_DATAVERS>,
+}
+
+impl ReportedDatavers {
+    /// Create an empty tracker.
+    pub const fn new() -> Self {
+        Self {
+            datavers: crate::utils::storage::Vec::new(),
+        }
+    }
+
+    /// Return the data version of the cluster as of the last delivered report,
+    /// or `None` if the cluster had not been reported yet, or is not tracked.
+    pub fn reported(&self, endpoint_id: EndptId, cluster_id: ClusterId) -> Option<u32> {
+        self.datavers
+            .iter()
+            .find(|dv| dv.endpoint_id == endpoint_id && dv.cluster_id == cluster_id)
+            .and_then(|dv| dv.reported)
+    }
+
+    /// Record the data version of the cluster as observed while generating the report in progress.
+    ///
+    /// Only the first data version observed for the cluster during a report is retained, so that
+    /// a cluster changing in the middle of a (chunked) report is reported again next time.
+    pub fn observe(&mut self, endpoint_id: EndptId, cluster_id: ClusterId, dataver: u32) {
+        if let Some(dv) = self
+            .datavers
+            .iter_mut()
+            .find(|dv| dv.endpoint_id == endpoint_id && dv.cluster_id == cluster_id)
+        {
+            if dv.current.is_none() {
+                dv.current = Some(dataver);
+            }
+        } else if self
+            .datavers
+            .push(ClusterDataver {
+                endpoint_id,
+                cluster_id,
+                reported: None,
+                current: Some(dataver),
+            })
+            .is_err()
+        {
+            debug!(
+                "No space to track the dataver of cluster {:?}::{:?}, it will always be reported",
+                endpoint_id, cluster_id
+            );
+        }
+    }
+
+    /// Mark the report in progress as delivered to the subscriber.
+    ///
+    /// Clusters which were not observed during the report (i.e. which are no longer part of the
+    /// subscription) are dropped from the tracker.
+    pub fn commit(&mut self) {
+        self.datavers.retain(|dv| dv.current.is_some());
+
+        for dv in self.datavers.iter_mut() {
+            dv.reported = dv.current.take();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU8;
+
+    use embassy_time::Instant;
+
+    use super::{
+        MaxIntPolicy, ReportedDatavers, Subscription, Subscriptions, MAX_CHANGED_ATTRS,
+        MAX_RESUME_ATTEMPTS, MAX_SUBSCRIPTION_DATAVERS,
+        SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT_SECS,
+    };
+
+    fn subscription(
+        min_int_secs: u16,
+        max_int_secs: u16,
+        reported_at: Option<u64>,
+    ) -> Subscription {
+        Subscription {
+            fabric_idx: NonZeroU8::MIN,
+            peer_node_id: 1,
+            session_id: Some(1),
+            id: 1,
+            min_int_secs,
+            max_int_secs,
+            reported_at: reported_at.map(Instant::from_secs),
+            changed: false,
+            resume_at: None,
+            resume_attempts: 0,
+        }
+    }
+
+    #[test]
+    fn test_max_int_policy() {
+        let policy = MaxIntPolicy::new();
+
+        assert_eq!(policy.max_int_secs(0, 10), 10);
+        assert_eq!(policy.max_int_secs(20, 10), 20);
+
+        let policy = MaxIntPolicy::min(60);
+
+        assert_eq!(policy.max_int_secs(0, 10), 60);
+        assert_eq!(policy.max_int_secs(0, 100), 100);
+
+        // Never above the publisher limit, unless the subscriber asked for more
+        let policy = MaxIntPolicy::min(u16::MAX);
+
+        assert_eq!(
+            policy.max_int_secs(0, 10),
+            SUBSCRIPTION_MAX_INTERVAL_PUBLISHER_LIMIT_SECS
+        );
+        assert_eq!(policy.max_int_secs(0, u16::MAX - 1), u16::MAX - 1);
+    }
+
+    #[test]
+    fn test_subscription_not_primed() {
+        let sub = subscription(0, 10, None);
+
+        assert!(!sub.report_due(Instant::MAX));
+        assert!(!sub.is_expired(Instant::MAX));
+        assert_eq!(sub.next_due(), None);
+    }
+
+    #[test]
+    fn test_subscription_due() {
+        let mut sub = subscription(2, 10, Some(100));
+
+        // Keep-alive at half the max interval
+        assert_eq!(sub.next_due(), Some(Instant::from_secs(105)));
+        assert!(!sub.report_due(Instant::from_secs(104)));
+        assert!(sub.report_due(Instant::from_secs(105)));
+        assert!(sub.keep_alive_due(Instant::from_secs(105)));
+
+        // A change is reported as soon as the min interval elapses, and not earlier
+        sub.changed = true;
+
+        assert_eq!(sub.next_due(), Some(Instant::from_secs(102)));
+        assert!(!sub.report_due(Instant::from_secs(101)));
+        assert!(sub.report_due(Instant::from_secs(102)));
+        assert!(!sub.keep_alive_due(Instant::from_secs(102)));
+
+        assert!(!sub.is_expired(Instant::from_secs(109)));
+        assert!(sub.is_expired(Instant::from_secs(110)));
+    }
+
+    #[test]
+    fn test_subscription_keep_alive_not_before_min_int() {
+        let sub = subscription(8, 10, Some(100));
+        assert_eq!(sub.next_due(), Some(Instant::from_secs(108)));
+
+        // The keep-alive report is never sent in a tight loop, and the subscription
+        // never expires before the keep-alive report is due
+        let sub = subscription(0, 0, Some(100));
+        assert_eq!(sub.next_due(), Some(Instant::from_secs(101)));
+        assert!(!sub.is_expired(Instant::from_secs(101)));
+        assert!(sub.is_expired(Instant::from_secs(102)));
+    }
+
+    #[test]
+    fn test_subscription_resume_due() {
+        let mut sub = subscription(2, 10, None);
+        sub.resume_at = Some(Instant::from_secs(100));
+
+        assert_eq!(sub.next_due(), Some(Instant::from_secs(100)));
+        assert!(!sub.report_due(Instant::from_secs(99)));
+        assert!(sub.report_due(Instant::from_secs(100)));
+        assert!(!sub.is_expired(Instant::MAX));
+    }
+
+    #[test]
+    fn test_subscriptions_resume() {
+        let subscriptions = Subscriptions::<2>::new();
+
+        assert!(subscriptions.add_resumed(NonZeroU8::MIN, 1, 7, 2, 10));
+        assert_eq!(subscriptions.intervals(7), Some((2, 10)));
+
+        // New subscriptions do not clash with the resumed one
+        assert_eq!(subscriptions.add(NonZeroU8::MIN, 1, 1, 2, 10), Some(8));
+
+        let (_, _, session_id, id, _) = unwrap!(subscriptions.find_report_due(Instant::now()));
+        assert_eq!((session_id, id), (None, 7));
+
+        // Retried a limited number of times
+        for _ in 1..MAX_RESUME_ATTEMPTS {
+            assert!(subscriptions.resume_failed(7));
+        }
+        assert!(!subscriptions.resume_failed(7));
+
+        subscriptions.mark_resumed(7, 3);
+
+        // Only subscriptions being resumed can fail resuming
+        assert!(!subscriptions.resume_failed(7));
+        assert!(subscriptions.find_report_due(Instant::now()).is_none());
+    }
+
+    #[test]
+    fn test_subscriptions_attr_changed() {
+        let subscriptions = Subscriptions::<1>::new();
+
+        let id = unwrap!(subscriptions.add(NonZeroU8::MIN, 1, 1, 0, 10));
+        assert!(subscriptions.mark_reported(id));
+
+        // Changed attributes are only tracked, as they are matched against the subscribed paths by the data model
+        subscriptions.notify_attr_changed(1, 3, 0);
+        subscriptions.notify_attr_changed(1, 3, 0);
+        assert!(subscriptions.find_report_due(Instant::now()).is_none());
+
+        assert_eq!(subscriptions.take_changed_attrs().as_slice(), &[(1, 3, 0)]);
+        assert!(subscriptions.take_changed_attrs().is_empty());
+
+        // Once too many attributes are changed, all subscriptions are considered changed
+        for attr_id in 0..MAX_CHANGED_ATTRS as u32 + 1 {
+            subscriptions.notify_attr_changed(1, 3, attr_id);
+        }
+        assert!(subscriptions.find_report_due(Instant::now()).is_some());
+    }
+
+    #[test]
+    fn test_datavers_reported_after_commit() {
+        let mut datavers = ReportedDatavers::new();
+
+        datavers.observe(0, 0x28, 5);
+        datavers.observe(1, 0x06, 7);
+
+        // Nothing is considered reported until the report is delivered
+        assert_eq!(datavers.reported(0, 0x28), None);
+
+        datavers.commit();
+
+        assert_eq!(datavers.reported(0, 0x28), Some(5));
+        assert_eq!(datavers.reported(1, 0x06), Some(7));
+        assert_eq!(datavers.reported(1, 0x28), None);
+
+        datavers.observe(0, 0x28, 6);
+        datavers.observe(1, 0x06, 7);
+        datavers.commit();
+
+        assert_eq!(datavers.reported(0, 0x28), Some(6));
+        assert_eq!(datavers.reported(1, 0x06), Some(7));
+    }
+
+    #[test]
+    fn test_datavers_first_observed_wins() {
+        let mut datavers = ReportedDatavers::new();
+
+        // The cluster changed between two chunks of the same report
+        datavers.observe(0, 0x28, 5);
+        datavers.observe(0, 0x28, 6);
+        datavers.commit();
+
+        assert_eq!(datavers.reported(0, 0x28), Some(5));
+    }
+
+    #[test]
+    fn test_datavers_unobserved_dropped() {
+        let mut datavers = ReportedDatavers::new();
+
+        datavers.observe(0, 0x28, 5);
+        datavers.observe(1, 0x06, 7);
+        datavers.commit();
+
+        datavers.observe(1, 0x06, 8);
+        datavers.commit();
+
+        assert_eq!(datavers.reported(0, 0x28), None);
+        assert_eq!(datavers.reported(1, 0x06), Some(8));
+    }
+
+    #[test]
+    fn test_datavers_overflow() {
+        let mut datavers = ReportedDatavers::new();
+
+        for cluster_id in 0..MAX_SUBSCRIPTION_DATAVERS as u32 + 1 {
+            datavers.observe(0, cluster_id, cluster_id);
+        }
+
+        datavers.commit();
+
+        assert_eq!(datavers.reported(0, 0), Some(0));
+        // The cluster which did not fit is not tracked and thus never filtered
+        assert_eq!(datavers.reported(0, MAX_SUBSCRIPTION_DATAVERS as u32), None);
+    }
+}
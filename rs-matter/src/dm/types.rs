@@ -23,6 +23,7 @@ pub use command::*;
 pub use dataver::*;
 pub use encoder::*;
 pub use endpoint::*;
+pub use event::*;
 pub use handler::*;
 pub use metadata::*;
 pub use node::*;
@@ -34,6 +35,7 @@ mod command;
 mod dataver;
 mod encoder;
 mod endpoint;
+mod event;
 mod handler;
 mod metadata;
 mod node;
@@ -43,6 +45,7 @@ pub type EndptId = u16;
 pub type ClusterId = u32;
 pub type AttrId = u32;
 pub type CmdId = u32;
+pub type EventId = u32;
 
 #[derive(Debug, ToTLV, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
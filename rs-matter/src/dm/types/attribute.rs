@@ -233,7 +233,7 @@ impl<T, E> ArrayAttributeWrite<T, E> {
 // TODO: What if we instead of creating this, we just pass the AttrData/AttrPath to the read/write
 // methods?
 /// The Attribute Details structure records the details about the attribute under consideration.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct AttrDetails<'a> {
     pub node: &'a Node<'a>,
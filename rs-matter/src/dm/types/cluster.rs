@@ -28,9 +28,11 @@ use crate::tlv::{TLVTag, TLVWrite};
 pub type WithAttrs = fn(&Attribute, u16, u32) -> bool;
 /// A type alias for the command matching function
 pub type WithCmds = fn(&Command, u16, u32) -> bool;
+/// A type alias for the event matching function
+pub type WithEvents = fn(&Event, u16, u32) -> bool;
 
 /// A struct modeling the cluster meta-data
-/// (i.e. what is the cluster ID, revision, features, attributes and their access, commands and their access)
+/// (i.e. what is the cluster ID, revision, features, attributes, commands and events and their access)
 /// in the Matter data model.
 #[derive(Debug, Clone)]
 pub struct Cluster<'a> {
@@ -52,12 +54,21 @@ pub struct Cluster<'a> {
     /// even if the concrete instantiation of the cluster supports only a subset of these.
     /// See` with_cmds` for more details.
     pub commands: &'a [Command],
+    /// The events of the cluster.
+    ///
+    /// These could be all events as specified in the Matter spec,
+    /// even if the concrete instantiation of the cluster supports only a subset of these.
+    /// See` with_events` for more details.
+    pub events: &'a [Event],
     /// A function that takes an attribute and returns a boolean indicating if the attribute
     /// is supported by the cluster.
     pub with_attrs: WithAttrs,
     /// A function that takes a command and returns a boolean indicating if the command
     /// is supported by the cluster.
     pub with_cmds: WithCmds,
+    /// A function that takes an event and returns a boolean indicating if the event
+    /// is supported by the cluster.
+    pub with_events: WithEvents,
 }
 
 impl<'a> Cluster<'a> {
@@ -69,16 +80,21 @@ impl<'a> Cluster<'a> {
     /// - `feature_map`: The feature map of the cluster
     /// - `attributes`: The attributes of the cluster
     /// - `commands`: The commands of the cluster
+    /// - `events`: The events of the cluster
     /// - `with_attrs`: A function that takes an attribute and returns a boolean indicating if the attribute should be included
     /// - `with_cmds`: A function that takes a command and returns a boolean indicating if the command should be included
+    /// - `with_events`: A function that takes an event and returns a boolean indicating if the event should be included
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         id: ClusterId,
         revision: u16,
         feature_map: u32,
         attributes: &'a [Attribute],
         commands: &'a [Command],
+        events: &'a [Event],
         with_attrs: WithAttrs,
         with_cmds: WithCmds,
+        with_events: WithEvents,
     ) -> Self {
         Self {
             id,
@@ -86,8 +102,10 @@ impl<'a> Cluster<'a> {
             feature_map,
             attributes,
             commands,
+            events,
             with_attrs,
             with_cmds,
+            with_events,
         }
     }
 
@@ -114,6 +132,14 @@ impl<'a> Cluster<'a> {
         Self { with_cmds, ..self }
     }
 
+    /// Return a new cluster with a modified events' matcher
+    pub const fn with_events(self, with_events: WithEvents) -> Self {
+        Self {
+            with_events,
+            ..self
+        }
+    }
+
     /// Check if the accessor has the required permissions to access the attribute
     /// designated by the provided path.
     ///
@@ -179,6 +205,30 @@ impl<'a> Cluster<'a> {
         }
     }
 
+    /// Check if the accessor has the required permissions to read the event
+    /// designated by the provided path.
+    pub(crate) fn check_event_access(
+        &self,
+        accessor: &Accessor,
+        path: GenericPath,
+        event_id: EventId,
+    ) -> Result<(), IMStatusCode> {
+        let mut access_req = AccessReq::new(accessor, path, Access::READ);
+
+        let target_perms = self
+            .events()
+            .find(|event| event.id == event_id)
+            .map(|event| event.access)
+            .ok_or(IMStatusCode::UnsupportedEvent)?;
+
+        access_req.set_target_perms(target_perms);
+        if access_req.allow() {
+            Ok(())
+        } else {
+            Err(IMStatusCode::UnsupportedAccess)
+        }
+    }
+
     /// Return an iterator over the attributes of the cluster which are
     /// configured to be included based on the provided configuration.
     pub(crate) fn attributes(&self) -> impl Iterator<Item = &Attribute> + '_ {
@@ -195,6 +245,14 @@ impl<'a> Cluster<'a> {
             .filter(|cmd| (self.with_cmds)(cmd, self.revision, self.feature_map))
     }
 
+    /// Return an iterator over the events of the cluster which are
+    /// configured to be included based on the provided configuration.
+    pub(crate) fn events(&self) -> impl Iterator<Item = &Event> + '_ {
+        self.events
+            .iter()
+            .filter(|event| (self.with_events)(event, self.revision, self.feature_map))
+    }
+
     /// Performs an IM attribute read for the given attribute ID.
     ///
     /// The provided attribute ID must be a global attribute, or else
@@ -276,8 +334,12 @@ impl<'a> Cluster<'a> {
             self.id
         );
 
-        // No events for now
         tw.start_array(tag)?;
+        for event in self.events() {
+            tw.u32(&TLVTag::Anonymous, event.id)?;
+            debug!("    Event: 0x{:02x},", event.id);
+        }
+
         tw.end_container()?;
 
         debug!("])");
@@ -326,6 +388,15 @@ impl core::fmt::Display for Cluster<'_> {
             }
         }
 
+        write!(f, "], events [")?;
+        for (index, event) in self.events().enumerate() {
+            if index > 0 {
+                write!(f, ", {}", event)?;
+            } else {
+                write!(f, "{}", event)?;
+            }
+        }
+
         write!(f, "]")
     }
 }
@@ -353,6 +424,15 @@ impl defmt::Format for Cluster<'_> {
             }
         }
 
+        defmt::write!(f, "], events [");
+        for (index, event) in self.events().enumerate() {
+            if index > 0 {
+                defmt::write!(f, ", {}", event);
+            } else {
+                defmt::write!(f, "{}", event);
+            }
+        }
+
         defmt::write!(f, "]")
     }
 }
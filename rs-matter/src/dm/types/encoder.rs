@@ -15,6 +15,7 @@
  *    limitations under the License.
  */
 
+use core::cell::Cell;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
@@ -35,20 +36,30 @@ use super::{
 
 pub struct AttrDataEncoder<'a, 'b, 'c> {
     dataver_filter: Option<u32>,
+    observed_dataver: Option<&'a Cell<Option<u32>>>,
     path: AttrPath,
     tw: &'a mut TLVWriter<'b, 'c>,
 }
 
 impl<'a, 'b, 'c> AttrDataEncoder<'a, 'b, 'c> {
+    /// Read the attribute denoted by `item` and encode it (or its status) into `tw`.
+    ///
+    /// If `observed_dataver` is provided, it is updated with the data version of the cluster
+    /// the attribute belongs to, regardless of whether the attribute was filtered out by the
+    /// data version filter of `item` or not.
+    ///
+    /// Returns `false` if there is no more space in `tw` for the attribute.
     pub async fn handle_read<T: DataModelHandler>(
         exchange: &Exchange<'_>,
         item: &Result<AttrDetails<'_>, AttrStatus>,
         handler: &T,
         tw: &mut TLVWriter<'_, '_>,
+        observed_dataver: Option<&Cell<Option<u32>>>,
     ) -> Result<bool, Error> {
         let status = match item {
             Ok(attr) => {
-                let encoder = AttrDataEncoder::new(attr, tw);
+                let mut encoder = AttrDataEncoder::new(attr, tw);
+                encoder.observed_dataver = observed_dataver;
 
                 let result = handler
                     .read(&ReadContext::new(exchange, attr), encoder)
@@ -108,12 +119,17 @@ impl<'a, 'b, 'c> AttrDataEncoder<'a, 'b, 'c> {
     pub fn new(attr: &AttrDetails, tw: &'a mut TLVWriter<'b, 'c>) -> Self {
         Self {
             dataver_filter: attr.dataver,
+            observed_dataver: None,
             path: attr.path(),
             tw,
         }
     }
 
     pub fn with_dataver(self, dataver: u32) -> Result<Option<AttrDataWriter<'a, 'b, 'c>>, Error> {
+        if let Some(observed_dataver) = self.observed_dataver {
+            observed_dataver.set(Some(dataver));
+        }
+
         if self
             .dataver_filter
             .map(|dataver_filter| dataver_filter != dataver)
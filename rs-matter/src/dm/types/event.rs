@@ -0,0 +1,75 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::fmt;
+
+use crate::tlv::{FromTLV, ToTLV};
+
+use super::{Access, EventId};
+
+/// The priority of an event, as per the Matter spec.
+///
+/// Events of each priority are stored in a separate buffer, so that
+/// e.g. a flood of `Debug` events cannot evict any `Critical` ones.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(datatype = "u8")]
+pub enum EventPriority {
+    Debug,
+    Info,
+    Critical,
+}
+
+/// A type modeling the event meta-data in the Matter data model.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Event {
+    /// The ID of the event
+    pub id: EventId,
+    /// The priority of the event
+    pub priority: EventPriority,
+    /// The access control for the event
+    pub access: Access,
+}
+
+impl Event {
+    /// Creates a new event with the given ID, priority, and access control.
+    pub const fn new(id: EventId, priority: EventPriority, access: Access) -> Self {
+        Self {
+            id,
+            priority,
+            access,
+        }
+    }
+}
+
+impl core::fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/// A macro to generate the events for a cluster.
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! events {
+    ($($event:expr $(,)?)*) => {
+        &[
+            $($event,)*
+        ]
+    }
+}
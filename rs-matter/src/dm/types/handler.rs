@@ -170,6 +170,23 @@ impl<'a> InvokeContext<'a> {
         self.notify
             .notify(self.cmd.endpoint_id, self.cmd.cluster_id);
     }
+
+    /// Notify that another cluster on the endpoint of the command has changed.
+    ///
+    /// Useful for commands which change the attributes of other clusters, like recalling a scene.
+    #[inline(always)]
+    pub fn notify_cluster_changed(&self, cluster_id: ClusterId) {
+        self.notify.notify(self.cmd.endpoint_id, cluster_id);
+    }
+
+    /// Notify that a cluster on another endpoint has changed.
+    ///
+    /// Useful for commands which change data reported by clusters on other endpoints, like the
+    /// group table reported by the Group Key Management cluster of the root endpoint.
+    #[inline(always)]
+    pub fn notify_endpoint_cluster_changed(&self, endpoint_id: EndptId, cluster_id: ClusterId) {
+        self.notify.notify(endpoint_id, cluster_id);
+    }
 }
 
 pub trait DataModelHandler: super::AsyncMetadata + AsyncHandler {}
@@ -26,7 +26,7 @@ use crate::im::{
 };
 use crate::tlv::{TLVArray, TLVElement};
 
-use super::{AttrDetails, ClusterId, CmdDetails, EndptId};
+use super::{AttrDetails, ClusterId, CmdDetails, EndptId, EventId};
 
 /// The main Matter metadata type describing a Matter Node.
 #[derive(Debug, Clone)]
@@ -49,6 +49,31 @@ impl<'a> Node<'a> {
         self.endpoints.iter().find(|endpoint| endpoint.id == id)
     }
 
+    /// Check whether the event designated by the provided concrete path exists
+    /// and is accessible by the accessor.
+    ///
+    /// Returns an error status indicating whether the endpoint, the cluster or the event
+    /// is not existing, or if the event is not accessible.
+    pub fn check_event_access(
+        &self,
+        accessor: &Accessor,
+        endpoint_id: EndptId,
+        cluster_id: ClusterId,
+        event_id: EventId,
+    ) -> Result<(), IMStatusCode> {
+        let cluster = self
+            .endpoint(endpoint_id)
+            .ok_or(IMStatusCode::UnsupportedEndpoint)?
+            .cluster(cluster_id)
+            .ok_or(IMStatusCode::UnsupportedCluster)?;
+
+        cluster.check_event_access(
+            accessor,
+            GenericPath::new(Some(endpoint_id), Some(cluster_id), Some(event_id)),
+            event_id,
+        )
+    }
+
     /// Expand (potentially wildcard) read requests into concrete attribute details
     /// using the node metadata.
     ///
@@ -433,7 +458,10 @@ where
         while (self.endpoint_index as usize) < self.node.endpoints.len() {
             let endpoint = &self.node.endpoints[self.endpoint_index as usize];
 
-            if path.endpoint.is_none() || path.endpoint == Some(endpoint.id) {
+            // Group requests only target the endpoints which are members of the group
+            if (path.endpoint.is_none() || path.endpoint == Some(endpoint.id))
+                && self.accessor.is_endpoint_member(endpoint.id)
+            {
                 while (self.cluster_index as usize) < endpoint.clusters.len() {
                     let cluster = &endpoint.clusters[self.cluster_index as usize];
 
@@ -719,6 +747,8 @@ mod test {
                     0,
                     &[Attribute::new(0, Access::all(), Quality::all())],
                     &[Command::new(0, None, Access::all())],
+                    &[],
+                    |_, _, _| true,
                     |_, _, _| true,
                     |_, _, _| true,
                 )],
@@ -828,6 +858,8 @@ mod test {
                             0,
                             &[Attribute::new(1, Access::all(), Quality::all())],
                             &[Command::new(1, None, Access::all())],
+                            &[],
+                            |_, _, _| true,
                             |_, _, _| true,
                             |_, _, _| true,
                         ),
@@ -837,6 +869,8 @@ mod test {
                             0,
                             &[Attribute::new(1, Access::all(), Quality::all())],
                             &[Command::new(1, None, Access::all())],
+                            &[],
+                            |_, _, _| true,
                             |_, _, _| true,
                             |_, _, _| true,
                         ),
@@ -852,6 +886,8 @@ mod test {
                             0,
                             &[Attribute::new(1, Access::all(), Quality::all())],
                             &[Command::new(1, None, Access::all())],
+                            &[],
+                            |_, _, _| true,
                             |_, _, _| true,
                             |_, _, _| true,
                         ),
@@ -867,6 +903,8 @@ mod test {
                                 Command::new(20, None, Access::all()),
                                 Command::new(30, None, Access::all()),
                             ],
+                            &[],
+                            |_, _, _| true,
                             |_, _, _| true,
                             |_, _, _| true,
                         ),
@@ -35,6 +35,7 @@ pub enum ErrorCode {
     CommandNotFound,
     Duplicate,
     EndpointNotFound,
+    EventNotFound,
     InvalidAction,
     InvalidCommand,
     FailSafeRequired,
@@ -93,6 +94,8 @@ pub enum ErrorCode {
     NocFabricConflict,
     NocLabelConflict,
     NocInvalidFabricIndex,
+    // A failure status reported by the peer, with no more specific error code
+    Failure,
 }
 
 impl From<ErrorCode> for Error {
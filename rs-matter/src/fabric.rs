@@ -16,7 +16,9 @@
  */
 
 use core::mem::MaybeUninit;
+use core::net::Ipv6Addr;
 use core::num::NonZeroU8;
+use core::time::Duration;
 
 use heapless::String;
 
@@ -25,8 +27,11 @@ use crate::cert::{CertRef, MAX_CERT_TLV_LEN};
 use crate::crypto::{self, hkdf_sha256, HmacSha256, KeyPair};
 use crate::dm::Privilege;
 use crate::error::{Error, ErrorCode};
-use crate::group_keys::KeySet;
-use crate::tlv::{FromTLV, TLVElement, TLVTag, TLVWrite, TagType, ToTLV};
+use crate::group_keys::{
+    self, GroupEntry, GroupEpochKey, GroupKeyMapEntry, GroupKeySet, KeySet, IPK_KEY_SET_ID,
+    MAX_GROUPS_PER_FABRIC, MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC, MAX_GROUP_KEY_SETS_PER_FABRIC,
+};
+use crate::tlv::{FromTLV, Optional, TLVElement, TLVTag, TLVWrite, TagType, ToTLV};
 use crate::utils::init::{init, Init, InitMaybeUninit, IntoFallibleInit};
 use crate::utils::storage::{Vec, WriteBuf};
 use crate::MatterMdnsService;
@@ -68,6 +73,16 @@ pub struct Fabric {
     label: String<32>,
     /// Access Control List
     acl: Vec<AclEntry, { acl::ENTRIES_PER_FABRIC }>,
+    /// Group key sets, not including the IPK key set
+    ///
+    /// This and the other group-related fields are optional in the TLV encoding only
+    /// so that fabrics persisted before group support was added can still be loaded;
+    /// an absent field is treated as an empty list.
+    group_key_sets: Optional<Vec<GroupKeySet, MAX_GROUP_KEY_SETS_PER_FABRIC>>,
+    /// Group ID to group key set mappings
+    group_key_map: Optional<Vec<GroupKeyMapEntry, MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC>>,
+    /// Group table (groups and their member endpoints)
+    groups: Optional<Vec<GroupEntry, MAX_GROUPS_PER_FABRIC>>,
 }
 
 impl Fabric {
@@ -93,6 +108,9 @@ impl Fabric {
             ipk <- KeySet::init(),
             label: String::new(),
             acl <- Vec::init(),
+            group_key_sets <- Optional::init_some(Vec::init()),
+            group_key_map <- Optional::init_some(Vec::init()),
+            groups <- Optional::init_some(Vec::init()),
         })
     }
 
@@ -164,17 +182,9 @@ impl Fabric {
 
     /// Is the fabric matching the privided destination ID
     pub fn is_dest_id(&self, random: &[u8], target: &[u8]) -> Result<(), Error> {
-        let mut mac = HmacSha256::new(self.ipk.op_key())?;
-
-        mac.update(random)?;
-        mac.update(CertRef::new(TLVElement::new(self.root_ca())).pubkey()?)?;
-
-        mac.update(&self.fabric_id.to_le_bytes())?;
-        mac.update(&self.node_id.to_le_bytes())?;
-
         let mut id = MaybeUninit::<[u8; crypto::SHA256_HASH_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
         let id = id.init_zeroed();
-        mac.finish(id)?;
+        self.compute_dest_id(random, self.node_id, id)?;
         if id.as_slice() == target {
             Ok(())
         } else {
@@ -182,6 +192,22 @@ impl Fabric {
         }
     }
 
+    /// Compute the CASE destination ID of the node with the provided node ID on this fabric,
+    /// for the provided initiator random.
+    ///
+    /// The destination ID is written into `id`, which must be `crypto::SHA256_HASH_LEN_BYTES` long.
+    pub fn compute_dest_id(&self, random: &[u8], node_id: u64, id: &mut [u8]) -> Result<(), Error> {
+        let mut mac = HmacSha256::new(self.ipk.op_key())?;
+
+        mac.update(random)?;
+        mac.update(CertRef::new(TLVElement::new(self.root_ca())).pubkey()?)?;
+
+        mac.update(&self.fabric_id.to_le_bytes())?;
+        mac.update(&node_id.to_le_bytes())?;
+
+        mac.finish(id)
+    }
+
     /// Sign a message with the fabric's key pair
     pub fn sign_msg(&self, msg: &[u8], signature: &mut [u8]) -> Result<usize, Error> {
         self.key_pair.sign_msg(msg, signature)
@@ -249,6 +275,292 @@ impl Fabric {
         &self.ipk
     }
 
+    /// Return an iterator over the group key sets of the fabric (not including the IPK key set)
+    pub fn group_key_sets_iter(&self) -> impl Iterator<Item = &GroupKeySet> {
+        self.group_key_sets().iter()
+    }
+
+    /// Return an iterator over the group ID to group key set mappings of the fabric
+    pub fn group_key_map_iter(&self) -> impl Iterator<Item = &GroupKeyMapEntry> {
+        self.group_key_map().iter()
+    }
+
+    /// Return an iterator over the group table entries of the fabric
+    pub fn groups_iter(&self) -> impl Iterator<Item = &GroupEntry> {
+        self.groups().iter()
+    }
+
+    /// Return the group table entry of the group with the provided ID
+    pub fn group(&self, group_id: u16) -> Option<&GroupEntry> {
+        self.groups()
+            .iter()
+            .find(|entry| entry.group_id == group_id)
+    }
+
+    /// Return `true` if the provided endpoint is a member of the group with the provided ID
+    pub fn is_group_member(&self, group_id: u16, endpoint_id: u16) -> bool {
+        self.group(group_id)
+            .map(|entry| entry.endpoints.contains(&endpoint_id))
+            .unwrap_or(false)
+    }
+
+    /// Return an iterator over the IPv6 multicast addresses of all groups of the fabric
+    /// which have at least one endpoint of the node as a member
+    pub fn group_multicast_addrs(&self) -> impl Iterator<Item = Ipv6Addr> + '_ {
+        self.groups()
+            .iter()
+            .map(|entry| group_keys::group_multicast_addr(self.fabric_id, entry.group_id))
+    }
+
+    /// Return the epoch keys which can decrypt a group message sent to the provided group ID
+    /// with the provided group session ID
+    pub fn group_keys_for_rx(
+        &self,
+        group_id: u16,
+        session_id: u16,
+    ) -> impl Iterator<Item = &GroupEpochKey> {
+        self.group_key_map()
+            .iter()
+            .filter(move |entry| entry.group_id == group_id)
+            .filter_map(|entry| self.group_key_set(entry.key_set_id))
+            .flat_map(move |key_set| key_set.for_session_id(session_id))
+    }
+
+    /// Return the epoch key which should be used for encrypting a group message
+    /// sent to the provided group ID at the provided time (time since the UNIX epoch)
+    pub fn group_key_for_tx(&self, group_id: u16, now: Duration) -> Option<&GroupEpochKey> {
+        self.group_key_map()
+            .iter()
+            .find(|entry| entry.group_id == group_id)
+            .and_then(|entry| self.group_key_set(entry.key_set_id))
+            .and_then(|key_set| key_set.current(now))
+    }
+
+    /// Return the group key set with the provided ID
+    pub fn group_key_set(&self, key_set_id: u16) -> Option<&GroupKeySet> {
+        self.group_key_sets()
+            .iter()
+            .find(|key_set| key_set.id == key_set_id)
+    }
+
+    /// Add a group key set to the fabric, replacing the existing key set with the same ID (if any)
+    fn group_key_set_add(
+        &mut self,
+        key_set_id: u16,
+        epoch_keys: &[(&[u8], u64)],
+    ) -> Result<(), Error> {
+        let key_set = GroupKeySet::new(key_set_id, epoch_keys, self.compressed_fabric_id)?;
+
+        if let Some(existing) = self
+            .group_key_sets_mut()
+            .iter_mut()
+            .find(|key_set| key_set.id == key_set_id)
+        {
+            *existing = key_set;
+        } else {
+            self.group_key_sets_mut()
+                .push(key_set)
+                .map_err(|_| ErrorCode::ResourceExhausted)?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the group key set with the provided ID, as well as all group mappings to it
+    fn group_key_set_remove(&mut self, key_set_id: u16) -> Result<(), Error> {
+        if self.group_key_set(key_set_id).is_none() {
+            Err(ErrorCode::NotFound)?;
+        }
+
+        self.group_key_sets_mut()
+            .retain(|key_set| key_set.id != key_set_id);
+        self.group_key_map_mut()
+            .retain(|entry| entry.key_set_id != key_set_id);
+
+        Ok(())
+    }
+
+    /// Add the provided endpoint to the group with the provided ID, creating the group
+    /// in the group table if it does not exist yet
+    ///
+    /// The name of the group is updated with the provided one.
+    fn group_add(&mut self, group_id: u16, endpoint_id: u16, name: &str) -> Result<(), Error> {
+        if group_id == 0 {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        let mut group_name = String::new();
+        group_name
+            .push_str(name)
+            .map_err(|_| ErrorCode::ConstraintError)?;
+
+        let entry = if let Some(entry) = self
+            .groups_mut()
+            .iter_mut()
+            .find(|entry| entry.group_id == group_id)
+        {
+            entry
+        } else {
+            self.groups_mut()
+                .push(GroupEntry {
+                    group_id,
+                    endpoints: Vec::new(),
+                    name: String::new(),
+                })
+                .map_err(|_| ErrorCode::ResourceExhausted)?;
+
+            unwrap!(self.groups_mut().last_mut())
+        };
+
+        if !entry.endpoints.contains(&endpoint_id) {
+            entry
+                .endpoints
+                .push(endpoint_id)
+                .map_err(|_| ErrorCode::ResourceExhausted)?;
+        }
+
+        entry.name = group_name;
+
+        Ok(())
+    }
+
+    /// Remove the provided endpoint from the group with the provided ID
+    ///
+    /// The group is removed from the group table once it has no member endpoints left.
+    fn group_remove(&mut self, group_id: u16, endpoint_id: u16) -> Result<(), Error> {
+        if !self.is_group_member(group_id, endpoint_id) {
+            Err(ErrorCode::NotFound)?;
+        }
+
+        self.group_remove_endpoint(Some(group_id), endpoint_id);
+
+        Ok(())
+    }
+
+    /// Remove the provided endpoint from all groups of the group table
+    fn group_remove_all(&mut self, endpoint_id: u16) {
+        self.group_remove_endpoint(None, endpoint_id);
+    }
+
+    /// Remove the provided endpoint from the group with the provided ID (or from all groups if the ID is `None`),
+    /// and then remove all groups which do not have member endpoints anymore
+    fn group_remove_endpoint(&mut self, group_id: Option<u16>, endpoint_id: u16) {
+        for entry in self
+            .groups_mut()
+            .iter_mut()
+            .filter(|entry| group_id.is_none() || group_id == Some(entry.group_id))
+        {
+            entry.endpoints.retain(|id| *id != endpoint_id);
+        }
+
+        self.groups_mut()
+            .retain(|entry| !entry.endpoints.is_empty());
+    }
+
+    /// Add a new group ID to group key set mapping to the fabric
+    ///
+    /// Return the index of the added entry.
+    fn group_key_map_add(&mut self, entry: GroupKeyMapEntry) -> Result<usize, Error> {
+        self.group_key_map_check(None, &entry)?;
+
+        self.group_key_map_mut()
+            .push(entry)
+            .map_err(|_| ErrorCode::ResourceExhausted)?;
+
+        Ok(self.group_key_map().len() - 1)
+    }
+
+    /// Update an existing group ID to group key set mapping in the fabric
+    fn group_key_map_update(&mut self, idx: usize, entry: GroupKeyMapEntry) -> Result<(), Error> {
+        if self.group_key_map().len() <= idx {
+            return Err(ErrorCode::NotFound.into());
+        }
+
+        self.group_key_map_check(Some(idx), &entry)?;
+
+        self.group_key_map_mut()[idx] = entry;
+
+        Ok(())
+    }
+
+    /// Remove a group ID to group key set mapping from the fabric
+    fn group_key_map_remove(&mut self, idx: usize) -> Result<(), Error> {
+        if self.group_key_map().len() <= idx {
+            return Err(ErrorCode::NotFound.into());
+        }
+
+        self.group_key_map_mut().remove(idx);
+
+        Ok(())
+    }
+
+    /// Remove all group ID to group key set mappings from the fabric
+    fn group_key_map_remove_all(&mut self) {
+        self.group_key_map_mut().clear();
+    }
+
+    /// Check that the provided group key map entry can be stored at the provided index
+    /// (or appended, if the index is `None`)
+    ///
+    /// As per the Matter spec, the IPK key set cannot be mapped to a group, and
+    /// each group can be mapped to at most one key set.
+    fn group_key_map_check(
+        &self,
+        idx: Option<usize>,
+        entry: &GroupKeyMapEntry,
+    ) -> Result<(), Error> {
+        if entry.group_id == 0 || entry.key_set_id == IPK_KEY_SET_ID {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        if self
+            .group_key_map()
+            .iter()
+            .enumerate()
+            .any(|(index, other)| Some(index) != idx && other.group_id == entry.group_id)
+        {
+            Err(ErrorCode::ConstraintError)?;
+        }
+
+        Ok(())
+    }
+
+    fn group_key_sets(&self) -> &[GroupKeySet] {
+        self.group_key_sets.as_opt_deref().unwrap_or(&[])
+    }
+
+    fn group_key_sets_mut(&mut self) -> &mut Vec<GroupKeySet, MAX_GROUP_KEY_SETS_PER_FABRIC> {
+        Self::present(&mut self.group_key_sets)
+    }
+
+    fn group_key_map(&self) -> &[GroupKeyMapEntry] {
+        self.group_key_map.as_opt_deref().unwrap_or(&[])
+    }
+
+    fn group_key_map_mut(
+        &mut self,
+    ) -> &mut Vec<GroupKeyMapEntry, MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC> {
+        Self::present(&mut self.group_key_map)
+    }
+
+    fn groups(&self) -> &[GroupEntry] {
+        self.groups.as_opt_deref().unwrap_or(&[])
+    }
+
+    fn groups_mut(&mut self) -> &mut Vec<GroupEntry, MAX_GROUPS_PER_FABRIC> {
+        Self::present(&mut self.groups)
+    }
+
+    /// Return the provided optional list, initializing it to an empty one first
+    /// if it was absent in the TLV data the fabric was loaded from
+    fn present<T, const N: usize>(list: &mut Optional<Vec<T, N>>) -> &mut Vec<T, N> {
+        if list.is_none() {
+            list.reinit(Optional::init_some(Vec::init()));
+        }
+
+        unwrap!(list.as_opt_mut())
+    }
+
     /// Return an iterator over the ACL entries of the fabric
     pub fn acl_iter(&self) -> impl Iterator<Item = &AclEntry> {
         self.acl.iter()
@@ -393,6 +705,7 @@ pub const MAX_SUPPORTED_FABRICS: usize = 3;
 pub struct FabricMgr {
     fabrics: Vec<Fabric, MAX_SUPPORTED_FABRICS>,
     changed: bool,
+    group_table_ver: u32,
 }
 
 impl Default for FabricMgr {
@@ -408,6 +721,7 @@ impl FabricMgr {
         Self {
             fabrics: Vec::new(),
             changed: false,
+            group_table_ver: 0,
         }
     }
 
@@ -416,6 +730,7 @@ impl FabricMgr {
         init!(Self {
             fabrics <- Vec::init(),
             changed: false,
+            group_table_ver: 0,
         })
     }
 
@@ -423,6 +738,7 @@ impl FabricMgr {
     pub fn reset(&mut self) {
         self.fabrics.clear();
         self.changed = false;
+        self.group_table_changed();
     }
 
     /// Load the fabrics from the provided TLV data
@@ -617,6 +933,7 @@ impl FabricMgr {
 
         mdns_notif();
         self.changed = true;
+        self.group_table_changed();
 
         Ok(())
     }
@@ -684,6 +1001,157 @@ impl FabricMgr {
         fabric.allow(req)
     }
 
+    /// Add a group key set to the fabric with the provided local index, replacing the
+    /// existing key set with the same ID (if any)
+    ///
+    /// The epoch keys are provided as `(epoch key, start time)` pairs.
+    pub fn group_key_set_add(
+        &mut self,
+        fab_idx: NonZeroU8,
+        key_set_id: u16,
+        epoch_keys: &[(&[u8], u64)],
+    ) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_set_add(key_set_id, epoch_keys)?;
+        self.changed = true;
+
+        Ok(())
+    }
+
+    /// Remove a group key set, as well as all group mappings to it, from the fabric with the provided local index
+    pub fn group_key_set_remove(
+        &mut self,
+        fab_idx: NonZeroU8,
+        key_set_id: u16,
+    ) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_set_remove(key_set_id)?;
+        self.changed = true;
+
+        Ok(())
+    }
+
+    /// Return the version of the group table of all fabrics
+    ///
+    /// The version changes whenever an endpoint is added to or removed from a group, and when a fabric is removed.
+    pub fn group_table_ver(&self) -> u32 {
+        self.group_table_ver
+    }
+
+    fn group_table_changed(&mut self) {
+        self.group_table_ver = self.group_table_ver.wrapping_add(1);
+    }
+
+    /// Add the provided endpoint to a group of the fabric with the provided local index,
+    /// creating the group in the group table of the fabric if it does not exist yet
+    pub fn group_add(
+        &mut self,
+        fab_idx: NonZeroU8,
+        group_id: u16,
+        endpoint_id: u16,
+        name: &str,
+    ) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_add(group_id, endpoint_id, name)?;
+        self.changed = true;
+        self.group_table_changed();
+
+        Ok(())
+    }
+
+    /// Remove the provided endpoint from a group of the fabric with the provided local index
+    pub fn group_remove(
+        &mut self,
+        fab_idx: NonZeroU8,
+        group_id: u16,
+        endpoint_id: u16,
+    ) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_remove(group_id, endpoint_id)?;
+        self.changed = true;
+        self.group_table_changed();
+
+        Ok(())
+    }
+
+    /// Remove the provided endpoint from all groups of the fabric with the provided local index
+    pub fn group_remove_all(&mut self, fab_idx: NonZeroU8, endpoint_id: u16) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_remove_all(endpoint_id);
+        self.changed = true;
+        self.group_table_changed();
+
+        Ok(())
+    }
+
+    /// Add a new group ID to group key set mapping to the fabric with the provided local index
+    ///
+    /// Return the index of the added entry.
+    pub fn group_key_map_add(
+        &mut self,
+        fab_idx: NonZeroU8,
+        group_id: u16,
+        key_set_id: u16,
+    ) -> Result<usize, Error> {
+        let index = self
+            .get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_map_add(GroupKeyMapEntry {
+                group_id,
+                key_set_id,
+            })?;
+        self.changed = true;
+
+        Ok(index)
+    }
+
+    /// Update an existing group ID to group key set mapping in the fabric with the provided local index
+    pub fn group_key_map_update(
+        &mut self,
+        fab_idx: NonZeroU8,
+        idx: usize,
+        group_id: u16,
+        key_set_id: u16,
+    ) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_map_update(
+                idx,
+                GroupKeyMapEntry {
+                    group_id,
+                    key_set_id,
+                },
+            )?;
+        self.changed = true;
+
+        Ok(())
+    }
+
+    /// Remove a group ID to group key set mapping from the fabric with the provided local index
+    pub fn group_key_map_remove(&mut self, fab_idx: NonZeroU8, idx: usize) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_map_remove(idx)?;
+        self.changed = true;
+
+        Ok(())
+    }
+
+    /// Remove all group ID to group key set mappings from the fabric with the provided local index
+    pub fn group_key_map_remove_all(&mut self, fab_idx: NonZeroU8) -> Result<(), Error> {
+        self.get_mut(fab_idx)
+            .ok_or(ErrorCode::NotFound)?
+            .group_key_map_remove_all();
+        self.changed = true;
+
+        Ok(())
+    }
+
     /// Add a new ACL entry to the fabric with the provided local index
     ///
     /// Return the index of the added entry.
@@ -766,3 +1234,45 @@ impl FabricMgr {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::KeyPair;
+    use crate::tlv::Optional;
+    use crate::utils::rand::dummy_rand;
+
+    use super::FabricMgr;
+
+    #[test]
+    fn test_load_fabric_without_groups() {
+        let mut mgr = FabricMgr::new();
+
+        // Fabrics persisted before the group support was added do not
+        // have the group-related fields at all
+        let fab_idx = unwrap!(mgr.add_with_post_init(
+            unwrap!(KeyPair::new(dummy_rand)),
+            |fabric| {
+                fabric.group_key_sets = Optional::none();
+                fabric.group_key_map = Optional::none();
+                fabric.groups = Optional::none();
+
+                Ok(())
+            }
+        ))
+        .fab_idx();
+
+        let mut buf = [0; 1024];
+        let data = unwrap!(unwrap!(mgr.store(&mut buf)));
+
+        let mut mgr = FabricMgr::new();
+        unwrap!(mgr.load(data, &mut || ()));
+
+        let fabric = unwrap!(mgr.get(fab_idx));
+        assert_eq!(fabric.group_key_sets_iter().count(), 0);
+        assert_eq!(fabric.group_key_map_iter().count(), 0);
+        assert_eq!(fabric.groups_iter().count(), 0);
+
+        unwrap!(mgr.group_add(fab_idx, 1, 1, "group"));
+        assert!(unwrap!(mgr.get(fab_idx)).is_group_member(1, 1));
+    }
+}
@@ -15,15 +15,41 @@
  *    limitations under the License.
  */
 
+use core::net::Ipv6Addr;
+use core::time::Duration;
+
+use heapless::String;
+
 use crate::{
     crypto::{self, SYMM_KEY_LEN_BYTES},
     error::{Error, ErrorCode},
     tlv::{FromTLV, ToTLV},
-    utils::init::{init, zeroed, Init},
+    utils::{
+        epoch::MATTER_EPOCH_SECS,
+        init::{init, zeroed, Init},
+        storage::Vec,
+    },
 };
 
 type KeySetKey = [u8; SYMM_KEY_LEN_BYTES];
 
+/// Max number of group key sets per fabric, not counting the IPK key set (key set ID 0)
+pub const MAX_GROUP_KEY_SETS_PER_FABRIC: usize = 3;
+/// Max number of group ID to group key set mappings per fabric
+pub const MAX_GROUP_KEY_MAP_ENTRIES_PER_FABRIC: usize = 4;
+/// Max number of epoch keys in a group key set, as per the Matter spec
+pub const MAX_EPOCH_KEYS: usize = 3;
+
+/// Max number of groups per fabric
+pub const MAX_GROUPS_PER_FABRIC: usize = 4;
+/// Max number of endpoints which can be members of a group
+pub const MAX_ENDPOINTS_PER_GROUP: usize = 4;
+/// Max length of a group name, as per the Matter spec
+pub const MAX_GROUP_NAME_LEN: usize = 16;
+
+/// The ID of the key set carrying the IPK of a fabric
+pub const IPK_KEY_SET_ID: u16 = 0;
+
 #[derive(Debug, Default, FromTLV, ToTLV)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct KeySet {
@@ -53,6 +79,20 @@ impl KeySet {
         Ok(ks)
     }
 
+    /// Return the group session ID of the operational key of this key set
+    ///
+    /// The group session ID is used as a hint by the receivers of a group message
+    /// as to which operational group key the message is encrypted with.
+    pub fn group_session_id(&self) -> Result<u16, Error> {
+        const GRP_KEY_HASH_INFO: &[u8] = b"GroupKeyHash";
+
+        let mut hash = [0; 2];
+        crypto::hkdf_sha256(&[], &self.op_key, GRP_KEY_HASH_INFO, &mut hash)
+            .map_err(|_| ErrorCode::NoSpace)?;
+
+        Ok(u16::from_be_bytes(hash))
+    }
+
     fn op_key_from_ipk(ipk: &[u8], compressed_id: &[u8], opkey: &mut [u8]) -> Result<(), Error> {
         const GRP_KEY_INFO: [u8; 13] = [
             0x47, 0x72, 0x6f, 0x75, 0x70, 0x4b, 0x65, 0x79, 0x20, 0x76, 0x31, 0x2e, 0x30,
@@ -70,3 +110,235 @@ impl KeySet {
         &self.epoch_key
     }
 }
+
+/// An epoch key of a group key set, together with the operational group key
+/// and the group session ID derived from it
+#[derive(Debug, Default, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GroupEpochKey {
+    /// The start time of the epoch key, in microseconds since the Matter epoch
+    pub start_time: u64,
+    /// The epoch key and the operational group key derived from it
+    pub keys: KeySet,
+    /// The group session ID of the operational group key
+    pub session_id: u16,
+}
+
+impl GroupEpochKey {
+    /// Create a new epoch key by deriving its operational group key and group session ID
+    /// for the fabric with the provided compressed fabric ID
+    pub fn new(
+        epoch_key: &[u8],
+        start_time: u64,
+        compressed_fabric_id: u64,
+    ) -> Result<Self, Error> {
+        if epoch_key.len() != SYMM_KEY_LEN_BYTES {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let keys = KeySet::new(epoch_key, &compressed_fabric_id.to_be_bytes())?;
+        let session_id = keys.group_session_id()?;
+
+        Ok(Self {
+            start_time,
+            keys,
+            session_id,
+        })
+    }
+}
+
+/// A group key set of a fabric
+#[derive(Debug, Default, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GroupKeySet {
+    /// The ID of the key set, unique within the fabric
+    pub id: u16,
+    /// The epoch keys of the key set
+    pub epoch_keys: Vec<GroupEpochKey, MAX_EPOCH_KEYS>,
+}
+
+impl GroupKeySet {
+    /// Create a new group key set with the provided ID and `(epoch key, start time)` pairs
+    /// for the fabric with the provided compressed fabric ID
+    pub fn new(
+        id: u16,
+        epoch_keys: &[(&[u8], u64)],
+        compressed_fabric_id: u64,
+    ) -> Result<Self, Error> {
+        if id == IPK_KEY_SET_ID || epoch_keys.is_empty() {
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        let mut key_set = Self {
+            id,
+            epoch_keys: Vec::new(),
+        };
+
+        for (epoch_key, start_time) in epoch_keys {
+            key_set
+                .epoch_keys
+                .push(GroupEpochKey::new(
+                    epoch_key,
+                    *start_time,
+                    compressed_fabric_id,
+                )?)
+                .map_err(|_| ErrorCode::NoSpace)?;
+        }
+
+        Ok(key_set)
+    }
+
+    /// Return the epoch key which should be used for encrypting outgoing group messages
+    /// at the provided time (time since the UNIX epoch)
+    ///
+    /// As per the Matter spec, this is the epoch key with the latest start time which is
+    /// not in the future. If the current time is not known (i.e. `now` is earlier than the
+    /// Matter epoch), the epoch key with the second-newest start time is used instead.
+    pub fn current(&self, now: Duration) -> Option<&GroupEpochKey> {
+        let now = now
+            .as_micros()
+            .checked_sub(MATTER_EPOCH_SECS as u128 * 1_000_000)
+            .map(|now| now.min(u64::MAX as u128) as u64);
+
+        if let Some(now) = now {
+            self.epoch_keys
+                .iter()
+                .filter(|key| key.start_time <= now)
+                .max_by_key(|key| key.start_time)
+                // All keys are in the future (i.e. our clock is likely behind): use the oldest one
+                .or_else(|| self.epoch_keys.iter().min_by_key(|key| key.start_time))
+        } else {
+            let newest = self.epoch_keys.iter().max_by_key(|key| key.start_time)?;
+
+            self.epoch_keys
+                .iter()
+                .filter(|key| key.start_time < newest.start_time)
+                .max_by_key(|key| key.start_time)
+                .or(Some(newest))
+        }
+    }
+
+    /// Iterate over the epoch keys which have the provided group session ID
+    pub fn for_session_id(&self, session_id: u16) -> impl Iterator<Item = &GroupEpochKey> {
+        self.epoch_keys
+            .iter()
+            .filter(move |key| key.session_id == session_id)
+    }
+}
+
+/// A mapping between a group ID and the ID of the group key set used by that group
+#[derive(Debug, Clone, Eq, PartialEq, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GroupKeyMapEntry {
+    /// The group ID
+    pub group_id: u16,
+    /// The ID of the group key set
+    pub key_set_id: u16,
+}
+
+/// An entry in the group table of a fabric: a group and the endpoints which are members of it
+#[derive(Debug, Clone, Eq, PartialEq, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GroupEntry {
+    /// The group ID
+    pub group_id: u16,
+    /// The endpoints which are members of the group
+    pub endpoints: Vec<u16, MAX_ENDPOINTS_PER_GROUP>,
+    /// The name of the group; might be empty
+    pub name: String<MAX_GROUP_NAME_LEN>,
+}
+
+/// Return the IPv6 multicast address on which the members of the group with the provided
+/// group ID on the fabric with the provided fabric ID listen for group messages
+///
+/// As per the Matter spec, this is a unicast-prefix-based multicast address of the form
+/// `FF35:0040:FD<Fabric ID>00:<Group ID>`.
+pub const fn group_multicast_addr(fabric_id: u64, group_id: u16) -> Ipv6Addr {
+    let fabric_id = fabric_id.to_be_bytes();
+    let group_id = group_id.to_be_bytes();
+
+    Ipv6Addr::new(
+        0xff35,
+        0x0040,
+        u16::from_be_bytes([0xfd, fabric_id[0]]),
+        u16::from_be_bytes([fabric_id[1], fabric_id[2]]),
+        u16::from_be_bytes([fabric_id[3], fabric_id[4]]),
+        u16::from_be_bytes([fabric_id[5], fabric_id[6]]),
+        u16::from_be_bytes([fabric_id[7], 0]),
+        u16::from_be_bytes(group_id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::Ipv6Addr;
+    use core::time::Duration;
+
+    use crate::utils::epoch::MATTER_EPOCH_SECS;
+
+    use super::{group_multicast_addr, GroupEpochKey, GroupKeySet};
+
+    #[test]
+    fn test_op_key() {
+        // Test vector from the Matter spec, section "Group Key Derivation"
+        let epoch_key = [
+            0x23, 0x5b, 0xf7, 0xe6, 0x28, 0x23, 0xd3, 0x58, 0xdc, 0xa4, 0xba, 0x50, 0xb1, 0x53,
+            0x5f, 0x4b,
+        ];
+        let compressed_fabric_id: u64 = 0x87e1b004e235a130;
+
+        let key = unwrap!(GroupEpochKey::new(&epoch_key, 0, compressed_fabric_id));
+
+        assert_eq!(
+            key.keys.op_key(),
+            &[
+                0xa6, 0xf5, 0x30, 0x6b, 0xaf, 0x6d, 0x05, 0x0a, 0xf2, 0x3b, 0xa4, 0xbd, 0x6b, 0x9d,
+                0xd9, 0x60
+            ]
+        );
+        assert_eq!(key.session_id, 0xb9f7);
+    }
+
+    #[test]
+    fn test_current_epoch_key() {
+        const EPOCH_KEY: &[u8] = &[0x55; 16];
+
+        let at = |secs: u64| Duration::from_secs(MATTER_EPOCH_SECS + secs);
+
+        let key_set = unwrap!(GroupKeySet::new(
+            1,
+            &[
+                (EPOCH_KEY, 10_000_000),
+                (EPOCH_KEY, 20_000_000),
+                (EPOCH_KEY, 30_000_000)
+            ],
+            0
+        ));
+
+        let start_time = |now| unwrap!(key_set.current(now)).start_time;
+
+        // The latest key which has already started
+        assert_eq!(start_time(at(15)), 10_000_000);
+        assert_eq!(start_time(at(20)), 20_000_000);
+        assert_eq!(start_time(at(100)), 30_000_000);
+        // No key has started yet: use the oldest one
+        assert_eq!(start_time(at(5)), 10_000_000);
+        // Unknown time: use the second-newest key
+        assert_eq!(start_time(Duration::ZERO), 20_000_000);
+
+        let key_set = unwrap!(GroupKeySet::new(1, &[(EPOCH_KEY, 10_000_000)], 0));
+
+        assert_eq!(
+            unwrap!(key_set.current(Duration::ZERO)).start_time,
+            10_000_000
+        );
+    }
+
+    #[test]
+    fn test_group_multicast_addr() {
+        assert_eq!(
+            group_multicast_addr(0x1122_3344_5566_7788, 0xabcd),
+            Ipv6Addr::new(0xff35, 0x0040, 0xfd11, 0x2233, 0x4455, 0x6677, 0x8800, 0xabcd)
+        );
+    }
+}
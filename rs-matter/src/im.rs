@@ -21,13 +21,14 @@ use core::time::Duration;
 use num::FromPrimitive;
 use num_derive::FromPrimitive;
 
-use crate::dm::{AttrDetails, AttrId, ClusterId, CmdId, EndptId};
+use crate::dm::{AttrDetails, AttrId, ClusterId, CmdId, EndptId, EventId, EventPriority};
 use crate::error::*;
 use crate::tlv::{FromTLV, Nullable, TLVArray, TLVElement, TLVTag, TLVWrite, TagType, ToTLV, TLV};
 use crate::transport::exchange::MessageMeta;
 use crate::utils::{epoch::Epoch, storage::WriteBuf};
 
 pub mod busy;
+pub mod client;
 
 #[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -67,6 +68,7 @@ impl From<ErrorCode> for IMStatusCode {
             ErrorCode::ClusterNotFound => IMStatusCode::UnsupportedCluster,
             ErrorCode::AttributeNotFound => IMStatusCode::UnsupportedAttribute,
             ErrorCode::CommandNotFound => IMStatusCode::UnsupportedCommand,
+            ErrorCode::EventNotFound => IMStatusCode::UnsupportedEvent,
             ErrorCode::InvalidAction => IMStatusCode::InvalidAction,
             ErrorCode::InvalidCommand => IMStatusCode::InvalidCommand,
             ErrorCode::UnsupportedAccess => IMStatusCode::UnsupportedAccess,
@@ -75,11 +77,35 @@ impl From<ErrorCode> for IMStatusCode {
             ErrorCode::ResourceExhausted => IMStatusCode::ResourceExhausted,
             ErrorCode::FailSafeRequired => IMStatusCode::FailSafeRequired,
             ErrorCode::ConstraintError => IMStatusCode::ConstraintError,
+            ErrorCode::NotFound => IMStatusCode::NotFound,
             _ => IMStatusCode::Failure,
         }
     }
 }
 
+impl From<IMStatusCode> for ErrorCode {
+    fn from(status: IMStatusCode) -> Self {
+        match status {
+            IMStatusCode::UnsupportedEndpoint => ErrorCode::EndpointNotFound,
+            IMStatusCode::UnsupportedCluster => ErrorCode::ClusterNotFound,
+            IMStatusCode::UnsupportedAttribute => ErrorCode::AttributeNotFound,
+            IMStatusCode::UnsupportedCommand => ErrorCode::CommandNotFound,
+            IMStatusCode::UnsupportedEvent => ErrorCode::EventNotFound,
+            IMStatusCode::InvalidAction => ErrorCode::InvalidAction,
+            IMStatusCode::InvalidCommand => ErrorCode::InvalidCommand,
+            IMStatusCode::UnsupportedAccess => ErrorCode::UnsupportedAccess,
+            IMStatusCode::Busy => ErrorCode::Busy,
+            IMStatusCode::DataVersionMismatch => ErrorCode::DataVersionMismatch,
+            IMStatusCode::ResourceExhausted => ErrorCode::ResourceExhausted,
+            IMStatusCode::FailSafeRequired => ErrorCode::FailSafeRequired,
+            IMStatusCode::ConstraintError => ErrorCode::ConstraintError,
+            IMStatusCode::InvalidDataType => ErrorCode::InvalidDataType,
+            IMStatusCode::NotFound => ErrorCode::NotFound,
+            _ => ErrorCode::Failure,
+        }
+    }
+}
+
 impl From<Error> for IMStatusCode {
     fn from(value: Error) -> Self {
         Self::from(value.code())
@@ -215,12 +241,42 @@ impl<'a> ReportDataReq<'a> {
         }
     }
 
+    pub fn event_requests(&self) -> Result<Option<TLVArray<'a, EventPath>>, Error> {
+        match self {
+            Self::Read(req) => req.event_requests(),
+            Self::Subscribe(req) | Self::SubscribeReport(req) => req.event_requests(),
+        }
+    }
+
+    pub fn event_filters(&self) -> Result<Option<TLVArray<'a, EventFilter>>, Error> {
+        match self {
+            Self::Read(req) => req.event_filters(),
+            Self::Subscribe(req) | Self::SubscribeReport(req) => req.event_filters(),
+        }
+    }
+
     pub fn fabric_filtered(&self) -> Result<bool, Error> {
         match self {
             Self::Read(req) => req.fabric_filtered(),
             Self::Subscribe(req) | Self::SubscribeReport(req) => req.fabric_filtered(),
         }
     }
+
+    /// Return the minimum event number the requestor is interested in,
+    /// as indicated by the event filters of the request.
+    pub fn event_min(&self) -> Result<u64, Error> {
+        let mut event_min = 0;
+
+        if let Some(filters) = self.event_filters()? {
+            for filter in filters {
+                if let Some(min) = filter?.event_min {
+                    event_min = event_min.max(min);
+                }
+            }
+        }
+
+        Ok(event_min)
+    }
 }
 
 impl StatusResp {
@@ -631,8 +687,7 @@ impl defmt::Format for WriteReqRef<'_> {
 pub struct ReportDataMsg<'a> {
     pub subscription_id: Option<u32>,
     pub attr_reports: Option<TLVArray<'a, AttrResp<'a>>>,
-    // TODO
-    pub event_reports: Option<bool>,
+    pub event_reports: Option<TLVArray<'a, EventResp<'a>>>,
     pub more_chunks: Option<bool>,
     pub suppress_response: Option<bool>,
 }
@@ -640,7 +695,7 @@ pub struct ReportDataMsg<'a> {
 pub enum ReportDataTag {
     SubscriptionId = 0,
     AttributeReports = 1,
-    _EventReport = 2,
+    EventReports = 2,
     MoreChunkedMsgs = 3,
     SupressResponse = 4,
 }
@@ -709,6 +764,10 @@ impl CmdStatus {
             },
         }
     }
+
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
 }
 
 #[derive(Debug, Clone, FromTLV, ToTLV)]
@@ -810,6 +869,85 @@ pub enum AttrDataTag {
     Data = 2,
 }
 
+// Event Response
+#[derive(Clone, FromTLV, ToTLV, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub enum EventResp<'a> {
+    Status(EventStatus),
+    Data(EventData<'a>),
+}
+
+impl<'a> EventResp<'a> {
+    pub fn unwrap_data(self) -> EventData<'a> {
+        match self {
+            EventResp::Data(d) => d,
+            _ => {
+                panic!("No data exists");
+            }
+        }
+    }
+}
+
+impl<'a> From<EventData<'a>> for EventResp<'a> {
+    fn from(value: EventData<'a>) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl From<EventStatus> for EventResp<'_> {
+    fn from(value: EventStatus) -> Self {
+        Self::Status(value)
+    }
+}
+
+pub enum EventRespTag {
+    Status = 0,
+    Data = 1,
+}
+
+// Event Data
+#[derive(Debug, Clone, PartialEq, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct EventData<'a> {
+    pub path: EventPath,
+    pub event_number: u64,
+    pub priority: EventPriority,
+    pub epoch_timestamp: Option<u64>,
+    pub system_timestamp: Option<u64>,
+    pub delta_epoch_timestamp: Option<u64>,
+    pub delta_system_timestamp: Option<u64>,
+    pub data: TLVElement<'a>,
+}
+
+pub enum EventDataTag {
+    Path = 0,
+    EventNumber = 1,
+    Priority = 2,
+    EpochTimestamp = 3,
+    SystemTimestamp = 4,
+    DeltaEpochTimestamp = 5,
+    DeltaSystemTimestamp = 6,
+    Data = 7,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromTLV, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventStatus {
+    path: EventPath,
+    status: Status,
+}
+
+impl EventStatus {
+    pub fn new(path: &GenericPath, status: IMStatusCode, cluster_status: u16) -> Self {
+        Self {
+            path: EventPath::new(path),
+            status: Status::new(status, cluster_status),
+        }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// Operations on an Interaction Model List
@@ -868,6 +1006,10 @@ impl AttrStatus {
             status: Status::new(status, cluster_status),
         }
     }
+
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
 }
 
 // Attribute Path
@@ -977,20 +1119,59 @@ pub struct DataVersionFilter {
     pub data_ver: u32,
 }
 
-#[derive(FromTLV, ToTLV, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash, FromTLV, ToTLV)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[tlvargs(datatype = "list")]
 pub struct EventPath {
     pub node: Option<u64>,
     pub endpoint: Option<EndptId>,
     pub cluster: Option<ClusterId>,
-    pub event: Option<u32>,
+    pub event: Option<EventId>,
     pub is_urgent: Option<bool>,
 }
 
+impl EventPath {
+    pub const fn new(path: &GenericPath) -> Self {
+        Self {
+            node: None,
+            endpoint: path.endpoint,
+            cluster: path.cluster,
+            event: path.leaf,
+            is_urgent: None,
+        }
+    }
+
+    pub fn to_gp(&self) -> GenericPath {
+        GenericPath::new(self.endpoint, self.cluster, self.event)
+    }
+}
+
 #[derive(FromTLV, ToTLV, Clone, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct EventFilter {
     pub node: Option<u64>,
     pub event_min: Option<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{Error, ErrorCode};
+
+    use super::IMStatusCode;
+
+    #[test]
+    fn test_not_found_status() {
+        // Handlers report missing entities (scenes, group key sets) with the `NotFound` IM status
+        assert_eq!(
+            IMStatusCode::from(Error::from(ErrorCode::NotFound)),
+            IMStatusCode::NotFound
+        );
+        assert_eq!(ErrorCode::from(IMStatusCode::NotFound), ErrorCode::NotFound);
+
+        // ... while errors without a matching IM status are still reported as a generic failure
+        assert_eq!(
+            IMStatusCode::from(ErrorCode::TLVNotFound),
+            IMStatusCode::Failure
+        );
+    }
+}
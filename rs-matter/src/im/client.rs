@@ -0,0 +1,682 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! An Interaction Model client, i.e. the initiator side of the Read, Write, Invoke and Subscribe interactions.
+//!
+//! The client operates on an already established exchange, as returned by `Exchange::initiate` or
+//! `Exchange::initiate_for_session`. Each interaction consumes the exchange, in that once the interaction
+//! is complete, the exchange should be dropped.
+//!
+//! Responses are delivered to the caller via callbacks, because their payload borrows from the RX buffer
+//! of the exchange, which is only valid until the next message is received.
+
+use core::num::NonZeroU8;
+
+use crate::dm::{AttrId, ClusterId, CmdId, EndptId};
+use crate::error::{Error, ErrorCode};
+use crate::tlv::{
+    get_root_node_struct, FromTLV, TLVElement, TLVTag, TLVWrite, TLVWriteParent, TagType, ToTLV,
+};
+use crate::transport::exchange::{Exchange, RxMessage};
+use crate::utils::storage::WriteBuf;
+
+use super::{
+    AttrData, AttrDataTag, AttrPath, AttrResp, CmdData, CmdDataTag, CmdPath, CmdResp,
+    DataVersionFilter, EventFilter, EventPath, GenericPath, IMStatusCode, InvReqTag, InvResp,
+    OpCode, ReportDataMsg, Status, StatusResp, SubscribeResp, TimedReq, WriteReqTag, WriteResp,
+    PROTO_ID_INTERACTION_MODEL,
+};
+
+/// The TLV builder parent used by the typed cluster clients generated with `import!` for
+/// building attribute values and command requests.
+pub type ClientParent<'w, 'b> = TLVWriteParent<&'static str, &'w mut WriteBuf<'b>>;
+
+/// A Read Request, as sent by the client.
+///
+/// Unlike `ReadReq`, which is used for parsing incoming requests, this type borrows its paths and filters from
+/// regular Rust slices.
+#[derive(Debug, Default, Clone, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct ReadRequest<'a> {
+    pub attr_requests: Option<&'a [AttrPath]>,
+    pub event_requests: Option<&'a [EventPath]>,
+    pub event_filters: Option<&'a [EventFilter]>,
+    pub fabric_filtered: bool,
+    pub dataver_filters: Option<&'a [DataVersionFilter]>,
+}
+
+impl<'a> ReadRequest<'a> {
+    /// Create an empty, fabric-filtered Read Request.
+    pub const fn new() -> Self {
+        Self {
+            attr_requests: None,
+            event_requests: None,
+            event_filters: None,
+            fabric_filtered: true,
+            dataver_filters: None,
+        }
+    }
+
+    /// Create a fabric-filtered Read Request for the provided attribute paths.
+    pub const fn attrs(paths: &'a [AttrPath]) -> Self {
+        Self {
+            attr_requests: Some(paths),
+            ..Self::new()
+        }
+    }
+
+    /// Create a fabric-filtered Read Request for the provided event paths.
+    pub const fn events(paths: &'a [EventPath]) -> Self {
+        Self {
+            event_requests: Some(paths),
+            ..Self::new()
+        }
+    }
+}
+
+/// A Subscribe Request, as sent by the client.
+#[derive(Debug, Default, Clone, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct SubscribeRequest<'a> {
+    pub keep_subs: bool,
+    pub min_int_floor: u16,
+    pub max_int_ceil: u16,
+    pub attr_requests: Option<&'a [AttrPath]>,
+    pub event_requests: Option<&'a [EventPath]>,
+    pub event_filters: Option<&'a [EventFilter]>,
+    // The Context Tags are discontiguous for some reason
+    pub _dummy: Option<bool>,
+    pub fabric_filtered: bool,
+    pub dataver_filters: Option<&'a [DataVersionFilter]>,
+}
+
+impl<'a> SubscribeRequest<'a> {
+    /// Create an empty, fabric-filtered Subscribe Request with the provided min interval floor and max interval ceiling.
+    pub const fn new(min_int_floor: u16, max_int_ceil: u16) -> Self {
+        Self {
+            keep_subs: false,
+            min_int_floor,
+            max_int_ceil,
+            attr_requests: None,
+            event_requests: None,
+            event_filters: None,
+            _dummy: None,
+            fabric_filtered: true,
+            dataver_filters: None,
+        }
+    }
+
+    /// Create a fabric-filtered Subscribe Request for the provided attribute paths.
+    pub const fn attrs(min_int_floor: u16, max_int_ceil: u16, paths: &'a [AttrPath]) -> Self {
+        Self {
+            attr_requests: Some(paths),
+            ..Self::new(min_int_floor, max_int_ceil)
+        }
+    }
+}
+
+/// A Write Request, as sent by the client.
+#[derive(Debug, Clone, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct WriteRequest<'a> {
+    pub suppress_response: Option<bool>,
+    pub timed_request: Option<bool>,
+    pub write_requests: &'a [AttrData<'a>],
+    pub more_chunked: Option<bool>,
+}
+
+impl<'a> WriteRequest<'a> {
+    /// Create a Write Request for the provided attribute data.
+    pub const fn new(write_requests: &'a [AttrData<'a>]) -> Self {
+        Self {
+            suppress_response: None,
+            timed_request: None,
+            write_requests,
+            more_chunked: None,
+        }
+    }
+
+    /// Return the request marked as a timed one. The request should then be preceded by `ImClient::timed`.
+    pub const fn timed(mut self) -> Self {
+        self.timed_request = Some(true);
+        self
+    }
+}
+
+/// An Invoke Request, as sent by the client.
+#[derive(Debug, Clone, ToTLV)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a")]
+pub struct InvokeRequest<'a> {
+    pub suppress_response: Option<bool>,
+    pub timed_request: Option<bool>,
+    pub inv_requests: &'a [CmdData<'a>],
+}
+
+impl<'a> InvokeRequest<'a> {
+    /// Create an Invoke Request for the provided commands.
+    pub const fn new(inv_requests: &'a [CmdData<'a>]) -> Self {
+        Self {
+            suppress_response: None,
+            timed_request: None,
+            inv_requests,
+        }
+    }
+
+    /// Return the request marked as a timed one. The request should then be preceded by `ImClient::timed`.
+    pub const fn timed(mut self) -> Self {
+        self.timed_request = Some(true);
+        self
+    }
+}
+
+/// A handler for the reports of the subscriptions established by this node as a client.
+///
+/// Subscription reports arrive on exchanges initiated by the publisher, hence they are delivered
+/// by the `DataModel` responder, which needs to be configured with the handler via `DataModel::with_report_handler`.
+pub trait ReportHandler {
+    /// Handle a (possibly chunked) report for one of the subscriptions of this node.
+    ///
+    /// The report comes from the node with the provided peer node ID, on the fabric with the provided index.
+    ///
+    /// Return `false` if the subscription ID of the report is unknown, in which case the publisher is answered
+    /// with an `InvalidSubscription` status and is expected to tear down the subscription.
+    fn handle(
+        &self,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        report: &ReportDataMsg<'_>,
+    ) -> Result<bool, Error>;
+}
+
+impl<T> ReportHandler for &T
+where
+    T: ReportHandler + ?Sized,
+{
+    fn handle(
+        &self,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        report: &ReportDataMsg<'_>,
+    ) -> Result<bool, Error> {
+        (**self).handle(fabric_idx, peer_node_id, report)
+    }
+}
+
+/// The Interaction Model client.
+///
+/// All interactions fail with an error mapped from the IM status code, if the server answers with a Status Response
+/// rather than with the expected message.
+pub struct ImClient(());
+
+impl ImClient {
+    /// Read attributes and/or events.
+    ///
+    /// The provided callback is called for each (chunked) report sent by the server.
+    pub async fn read<F>(
+        exchange: &mut Exchange<'_>,
+        req: &ReadRequest<'_>,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&ReportDataMsg<'_>) -> Result<(), Error>,
+    {
+        Self::send(exchange, OpCode::ReadRequest, req).await?;
+
+        Self::recv_reports(exchange, |report| {
+            f(report)?;
+
+            Ok(IMStatusCode::Success)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Subscribe to attributes and/or events.
+    ///
+    /// The provided callback is called for each (chunked) priming report sent by the server.
+    /// Subsequent reports for the established subscription are delivered to the `ReportHandler` of the `DataModel`.
+    ///
+    /// Return the ID of the established subscription, as well as the max interval chosen by the server.
+    pub async fn subscribe<F>(
+        exchange: &mut Exchange<'_>,
+        req: &SubscribeRequest<'_>,
+        mut f: F,
+    ) -> Result<SubscribeResp, Error>
+    where
+        F: FnMut(&ReportDataMsg<'_>) -> Result<(), Error>,
+    {
+        Self::send(exchange, OpCode::SubscribeRequest, req).await?;
+
+        Self::recv_reports(exchange, |report| {
+            f(report)?;
+
+            Ok(IMStatusCode::Success)
+        })
+        .await?;
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::SubscribeResponse)?;
+
+        let resp = SubscribeResp::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+        exchange.rx_done()?;
+        exchange.acknowledge().await?;
+
+        Ok(resp)
+    }
+
+    /// Write attributes.
+    ///
+    /// The provided callback is called with the Write Response sent by the server.
+    /// Return `None` (and do not call the callback) if the request had suppressed the response.
+    pub async fn write<F, R>(
+        exchange: &mut Exchange<'_>,
+        req: &WriteRequest<'_>,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        F: FnOnce(&WriteResp<'_>) -> Result<R, Error>,
+    {
+        Self::send(exchange, OpCode::WriteRequest, req).await?;
+
+        if req.suppress_response.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::WriteResponse)?;
+
+        let result = f(&WriteResp::from_tlv(&get_root_node_struct(rx.payload())?)?)?;
+
+        exchange.rx_done()?;
+        exchange.acknowledge().await?;
+
+        Ok(Some(result))
+    }
+
+    /// Invoke commands.
+    ///
+    /// The provided callback is called with the Invoke Response sent by the server.
+    /// Return `None` (and do not call the callback) if the request had suppressed the response.
+    pub async fn invoke<F, R>(
+        exchange: &mut Exchange<'_>,
+        req: &InvokeRequest<'_>,
+        f: F,
+    ) -> Result<Option<R>, Error>
+    where
+        F: FnOnce(&InvResp<'_>) -> Result<R, Error>,
+    {
+        Self::send(exchange, OpCode::InvokeRequest, req).await?;
+
+        if req.suppress_response.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::InvokeResponse)?;
+
+        let result = f(&InvResp::from_tlv(&get_root_node_struct(rx.payload())?)?)?;
+
+        exchange.rx_done()?;
+        exchange.acknowledge().await?;
+
+        Ok(Some(result))
+    }
+
+    /// Send a Timed Request with the provided timeout.
+    ///
+    /// Must be followed - on the same exchange - by a timed Write or Invoke request, which also acknowledges
+    /// the Status Response of the server.
+    pub async fn timed(exchange: &mut Exchange<'_>, timeout_ms: u16) -> Result<(), Error> {
+        Self::send(
+            exchange,
+            OpCode::TimedRequest,
+            &TimedReq {
+                timeout: timeout_ms,
+            },
+        )
+        .await?;
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::StatusResponse)?;
+
+        let resp = StatusResp::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+        exchange.rx_done()?;
+
+        if resp.status != IMStatusCode::Success {
+            warn!("Timed request rejected with status {:?}", resp.status);
+            Err(ErrorCode::from(resp.status))?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a single attribute of a concrete endpoint and cluster.
+    ///
+    /// The provided callback is called with the attribute value.
+    /// If the server reports an attribute status instead, fail with an error mapped from the status code.
+    pub async fn read_attr<F, R>(
+        exchange: &mut Exchange<'_>,
+        endpoint: EndptId,
+        cluster: ClusterId,
+        attr: AttrId,
+        f: F,
+    ) -> Result<R, Error>
+    where
+        F: FnOnce(&TLVElement<'_>) -> Result<R, Error>,
+    {
+        let path = AttrPath::new(&GenericPath::new(Some(endpoint), Some(cluster), Some(attr)));
+
+        let mut f = Some(f);
+        let mut result = None;
+
+        Self::read(
+            exchange,
+            &ReadRequest::attrs(core::slice::from_ref(&path)),
+            |report| {
+                for resp in report
+                    .attr_reports
+                    .iter()
+                    .flat_map(|reports| reports.iter())
+                {
+                    match resp? {
+                        AttrResp::Data(data) => {
+                            if let Some(f) = f.take() {
+                                result = Some(f(&data.data));
+                            }
+                        }
+                        AttrResp::Status(status) => {
+                            // A success status without data is not expected for a read
+                            result = Some(
+                                Self::status_result(status.status())
+                                    .and(Err(ErrorCode::InvalidData.into())),
+                            );
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )
+        .await?;
+
+        result.ok_or(ErrorCode::AttributeNotFound)?
+    }
+
+    /// Write a single attribute of a concrete endpoint and cluster.
+    ///
+    /// The provided callback writes the attribute value with the provided tag. Note that the callback
+    /// might be called more than once, in case the request needs to be re-transmitted.
+    ///
+    /// If `timeout_ms` is provided, the write is preceded by a Timed Request with that timeout.
+    pub async fn write_attr<F>(
+        exchange: &mut Exchange<'_>,
+        endpoint: EndptId,
+        cluster: ClusterId,
+        attr: AttrId,
+        timeout_ms: Option<u16>,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&TLVTag, &mut WriteBuf) -> Result<(), Error>,
+    {
+        if let Some(timeout_ms) = timeout_ms {
+            Self::timed(exchange, timeout_ms).await?;
+        }
+
+        let path = AttrPath::new(&GenericPath::new(Some(endpoint), Some(cluster), Some(attr)));
+
+        exchange
+            .send_with(|_, wb| {
+                wb.start_struct(&TLVTag::Anonymous)?;
+
+                if timeout_ms.is_some() {
+                    wb.bool(&TLVTag::Context(WriteReqTag::TimedRequest as _), true)?;
+                }
+
+                wb.start_array(&TLVTag::Context(WriteReqTag::WriteRequests as _))?;
+                wb.start_struct(&TLVTag::Anonymous)?;
+                path.to_tlv(&TLVTag::Context(AttrDataTag::Path as _), &mut *wb)?;
+                f(&TLVTag::Context(AttrDataTag::Data as _), wb)?;
+                wb.end_container()?;
+                wb.end_container()?;
+                wb.end_container()?;
+
+                Ok(Some(OpCode::WriteRequest.into()))
+            })
+            .await?;
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::WriteResponse)?;
+
+        let resp = WriteResp::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+        let result = resp
+            .write_responses
+            .iter()
+            .next()
+            .ok_or(ErrorCode::InvalidData)?
+            .and_then(|status| Self::status_result(status.status()));
+
+        exchange.rx_done()?;
+        exchange.acknowledge().await?;
+
+        result
+    }
+
+    /// Invoke a single command on a concrete endpoint and cluster.
+    ///
+    /// The `request` callback writes the command request data with the provided tag. Note that the callback
+    /// might be called more than once, in case the request needs to be re-transmitted.
+    ///
+    /// The `response` callback is called with the command response data, or with `None` if the server
+    /// answered with a success status only. If the server answers with a non-success status instead,
+    /// fail with an error mapped from the status code.
+    ///
+    /// If `timeout_ms` is provided, the invocation is preceded by a Timed Request with that timeout.
+    pub async fn invoke_cmd<F, G, R>(
+        exchange: &mut Exchange<'_>,
+        endpoint: EndptId,
+        cluster: ClusterId,
+        cmd: CmdId,
+        timeout_ms: Option<u16>,
+        request: F,
+        response: G,
+    ) -> Result<R, Error>
+    where
+        F: Fn(&TLVTag, &mut WriteBuf) -> Result<(), Error>,
+        G: FnOnce(Option<&TLVElement<'_>>) -> Result<R, Error>,
+    {
+        if let Some(timeout_ms) = timeout_ms {
+            Self::timed(exchange, timeout_ms).await?;
+        }
+
+        let path = CmdPath::new(Some(endpoint), Some(cluster), Some(cmd));
+
+        exchange
+            .send_with(|_, wb| {
+                wb.start_struct(&TLVTag::Anonymous)?;
+                wb.bool(&TLVTag::Context(InvReqTag::SupressResponse as _), false)?;
+                wb.bool(
+                    &TLVTag::Context(InvReqTag::TimedReq as _),
+                    timeout_ms.is_some(),
+                )?;
+                wb.start_array(&TLVTag::Context(InvReqTag::InvokeRequests as _))?;
+                wb.start_struct(&TLVTag::Anonymous)?;
+                path.to_tlv(&TLVTag::Context(CmdDataTag::Path as _), &mut *wb)?;
+                request(&TLVTag::Context(CmdDataTag::Data as _), wb)?;
+                wb.end_container()?;
+                wb.end_container()?;
+                wb.end_container()?;
+
+                Ok(Some(OpCode::InvokeRequest.into()))
+            })
+            .await?;
+
+        let rx = exchange.recv_fetch().await?;
+        Self::check_opcode(rx, OpCode::InvokeResponse)?;
+
+        let resp = InvResp::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+        let result = match resp
+            .inv_responses
+            .as_ref()
+            .and_then(|resps| resps.iter().next())
+            .ok_or(ErrorCode::InvalidData)??
+        {
+            CmdResp::Cmd(data) => response(Some(&data.data)),
+            CmdResp::Status(status) => {
+                Self::status_result(status.status()).and_then(|_| response(None))
+            }
+        };
+
+        exchange.rx_done()?;
+        exchange.acknowledge().await?;
+
+        result
+    }
+
+    /// Handle a subscription report, as sent on an exchange initiated by the publisher.
+    ///
+    /// Typically called by the `DataModel` responder, which had already fetched the first message of the exchange.
+    pub async fn handle_report<H>(exchange: &mut Exchange<'_>, handler: H) -> Result<(), Error>
+    where
+        H: ReportHandler,
+    {
+        let (fabric_idx, peer_node_id) = exchange.with_session(|sess| {
+            Ok((
+                NonZeroU8::new(sess.get_local_fabric_idx()).ok_or(ErrorCode::NoFabricId)?,
+                sess.get_peer_node_id().ok_or(ErrorCode::NoNodeId)?,
+            ))
+        })?;
+
+        Self::recv_reports(exchange, |report| {
+            if handler.handle(fabric_idx, peer_node_id, report)? {
+                Ok(IMStatusCode::Success)
+            } else {
+                warn!(
+                    "Got a report for unknown subscription {:?}",
+                    report.subscription_id
+                );
+
+                Ok(IMStatusCode::InvalidSubscription)
+            }
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Receive a sequence of chunked reports, answering each one with the status returned by the provided callback.
+    ///
+    /// Return `false` if the sequence had been aborted, because the callback returned a non-success status.
+    async fn recv_reports<F>(exchange: &mut Exchange<'_>, mut f: F) -> Result<bool, Error>
+    where
+        F: FnMut(&ReportDataMsg<'_>) -> Result<IMStatusCode, Error>,
+    {
+        loop {
+            let rx = exchange.recv_fetch().await?;
+            Self::check_opcode(rx, OpCode::ReportData)?;
+
+            let report = ReportDataMsg::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+            let more_chunks = report.more_chunks.unwrap_or(false);
+            let suppress_response = report.suppress_response.unwrap_or(false);
+            let status = f(&report)?;
+
+            exchange.rx_done()?;
+
+            if status != IMStatusCode::Success {
+                Self::send(exchange, OpCode::StatusResponse, &StatusResp { status }).await?;
+
+                break Ok(false);
+            }
+
+            if more_chunks || !suppress_response {
+                Self::send(exchange, OpCode::StatusResponse, &StatusResp { status }).await?;
+            } else {
+                exchange.acknowledge().await?;
+            }
+
+            if !more_chunks {
+                break Ok(true);
+            }
+        }
+    }
+
+    fn status_result(status: &Status) -> Result<(), Error> {
+        if status.status == IMStatusCode::Success {
+            Ok(())
+        } else {
+            warn!(
+                "Got status {:?}, cluster status {}",
+                status.status, status.cluster_status
+            );
+
+            Err(ErrorCode::from(status.status).into())
+        }
+    }
+
+    /// Check that the provided message is an IM message with the expected opcode.
+    ///
+    /// If the message is a Status Response instead, return an error mapped from its status code.
+    fn check_opcode(rx: &RxMessage<'_>, opcode: OpCode) -> Result<(), Error> {
+        let meta = rx.meta();
+        if meta.proto_id != PROTO_ID_INTERACTION_MODEL {
+            Err(ErrorCode::InvalidProto)?;
+        }
+
+        if meta.proto_opcode == opcode as u8 {
+            Ok(())
+        } else if meta.proto_opcode == OpCode::StatusResponse as u8 {
+            let resp = StatusResp::from_tlv(&get_root_node_struct(rx.payload())?)?;
+
+            warn!(
+                "Got status response {:?}, while expecting opcode {:?}",
+                resp.status, opcode
+            );
+
+            Err(ErrorCode::from(resp.status).into())
+        } else {
+            warn!(
+                "Got opcode {:02x}, while expecting opcode {:02x}",
+                meta.proto_opcode, opcode as u8
+            );
+
+            Err(ErrorCode::InvalidOpcode.into())
+        }
+    }
+
+    async fn send<T>(exchange: &mut Exchange<'_>, opcode: OpCode, msg: &T) -> Result<(), Error>
+    where
+        T: ToTLV,
+    {
+        exchange
+            .send_with(|_, wb| {
+                msg.to_tlv(&TagType::Anonymous, &mut *wb)?;
+
+                Ok(Some(opcode.into()))
+            })
+            .await
+    }
+}
@@ -84,6 +84,7 @@ use crate::fabric::FabricMgr;
 use crate::failsafe::FailSafe;
 use crate::pairing::{print_pairing_code_and_qr, DiscoveryCapabilities};
 use crate::sc::pake::PaseMgr;
+use crate::transport::network::mdns::MdnsResolver;
 use crate::transport::network::{NetworkReceive, NetworkSend};
 use crate::transport::{PacketBufferExternalAccess, TransportMgr};
 use crate::utils::cell::RefCell;
@@ -104,7 +105,10 @@ extern crate alloc;
 pub(crate) mod fmt;
 
 pub mod acl;
+pub mod bdx;
 pub mod cert;
+#[cfg(feature = "client")]
+pub mod commissioner;
 pub mod crypto;
 pub mod dm;
 pub mod error;
@@ -519,7 +523,16 @@ impl<'a> Matter<'a> {
         S: NetworkSend,
         R: NetworkReceive,
     {
-        self.transport_mgr.run(send, recv).await
+        self.transport_mgr.run(&self.fabric_mgr, send, recv).await
+    }
+
+    /// Run the provided mDNS resolver, so that `Exchange::initiate` can establish CASE sessions
+    /// with peers to which there is no session yet
+    pub async fn run_resolver<R>(&self, resolver: R) -> Result<(), Error>
+    where
+        R: MdnsResolver,
+    {
+        self.transport_mgr.run_resolver(resolver).await
     }
 
     /// Notify that the ACLs, Fabrics or Basic Info _might_ have changed
@@ -529,7 +542,7 @@ impl<'a> Matter<'a> {
     ///
     /// TODO: Fix the method name as it is not clear enough. Potentially revamp the whole persistence notification logic
     pub fn notify_persist(&self) {
-        if self.fabrics_changed() || self.basic_info_changed() {
+        if self.fabrics_changed() || self.basic_info_changed() || self.group_msg_ctr_changed() {
             self.persist_notification.notify();
         }
     }
@@ -560,6 +573,27 @@ impl<'a> Matter<'a> {
         self.basic_info_settings.borrow().changed
     }
 
+    pub fn load_group_msg_ctr(&self, data: &[u8]) -> Result<(), Error> {
+        self.transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .load_group_msg_ctr(data)
+    }
+
+    pub fn store_group_msg_ctr<'b>(&self, buf: &'b mut [u8]) -> Result<Option<&'b [u8]>, Error> {
+        self.transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .store_group_msg_ctr(buf)
+    }
+
+    pub fn group_msg_ctr_changed(&self) -> bool {
+        self.transport_mgr
+            .session_mgr
+            .borrow()
+            .group_msg_ctr_changed()
+    }
+
     /// A hook for user persistence code to wait for potential changes in ACLs, Fabrics or basic info.
     ///
     /// Once this future resolves, user code is supposed to inspect ACLs, Fabrics and basic info for changes, and
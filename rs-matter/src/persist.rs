@@ -25,17 +25,23 @@ pub mod fileio {
     use std::io::{Read, Write};
     use std::path::Path;
 
-    use embassy_futures::select::{select, Either};
+    use embassy_futures::select::{select, select3, Either, Either3};
     use embassy_sync::blocking_mutex::raw::{NoopRawMutex, RawMutex};
 
+    use crate::dm::clusters::scenes::ScenesHandler;
     use crate::dm::networks::wireless::{Wifi, WirelessNetwork, WirelessNetworks};
+    use crate::dm::{DataModel, DataModelHandler, EndptId, IMBuffer};
     use crate::error::{Error, ErrorCode};
     use crate::utils::init::{init, Init};
+    use crate::utils::storage::pooled::BufferAccess;
     use crate::Matter;
 
     const KEY_FABRICS: &str = "fabrics";
     const KEY_BASIC_INFO: &str = "basic_info";
+    const KEY_GROUP_MSG_CTR: &str = "group_msg_ctr";
     const KEY_WIRELESS_NETWORKS: &str = "wireless_networks";
+    const KEY_SUBSCRIPTIONS: &str = "subscriptions";
+    const KEY_SCENES: &str = "scenes";
 
     pub struct Psm<const N: usize = 4096> {
         buf: MaybeUninit<[u8; N]>,
@@ -76,11 +82,27 @@ pub mod fileio {
                 matter.load_basic_info(data)?;
             }
 
+            if let Some(data) = Self::load_key(dir, KEY_GROUP_MSG_CTR, unsafe {
+                self.buf.assume_init_mut()
+            })? {
+                matter.load_group_msg_ctr(data)?;
+
+                // Persist the newly reserved group message counter values before any of them is used
+                if let Some(data) =
+                    matter.store_group_msg_ctr(unsafe { self.buf.assume_init_mut() })?
+                {
+                    Self::store_key(dir, KEY_GROUP_MSG_CTR, data)?;
+                }
+            }
+
             Ok(())
         }
 
         pub fn store(&mut self, dir: &Path, matter: &Matter) -> Result<(), Error> {
-            if matter.fabrics_changed() || matter.basic_info_changed() {
+            if matter.fabrics_changed()
+                || matter.basic_info_changed()
+                || matter.group_msg_ctr_changed()
+            {
                 fs::create_dir_all(dir)?;
             }
 
@@ -98,6 +120,14 @@ pub mod fileio {
                 }
             }
 
+            if matter.group_msg_ctr_changed() {
+                if let Some(data) =
+                    matter.store_group_msg_ctr(unsafe { self.buf.assume_init_mut() })?
+                {
+                    Self::store_key(dir, KEY_GROUP_MSG_CTR, data)?;
+                }
+            }
+
             Ok(())
         }
 
@@ -141,6 +171,94 @@ pub mod fileio {
             Ok(())
         }
 
+        /// Load the persisted subscriptions into the provided data model, which would then resume them.
+        ///
+        /// Should be called after `load`, as the subscriptions can only be resumed for the loaded fabrics.
+        pub async fn load_subscriptions<const S: usize, B, T>(
+            &mut self,
+            dir: &Path,
+            dm: &DataModel<'_, S, B, T>,
+        ) -> Result<(), Error>
+        where
+            B: BufferAccess<IMBuffer>,
+            T: DataModelHandler,
+        {
+            fs::create_dir_all(dir)?;
+
+            if let Some(data) = Self::load_key(dir, KEY_SUBSCRIPTIONS, unsafe {
+                self.buf.assume_init_mut()
+            })? {
+                dm.load_subscriptions(data).await?;
+            }
+
+            Ok(())
+        }
+
+        /// Persist the subscriptions established with the provided data model, if these had changed
+        /// since they were last persisted.
+        ///
+        /// The persisted subscriptions are resumed after a restart with `load_subscriptions`.
+        pub fn store_subscriptions<const S: usize, B, T>(
+            &mut self,
+            dir: &Path,
+            dm: &DataModel<'_, S, B, T>,
+        ) -> Result<(), Error>
+        where
+            B: BufferAccess<IMBuffer>,
+            T: DataModelHandler,
+        {
+            if dm.subscriptions_changed() {
+                fs::create_dir_all(dir)?;
+
+                if let Some(data) = dm.store_subscriptions(unsafe { self.buf.assume_init_mut() })? {
+                    Self::store_key(dir, KEY_SUBSCRIPTIONS, data)?;
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Load the persisted scene table of the provided endpoint into its scenes handler.
+        pub fn load_scenes<const S: usize>(
+            &mut self,
+            dir: &Path,
+            endpoint_id: EndptId,
+            scenes: &ScenesHandler<'_, S>,
+        ) -> Result<(), Error> {
+            fs::create_dir_all(dir)?;
+
+            if let Some(data) =
+                Self::load_key(dir, &format!("{KEY_SCENES}_{endpoint_id}"), unsafe {
+                    self.buf.assume_init_mut()
+                })?
+            {
+                scenes.load(data)?;
+            }
+
+            Ok(())
+        }
+
+        /// Persist the scene table of the provided endpoint, if it had changed
+        /// since it was last persisted.
+        ///
+        /// User code is supposed to call this method each time `ScenesHandler::wait_persist` resolves.
+        pub fn store_scenes<const S: usize>(
+            &mut self,
+            dir: &Path,
+            endpoint_id: EndptId,
+            scenes: &ScenesHandler<'_, S>,
+        ) -> Result<(), Error> {
+            if scenes.changed() {
+                fs::create_dir_all(dir)?;
+
+                if let Some(data) = scenes.store(unsafe { self.buf.assume_init_mut() })? {
+                    Self::store_key(dir, &format!("{KEY_SCENES}_{endpoint_id}"), data)?;
+                }
+            }
+
+            Ok(())
+        }
+
         pub async fn run<P: AsRef<Path>>(
             &mut self,
             dir: P,
@@ -194,6 +312,60 @@ pub mod fileio {
             }
         }
 
+        /// Same as `run_with_networks`, but also persists the subscriptions established with the provided data model,
+        /// so that they can be resumed after a restart with `load_subscriptions`.
+        pub async fn run_with_subscriptions<
+            P: AsRef<Path>,
+            const W: usize,
+            M,
+            T,
+            const S: usize,
+            B,
+            H,
+        >(
+            &mut self,
+            dir: P,
+            matter: &Matter<'_>,
+            networks: Option<&WirelessNetworks<W, M, T>>,
+            dm: &DataModel<'_, S, B, H>,
+        ) -> Result<(), Error>
+        where
+            M: RawMutex,
+            T: WirelessNetwork,
+            B: BufferAccess<IMBuffer>,
+            H: DataModelHandler,
+        {
+            let dir = dir.as_ref();
+
+            // NOTE: See `run_with_networks` as to why `load` and `load_subscriptions` are not called here
+
+            loop {
+                let networks_persist = async {
+                    if let Some(networks) = networks {
+                        networks.wait_persist().await
+                    } else {
+                        core::future::pending().await
+                    }
+                };
+
+                match select3(
+                    matter.wait_persist(),
+                    networks_persist,
+                    dm.wait_persist_subscriptions(),
+                )
+                .await
+                {
+                    Either3::First(_) => self.store(dir, matter)?,
+                    Either3::Second(_) => {
+                        if let Some(networks) = networks {
+                            self.store_networks(dir, networks)?;
+                        }
+                    }
+                    Either3::Third(_) => self.store_subscriptions(dir, dm)?,
+                }
+            }
+        }
+
         fn load_key<'b>(
             dir: &Path,
             key: &str,
@@ -296,3 +296,23 @@ fn check_opcode(exchange: &Exchange<'_>, opcode: OpCode) -> Result<(), Error> {
         Err(ErrorCode::Invalid.into())
     }
 }
+
+/// Check that the received message is a status report indicating a successful session establishment.
+/// Logs an error with the details of the status report if that's not the case.
+fn check_session_established(exchange: &Exchange<'_>) -> Result<(), Error> {
+    check_opcode(exchange, OpCode::StatusReport)?;
+
+    let mut rb = ReadBuf::new(exchange.rx()?.payload());
+    let status_report = StatusReport::read(&mut rb)?;
+
+    if status_report.general_code == GeneralCode::Success
+        && status_report.proto_id == PROTO_ID_SECURE_CHANNEL as u32
+        && status_report.proto_code == SCStatusCodes::SessionEstablishmentSuccess as u16
+    {
+        Ok(())
+    } else {
+        error!("Session establishment failed: {:?}", status_report);
+
+        Err(ErrorCode::Invalid.into())
+    }
+}
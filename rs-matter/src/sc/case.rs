@@ -23,13 +23,17 @@ use crate::crypto::{self, KeyPair, Sha256};
 use crate::error::{Error, ErrorCode};
 use crate::fabric::Fabric;
 use crate::sc::{
-    check_opcode, complete_with_status, sc_write, OpCode, SCStatusCodes, SessionParameters,
+    check_opcode, check_session_established, complete_with_status, sc_write, OpCode, SCStatusCodes,
+    SessionParameters,
 };
 use crate::tlv::{get_root_node_struct, FromTLV, OctetStr, TLVElement, TLVTag, TLVWrite};
 use crate::transport::exchange::Exchange;
-use crate::transport::session::{NocCatIds, ReservedSession, SessionMode};
+use crate::transport::session::{
+    NocCatIds, ReservedSession, ResumptionState, SessionMode, RESUMPTION_ID_LEN,
+};
 use crate::utils::init::{init, zeroed, Init, InitMaybeUninit};
 use crate::utils::storage::WriteBuf;
+use crate::Matter;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -41,6 +45,7 @@ pub struct CaseSession {
     our_pub_key: [u8; crypto::EC_POINT_LEN_BYTES],
     peer_pub_key: [u8; crypto::EC_POINT_LEN_BYTES],
     local_fabric_idx: u8,
+    resumption_id: [u8; RESUMPTION_ID_LEN],
 }
 
 impl Default for CaseSession {
@@ -60,6 +65,7 @@ impl CaseSession {
             our_pub_key: [0; crypto::EC_POINT_LEN_BYTES],
             peer_pub_key: [0; crypto::EC_POINT_LEN_BYTES],
             local_fabric_idx: 0,
+            resumption_id: [0; RESUMPTION_ID_LEN],
         }
     }
 
@@ -72,6 +78,7 @@ impl CaseSession {
             our_pub_key <- zeroed(),
             peer_pub_key <- zeroed(),
             local_fabric_idx: 0,
+            resumption_id <- zeroed(),
         })
     }
 }
@@ -89,19 +96,510 @@ impl Case {
         exchange: &mut Exchange<'_>,
         case_session: &mut CaseSession,
     ) -> Result<(), Error> {
+        let mut session = ReservedSession::reserve(exchange.matter()).await?;
+
+        if let Some(resumption) = self
+            .handle_casesigma1(exchange, case_session, &mut session)
+            .await?
+        {
+            // The initiator asked us to resume a previous session, and we agreed
+            exchange.recv_fetch().await?;
+
+            self.handle_resume_status(exchange, &resumption, case_session, session)?;
+        } else {
+            exchange.recv_fetch().await?;
+
+            self.handle_casesigma3(exchange, case_session, session)
+                .await?;
+        }
+
+        exchange.acknowledge().await?;
+        exchange.matter().notify_persist();
+
+        Ok(())
+    }
+
+    /// Establish a CASE session with the peer with the provided node ID on the provided fabric,
+    /// acting as a CASE initiator.
+    ///
+    /// The provided exchange should be an exchange on an unencrypted session with the peer,
+    /// as created with `Exchange::initiate_unsecured`.
+    ///
+    /// If a previous CASE session with the peer had been established, the initiator would first
+    /// try to resume it, falling back to a full CASE handshake if the peer refuses.
+    ///
+    /// Return the internal ID of the newly established session, which can then be used
+    /// to initiate exchanges on it with `Exchange::initiate_for_session`.
+    pub async fn initiate(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        case_session: &mut CaseSession,
+    ) -> Result<u32, Error> {
         let session = ReservedSession::reserve(exchange.matter()).await?;
 
-        self.handle_casesigma1(exchange, case_session).await?;
+        let resumption = exchange
+            .matter()
+            .transport_mgr
+            .session_mgr
+            .borrow()
+            .get_resumption_for_node(fabric_idx, peer_node_id)
+            .cloned();
+
+        let mut our_random = MaybeUninit::<[u8; 32]>::uninit(); // TODO MEDIUM BUFFER
+        let our_random = our_random.init_zeroed();
+        (exchange.matter().rand())(our_random);
+
+        let key_pair = self
+            .send_casesigma1(
+                exchange,
+                fabric_idx,
+                peer_node_id,
+                our_random,
+                resumption.as_ref(),
+                case_session,
+            )
+            .await?;
 
         exchange.recv_fetch().await?;
 
-        self.handle_casesigma3(exchange, case_session, session)
+        let resumed = exchange.rx()?.meta().opcode::<OpCode>()? == OpCode::CASESigma2Resume;
+
+        if let Some(resumption) = resumption.as_ref().filter(|_| resumed) {
+            return self
+                .handle_casesigma2resume(exchange, our_random, resumption, case_session, session)
+                .await;
+        }
+
+        let peer_catids = self
+            .handle_casesigma2(exchange, peer_node_id, key_pair, case_session)
             .await?;
 
+        self.send_casesigma3(exchange, case_session).await?;
+
+        exchange.recv_fetch().await?;
+
+        self.handle_sigma3_status(exchange, peer_node_id, peer_catids, case_session, session)
+            .await
+    }
+
+    async fn handle_sigma3_status(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        peer_node_id: u64,
+        peer_catids: NocCatIds,
+        case_session: &mut CaseSession,
+        mut session: ReservedSession<'_>,
+    ) -> Result<u32, Error> {
+        check_session_established(exchange)?;
+
+        let fabric_idx =
+            NonZeroU8::new(case_session.local_fabric_idx).ok_or(ErrorCode::NoFabricId)?;
+
+        let local_nodeid = {
+            let fabric_mgr = exchange.matter().fabric_mgr.borrow();
+            let fabric = fabric_mgr.get(fabric_idx).ok_or(ErrorCode::NoFabricId)?;
+
+            let mut session_keys = MaybeUninit::<[u8; 3 * crypto::SYMM_KEY_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
+            let session_keys = session_keys.init_zeroed();
+            Case::get_session_keys(
+                fabric.ipk().op_key(),
+                unwrap!(case_session.tt_hash.as_ref()),
+                &case_session.shared_secret,
+                session_keys,
+            )?;
+
+            let peer_addr = exchange.with_session(|sess| Ok(sess.get_peer_addr()))?;
+
+            session.update(
+                fabric.node_id(),
+                peer_node_id,
+                case_session.peer_sessid,
+                case_session.local_sessid,
+                peer_addr,
+                SessionMode::Case {
+                    fab_idx: fabric_idx,
+                    cat_ids: peer_catids,
+                },
+                // As the initiator, we decrypt with the R2I key and encrypt with the I2R key
+                Some(&session_keys[16..32]),
+                Some(&session_keys[0..16]),
+                Some(&session_keys[32..48]),
+            )?;
+
+            fabric.node_id()
+        };
+
+        let session_id = session.id();
+
+        // Complete the reserved session and thus make the `Session` instance
+        // immediately available for use by the system.
+        session.complete();
+
+        Case::save_resumption(
+            exchange.matter(),
+            case_session,
+            fabric_idx,
+            peer_node_id,
+            peer_catids,
+        );
+
         exchange.acknowledge().await?;
-        exchange.matter().notify_persist();
 
-        Ok(())
+        debug!(
+            "CASE session with peer node {:x} established as local node {:x}",
+            peer_node_id, local_nodeid
+        );
+
+        Ok(session_id)
+    }
+
+    async fn handle_casesigma2resume(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        initiator_random: &[u8],
+        resumption: &ResumptionState,
+        case_session: &mut CaseSession,
+        mut session: ReservedSession<'_>,
+    ) -> Result<u32, Error> {
+        check_opcode(exchange, OpCode::CASESigma2Resume)?;
+
+        let status = {
+            let root = get_root_node_struct(exchange.rx()?.payload())?;
+            let r = Sigma2ResumeResp::from_tlv(&root)?;
+
+            if r.resumption_id.0.len() != RESUMPTION_ID_LEN {
+                error!("Invalid resumption ID length");
+                Err(SCStatusCodes::InvalidParameter)
+            } else if let Err(e) = Case::validate_resume_mic(
+                &S2R_INFO,
+                &S2R_NONCE,
+                initiator_random,
+                r.resumption_id.0,
+                &resumption.shared_secret,
+                r.sigma2_resume_mic.0,
+            ) {
+                error!("Sigma2Resume MIC doesn't match: {}", e);
+                Err(SCStatusCodes::InvalidParameter)
+            } else {
+                case_session.peer_sessid = r.responder_sessid;
+                case_session
+                    .resumption_id
+                    .copy_from_slice(r.resumption_id.0);
+                case_session
+                    .shared_secret
+                    .copy_from_slice(&resumption.shared_secret);
+
+                Ok(())
+            }
+        };
+
+        if let Err(status) = status {
+            complete_with_status(exchange, status, &[]).await?;
+
+            Err(ErrorCode::Invalid)?;
+        }
+
+        let local_nodeid = exchange
+            .matter()
+            .fabric_mgr
+            .borrow()
+            .get(resumption.fab_idx)
+            .ok_or(ErrorCode::NoFabricId)?
+            .node_id();
+
+        let mut session_keys = MaybeUninit::<[u8; 3 * crypto::SYMM_KEY_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
+        let session_keys = session_keys.init_zeroed();
+        Case::get_resumption_session_keys(
+            initiator_random,
+            &case_session.resumption_id,
+            &case_session.shared_secret,
+            session_keys,
+        )?;
+
+        let peer_addr = exchange.with_session(|sess| Ok(sess.get_peer_addr()))?;
+
+        session.update(
+            local_nodeid,
+            resumption.peer_nodeid,
+            case_session.peer_sessid,
+            case_session.local_sessid,
+            peer_addr,
+            SessionMode::Case {
+                fab_idx: resumption.fab_idx,
+                cat_ids: resumption.cat_ids,
+            },
+            // As the initiator, we decrypt with the R2I key and encrypt with the I2R key
+            Some(&session_keys[16..32]),
+            Some(&session_keys[0..16]),
+            Some(&session_keys[32..48]),
+        )?;
+
+        let session_id = session.id();
+
+        // Complete the reserved session before confirming the resumption to the peer,
+        // so that we do not miss any messages the peer might send on it right after
+        session.complete();
+
+        complete_with_status(exchange, SCStatusCodes::SessionEstablishmentSuccess, &[]).await?;
+
+        Case::save_resumption(
+            exchange.matter(),
+            case_session,
+            resumption.fab_idx,
+            resumption.peer_nodeid,
+            resumption.cat_ids,
+        );
+
+        debug!(
+            "CASE session with peer node {:x} resumed as local node {:x}",
+            resumption.peer_nodeid, local_nodeid
+        );
+
+        Ok(session_id)
+    }
+
+    async fn send_casesigma1(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        our_random: &[u8],
+        resumption: Option<&ResumptionState>,
+        case_session: &mut CaseSession,
+    ) -> Result<KeyPair, Error> {
+        let local_sessid = exchange
+            .matter()
+            .transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .get_next_sess_id();
+        case_session.local_sessid = local_sessid;
+        case_session.local_fabric_idx = fabric_idx.get();
+        case_session.tt_hash = Some(Sha256::new()?);
+
+        // Create an ephemeral Key Pair
+        let key_pair = KeyPair::new(exchange.matter().rand())?;
+        let _ = key_pair.get_public_key(&mut case_session.our_pub_key)?;
+
+        let mut resume_mic = [0_u8; crypto::AEAD_MIC_LEN_BYTES];
+        if let Some(resumption) = resumption {
+            Case::get_resume_mic(
+                &S1R_INFO,
+                &S1R_NONCE,
+                our_random,
+                &resumption.resumption_id,
+                &resumption.shared_secret,
+                &mut resume_mic,
+            )?;
+        }
+
+        let mut dest_id = MaybeUninit::<[u8; crypto::SHA256_HASH_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
+        let dest_id = dest_id.init_zeroed();
+        exchange
+            .matter()
+            .fabric_mgr
+            .borrow()
+            .get(fabric_idx)
+            .ok_or(ErrorCode::NoFabricId)?
+            .compute_dest_id(our_random, peer_node_id, dest_id)?;
+
+        let mut hash_updated = false;
+        exchange
+            .send_with(|_, tw| {
+                tw.start_struct(&TLVTag::Anonymous)?;
+                tw.str(&TLVTag::Context(1), our_random)?;
+                tw.u16(&TLVTag::Context(2), local_sessid)?;
+                tw.str(&TLVTag::Context(3), &*dest_id)?;
+                tw.str(&TLVTag::Context(4), &case_session.our_pub_key)?;
+                if let Some(resumption) = resumption {
+                    tw.str(&TLVTag::Context(6), &resumption.resumption_id)?;
+                    tw.str(&TLVTag::Context(7), &resume_mic)?;
+                }
+                tw.end_container()?;
+
+                if !hash_updated {
+                    unwrap!(case_session.tt_hash.as_mut()).update(tw.as_slice())?;
+                    hash_updated = true;
+                }
+
+                Ok(Some(OpCode::CASESigma1.into()))
+            })
+            .await?;
+
+        Ok(key_pair)
+    }
+
+    async fn handle_casesigma2(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        peer_node_id: u64,
+        key_pair: KeyPair,
+        case_session: &mut CaseSession,
+    ) -> Result<NocCatIds, Error> {
+        check_opcode(exchange, OpCode::CASESigma2)?;
+
+        let status = {
+            let root = get_root_node_struct(exchange.rx()?.payload())?;
+            let r = Sigma2Resp::from_tlv(&root)?;
+
+            if r.responder_pub_key.0.len() != crypto::EC_POINT_LEN_BYTES {
+                error!("Invalid public key length");
+                Err(ErrorCode::Invalid)?;
+            }
+            case_session.peer_sessid = r.responder_sessid;
+            case_session
+                .peer_pub_key
+                .copy_from_slice(r.responder_pub_key.0);
+
+            // Derive the Shared Secret
+            let len =
+                key_pair.derive_secret(r.responder_pub_key.0, &mut case_session.shared_secret)?;
+            if len != 32 {
+                error!("Derived secret length incorrect");
+                Err(ErrorCode::Invalid)?;
+            }
+
+            let fabric_mgr = exchange.matter().fabric_mgr.borrow();
+            let fabric = NonZeroU8::new(case_session.local_fabric_idx)
+                .and_then(|fabric_idx| fabric_mgr.get(fabric_idx))
+                .ok_or(ErrorCode::NoFabricId)?;
+
+            let encrypted = r.encrypted2.0;
+
+            let mut decrypted = alloc!([0; 800]); // TODO LARGE BUFFER
+            if encrypted.len() > decrypted.len() {
+                error!("Data too large");
+                Err(ErrorCode::NoSpace)?;
+            }
+            let decrypted = &mut decrypted[..encrypted.len()];
+            decrypted.copy_from_slice(encrypted);
+
+            let len = Case::get_sigma2_decryption(
+                fabric.ipk().op_key(),
+                r.responder_random.0,
+                case_session,
+                decrypted,
+            )?;
+            let decrypted = &decrypted[..len];
+
+            let root = get_root_node_struct(decrypted)?;
+            let d = Sigma2Decrypt::from_tlv(&root)?;
+
+            if d.resumption_id.0.len() != RESUMPTION_ID_LEN {
+                error!("Invalid resumption ID length");
+                Err(ErrorCode::Invalid)?;
+            }
+            case_session
+                .resumption_id
+                .copy_from_slice(d.resumption_id.0);
+
+            let responder_noc = CertRef::new(TLVElement::new(d.responder_noc.0));
+            let responder_icac = d
+                .responder_icac
+                .map(|icac| CertRef::new(TLVElement::new(icac.0)));
+
+            let mut buf = alloc!([0; 800]); // TODO LARGE BUFFER
+            let buf = &mut buf[..];
+            if let Err(e) =
+                Case::validate_certs(fabric, &responder_noc, responder_icac.as_ref(), buf)
+            {
+                error!("Certificate Chain doesn't match: {}", e);
+                Err(SCStatusCodes::InvalidParameter)
+            } else if responder_noc.get_node_id()? != peer_node_id {
+                error!("Peer node ID doesn't match");
+                Err(SCStatusCodes::InvalidParameter)
+            } else if let Err(e) = Case::validate_tbs_sign(
+                d.responder_noc.0,
+                d.responder_icac.map(|a| a.0),
+                &responder_noc,
+                d.signature.0,
+                case_session,
+                buf,
+            ) {
+                error!("Sigma2 Signature doesn't match: {}", e);
+                Err(SCStatusCodes::InvalidParameter)
+            } else {
+                // Only now do we add this message to the TT Hash
+                let mut peer_catids: NocCatIds = Default::default();
+                responder_noc.get_cat_ids(&mut peer_catids)?;
+                unwrap!(case_session.tt_hash.as_mut()).update(exchange.rx()?.payload())?;
+
+                Ok(peer_catids)
+            }
+        };
+
+        match status {
+            Ok(peer_catids) => Ok(peer_catids),
+            Err(status) => {
+                complete_with_status(exchange, status, &[]).await?;
+
+                Err(ErrorCode::Invalid.into())
+            }
+        }
+    }
+
+    async fn send_casesigma3(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        case_session: &mut CaseSession,
+    ) -> Result<(), Error> {
+        let mut signature = MaybeUninit::<[u8; crypto::EC_SIGNATURE_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
+        let signature = signature.init_zeroed();
+
+        let mut sigma3_key = [0_u8; crypto::SYMM_KEY_LEN_BYTES];
+
+        // Compute the signature and the encryption key upfront, so that the message is
+        // constructed identically in case it needs to be re-transmitted
+        let sign_len = {
+            let fabric_mgr = exchange.matter().fabric_mgr.borrow();
+            let fabric = NonZeroU8::new(case_session.local_fabric_idx)
+                .and_then(|fabric_idx| fabric_mgr.get(fabric_idx))
+                .ok_or(ErrorCode::NoFabricId)?;
+
+            Case::get_sigma3_key(
+                fabric.ipk().op_key(),
+                unwrap!(case_session.tt_hash.as_ref()),
+                &case_session.shared_secret,
+                &mut sigma3_key,
+            )?;
+
+            let mut buf = alloc!([0; 800]); // TODO LARGE BUFFER
+            Case::get_tbs_sign(
+                fabric,
+                &case_session.our_pub_key,
+                &case_session.peer_pub_key,
+                &mut buf[..],
+                signature,
+            )?
+        };
+
+        let signature = &signature[..sign_len];
+
+        let mut hash_updated = false;
+        exchange
+            .send_with(|exchange, tw| {
+                let fabric_mgr = exchange.matter().fabric_mgr.borrow();
+
+                let fabric = NonZeroU8::new(case_session.local_fabric_idx)
+                    .and_then(|fabric_idx| fabric_mgr.get(fabric_idx))
+                    .ok_or(ErrorCode::NoFabricId)?;
+
+                tw.start_struct(&TLVTag::Anonymous)?;
+                tw.str_cb(&TLVTag::Context(1), |buf| {
+                    Case::get_sigma3_encryption(fabric, &sigma3_key, signature, buf)
+                })?;
+                tw.end_container()?;
+
+                if !hash_updated {
+                    unwrap!(case_session.tt_hash.as_mut()).update(tw.as_slice())?;
+                    hash_updated = true;
+                }
+
+                Ok(Some(OpCode::CASESigma3.into()))
+            })
+            .await
     }
 
     async fn handle_casesigma3(
@@ -148,7 +646,7 @@ impl Case {
                 {
                     error!("Certificate Chain doesn't match: {}", e);
                     SCStatusCodes::InvalidParameter
-                } else if let Err(e) = Case::validate_sigma3_sign(
+                } else if let Err(e) = Case::validate_tbs_sign(
                     d.initiator_noc.0,
                     d.initiator_icac.map(|a| a.0),
                     &initiator_noc,
@@ -165,7 +663,7 @@ impl Case {
                     unwrap!(case_session.tt_hash.as_mut()).update(exchange.rx()?.payload())?;
 
                     let mut session_keys =
-                        MaybeUninit::<[u8; 3 * crypto::SYMM_KEY_LEN_BYTES]>::uninit(); // TODO MEDIM BUFFER
+                        MaybeUninit::<[u8; 3 * crypto::SYMM_KEY_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
                     let session_keys = session_keys.init_zeroed();
                     Case::get_session_keys(
                         fabric.ipk().op_key(),
@@ -176,15 +674,18 @@ impl Case {
 
                     let peer_addr = exchange.with_session(|sess| Ok(sess.get_peer_addr()))?;
 
+                    // Unwrapping is safe, because if the fabric index was 0, we would not be in here
+                    let fab_idx = unwrap!(NonZeroU8::new(case_session.local_fabric_idx));
+                    let peer_nodeid = initiator_noc.get_node_id()?;
+
                     session.update(
                         fabric.node_id(),
-                        initiator_noc.get_node_id()?,
+                        peer_nodeid,
                         case_session.peer_sessid,
                         case_session.local_sessid,
                         peer_addr,
                         SessionMode::Case {
-                            // Unwrapping is safe, because if the fabric index was 0, we would not be in here
-                            fab_idx: unwrap!(NonZeroU8::new(case_session.local_fabric_idx)),
+                            fab_idx,
                             cat_ids: peer_catids,
                         },
                         Some(&session_keys[0..16]),
@@ -201,6 +702,14 @@ impl Case {
                     // as reserved.
                     session.complete();
 
+                    Case::save_resumption(
+                        exchange.matter(),
+                        case_session,
+                        fab_idx,
+                        peer_nodeid,
+                        peer_catids,
+                    );
+
                     SCStatusCodes::SessionEstablishmentSuccess
                 }
             } else {
@@ -211,13 +720,33 @@ impl Case {
         complete_with_status(exchange, status, &[]).await
     }
 
+    /// Handle a Sigma1 message from the initiator.
+    ///
+    /// Return the state of the resumed session if the initiator requested the resumption of
+    /// a previous session and a Sigma2Resume message was sent back, or `None` if a full CASE
+    /// handshake is in progress.
     async fn handle_casesigma1(
         &mut self,
         exchange: &mut Exchange<'_>,
         case_session: &mut CaseSession,
-    ) -> Result<(), Error> {
+        session: &mut ReservedSession<'_>,
+    ) -> Result<Option<ResumptionState>, Error> {
         check_opcode(exchange, OpCode::CASESigma1)?;
 
+        let resumption = {
+            let root = get_root_node_struct(exchange.rx()?.payload())?;
+            let r = Sigma1Req::from_tlv(&root)?;
+
+            Case::get_sigma1_resumption(exchange.matter(), &r)
+        };
+
+        if let Some(resumption) = resumption {
+            self.send_casesigma2resume(exchange, case_session, session, &resumption)
+                .await?;
+
+            return Ok(Some(resumption));
+        }
+
         let root = get_root_node_struct(exchange.rx()?.payload())?;
         let r = Sigma1Req::from_tlv(&root)?;
 
@@ -231,7 +760,7 @@ impl Case {
             error!("Fabric Index mismatch");
             complete_with_status(exchange, SCStatusCodes::NoSharedTrustRoots, &[]).await?;
 
-            return Ok(());
+            return Ok(None);
         }
 
         let local_sessid = exchange
@@ -271,9 +800,7 @@ impl Case {
         let our_random = our_random.init_zeroed();
         (exchange.matter().rand())(our_random);
 
-        let mut resumption_id = MaybeUninit::<[u8; 16]>::uninit(); // TODO MEDIUM BUFFER
-        let resumption_id = resumption_id.init_zeroed();
-        (exchange.matter().rand())(resumption_id);
+        (exchange.matter().rand())(&mut case_session.resumption_id);
 
         let mut tt_hash = MaybeUninit::<[u8; crypto::SHA256_HASH_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
         let tt_hash = tt_hash.init_zeroed();
@@ -299,7 +826,7 @@ impl Case {
                 // Use the remainder of the TX buffer as scratch space for computing the signature
                 let sign_buf = tw.empty_as_mut_slice();
 
-                let sign_len = Case::get_sigma2_sign(
+                let sign_len = Case::get_tbs_sign(
                     fabric,
                     &case_session.our_pub_key,
                     &case_session.peer_pub_key,
@@ -322,7 +849,7 @@ impl Case {
                         tt_hash,
                         &case_session.shared_secret,
                         signature,
-                        resumption_id,
+                        &case_session.resumption_id,
                         buf,
                     )
                 })?;
@@ -335,13 +862,288 @@ impl Case {
 
                 Ok(Some(OpCode::CASESigma2.into()))
             })
+            .await?;
+
+        Ok(None)
+    }
+
+    /// Check if the Sigma1 message requests the resumption of a previous session
+    /// which we know about, and return its resumption state if the resume MIC is valid.
+    fn get_sigma1_resumption(matter: &Matter, r: &Sigma1Req) -> Option<ResumptionState> {
+        let (Some(resumption_id), Some(resume_mic)) = (r.resumption_id, r.initiator_resume_mic)
+        else {
+            return None;
+        };
+
+        let Some(resumption) = matter
+            .transport_mgr
+            .session_mgr
+            .borrow()
+            .get_resumption(resumption_id.0)
+            .cloned()
+        else {
+            debug!("Unknown resumption ID, falling back to a full CASE handshake");
+            return None;
+        };
+
+        if let Err(e) = Case::validate_resume_mic(
+            &S1R_INFO,
+            &S1R_NONCE,
+            r.initiator_random.0,
+            resumption_id.0,
+            &resumption.shared_secret,
+            resume_mic.0,
+        ) {
+            warn!(
+                "Sigma1 resume MIC doesn't match: {}, falling back to a full CASE handshake",
+                e
+            );
+            return None;
+        }
+
+        Some(resumption)
+    }
+
+    async fn send_casesigma2resume(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        case_session: &mut CaseSession,
+        session: &mut ReservedSession<'_>,
+        resumption: &ResumptionState,
+    ) -> Result<(), Error> {
+        let mut initiator_random = MaybeUninit::<[u8; 32]>::uninit(); // TODO MEDIUM BUFFER
+        let initiator_random = initiator_random.init_zeroed();
+
+        {
+            let root = get_root_node_struct(exchange.rx()?.payload())?;
+            let r = Sigma1Req::from_tlv(&root)?;
+
+            if r.initiator_random.0.len() != initiator_random.len() {
+                error!("Invalid initiator random length");
+                Err(ErrorCode::Invalid)?;
+            }
+
+            initiator_random.copy_from_slice(r.initiator_random.0);
+            case_session.peer_sessid = r.initiator_sessid;
+        }
+
+        let local_sessid = exchange
+            .matter()
+            .transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .get_next_sess_id();
+        case_session.local_sessid = local_sessid;
+        case_session.local_fabric_idx = resumption.fab_idx.get();
+        case_session
+            .shared_secret
+            .copy_from_slice(&resumption.shared_secret);
+        (exchange.matter().rand())(&mut case_session.resumption_id);
+
+        let mut resume_mic = [0_u8; crypto::AEAD_MIC_LEN_BYTES];
+        Case::get_resume_mic(
+            &S2R_INFO,
+            &S2R_NONCE,
+            initiator_random,
+            &case_session.resumption_id,
+            &case_session.shared_secret,
+            &mut resume_mic,
+        )?;
+
+        let local_nodeid = exchange
+            .matter()
+            .fabric_mgr
+            .borrow()
+            .get(resumption.fab_idx)
+            .ok_or(ErrorCode::NoFabricId)?
+            .node_id();
+
+        let mut session_keys = MaybeUninit::<[u8; 3 * crypto::SYMM_KEY_LEN_BYTES]>::uninit(); // TODO MEDIUM BUFFER
+        let session_keys = session_keys.init_zeroed();
+        Case::get_resumption_session_keys(
+            initiator_random,
+            &case_session.resumption_id,
+            &case_session.shared_secret,
+            session_keys,
+        )?;
+
+        let peer_addr = exchange.with_session(|sess| Ok(sess.get_peer_addr()))?;
+
+        // The session is only completed once the initiator confirms the resumption
+        session.update(
+            local_nodeid,
+            resumption.peer_nodeid,
+            case_session.peer_sessid,
+            local_sessid,
+            peer_addr,
+            SessionMode::Case {
+                fab_idx: resumption.fab_idx,
+                cat_ids: resumption.cat_ids,
+            },
+            Some(&session_keys[0..16]),
+            Some(&session_keys[16..32]),
+            Some(&session_keys[32..48]),
+        )?;
+
+        exchange
+            .send_with(|_, tw| {
+                tw.start_struct(&TLVTag::Anonymous)?;
+                tw.str(&TLVTag::Context(1), &case_session.resumption_id)?;
+                tw.str(&TLVTag::Context(2), &resume_mic)?;
+                tw.u16(&TLVTag::Context(3), local_sessid)?;
+                tw.end_container()?;
+
+                Ok(Some(OpCode::CASESigma2Resume.into()))
+            })
             .await
     }
 
-    fn validate_sigma3_sign(
-        initiator_noc: &[u8],
-        initiator_icac: Option<&[u8]>,
-        initiator_noc_cert: &CertRef,
+    fn handle_resume_status(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        resumption: &ResumptionState,
+        case_session: &CaseSession,
+        session: ReservedSession<'_>,
+    ) -> Result<(), Error> {
+        check_session_established(exchange)?;
+
+        // Complete the reserved session and thus make the `Session` instance
+        // immediately available for use by the system.
+        session.complete();
+
+        Case::save_resumption(
+            exchange.matter(),
+            case_session,
+            resumption.fab_idx,
+            resumption.peer_nodeid,
+            resumption.cat_ids,
+        );
+
+        Ok(())
+    }
+
+    /// Remember the state necessary for resuming the newly established session with the peer.
+    fn save_resumption(
+        matter: &Matter,
+        case_session: &CaseSession,
+        fab_idx: NonZeroU8,
+        peer_nodeid: u64,
+        cat_ids: NocCatIds,
+    ) {
+        matter
+            .transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .add_resumption(ResumptionState {
+                resumption_id: case_session.resumption_id,
+                shared_secret: case_session.shared_secret,
+                fab_idx,
+                peer_nodeid,
+                cat_ids,
+            });
+    }
+
+    fn get_resume_key(
+        info: &[u8],
+        initiator_random: &[u8],
+        resumption_id: &[u8],
+        shared_secret: &[u8],
+        key: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut salt = heapless::Vec::<u8, 256>::new();
+        salt.extend_from_slice(initiator_random)
+            .map_err(|_| ErrorCode::NoSpace)?;
+        salt.extend_from_slice(resumption_id)
+            .map_err(|_| ErrorCode::NoSpace)?;
+
+        crypto::hkdf_sha256(salt.as_slice(), shared_secret, info, key)
+            .map_err(|_x| ErrorCode::NoSpace)?;
+
+        Ok(())
+    }
+
+    fn get_resume_mic(
+        info: &[u8],
+        nonce: &[u8],
+        initiator_random: &[u8],
+        resumption_id: &[u8],
+        shared_secret: &[u8],
+        mic: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut resume_key = [0_u8; crypto::SYMM_KEY_LEN_BYTES];
+        Case::get_resume_key(
+            info,
+            initiator_random,
+            resumption_id,
+            shared_secret,
+            &mut resume_key,
+        )?;
+
+        // The MIC is the tag of encrypting an empty plain text
+        crypto::encrypt_in_place(&resume_key, nonce, &[], mic, 0)?;
+
+        Ok(())
+    }
+
+    fn validate_resume_mic(
+        info: &[u8],
+        nonce: &[u8],
+        initiator_random: &[u8],
+        resumption_id: &[u8],
+        shared_secret: &[u8],
+        mic: &[u8],
+    ) -> Result<(), Error> {
+        if mic.len() != crypto::AEAD_MIC_LEN_BYTES {
+            Err(ErrorCode::Invalid)?;
+        }
+
+        let mut resume_key = [0_u8; crypto::SYMM_KEY_LEN_BYTES];
+        Case::get_resume_key(
+            info,
+            initiator_random,
+            resumption_id,
+            shared_secret,
+            &mut resume_key,
+        )?;
+
+        let mut buf = [0_u8; crypto::AEAD_MIC_LEN_BYTES];
+        buf.copy_from_slice(mic);
+
+        crypto::decrypt_in_place(&resume_key, nonce, &[], &mut buf)?;
+
+        Ok(())
+    }
+
+    fn get_resumption_session_keys(
+        initiator_random: &[u8],
+        resumption_id: &[u8],
+        shared_secret: &[u8],
+        key: &mut [u8],
+    ) -> Result<(), Error> {
+        const SERKEYS_INFO: [u8; 21] = [
+            0x53, 0x65, 0x73, 0x73, 0x69, 0x6f, 0x6e, 0x52, 0x65, 0x73, 0x75, 0x6d, 0x70, 0x74,
+            0x69, 0x6f, 0x6e, 0x4b, 0x65, 0x79, 0x73,
+        ];
+        if key.len() < 48 {
+            Err(ErrorCode::NoSpace)?;
+        }
+
+        Case::get_resume_key(
+            &SERKEYS_INFO,
+            initiator_random,
+            resumption_id,
+            shared_secret,
+            key,
+        )
+    }
+
+    /// Validate the peer signature of the to-be-signed data of a Sigma2 or a Sigma3 message.
+    ///
+    /// The TBS data is the same for both messages, if looked at from the perspective of the signing peer.
+    fn validate_tbs_sign(
+        peer_noc: &[u8],
+        peer_icac: Option<&[u8]>,
+        peer_noc_cert: &CertRef,
         sign: &[u8],
         case_session: &CaseSession,
         buf: &mut [u8],
@@ -349,15 +1151,15 @@ impl Case {
         let mut write_buf = WriteBuf::new(buf);
         let tw = &mut write_buf;
         tw.start_struct(&TLVTag::Anonymous)?;
-        tw.str(&TLVTag::Context(1), initiator_noc)?;
-        if let Some(icac) = initiator_icac {
+        tw.str(&TLVTag::Context(1), peer_noc)?;
+        if let Some(icac) = peer_icac {
             tw.str(&TLVTag::Context(2), icac)?;
         }
         tw.str(&TLVTag::Context(3), &case_session.peer_pub_key)?;
         tw.str(&TLVTag::Context(4), &case_session.our_pub_key)?;
         tw.end_container()?;
 
-        let key = KeyPair::new_from_public(initiator_noc_cert.pubkey()?)?;
+        let key = KeyPair::new_from_public(peer_noc_cert.pubkey()?)?;
         key.verify_msg(write_buf.as_slice(), sign)?;
         Ok(())
     }
@@ -440,6 +1242,72 @@ impl Case {
         Ok(encrypted_len - crypto::AEAD_MIC_LEN_BYTES)
     }
 
+    fn get_sigma2_decryption(
+        ipk: &[u8],
+        responder_random: &[u8],
+        case_session: &CaseSession,
+        encrypted: &mut [u8],
+    ) -> Result<usize, Error> {
+        // At this point, the TT Hash only contains Sigma1
+        let mut sigma1_hash = [0u8; crypto::SHA256_HASH_LEN_BYTES];
+        unwrap!(case_session.tt_hash.as_ref())
+            .clone()
+            .finish(&mut sigma1_hash)?;
+
+        let mut sigma2_key = [0_u8; crypto::SYMM_KEY_LEN_BYTES];
+        Case::get_sigma2_key(
+            ipk,
+            responder_random,
+            &case_session.peer_pub_key,
+            &sigma1_hash,
+            &case_session.shared_secret,
+            &mut sigma2_key,
+        )?;
+
+        let nonce: [u8; crypto::AEAD_NONCE_LEN_BYTES] = [
+            0x4e, 0x43, 0x41, 0x53, 0x45, 0x5f, 0x53, 0x69, 0x67, 0x6d, 0x61, 0x32, 0x4e,
+        ];
+
+        let encrypted_len = encrypted.len();
+        crypto::decrypt_in_place(&sigma2_key, &nonce, &[], encrypted)?;
+        Ok(encrypted_len - crypto::AEAD_MIC_LEN_BYTES)
+    }
+
+    fn get_sigma3_encryption(
+        fabric: &Fabric,
+        sigma3_key: &[u8],
+        signature: &[u8],
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut write_buf = WriteBuf::new(out);
+        let tw = &mut write_buf;
+        tw.start_struct(&TLVTag::Anonymous)?;
+        tw.str(&TLVTag::Context(1), fabric.noc())?;
+        if !fabric.icac().is_empty() {
+            tw.str(&TLVTag::Context(2), fabric.icac())?
+        };
+
+        tw.str(&TLVTag::Context(3), signature)?;
+        tw.end_container()?;
+
+        let nonce: [u8; crypto::AEAD_NONCE_LEN_BYTES] = [
+            0x4e, 0x43, 0x41, 0x53, 0x45, 0x5f, 0x53, 0x69, 0x67, 0x6d, 0x61, 0x33, 0x4e,
+        ];
+
+        let tag = [0u8; crypto::AEAD_MIC_LEN_BYTES];
+        write_buf.append(&tag)?;
+        let cipher_text = write_buf.as_mut_slice();
+
+        crypto::encrypt_in_place(
+            sigma3_key,
+            &nonce,
+            &[],
+            cipher_text,
+            cipher_text.len() - crypto::AEAD_MIC_LEN_BYTES,
+        )?;
+        Ok(write_buf.as_slice().len())
+    }
+
     fn get_sigma3_key(
         ipk: &[u8],
         tt: &Sha256,
@@ -469,9 +1337,9 @@ impl Case {
 
     fn get_sigma2_key(
         ipk: &[u8],
-        our_random: &[u8],
-        our_pub_key: &[u8],
-        our_hash: &[u8],
+        responder_random: &[u8],
+        responder_pub_key: &[u8],
+        sigma1_hash: &[u8],
         shared_secret: &[u8],
         key: &mut [u8],
     ) -> Result<(), Error> {
@@ -481,9 +1349,9 @@ impl Case {
         }
         let mut salt = heapless::Vec::<u8, 256>::new();
         unwrap!(salt.extend_from_slice(ipk));
-        unwrap!(salt.extend_from_slice(our_random));
-        unwrap!(salt.extend_from_slice(our_pub_key));
-        unwrap!(salt.extend_from_slice(our_hash));
+        unwrap!(salt.extend_from_slice(responder_random));
+        unwrap!(salt.extend_from_slice(responder_pub_key));
+        unwrap!(salt.extend_from_slice(sigma1_hash));
 
         crypto::hkdf_sha256(salt.as_slice(), shared_secret, &S2K_INFO, key)
             .map_err(|_x| ErrorCode::NoSpace)?;
@@ -546,7 +1414,10 @@ impl Case {
         Ok(write_buf.as_slice().len())
     }
 
-    fn get_sigma2_sign(
+    /// Compute our signature of the to-be-signed data of a Sigma2 or a Sigma3 message.
+    ///
+    /// The TBS data is the same for both messages, if looked at from the perspective of the signing peer.
+    fn get_tbs_sign(
         fabric: &Fabric,
         our_pub_key: &[u8],
         peer_pub_key: &[u8],
@@ -568,6 +1439,19 @@ impl Case {
     }
 }
 
+const S1R_INFO: [u8; 13] = [
+    0x53, 0x69, 0x67, 0x6d, 0x61, 0x31, 0x5f, 0x52, 0x65, 0x73, 0x75, 0x6d, 0x65,
+];
+const S2R_INFO: [u8; 13] = [
+    0x53, 0x69, 0x67, 0x6d, 0x61, 0x32, 0x5f, 0x52, 0x65, 0x73, 0x75, 0x6d, 0x65,
+];
+const S1R_NONCE: [u8; crypto::AEAD_NONCE_LEN_BYTES] = [
+    0x4e, 0x43, 0x41, 0x53, 0x45, 0x5f, 0x53, 0x69, 0x67, 0x6d, 0x61, 0x53, 0x31,
+];
+const S2R_NONCE: [u8; crypto::AEAD_NONCE_LEN_BYTES] = [
+    0x4e, 0x43, 0x41, 0x53, 0x45, 0x5f, 0x53, 0x69, 0x67, 0x6d, 0x61, 0x53, 0x32,
+];
+
 impl Default for Case {
     fn default() -> Self {
         Self::new()
@@ -595,3 +1479,34 @@ struct Sigma3Decrypt<'a> {
     initiator_icac: Option<OctetStr<'a>>,
     signature: OctetStr<'a>,
 }
+
+#[derive(FromTLV, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(start = 1, lifetime = "'a")]
+struct Sigma2Resp<'a> {
+    responder_random: OctetStr<'a>,
+    responder_sessid: u16,
+    responder_pub_key: OctetStr<'a>,
+    encrypted2: OctetStr<'a>,
+    session_parameters: Option<SessionParameters>,
+}
+
+#[derive(FromTLV, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(start = 1, lifetime = "'a")]
+struct Sigma2Decrypt<'a> {
+    responder_noc: OctetStr<'a>,
+    responder_icac: Option<OctetStr<'a>>,
+    signature: OctetStr<'a>,
+    resumption_id: OctetStr<'a>,
+}
+
+#[derive(FromTLV, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(start = 1, lifetime = "'a")]
+struct Sigma2ResumeResp<'a> {
+    resumption_id: OctetStr<'a>,
+    sigma2_resume_mic: OctetStr<'a>,
+    responder_sessid: u16,
+    session_parameters: Option<SessionParameters>,
+}
@@ -62,6 +62,11 @@ impl CryptoSpake2 {
         Err(ErrorCode::Invalid.into())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, _pA: &mut [u8], _rand: Rand) -> Result<(), Error> {
+        Err(ErrorCode::Invalid.into())
+    }
+
     #[allow(non_snake_case)]
     pub fn get_TT_as_verifier(
         &mut self,
@@ -72,4 +77,15 @@ impl CryptoSpake2 {
     ) -> Result<(), Error> {
         Err(ErrorCode::Invalid.into())
     }
+
+    #[allow(non_snake_case)]
+    pub fn get_TT_as_prover(
+        &mut self,
+        _context: &[u8],
+        _pA: &[u8],
+        _pB: &[u8],
+        _out: &mut [u8],
+    ) -> Result<(), Error> {
+        Err(ErrorCode::Invalid.into())
+    }
 }
@@ -15,7 +15,7 @@
  *    limitations under the License.
  */
 
-use crate::error::Error;
+use crate::error::{Error, ErrorCode};
 use crate::utils::rand::Rand;
 
 const MATTER_M_BIN: [u8; 65] = [
@@ -97,6 +97,12 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, _pA: &mut [u8], _rand: Rand) -> Result<(), Error> {
+        // The prover side is not implemented for this backend
+        Err(ErrorCode::Invalid.into())
+    }
+
     #[allow(non_snake_case)]
     pub fn get_TT_as_verifier(
         &mut self,
@@ -107,6 +113,18 @@ impl CryptoSpake2 {
     ) -> Result<(), Error> {
         Ok(())
     }
+
+    #[allow(non_snake_case)]
+    pub fn get_TT_as_prover(
+        &mut self,
+        _context: &[u8],
+        _pA: &[u8],
+        _pB: &[u8],
+        _out: &mut [u8],
+    ) -> Result<(), Error> {
+        // The prover side is not implemented for this backend
+        Err(ErrorCode::Invalid.into())
+    }
 }
 
 #[cfg(test)]
@@ -161,6 +161,31 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, pA: &mut [u8], _rand: Rand) -> Result<(), Error> {
+        // From the SPAKE2+ spec (https://datatracker.ietf.org/doc/draft-bar-cfrg-spake2plus/)
+        //   for x
+        //   - select random x between 0 to p
+        //   - X = x*P + w0*M
+        //   - pA = X
+
+        // A private key on this curve is a random number between 0 to p
+        let mut ctr_drbg: CtrDrbg = CtrDrbg::new(Arc::new(OsEntropy::new()), None)?;
+        self.xy = Pk::generate_ec(&mut ctr_drbg, EcGroupId::SecP256R1)?.ec_private()?;
+
+        let P = self.group.generator()?;
+        let pA_internal = EcPoint::muladd(&mut self.group, &P, &self.xy, &self.M, &self.w0)?;
+
+        let pA_internal = pA_internal.to_binary(&self.group, false)?;
+        let pA_internal = pA_internal.as_slice();
+        if pA_internal.len() != pA.len() {
+            error!("pA length mismatch");
+            Err(ErrorCode::Invalid)?;
+        }
+        pA.copy_from_slice(pA_internal);
+        Ok(())
+    }
+
     #[allow(non_snake_case)]
     pub fn get_TT_as_verifier(
         &mut self,
@@ -214,6 +239,59 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_TT_as_prover(
+        &mut self,
+        context: &[u8],
+        pA: &[u8],
+        pB: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut TT = Md::new(mbedtls::hash::Type::Sha256)?;
+        // context
+        Self::add_to_tt(&mut TT, context)?;
+        // 2 empty identifiers
+        Self::add_to_tt(&mut TT, &[])?;
+        Self::add_to_tt(&mut TT, &[])?;
+        // M
+        Self::add_to_tt(&mut TT, &MATTER_M_BIN)?;
+        // N
+        Self::add_to_tt(&mut TT, &MATTER_N_BIN)?;
+        // X = pA
+        Self::add_to_tt(&mut TT, pA)?;
+        // Y = pB
+        Self::add_to_tt(&mut TT, pB)?;
+
+        let Y = EcPoint::from_binary(&self.group, pB)?;
+        let (Z, V) = Self::get_ZV_as_prover(
+            &self.w0,
+            &self.w1,
+            &self.N,
+            &Y,
+            &self.xy,
+            &self.order,
+            &mut self.group,
+        )?;
+
+        // Z
+        let tmp = Z.to_binary(&self.group, false)?;
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        // V
+        let tmp = V.to_binary(&self.group, false)?;
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        // w0
+        let tmp = self.w0.to_binary()?;
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        TT.finish(out)?;
+        Ok(())
+    }
+
     fn add_to_tt(tt: &mut Md, buf: &[u8]) -> Result<(), Error> {
         let mut len_buf: [u8; 8] = [0; 8];
         LittleEndian::write_u64(&mut len_buf, buf.len() as u64);
@@ -226,7 +304,6 @@ impl CryptoSpake2 {
 
     #[inline(always)]
     #[allow(non_snake_case)]
-    #[allow(dead_code)]
     fn get_ZV_as_prover(
         w0: &Mpi,
         w1: &Mpi,
@@ -166,6 +166,37 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, pA: &mut [u8], _rand: Rand) -> Result<(), Error> {
+        // From the SPAKE2+ spec (https://datatracker.ietf.org/doc/draft-bar-cfrg-spake2plus/)
+        //   for x
+        //   - select random x between 0 to p
+        //   - X = x*P + w0*M
+        //   - pA = X
+        self.order.rand_range(&mut self.xy)?;
+        let P = self.group.generator();
+        let pA_internal = Self::do_add_mul(
+            P,
+            &self.xy,
+            &self.M,
+            &self.w0,
+            &self.group,
+            &mut self.bn_ctx,
+        )?;
+        let pA_internal = pA_internal.to_bytes(
+            &self.group,
+            PointConversionForm::UNCOMPRESSED,
+            &mut self.bn_ctx,
+        )?;
+        let pA_internal = pA_internal.as_slice();
+        if pA_internal.len() != pA.len() {
+            error!("pA length mismatch");
+            Err(ErrorCode::Invalid)?;
+        }
+        pA.copy_from_slice(pA_internal);
+        Ok(())
+    }
+
     #[allow(non_snake_case)]
     pub fn get_TT_as_verifier(
         &mut self,
@@ -229,6 +260,69 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_TT_as_prover(
+        &mut self,
+        context: &[u8],
+        pA: &[u8],
+        pB: &[u8],
+        TT_hash: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut TT = Hasher::new(MessageDigest::sha256())?;
+        // context
+        Self::add_to_tt(&mut TT, context)?;
+        // 2 empty identifiers
+        Self::add_to_tt(&mut TT, &[])?;
+        Self::add_to_tt(&mut TT, &[])?;
+        // M
+        Self::add_to_tt(&mut TT, &MATTER_M_BIN)?;
+        // N
+        Self::add_to_tt(&mut TT, &MATTER_N_BIN)?;
+        // X = pA
+        Self::add_to_tt(&mut TT, pA)?;
+        // Y = pB
+        Self::add_to_tt(&mut TT, pB)?;
+
+        let Y = EcPoint::from_bytes(&self.group, pB, &mut self.bn_ctx)?;
+        let (Z, V) = Self::get_ZV_as_prover(
+            &self.w0,
+            &self.w1,
+            &mut self.N,
+            &Y,
+            &self.xy,
+            &self.order,
+            &self.group,
+            &mut self.bn_ctx,
+        )?;
+
+        // Z
+        let tmp = Z.to_bytes(
+            &self.group,
+            PointConversionForm::UNCOMPRESSED,
+            &mut self.bn_ctx,
+        )?;
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        // V
+        let tmp = V.to_bytes(
+            &self.group,
+            PointConversionForm::UNCOMPRESSED,
+            &mut self.bn_ctx,
+        )?;
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        // w0
+        let tmp = self.w0.to_vec();
+        let tmp = tmp.as_slice();
+        Self::add_to_tt(&mut TT, tmp)?;
+
+        let h = TT.finish()?;
+        TT_hash.copy_from_slice(h.as_ref());
+        Ok(())
+    }
+
     fn add_to_tt(tt: &mut Hasher, buf: &[u8]) -> Result<(), Error> {
         let mut len_buf: [u8; 8] = [0; 8];
         LittleEndian::write_u64(&mut len_buf, buf.len() as u64);
@@ -260,7 +354,6 @@ impl CryptoSpake2 {
 
     #[inline(always)]
     #[allow(non_snake_case)]
-    #[allow(dead_code)]
     #[allow(clippy::too_many_arguments)]
     fn get_ZV_as_prover(
         w0: &BigNum,
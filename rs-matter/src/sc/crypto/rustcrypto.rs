@@ -25,7 +25,7 @@ use elliptic_curve::PrimeField;
 use sha2::Digest;
 
 use crate::crypto::RandRngCore;
-use crate::error::Error;
+use crate::error::{Error, ErrorCode};
 use crate::utils::rand::Rand;
 
 const MATTER_M_BIN: [u8; 65] = [
@@ -188,6 +188,24 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, pA: &mut [u8], rand: Rand) -> Result<(), Error> {
+        // From the SPAKE2+ spec (https://datatracker.ietf.org/doc/draft-bar-cfrg-spake2plus/)
+        //   for x
+        //   - select random x between 0 to p
+        //   - X = x*P + w0*M
+        //   - pA = X
+        let mut rand = RandRngCore(rand);
+        self.xy = p256::Scalar::random(&mut rand);
+
+        let P = p256::AffinePoint::GENERATOR;
+        let M = p256::AffinePoint::from_encoded_point(&self.M).unwrap();
+        let pA_internal = Self::do_add_mul(P, self.xy, M, self.w0)?;
+        pA.copy_from_slice(pA_internal.as_bytes());
+
+        Ok(())
+    }
+
     #[allow(non_snake_case)]
     pub fn get_TT_as_verifier(
         &mut self,
@@ -230,6 +248,48 @@ impl CryptoSpake2 {
         Ok(())
     }
 
+    #[allow(non_snake_case)]
+    pub fn get_TT_as_prover(
+        &mut self,
+        context: &[u8],
+        pA: &[u8],
+        pB: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut TT = sha2::Sha256::new();
+        // Context
+        Self::add_to_tt(&mut TT, context)?;
+        // 2 empty identifiers
+        Self::add_to_tt(&mut TT, &[])?;
+        Self::add_to_tt(&mut TT, &[])?;
+        // M
+        Self::add_to_tt(&mut TT, &MATTER_M_BIN)?;
+        // N
+        Self::add_to_tt(&mut TT, &MATTER_N_BIN)?;
+        // X = pA
+        Self::add_to_tt(&mut TT, pA)?;
+        // Y = pB
+        Self::add_to_tt(&mut TT, pB)?;
+
+        let Y = p256::EncodedPoint::from_bytes(pB)?;
+        let Y = Option::<p256::AffinePoint>::from(p256::AffinePoint::from_encoded_point(&Y))
+            .ok_or(ErrorCode::InvalidData)?;
+        let N = p256::AffinePoint::from_encoded_point(&self.N).unwrap();
+        let (Z, V) = Self::get_ZV_as_prover(self.w0, self.w1, N, Y, self.xy)?;
+
+        // Z
+        Self::add_to_tt(&mut TT, Z.as_bytes())?;
+        // V
+        Self::add_to_tt(&mut TT, V.as_bytes())?;
+        // w0
+        Self::add_to_tt(&mut TT, self.w0.to_bytes().to_vec().as_ref())?;
+
+        let h = TT.finalize();
+        out.copy_from_slice(h.as_slice());
+
+        Ok(())
+    }
+
     fn add_to_tt(tt: &mut sha2::Sha256, buf: &[u8]) -> Result<(), Error> {
         tt.update((buf.len() as u64).to_le_bytes());
         if !buf.is_empty() {
@@ -250,7 +310,6 @@ impl CryptoSpake2 {
 
     #[inline(always)]
     #[allow(non_snake_case)]
-    #[allow(dead_code)]
     fn get_ZV_as_prover(
         w0: p256::Scalar,
         w1: p256::Scalar,
@@ -18,7 +18,9 @@
 use core::time::Duration;
 
 use crate::error::{Error, ErrorCode};
-use crate::sc::{check_opcode, complete_with_status, OpCode, SessionParameters};
+use crate::sc::{
+    check_opcode, check_session_established, complete_with_status, OpCode, SessionParameters,
+};
 use crate::tlv::{get_root_node_struct, FromTLV, OctetStr, TLVElement, TagType, ToTLV};
 use crate::transport::exchange::{Exchange, ExchangeId};
 use crate::transport::session::{ReservedSession, SessionMode};
@@ -260,6 +262,211 @@ impl Pake {
         Ok(())
     }
 
+    /// Establish a PASE session with a commissionable peer, using the provided passcode.
+    ///
+    /// The exchange is expected to be an unsecured one, i.e. initiated with `Exchange::initiate_unsecured`.
+    ///
+    /// Return the ID of the newly-established PASE session.
+    #[allow(non_snake_case)]
+    pub async fn initiate(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        spake2p: &mut Spake2P,
+        password: u32,
+    ) -> Result<u32, Error> {
+        let session = ReservedSession::reserve(exchange.matter()).await?;
+
+        let mut our_random = [0; 32];
+        (exchange.matter().rand())(&mut our_random);
+
+        let local_sessid = self
+            .send_pbkdfparamrequest(exchange, spake2p, &our_random)
+            .await?;
+
+        exchange.recv_fetch().await?;
+
+        let peer_sessid = self
+            .handle_pbkdfparamresponse(exchange, spake2p, &our_random, password)
+            .await?;
+
+        let mut pA = [0; 65];
+        self.send_pasepake1(exchange, spake2p, &mut pA).await?;
+
+        exchange.recv_fetch().await?;
+
+        let session_keys = self.handle_pasepake2(exchange, spake2p, &pA).await?;
+
+        exchange.recv_fetch().await?;
+
+        self.handle_pake3_status(exchange, session, local_sessid, peer_sessid, &session_keys)
+            .await
+    }
+
+    async fn handle_pake3_status(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        mut session: ReservedSession<'_>,
+        local_sessid: u16,
+        peer_sessid: u16,
+        session_keys: &[u8; 48],
+    ) -> Result<u32, Error> {
+        check_session_established(exchange)?;
+
+        let peer_addr = exchange.with_session(|sess| Ok(sess.get_peer_addr()))?;
+
+        session.update(
+            0,
+            0,
+            peer_sessid,
+            local_sessid,
+            peer_addr,
+            SessionMode::Pase { fab_idx: 0 },
+            // As the initiator, we decrypt with the R2I key and encrypt with the I2R key
+            Some(&session_keys[16..32]),
+            Some(&session_keys[0..16]),
+            Some(&session_keys[32..48]),
+        )?;
+
+        let session_id = session.id();
+
+        // Complete the reserved session and thus make the `Session` instance
+        // immediately available for use by the system.
+        session.complete();
+
+        exchange.acknowledge().await?;
+
+        debug!("PASE session established");
+
+        Ok(session_id)
+    }
+
+    #[allow(non_snake_case)]
+    async fn handle_pasepake2(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        spake2p: &mut Spake2P,
+        pA: &[u8],
+    ) -> Result<[u8; 48], Error> {
+        check_opcode(exchange, OpCode::PASEPake2)?;
+
+        let resp = Pake1Resp::from_tlv(&TLVElement::new(exchange.rx()?.payload()))?;
+
+        let mut cA: [u8; 32] = [0; 32];
+        let mut session_keys: [u8; 48] = [0; 48];
+
+        let ke = match spake2p.handle_pB(pA, resp.pb.0, resp.cb.0, &mut cA) {
+            Ok(ke) => ke,
+            Err(e) => {
+                complete_with_status(exchange, SCStatusCodes::InvalidParameter, &[]).await?;
+                return Err(e);
+            }
+        };
+        crypto::hkdf_sha256(&[], ke, SPAKE2_SESSION_KEYS_INFO, &mut session_keys)
+            .map_err(|_x| ErrorCode::NoSpace)?;
+
+        exchange
+            .send_with(|_, wb| {
+                let req = Pake1Or3Req {
+                    value: OctetStr::new(&cA),
+                };
+                req.to_tlv(&TagType::Anonymous, wb)?;
+
+                Ok(Some(OpCode::PASEPake3.into()))
+            })
+            .await?;
+
+        Ok(session_keys)
+    }
+
+    #[allow(non_snake_case)]
+    async fn send_pasepake1(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        spake2p: &mut Spake2P,
+        pA: &mut [u8; 65],
+    ) -> Result<(), Error> {
+        spake2p.get_pA(pA, exchange.matter().rand())?;
+
+        exchange
+            .send_with(|_, wb| {
+                let req = Pake1Or3Req {
+                    value: OctetStr::new(pA),
+                };
+                req.to_tlv(&TagType::Anonymous, wb)?;
+
+                Ok(Some(OpCode::PASEPake1.into()))
+            })
+            .await
+    }
+
+    async fn handle_pbkdfparamresponse(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        spake2p: &mut Spake2P,
+        our_random: &[u8],
+        password: u32,
+    ) -> Result<u16, Error> {
+        check_opcode(exchange, OpCode::PBKDFParamResponse)?;
+
+        let payload = exchange.rx()?.payload();
+
+        let resp = PBKDFParamResp::from_tlv(&TLVElement::new(payload))?;
+        if resp.init_random.0 != our_random {
+            error!("Initiator random mismatch");
+            Err(ErrorCode::Invalid)?;
+        }
+
+        let Some(params) = resp.params else {
+            error!("PBKDF parameters missing from the response");
+            Err(ErrorCode::Invalid)?
+        };
+
+        spake2p.update_context(payload)?;
+        spake2p.start_prover(password, params.count, params.salt.0)?;
+
+        Ok(resp.local_sessid)
+    }
+
+    async fn send_pbkdfparamrequest(
+        &mut self,
+        exchange: &mut Exchange<'_>,
+        spake2p: &mut Spake2P,
+        our_random: &[u8],
+    ) -> Result<u16, Error> {
+        let local_sessid = exchange
+            .matter()
+            .transport_mgr
+            .session_mgr
+            .borrow_mut()
+            .get_next_sess_id();
+
+        let req = PBKDFParamReq {
+            initiator_random: OctetStr::new(our_random),
+            initiator_ssid: local_sessid,
+            passcode_id: 0,
+            has_params: false,
+            session_parameters: None,
+        };
+
+        spake2p.set_context()?;
+
+        let mut context_set = false;
+        exchange
+            .send_with(|_, wb| {
+                req.to_tlv(&TagType::Anonymous, &mut *wb)?;
+
+                if !context_set {
+                    spake2p.update_context(wb.as_slice())?;
+                    context_set = true;
+                }
+
+                Ok(Some(OpCode::PBKDFParamRequest.into()))
+            })
+            .await?;
+
+        Ok(local_sessid)
+    }
+
     #[allow(non_snake_case)]
     async fn handle_pasepake3(
         &mut self,
@@ -504,22 +711,29 @@ impl Default for Pake {
 #[derive(ToTLV, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[tlvargs(start = 1)]
+struct Pake1Or3Req<'a> {
+    value: OctetStr<'a>,
+}
+
+#[derive(FromTLV, ToTLV, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[tlvargs(lifetime = "'a", start = 1)]
 struct Pake1Resp<'a> {
     pb: OctetStr<'a>,
     cb: OctetStr<'a>,
 }
 
-#[derive(ToTLV, Debug)]
+#[derive(FromTLV, ToTLV, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[tlvargs(start = 1)]
+#[tlvargs(lifetime = "'a", start = 1)]
 struct PBKDFParamRespParams<'a> {
     count: u32,
     salt: OctetStr<'a>,
 }
 
-#[derive(ToTLV, Debug)]
+#[derive(FromTLV, ToTLV, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[tlvargs(start = 1)]
+#[tlvargs(lifetime = "'a", start = 1)]
 struct PBKDFParamResp<'a> {
     init_random: OctetStr<'a>,
     our_random: OctetStr<'a>,
@@ -534,7 +748,7 @@ fn extract_pasepake_1_or_3_params(buf: &[u8]) -> Result<&[u8], Error> {
     Ok(pA)
 }
 
-#[derive(FromTLV, Debug)]
+#[derive(FromTLV, ToTLV, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[tlvargs(lifetime = "'a", start = 1)]
 struct PBKDFParamReq<'a> {
@@ -35,6 +35,11 @@ use super::{crypto::CryptoSpake2, SCStatusCodes};
 pub const SPAKE2_ITERATION_COUNT: u32 = 2000;
 pub const MAX_SALT_SIZE_BYTES: usize = 32;
 
+/// The range of PBKDF2 iteration counts allowed by the Matter spec
+const SPAKE2_ITERATION_COUNTS: core::ops::RangeInclusive<u32> = 1000..=100000;
+/// The minimum PBKDF2 salt length allowed by the Matter spec
+const MIN_SALT_SIZE_BYTES: usize = 16;
+
 const SPAKE2P_KEY_CONFIRM_INFO: &[u8] = b"ConfirmationKeys";
 const SPAKE2P_CONTEXT_PREFIX: &[u8] = b"CHIP PAKE V1 Commissioning";
 const CRYPTO_GROUP_SIZE_BYTES: usize = 32;
@@ -149,6 +154,85 @@ impl Spake2P {
         Ok(())
     }
 
+    pub(crate) fn start_prover(
+        &mut self,
+        password: u32,
+        count: u32,
+        salt: &[u8],
+    ) -> Result<(), Error> {
+        // The PBKDF parameters come from the peer, so make sure they are
+        // in the ranges of the spec before running PBKDF2 with them
+        if !SPAKE2_ITERATION_COUNTS.contains(&count) {
+            error!("Invalid PBKDF2 iteration count {}", count);
+            Err(ErrorCode::Invalid)?;
+        }
+
+        if !(MIN_SALT_SIZE_BYTES..=MAX_SALT_SIZE_BYTES).contains(&salt.len()) {
+            error!("Invalid PBKDF2 salt length {}", salt.len());
+            Err(ErrorCode::Invalid)?;
+        }
+
+        self.crypto_spake2 = Some(CryptoSpake2::new()?);
+
+        // Derive w0 and w1 from the password
+        let mut w0w1s: [u8; 2 * CRYPTO_W_SIZE_BYTES] = [0; (2 * CRYPTO_W_SIZE_BYTES)];
+        Spake2P::get_w0w1s(password, count, salt, &mut w0w1s);
+
+        let w0s_len = w0w1s.len() / 2;
+        if let Some(crypto_spake2) = &mut self.crypto_spake2 {
+            crypto_spake2.set_w0_from_w0s(&w0w1s[0..w0s_len])?;
+            crypto_spake2.set_w1_from_w1s(&w0w1s[w0s_len..])?;
+        }
+
+        self.mode = Spake2Mode::Prover;
+        Ok(())
+    }
+
+    #[allow(non_snake_case)]
+    pub fn get_pA(&mut self, pA: &mut [u8], rand: Rand) -> Result<(), Error> {
+        if self.mode != Spake2Mode::Prover {
+            Err(ErrorCode::InvalidState)?;
+        }
+
+        let crypto_spake2 = self.crypto_spake2.as_mut().ok_or(ErrorCode::InvalidState)?;
+        crypto_spake2.get_pA(pA, rand)
+    }
+
+    /// Process the `pB` and `cB` values of the verifier.
+    ///
+    /// On success, `cA` is filled with the confirmation value to be sent to the verifier,
+    /// and the `Ke` key is returned.
+    #[allow(non_snake_case)]
+    pub fn handle_pB(
+        &mut self,
+        pA: &[u8],
+        pB: &[u8],
+        cB: &[u8],
+        cA: &mut [u8],
+    ) -> Result<&[u8], Error> {
+        if self.mode != Spake2Mode::Prover {
+            Err(ErrorCode::InvalidState)?;
+        }
+
+        let mut crypto_spake2 = self.crypto_spake2.take().ok_or(ErrorCode::InvalidState)?;
+        let context = self.context.take().ok_or(ErrorCode::InvalidState)?;
+
+        let mut hash = [0u8; crypto::SHA256_HASH_LEN_BYTES];
+        context.finish(&mut hash)?;
+        let mut TT = [0u8; crypto::SHA256_HASH_LEN_BYTES];
+        crypto_spake2.get_TT_as_prover(&hash, pA, pB, &mut TT)?;
+
+        let mut our_cB = [0u8; 32];
+        Spake2P::get_Ke_and_cAcB(&TT, pA, pB, &mut self.Ke, cA, &mut our_cB)?;
+
+        if cB.ct_eq(&our_cB).unwrap_u8() != 1 {
+            error!("cB mismatch");
+            Err(ErrorCode::InvalidData)?;
+        }
+
+        Ok(&self.Ke)
+    }
+
     #[allow(non_snake_case)]
     pub fn handle_pA(
         &mut self,
@@ -194,7 +278,6 @@ impl Spake2P {
 
     #[inline(always)]
     #[allow(non_snake_case)]
-    #[allow(dead_code)]
     fn get_Ke_and_cAcB(
         TT: &[u8],
         pA: &[u8],
@@ -783,8 +866,14 @@ pub mod test_vectors {
 
 #[cfg(test)]
 mod tests {
-    use super::{test_vectors::*, Spake2P, CRYPTO_W_SIZE_BYTES};
+    use super::{
+        test_vectors::*, Spake2P, VerifierData, CRYPTO_W_SIZE_BYTES, MAX_SALT_SIZE_BYTES,
+        SPAKE2_ITERATION_COUNT, VERIFIER_SIZE_BYTES,
+    };
     use crate::crypto;
+    use crate::error::ErrorCode;
+    use crate::sc::SCStatusCodes;
+    use crate::utils::rand::sys_rand;
 
     #[test]
     fn test_pbkdf2() {
@@ -827,4 +916,65 @@ mod tests {
             assert_eq!(cB, t.cB);
         }
     }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_prover_verifier() {
+        let salt = [0x5a; MAX_SALT_SIZE_BYTES];
+        let verifier_data = VerifierData {
+            password: Some(20202021),
+            verifier: [0; VERIFIER_SIZE_BYTES],
+            salt,
+            count: SPAKE2_ITERATION_COUNT,
+        };
+
+        let mut prover = Spake2P::new();
+        let mut verifier = Spake2P::new();
+
+        for spake2p in [&mut prover, &mut verifier] {
+            unwrap!(spake2p.set_context());
+            unwrap!(spake2p.update_context(b"context"));
+        }
+
+        let mut pA = [0; 65];
+        let mut pB = [0; 65];
+        let mut cA = [0; 32];
+        let mut cB = [0; 32];
+
+        unwrap!(prover.start_prover(20202021, SPAKE2_ITERATION_COUNT, &salt));
+        unwrap!(prover.get_pA(&mut pA, sys_rand));
+
+        unwrap!(verifier.start_verifier(&verifier_data));
+        unwrap!(verifier.handle_pA(&pA, &mut pB, &mut cB, sys_rand));
+
+        let mut Ke = [0; 16];
+        Ke.copy_from_slice(unwrap!(prover.handle_pB(&pA, &pB, &cB, &mut cA)));
+
+        let (status, verifier_Ke) = verifier.handle_cA(&cA);
+        assert_eq!(status, SCStatusCodes::SessionEstablishmentSuccess);
+        assert_eq!(verifier_Ke, Some(&Ke[..]));
+    }
+
+    #[test]
+    fn test_prover_invalid_pbkdf_params() {
+        let salt = [0x5a; MAX_SALT_SIZE_BYTES + 1];
+
+        let mut prover = Spake2P::new();
+
+        for (count, salt) in [
+            (999, &salt[..MAX_SALT_SIZE_BYTES]),
+            (100001, &salt[..MAX_SALT_SIZE_BYTES]),
+            (SPAKE2_ITERATION_COUNT, &salt[..15]),
+            (SPAKE2_ITERATION_COUNT, &salt[..]),
+        ] {
+            assert_eq!(
+                prover
+                    .start_prover(20202021, count, salt)
+                    .map_err(|e| e.code()),
+                Err(ErrorCode::Invalid)
+            );
+        }
+
+        unwrap!(prover.start_prover(20202021, 1000, &salt[..16]));
+    }
 }
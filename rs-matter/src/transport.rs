@@ -15,29 +15,36 @@
  *    limitations under the License.
  */
 
+use core::cell::Cell;
 use core::fmt::{self, Display};
+use core::mem::MaybeUninit;
+use core::num::NonZeroU8;
 use core::ops::{Deref, DerefMut};
 use core::pin::pin;
 
-use embassy_futures::select::{select, select3};
+use embassy_futures::select::{select, select3, Either};
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
-use embassy_time::Timer;
+use embassy_time::{Duration, Timer};
 
 use crate::dm::clusters::basic_info::BasicInfoConfig;
 use crate::error::{Error, ErrorCode};
+use crate::fabric::FabricMgr;
 use crate::fmt::Bytes;
+use crate::group_keys;
+use crate::sc::case::{Case, CaseSession};
 use crate::sc::{sc_write, OpCode, SCStatusCodes, StatusReport, PROTO_ID_SECURE_CHANNEL};
 use crate::tlv::TLVElement;
 use crate::utils::cell::RefCell;
 use crate::utils::epoch::Epoch;
-use crate::utils::init::{init, Init};
+use crate::utils::init::{init, Init, InitMaybeUninit};
 use crate::utils::rand::Rand;
 use crate::utils::select::Coalesce;
 use crate::utils::storage::{pooled::BufferAccess, ParseBuf, WriteBuf};
-use crate::utils::sync::{IfMutex, IfMutexGuard, Notification};
+use crate::utils::sync::{IfMutex, IfMutexGuard, Notification, Signal};
 use crate::{Matter, MATTER_PORT};
 
 use exchange::{Exchange, ExchangeId, ExchangeState, MessageMeta, ResponderState, Role};
+use network::mdns::MdnsResolver;
 use network::{Address, Ipv6Addr, NetworkReceive, NetworkSend, SocketAddr, SocketAddrV6};
 use packet::PacketHdr;
 use proto_hdr::ProtoHdr;
@@ -61,6 +68,16 @@ pub const MATTER_SOCKET_BIND_ADDR: SocketAddr =
 
 const ACCEPT_TIMEOUT_MS: u64 = 1000;
 
+/// How many times to try establishing a CASE session with a peer whose address keeps changing
+const MAX_CASE_ATTEMPTS: usize = 3;
+
+/// How many peer operational addresses to remember, so that CASE sessions with them
+/// can be re-established without resolving them first
+const MAX_PEER_ADDRS: usize = 8;
+
+/// How long to wait for the resolver run with `Matter::run_resolver` to resolve a peer
+const RESOLVE_TIMEOUT_MS: u64 = 10000;
+
 #[cfg(all(feature = "large-buffers", feature = "alloc"))]
 pub(crate) const MAX_RX_BUF_SIZE: usize = network::MAX_RX_LARGE_PACKET_SIZE;
 #[cfg(all(feature = "large-buffers", feature = "alloc"))]
@@ -81,6 +98,8 @@ pub struct TransportMgr {
     pub(crate) dropped: Notification<NoopRawMutex>,
     pub(crate) session_removed: Notification<NoopRawMutex>,
     pub session_mgr: RefCell<SessionMgr>, // For testing
+    peer_addrs: RefCell<PeerAddrs>,
+    resolver: PeerResolver,
     #[allow(dead_code)]
     rand: Rand,
     device_sai: Option<u16>,
@@ -96,6 +115,8 @@ impl TransportMgr {
             dropped: Notification::new(),
             session_removed: Notification::new(),
             session_mgr: RefCell::new(SessionMgr::new(epoch, rand)),
+            peer_addrs: RefCell::new(PeerAddrs::new()),
+            resolver: PeerResolver::new(),
             rand,
             device_sai: dev_det.sai,
             device_sii: dev_det.sii,
@@ -113,6 +134,8 @@ impl TransportMgr {
             dropped: Notification::new(),
             session_removed: Notification::new(),
             session_mgr <- RefCell::init(SessionMgr::init(epoch, rand)),
+            peer_addrs: RefCell::new(PeerAddrs::new()),
+            resolver: PeerResolver::new(),
             rand,
             device_sai: dev_det.sai,
             device_sii: dev_det.sii,
@@ -146,6 +169,7 @@ impl TransportMgr {
     /// NOTE: User should be careful _not_ to call this method while the transport layer and/or the built-in mDNS is running.
     pub fn reset(&self) -> Result<(), Error> {
         self.session_mgr.borrow_mut().reset();
+        self.peer_addrs.borrow_mut().clear();
         self.rx
             .try_lock()
             .map_err(|_| ErrorCode::InvalidState)?
@@ -167,18 +191,211 @@ impl TransportMgr {
         peer_node_id: u64,
         secure: bool,
     ) -> Result<Exchange<'a>, Error> {
-        // TODO: Future: once we have mDNS lookups in place
-        // create a new session if no suitable one is found
+        self.initiate_with(matter, &self.resolver, fabric_idx, peer_node_id, secure)
+            .await
+    }
+
+    pub(crate) async fn initiate_with<'a, R>(
+        &'a self,
+        matter: &'a Matter<'a>,
+        mut resolver: R,
+        fabric_idx: u8,
+        peer_node_id: u64,
+        secure: bool,
+    ) -> Result<Exchange<'a>, Error>
+    where
+        R: MdnsResolver,
+    {
+        if let Some(session_id) = self.session_id_for_node(fabric_idx, peer_node_id, secure) {
+            return self.initiate_for_session(matter, session_id);
+        }
+
+        if !secure {
+            // Unsecured sessions are only ever created as a means to establish a secure one
+            Err(ErrorCode::NoSession)?;
+        }
+
+        let fabric_idx = NonZeroU8::new(fabric_idx).ok_or(ErrorCode::NoFabricId)?;
+
+        let compressed_fabric_id = matter
+            .fabric_mgr
+            .borrow()
+            .get(fabric_idx)
+            .ok_or(ErrorCode::NoFabricId)?
+            .compressed_fabric_id();
+
+        // Try the last known address of the peer first, if any
+        let mut peer_addr = self.peer_addrs.borrow().get(fabric_idx, peer_node_id);
+        let mut prev_peer_addr = None;
 
+        for _ in 0..MAX_CASE_ATTEMPTS {
+            let addr = if let Some(addr) = peer_addr.take() {
+                addr
+            } else {
+                Address::Udp(resolver.resolve(compressed_fabric_id, peer_node_id).await?)
+            };
+
+            if prev_peer_addr == Some(addr) {
+                // The peer did not move, so there is no point in trying again
+                break;
+            }
+
+            debug!(
+                "Establishing a CASE session with peer node {:x} at {}",
+                peer_node_id, addr
+            );
+
+            match self
+                .establish_case(matter, addr, fabric_idx, peer_node_id)
+                .await
+            {
+                Ok(session_id) => {
+                    self.peer_addrs
+                        .borrow_mut()
+                        .set(fabric_idx, peer_node_id, addr);
+
+                    return self.initiate_for_session(matter, session_id);
+                }
+                Err(e) => {
+                    warn!(
+                        "Establishing a CASE session with peer node {:x} at {} failed: {}",
+                        peer_node_id, addr, e
+                    );
+                }
+            }
+
+            self.peer_addrs
+                .borrow_mut()
+                .remove(fabric_idx, peer_node_id);
+
+            prev_peer_addr = Some(addr);
+        }
+
+        Err(ErrorCode::NoSession.into())
+    }
+
+    /// Serve the peer resolution requests of `Exchange::initiate` with the provided mDNS resolver.
+    ///
+    /// While this future is not running, `Exchange::initiate` can only reach peers
+    /// with which a session already exists, or whose operational address is already known.
+    pub(crate) async fn run_resolver<R>(&self, mut resolver: R) -> Result<(), Error>
+    where
+        R: MdnsResolver,
+    {
+        if self.resolver.running.replace(true) {
+            // Only one resolver can serve the requests
+            Err(ErrorCode::InvalidState)?;
+        }
+
+        let _guard = scopeguard::guard((), |_| self.resolver.running.set(false));
+
+        loop {
+            let (id, compressed_fabric_id, node_id) =
+                self.resolver.request.wait(|request| request.take()).await;
+
+            let result = resolver
+                .resolve(compressed_fabric_id, node_id)
+                .await
+                .map_err(|e| e.code());
+
+            self.resolver.response.modify(|response| {
+                *response = Some((id, result));
+                (true, ())
+            });
+        }
+    }
+
+    async fn establish_case(
+        &self,
+        matter: &Matter<'_>,
+        peer_addr: Address,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+    ) -> Result<u32, Error> {
+        let mut exchange = Exchange::initiate_unsecured(matter, peer_addr).await?;
+
+        let mut case_session = MaybeUninit::uninit(); // TODO LARGE BUFFER
+        let case_session = case_session.init_with(CaseSession::init());
+
+        Case::new()
+            .initiate(&mut exchange, fabric_idx, peer_node_id, case_session)
+            .await
+    }
+
+    fn session_id_for_node(&self, fabric_idx: u8, peer_node_id: u64, secure: bool) -> Option<u32> {
+        self.session_mgr
+            .borrow_mut()
+            .get_for_node(fabric_idx, peer_node_id, secure)
+            .map(|sess| sess.id)
+    }
+
+    pub(crate) async fn initiate_unsecured<'a>(
+        &'a self,
+        matter: &'a Matter<'a>,
+        peer_addr: Address,
+    ) -> Result<Exchange<'a>, Error> {
         let session_id = {
-            // (block necessary, or else we end up re-borrowing `SessionMgr` as mut twice)
+            // (block necessary, so that the `SessionMgr` borrow is released before we `await` below)
 
             let mut session_mgr = self.session_mgr.borrow_mut();
 
-            session_mgr
-                .get_for_node(fabric_idx, peer_node_id, secure)
-                .ok_or(ErrorCode::NoSession)?
-                .id
+            session_mgr.add_unsecured(peer_addr).map(|sess| sess.id)
+        };
+
+        let session_id = if let Ok(session_id) = session_id {
+            session_id
+        } else {
+            self.evict_some_session().await?;
+
+            self.session_mgr.borrow_mut().add_unsecured(peer_addr)?.id
+        };
+
+        self.initiate_for_session(matter, session_id)
+    }
+
+    pub(crate) async fn initiate_group<'a>(
+        &'a self,
+        matter: &'a Matter<'a>,
+        fabric_idx: u8,
+        group_id: u16,
+    ) -> Result<Exchange<'a>, Error> {
+        let fabric_idx = NonZeroU8::new(fabric_idx).ok_or(ErrorCode::NotFound)?;
+
+        let add_group_tx = || {
+            let fabric_mgr = matter.fabric_mgr.borrow();
+
+            let fabric = fabric_mgr.get(fabric_idx).ok_or(ErrorCode::NotFound)?;
+            let key = fabric
+                .group_key_for_tx(group_id, matter.epoch()())
+                .ok_or(ErrorCode::NotFound)?;
+
+            let multicast_addr = Address::Udp(SocketAddr::V6(SocketAddrV6::new(
+                group_keys::group_multicast_addr(fabric.fabric_id(), group_id),
+                MATTER_PORT,
+                0,
+                0,
+            )));
+
+            self.session_mgr
+                .borrow_mut()
+                .add_group_tx(
+                    fabric_idx,
+                    group_id,
+                    key.session_id,
+                    key.keys.op_key(),
+                    multicast_addr,
+                    fabric.node_id(),
+                )
+                .map(|sess| sess.id)
+        };
+
+        let session_id = match add_group_tx() {
+            Err(e) if matches!(e.code(), ErrorCode::NoSpaceSessions) => {
+                self.evict_some_session().await?;
+
+                add_group_tx()?
+            }
+            other => other?,
         };
 
         self.initiate_for_session(matter, session_id)
@@ -262,7 +479,12 @@ impl TransportMgr {
         Ok(exchange)
     }
 
-    pub async fn run<S, R>(&self, send: S, recv: R) -> Result<(), Error>
+    pub async fn run<S, R>(
+        &self,
+        fabric_mgr: &RefCell<FabricMgr>,
+        send: S,
+        recv: R,
+    ) -> Result<(), Error>
     where
         S: NetworkSend,
         R: NetworkReceive,
@@ -271,7 +493,7 @@ impl TransportMgr {
 
         let send = IfMutex::new(send);
 
-        let mut rx = pin!(self.process_rx(recv, &send));
+        let mut rx = pin!(self.process_rx(fabric_mgr, recv, &send));
         let mut tx = pin!(self.process_tx(&send));
         let mut orphaned = pin!(self.process_orphaned());
 
@@ -332,6 +554,7 @@ impl TransportMgr {
 
     async fn process_rx<R, S>(
         &self,
+        fabric_mgr: &RefCell<FabricMgr>,
         mut recv: R,
         send: &IfMutex<NoopRawMutex, S>,
     ) -> Result<(), Error>
@@ -357,7 +580,7 @@ impl TransportMgr {
             rx.buf.truncate(len);
             rx.payload_start = 0;
 
-            match self.handle_rx_packet(&mut rx, send).await {
+            match self.handle_rx_packet(fabric_mgr, &mut rx, send).await {
                 Ok(true) => {
                     // Leave the packet in place for accepting by responders
                     rx.clear_on_drop(false);
@@ -439,16 +662,18 @@ impl TransportMgr {
 
     async fn handle_rx_packet<const N: usize, S>(
         &self,
+        fabric_mgr: &RefCell<FabricMgr>,
         packet: &mut Packet<N>,
         send: &IfMutex<NoopRawMutex, S>,
     ) -> Result<bool, Error>
     where
         S: NetworkSend,
     {
-        let result = self.decode_packet(packet);
+        let result = self.decode_packet(fabric_mgr, packet);
         match result {
             Err(e) if matches!(e.code(), ErrorCode::Duplicate) => {
                 if !packet.peer.is_reliable()
+                    && !packet.header.plain.is_group_session()
                     && !MessageMeta::from(&packet.header.proto).is_standalone_ack()
                 {
                     debug!("\n>>RCV {}\n      => Duplicate, sending ACK", packet);
@@ -517,7 +742,10 @@ impl TransportMgr {
                     );
                 }
             }
-            Err(e) if matches!(e.code(), ErrorCode::NoSpaceExchanges) => {
+            Err(e)
+                if matches!(e.code(), ErrorCode::NoSpaceExchanges)
+                    && !packet.header.plain.is_group_session() =>
+            {
                 // TODO: Before closing the session, try to take other measures:
                 // - For CASESigma1 & PBKDFParamRequest - send Busy instead
                 // - For Interaction Model interactions that do need an ACK - send IM Busy,
@@ -766,7 +994,11 @@ impl TransportMgr {
         }
     }
 
-    fn decode_packet<const N: usize>(&self, packet: &mut Packet<N>) -> Result<bool, Error> {
+    fn decode_packet<const N: usize>(
+        &self,
+        fabric_mgr: &RefCell<FabricMgr>,
+        packet: &mut Packet<N>,
+    ) -> Result<bool, Error> {
         packet.header.reset();
 
         let mut pb = ParseBuf::new(&mut packet.buf[packet.payload_start..]);
@@ -780,6 +1012,117 @@ impl TransportMgr {
             packet.buf.truncate(end);
         };
 
+        if packet.header.plain.is_group_session() {
+            // Group messages are decrypted with the operational group key matching the group session ID
+            // of the message, and their message counters are tracked per source node
+
+            let plain = &packet.header.plain;
+
+            let (Some(src_nodeid), Some(group_id), false) = (
+                plain.get_src_nodeid(),
+                plain.get_dst_groupcast_nodeid(),
+                plain.is_privacy(),
+            ) else {
+                // Group messages without a source node ID or a group ID are invalid,
+                // and privacy-obfuscated headers are not supported (yet)
+                set_payload(packet, (0, 0));
+                Err(ErrorCode::NoSession)?
+            };
+
+            let sess_id = plain.sess_id;
+            let hdr_len = pb.parsed_as_slice().len();
+
+            let fabric_mgr = fabric_mgr.borrow();
+
+            // Only messages for groups which have at least one endpoint of this node as a member are accepted
+            //
+            // More than one operational group key might match the group session ID of the message
+            // (the same group in several fabrics, or a group session ID collision), so each one of
+            // those is tried until the message is decrypted
+            let mut keys = fabric_mgr
+                .iter()
+                .filter(|fabric| fabric.group(group_id).is_some())
+                .flat_map(|fabric| {
+                    fabric
+                        .group_keys_for_rx(group_id, sess_id)
+                        .map(|key| (fabric.fab_idx(), key.keys.op_key()))
+                })
+                .peekable();
+
+            let enc_start = packet.payload_start + hdr_len;
+            let enc_end = packet.buf.len();
+
+            let (fab_idx, key, payload_range) = loop {
+                let Some((fab_idx, key)) = keys.next() else {
+                    set_payload(packet, (0, 0));
+                    Err(ErrorCode::NoSession)?
+                };
+
+                // A failed decryption attempt garbles the encrypted part of the message, so unless this is the
+                // last key to try, that part is first saved in the unused space at the end of the packet buffer,
+                // to be restored from there before trying the next key
+                let saved = keys.peek().is_some()
+                    && packet
+                        .buf
+                        .resize_default(enc_end + (enc_end - enc_start))
+                        .is_ok();
+
+                if saved {
+                    packet.buf.copy_within(enc_start..enc_end, enc_end);
+                } else if keys.peek().is_some() {
+                    warn!("Group message too large to be decrypted with more than one key");
+                }
+
+                let mut pb = ParseBuf::new(&mut packet.buf[packet.payload_start..enc_end]);
+                pb.parse_head_with(hdr_len, |_| ())?;
+
+                let result = packet
+                    .header
+                    .decode_remaining(&mut pb, src_nodeid, Some(key));
+                let payload_range = pb.slice_range();
+
+                if saved {
+                    if result.is_err() {
+                        packet.buf.copy_within(enc_end.., enc_start);
+                    }
+
+                    packet.buf.truncate(enc_end);
+                }
+
+                match result {
+                    Ok(()) => break (fab_idx, key, payload_range),
+                    Err(_) if saved => (),
+                    Err(err) => Err(err)?,
+                }
+            };
+
+            packet.header.proto.adjust_reliability(true, &packet.peer);
+
+            // Group messages are never acknowledged
+            packet.header.proto.unset_reliable();
+
+            set_payload(packet, payload_range);
+
+            if !session_mgr.post_group_recv(fab_idx, &packet.header.plain) {
+                Err(ErrorCode::Duplicate)?;
+            }
+
+            if let Some(session) = session_mgr.get_for_rx(&packet.peer, &packet.header.plain) {
+                return session.post_recv(&packet.header, epoch);
+            }
+
+            let session = session_mgr.add_group_rx(
+                fab_idx,
+                group_id,
+                packet.header.plain.sess_id,
+                key,
+                packet.peer,
+                src_nodeid,
+            )?;
+
+            return session.post_recv(&packet.header, epoch);
+        }
+
         if let Some(session) = session_mgr.get_for_rx(&packet.peer, &packet.header.plain) {
             // Found existing session: decode, indicate packet payload slice and process further
 
@@ -1020,6 +1363,119 @@ impl TransportMgr {
     }
 }
 
+// The last known operational addresses of the peers with which CASE sessions were established,
+// most recently used last.
+struct PeerAddrs(heapless::Vec<(NonZeroU8, u64, Address), MAX_PEER_ADDRS>);
+
+impl PeerAddrs {
+    const fn new() -> Self {
+        Self(heapless::Vec::new())
+    }
+
+    fn get(&self, fabric_idx: NonZeroU8, node_id: u64) -> Option<Address> {
+        self.0
+            .iter()
+            .find(|(fi, ni, _)| *fi == fabric_idx && *ni == node_id)
+            .map(|(_, _, addr)| *addr)
+    }
+
+    fn set(&mut self, fabric_idx: NonZeroU8, node_id: u64, addr: Address) {
+        self.remove(fabric_idx, node_id);
+
+        if self.0.is_full() {
+            // Forget the least recently used peer
+            self.0.remove(0);
+        }
+
+        unwrap!(self.0.push((fabric_idx, node_id, addr)).map_err(|_| ()));
+    }
+
+    fn remove(&mut self, fabric_idx: NonZeroU8, node_id: u64) {
+        self.0
+            .retain(|(fi, ni, _)| *fi != fabric_idx || *ni != node_id);
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+// Forwards the peer resolution requests of `TransportMgr::initiate` to the
+// resolver run with `TransportMgr::run_resolver`, if any.
+//
+// Each request carries an ID which the response echoes, so that the late response
+// to a request which had timed out is not taken as the response to the next request.
+struct PeerResolver {
+    lock: IfMutex<NoopRawMutex, ()>,
+    next_id: Cell<u32>,
+    request: Signal<NoopRawMutex, Option<(u32, u64, u64)>>,
+    response: Signal<NoopRawMutex, Option<(u32, ResolveResult)>>,
+    running: Cell<bool>,
+}
+
+type ResolveResult = Result<SocketAddr, ErrorCode>;
+
+impl PeerResolver {
+    const fn new() -> Self {
+        Self {
+            lock: IfMutex::new(()),
+            next_id: Cell::new(0),
+            request: Signal::new(None),
+            response: Signal::new(None),
+            running: Cell::new(false),
+        }
+    }
+}
+
+impl MdnsResolver for &PeerResolver {
+    async fn resolve(
+        &mut self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        if !self.running.get() {
+            // Nobody to resolve the peer
+            Err(ErrorCode::NoSession)?;
+        }
+
+        let _lock = self.lock.lock().await;
+
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+
+        self.response.modify(|response| {
+            *response = None;
+            (false, ())
+        });
+
+        self.request.modify(|request| {
+            *request = Some((id, compressed_fabric_id, node_id));
+            (true, ())
+        });
+
+        let mut response = pin!(self.response.wait(|response| {
+            // Responses to earlier requests which had timed out are discarded
+            match response.take() {
+                Some((response_id, result)) if response_id == id => Some(result),
+                _ => None,
+            }
+        }));
+        let mut timeout = pin!(Timer::after(Duration::from_millis(RESOLVE_TIMEOUT_MS)));
+
+        match select(&mut response, &mut timeout).await {
+            Either::First(result) => Ok(result?),
+            Either::Second(_) => {
+                self.request.modify(|request| {
+                    *request = None;
+                    (false, ())
+                });
+
+                Err(ErrorCode::RxTimeout.into())
+            }
+        }
+    }
+}
+
 // The internal representation of a packet in the transport layer.
 // There are only two such packets - RX and TX.
 //
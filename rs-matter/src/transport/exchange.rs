@@ -22,6 +22,7 @@ use embassy_futures::select::{select, select3, Either, Either3};
 use embassy_time::{Duration, Instant, Timer};
 
 use crate::acl::Accessor;
+use crate::bdx::{self, PROTO_ID_BDX};
 use crate::error::{Error, ErrorCode};
 use crate::im::{self, PROTO_ID_INTERACTION_MODEL};
 use crate::sc::{self, PROTO_ID_SECURE_CHANNEL};
@@ -30,7 +31,8 @@ use crate::utils::storage::WriteBuf;
 use crate::Matter;
 
 use super::mrp::{ReliableMessage, RetransEntry};
-use super::network;
+use super::network::mdns::MdnsResolver;
+use super::network::{self, Address};
 use super::packet::PacketHdr;
 use super::plain_hdr::PlainHdr;
 use super::proto_hdr::ProtoHdr;
@@ -563,6 +565,13 @@ impl Display for MessageMeta {
                     write!(f, "IM::{:02x}", self.proto_opcode)
                 }
             }
+            PROTO_ID_BDX => {
+                if let Ok(opcode) = self.opcode::<bdx::OpCode>() {
+                    write!(f, "BDX::{:?}", opcode)
+                } else {
+                    write!(f, "BDX::{:02x}", self.proto_opcode)
+                }
+            }
             _ => write!(f, "{:02x}::{:02x}", self.proto_id, self.proto_opcode),
         }
     }
@@ -586,6 +595,13 @@ impl defmt::Format for MessageMeta {
                     defmt::write!(f, "IM::{:02x}", self.proto_opcode)
                 }
             }
+            PROTO_ID_BDX => {
+                if let Ok(opcode) = self.opcode::<bdx::OpCode>() {
+                    defmt::write!(f, "BDX::{:?}", opcode)
+                } else {
+                    defmt::write!(f, "BDX::{:02x}", self.proto_opcode)
+                }
+            }
             _ => defmt::write!(f, "{:02x}::{:02x}", self.proto_id, self.proto_opcode),
         }
     }
@@ -652,9 +668,28 @@ impl TxMessage<'_> {
         let mut session_mgr = self.matter.transport_mgr.session_mgr.borrow_mut();
 
         let session = session_mgr
-            .get(self.exchange_id.session_id())
+            .get_for_tx(self.exchange_id.session_id())
             .ok_or(ErrorCode::NoSession)?;
 
+        let group = session.is_group();
+
+        if group
+            && matches!(
+                session.exchanges[self.exchange_id.exchange_index()]
+                    .as_ref()
+                    .map(|exch| &exch.role),
+                Some(Role::Responder(_))
+            )
+        {
+            // As per spec, group messages are never responded to
+            debug!(
+                "\n<<SND {}\n      => Suppressed (group session)",
+                self.exchange_id.display(session)
+            );
+
+            return Ok(());
+        }
+
         let (peer, retransmission) = session.pre_send(
             Some(self.exchange_id.exchange_index()),
             &mut self.packet.header,
@@ -705,6 +740,13 @@ impl TxMessage<'_> {
         self.packet.buf.truncate(encoded_payload_end);
         self.packet.clear_on_drop(false);
 
+        drop(session_mgr);
+
+        if group {
+            // Sending on a group session might have reserved new global group message counter values
+            self.matter.notify_persist();
+        }
+
         Ok(())
     }
 }
@@ -870,10 +912,11 @@ impl<'a> Exchange<'a> {
 
     /// Create a new initiator exchange on the provided Matter stack for the provided peer Node ID.
     ///
-    /// For now, this method will fail if there is no existing session in the provided Matter stack
-    /// for the provided peer Node ID.
-    ///
-    /// In future, this method will do an mDNS lookup and create a new session on its own.
+    /// If there is no existing secure session for the peer Node ID in the provided Matter stack,
+    /// a new CASE session is established with the peer, first at its last known operational address
+    /// and then - should that fail - at the address looked up with the mDNS resolver run with
+    /// `Matter::run_resolver`. Without a running resolver, this method fails for peers whose
+    /// address is not known yet.
     #[inline(always)]
     pub async fn initiate(
         matter: &'a Matter<'a>,
@@ -887,6 +930,63 @@ impl<'a> Exchange<'a> {
             .await
     }
 
+    /// Create a new initiator exchange on the provided Matter stack for the provided peer Node ID.
+    ///
+    /// If there is no existing secure session for the peer Node ID in the provided Matter stack,
+    /// the operational address of the peer is looked up with the provided mDNS resolver, and a new
+    /// CASE session is established with the peer. Should the CASE session establishment fail,
+    /// the peer is resolved again and - if its address had changed in the meantime - the session
+    /// establishment is retried with the new address.
+    #[inline(always)]
+    pub async fn initiate_with<R>(
+        matter: &'a Matter<'a>,
+        resolver: R,
+        fabric_idx: u8,
+        peer_node_id: u64,
+        secure: bool,
+    ) -> Result<Self, Error>
+    where
+        R: MdnsResolver,
+    {
+        matter
+            .transport_mgr
+            .initiate_with(matter, resolver, fabric_idx, peer_node_id, secure)
+            .await
+    }
+
+    /// Create a new initiator exchange on a new unencrypted session with the peer at the provided address.
+    ///
+    /// The exchange is meant to be used for establishing an encrypted session with the peer (i.e. CASE or PASE).
+    #[inline(always)]
+    pub async fn initiate_unsecured(
+        matter: &'a Matter<'a>,
+        peer_addr: Address,
+    ) -> Result<Self, Error> {
+        matter
+            .transport_mgr
+            .initiate_unsecured(matter, peer_addr)
+            .await
+    }
+
+    /// Create a new initiator exchange for sending group messages to the provided group on the provided fabric.
+    ///
+    /// The messages are encrypted with the current operational group key of the group key set mapped to the group,
+    /// and are sent to the IPv6 multicast address of the group. Group messages are never acknowledged and
+    /// never responded to, so the exchange can only be used for sending.
+    ///
+    /// This method will fail if the group does not have a group key set mapped to it.
+    #[inline(always)]
+    pub async fn initiate_group(
+        matter: &'a Matter<'a>,
+        fabric_idx: u8,
+        group_id: u16,
+    ) -> Result<Self, Error> {
+        matter
+            .transport_mgr
+            .initiate_group(matter, fabric_idx, group_id)
+            .await
+    }
+
     /// Create a new initiator exchange on the provided Matter stack for the provided session ID.
     #[inline(always)]
     pub fn initiate_for_session(matter: &'a Matter<'a>, session_id: u32) -> Result<Self, Error> {
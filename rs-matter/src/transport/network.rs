@@ -26,6 +26,7 @@ use crate::error::{Error, ErrorCode};
 
 pub mod btp;
 pub mod mdns;
+pub mod tcp;
 pub mod udp;
 pub mod wifi;
 
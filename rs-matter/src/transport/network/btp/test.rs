@@ -44,8 +44,7 @@ const BASIC_INFO: BasicInfoConfig<'static> = BasicInfoConfig {
     device_name: "Test Device",
     product_name: "TestProd",
     vendor_name: "TestVendor",
-    sai: None,
-    sii: None,
+    ..BasicInfoConfig::new()
 };
 
 #[derive(Debug, Clone)]
@@ -18,6 +18,8 @@
 use core::fmt::Write;
 use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
 
+use embassy_time::Duration;
+
 use crate::dm::clusters::basic_info::BasicInfoConfig;
 use crate::error::Error;
 use crate::{MatterMdnsService, MATTER_SERVICE_MAX_NAME_LEN};
@@ -72,6 +74,346 @@ where
     }
 }
 
+/// The value of the `T` TXT entry advertising support for Matter over TCP,
+/// both as a client (bit 1) and as a server (bit 2)
+const TCP_SUPPORT: &str = "6";
+
+/// The maximum number of addresses reported for a node discovered over mDNS
+pub const MAX_NODE_ADDRS: usize = 4;
+
+/// The maximum length of the instance name of a commissionable node
+pub const MAX_INSTANCE_NAME_LEN: usize = 32;
+
+/// The maximum length of the device name (`DN`) TXT entry of a commissionable node
+pub const MAX_DEVICE_NAME_LEN: usize = 32;
+
+/// The maximum length of the pairing instruction (`PI`) TXT entry of a commissionable node
+pub const MAX_PAIRING_INSTRUCTION_LEN: usize = 128;
+
+/// The maximum length of a DNS-SD service type browsed for Matter nodes, including the subtype
+pub const MAX_SERVICE_TYPE_LEN: usize = 48;
+
+/// The MRP parameters advertised in the TXT record of a Matter node
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MdnsMrpParams {
+    /// The session idle interval in milliseconds (`SII`)
+    pub session_idle_interval: Option<u32>,
+    /// The session active interval in milliseconds (`SAI`)
+    pub session_active_interval: Option<u32>,
+    /// The session active threshold in milliseconds (`SAT`)
+    pub session_active_threshold: Option<u16>,
+}
+
+impl MdnsMrpParams {
+    /// Update the MRP parameters from the provided TXT entry key and value.
+    ///
+    /// Returns `false` if the key is not one of the MRP parameters keys.
+    fn update(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "SII" => self.session_idle_interval = value.parse().ok(),
+            "SAI" => self.session_active_interval = value.parse().ok(),
+            "SAT" => self.session_active_threshold = value.parse().ok(),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// A commissionable Matter node (`_matterc._udp`) discovered over mDNS
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CommissionableNode {
+    /// The mDNS instance name of the node
+    pub instance_name: heapless::String<MAX_INSTANCE_NAME_LEN>,
+    /// The addresses of the node, including the commissioning port
+    pub addrs: heapless::Vec<SocketAddr, MAX_NODE_ADDRS>,
+    /// The long discriminator (`D`)
+    pub discriminator: u16,
+    /// The vendor ID (`VP`)
+    pub vendor_id: Option<u16>,
+    /// The product ID (`VP`)
+    pub product_id: Option<u16>,
+    /// The commissioning mode (`CM`): 0 - not in commissioning mode,
+    /// 1 - basic commissioning mode, 2 - enhanced commissioning mode
+    pub commissioning_mode: u8,
+    /// The device type (`DT`)
+    pub device_type: Option<u32>,
+    /// The device name (`DN`)
+    pub device_name: heapless::String<MAX_DEVICE_NAME_LEN>,
+    /// The pairing hint bitmap (`PH`)
+    pub pairing_hint: Option<u32>,
+    /// The pairing instruction (`PI`)
+    pub pairing_instruction: heapless::String<MAX_PAIRING_INSTRUCTION_LEN>,
+    /// The MRP parameters of the node (`SII`, `SAI`, `SAT`)
+    pub mrp: MdnsMrpParams,
+    /// The TCP support bitmap (`T`): bit 1 - TCP client, bit 2 - TCP server
+    pub tcp: Option<u8>,
+}
+
+impl CommissionableNode {
+    /// Create a new commissionable node from its mDNS instance name, addresses and TXT entries.
+    ///
+    /// Addresses beyond `MAX_NODE_ADDRS` are ignored.
+    /// Returns `None` if the instance name is too long.
+    pub fn new<'a, I>(instance_name: &str, addrs: &[SocketAddr], txt: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut node = Self {
+            instance_name: instance_name.try_into().ok()?,
+            addrs: addrs.iter().copied().take(MAX_NODE_ADDRS).collect(),
+            ..Default::default()
+        };
+
+        for entry in txt {
+            node.update_txt(entry);
+        }
+
+        Some(node)
+    }
+
+    /// Update the node from the provided TXT entry, in the `key=value` form.
+    ///
+    /// Unknown or malformed entries are ignored.
+    pub fn update_txt(&mut self, entry: &[u8]) {
+        let Some((key, value)) = split_txt(entry) else {
+            return;
+        };
+
+        match key {
+            "D" => self.discriminator = value.parse().unwrap_or(0),
+            "VP" => {
+                let mut parts = value.split('+');
+
+                self.vendor_id = parts.next().and_then(|vid| vid.parse().ok());
+                self.product_id = parts.next().and_then(|pid| pid.parse().ok());
+            }
+            "CM" => self.commissioning_mode = value.parse().unwrap_or(0),
+            "DT" => self.device_type = value.parse().ok(),
+            "DN" => self.device_name = value.try_into().unwrap_or_default(),
+            "PH" => self.pairing_hint = value.parse().ok(),
+            "PI" => self.pairing_instruction = value.try_into().unwrap_or_default(),
+            "T" => self.tcp = value.parse().ok(),
+            _ => {
+                self.mrp.update(key, value);
+            }
+        }
+    }
+}
+
+/// An operational Matter node (`_matter._tcp`) discovered over mDNS
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OperationalNode {
+    /// The compressed fabric ID of the fabric the node is commissioned in
+    pub compressed_fabric_id: u64,
+    /// The node ID of the node
+    pub node_id: u64,
+    /// The addresses of the node, including the operational port
+    pub addrs: heapless::Vec<SocketAddr, MAX_NODE_ADDRS>,
+    /// The MRP parameters of the node (`SII`, `SAI`, `SAT`)
+    pub mrp: MdnsMrpParams,
+    /// The TCP support bitmap (`T`): bit 1 - TCP client, bit 2 - TCP server
+    pub tcp: Option<u8>,
+}
+
+impl OperationalNode {
+    /// Create a new operational node from its mDNS instance name, addresses and TXT entries.
+    ///
+    /// The instance name is in the form `<compressed-fabric-id-hex>-<node-id-hex>`.
+    /// Addresses beyond `MAX_NODE_ADDRS` are ignored.
+    /// Returns `None` if the instance name is malformed.
+    pub fn new<'a, I>(instance_name: &str, addrs: &[SocketAddr], txt: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let (compressed_fabric_id, node_id) = instance_name.split_once('-')?;
+
+        let mut node = Self {
+            compressed_fabric_id: u64::from_str_radix(compressed_fabric_id, 16).ok()?,
+            node_id: u64::from_str_radix(node_id, 16).ok()?,
+            addrs: addrs.iter().copied().take(MAX_NODE_ADDRS).collect(),
+            ..Default::default()
+        };
+
+        for entry in txt {
+            node.update_txt(entry);
+        }
+
+        Some(node)
+    }
+
+    /// Update the node from the provided TXT entry, in the `key=value` form.
+    ///
+    /// Unknown or malformed entries are ignored.
+    pub fn update_txt(&mut self, entry: &[u8]) {
+        let Some((key, value)) = split_txt(entry) else {
+            return;
+        };
+
+        if key == "T" {
+            self.tcp = value.parse().ok();
+        } else {
+            self.mrp.update(key, value);
+        }
+    }
+}
+
+/// A filter for browsing commissionable Matter nodes
+///
+/// Apart from `Any`, each filter corresponds to one of the DNS-SD subtypes of the `_matterc._udp` service.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CommissionableFilter {
+    /// All commissionable nodes
+    Any,
+    /// Nodes with the provided long discriminator (`_L<discriminator>`)
+    LongDiscriminator(u16),
+    /// Nodes with the provided short discriminator (`_S<short-discriminator>`)
+    ShortDiscriminator(u8),
+    /// Nodes with the provided vendor ID (`_V<vendor-id>`)
+    VendorId(u16),
+    /// Nodes with the provided device type (`_T<device-type>`)
+    DeviceType(u32),
+    /// Nodes which are in commissioning mode (`_CM`)
+    CommissioningMode,
+}
+
+impl CommissionableFilter {
+    /// Return the DNS-SD service type to browse for, without the domain:
+    /// `_matterc._udp` for `Any`, or `<subtype>._sub._matterc._udp` otherwise
+    pub fn service_type(&self) -> heapless::String<MAX_SERVICE_TYPE_LEN> {
+        let mut service_type = heapless::String::new();
+
+        match self {
+            Self::Any => (),
+            Self::LongDiscriminator(discriminator) => {
+                write_unwrap!(service_type, "_L{}._sub.", discriminator)
+            }
+            Self::ShortDiscriminator(short) => write_unwrap!(service_type, "_S{}._sub.", short),
+            Self::VendorId(vendor_id) => write_unwrap!(service_type, "_V{}._sub.", vendor_id),
+            Self::DeviceType(device_type) => {
+                write_unwrap!(service_type, "_T{}._sub.", device_type)
+            }
+            Self::CommissioningMode => write_unwrap!(service_type, "_CM._sub."),
+        }
+
+        write_unwrap!(service_type, "_matterc._udp");
+
+        service_type
+    }
+
+    /// Return `true` if the provided node matches this filter.
+    ///
+    /// Useful with mDNS implementations which cannot browse for service subtypes.
+    pub fn matches(&self, node: &CommissionableNode) -> bool {
+        match self {
+            Self::Any => true,
+            Self::LongDiscriminator(discriminator) => node.discriminator == *discriminator,
+            Self::ShortDiscriminator(short) => {
+                Service::compute_short_discriminator(node.discriminator) == *short as u16
+            }
+            Self::VendorId(vendor_id) => node.vendor_id == Some(*vendor_id),
+            Self::DeviceType(device_type) => node.device_type == Some(*device_type),
+            Self::CommissioningMode => node.commissioning_mode > 0,
+        }
+    }
+}
+
+/// Return the DNS-SD service type to browse for operational nodes, without the domain:
+/// `_matter._tcp`, or `_I<compressed-fabric-id-hex>._sub._matter._tcp` if a compressed fabric ID is provided
+pub fn operational_service_type(
+    compressed_fabric_id: Option<u64>,
+) -> heapless::String<MAX_SERVICE_TYPE_LEN> {
+    let mut service_type = heapless::String::new();
+
+    if let Some(compressed_fabric_id) = compressed_fabric_id {
+        write_unwrap!(service_type, "_I{:016X}._sub.", compressed_fabric_id);
+    }
+
+    write_unwrap!(service_type, "_matter._tcp");
+
+    service_type
+}
+
+/// Split a TXT entry into its key and value; entries without a value have an empty value
+fn split_txt(entry: &[u8]) -> Option<(&str, &str)> {
+    let entry = core::str::from_utf8(entry).ok()?;
+
+    Some(entry.split_once('=').unwrap_or((entry, "")))
+}
+
+/// A trait for browsing Matter nodes over mDNS
+pub trait MdnsBrowser {
+    /// Browse for commissionable Matter nodes (`_matterc._udp`).
+    ///
+    /// Browses for the duration of `timeout` and then calls `f` once for each discovered node
+    /// which matches the filter.
+    ///
+    /// # Arguments
+    /// - `filter`: The filter the discovered nodes should match.
+    /// - `timeout`: For how long to browse.
+    /// - `f`: A closure called with each discovered node.
+    async fn browse_commissionable<F>(
+        &mut self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>;
+
+    /// Browse for operational Matter nodes (`_matter._tcp`).
+    ///
+    /// Browses for the duration of `timeout` and then calls `f` once for each discovered node.
+    ///
+    /// # Arguments
+    /// - `compressed_fabric_id`: If provided, only nodes on the fabric with this compressed fabric ID are reported.
+    /// - `timeout`: For how long to browse.
+    /// - `f`: A closure called with each discovered node.
+    async fn browse_operational<F>(
+        &mut self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>;
+}
+
+impl<T> MdnsBrowser for &mut T
+where
+    T: MdnsBrowser,
+{
+    async fn browse_commissionable<F>(
+        &mut self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        (*self).browse_commissionable(filter, timeout, f).await
+    }
+
+    async fn browse_operational<F>(
+        &mut self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        (*self)
+            .browse_operational(compressed_fabric_id, timeout, f)
+            .await
+    }
+}
+
 /// A utility type for expanding a `MatterMdnsService` type into a full mDNS service description
 ///
 /// Useful as an implementation detail when interfacing with OS-specific mDNS libraries.
@@ -110,6 +452,13 @@ impl Service<'_> {
 
         match matter_service {
             MatterMdnsService::Commissioned { .. } => {
+                let txt_kvs: &[(&str, &str)] = if dev_det.tcp {
+                    &[("T", TCP_SUPPORT)]
+                } else {
+                    // Some mDNS responders do not accept empty TXT records
+                    &[("dummy", "dummy")]
+                };
+
                 f(&Service {
                     name: matter_service.name(&mut name_buf),
                     service: "_matter",
@@ -117,8 +466,7 @@ impl Service<'_> {
                     service_protocol: "_matter._tcp",
                     port: matter_port,
                     service_subtypes: &[],
-                    // Some mDNS responders do not accept empty TXT records
-                    txt_kvs: &[("dummy", "dummy")],
+                    txt_kvs,
                 })
                 .await
             }
@@ -141,7 +489,13 @@ impl Service<'_> {
                     ("SII", sii_str.as_str()), // Session Idle Interval
                     ("PH", "33"),              // Pairing Hint
                     ("PI", ""),                // Pairing Instruction
+                    ("T", TCP_SUPPORT),        // TCP support
                 ];
+                let txt_kvs = if dev_det.tcp {
+                    &txt_kvs[..]
+                } else {
+                    &txt_kvs[..txt_kvs.len() - 1]
+                };
 
                 f(&Service {
                     name: matter_service.name(&mut name_buf),
@@ -235,4 +589,89 @@ mod tests {
         let short = Service::compute_short_discriminator(discriminator);
         assert_eq!(short, 3);
     }
+
+    #[test]
+    fn can_parse_commissionable_node() {
+        let addr = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 5540, 0, 0));
+
+        let txt: &[&[u8]] = &[
+            b"D=840",
+            b"VP=65521+32769",
+            b"CM=2",
+            b"DT=257",
+            b"DN=Light",
+            b"SII=5000",
+            b"SAI=300",
+            b"PH=33",
+            b"PI",
+            b"XX=unknown",
+        ];
+
+        let node = unwrap!(CommissionableNode::new(
+            "1234567890ABCDEF",
+            &[addr],
+            txt.iter().copied()
+        ));
+
+        assert_eq!(node.instance_name, "1234567890ABCDEF");
+        assert_eq!(node.addrs.as_slice(), &[addr]);
+        assert_eq!(node.discriminator, 840);
+        assert_eq!(node.vendor_id, Some(65521));
+        assert_eq!(node.product_id, Some(32769));
+        assert_eq!(node.commissioning_mode, 2);
+        assert_eq!(node.device_type, Some(257));
+        assert_eq!(node.device_name, "Light");
+        assert_eq!(node.pairing_hint, Some(33));
+        assert_eq!(node.pairing_instruction, "");
+        assert_eq!(node.mrp.session_idle_interval, Some(5000));
+        assert_eq!(node.mrp.session_active_interval, Some(300));
+        assert_eq!(node.mrp.session_active_threshold, None);
+
+        assert!(CommissionableFilter::Any.matches(&node));
+        assert!(CommissionableFilter::LongDiscriminator(840).matches(&node));
+        assert!(!CommissionableFilter::LongDiscriminator(841).matches(&node));
+        assert!(CommissionableFilter::ShortDiscriminator(3).matches(&node));
+        assert!(CommissionableFilter::VendorId(65521).matches(&node));
+        assert!(!CommissionableFilter::DeviceType(256).matches(&node));
+        assert!(CommissionableFilter::CommissioningMode.matches(&node));
+    }
+
+    #[test]
+    fn can_parse_operational_node() {
+        let node = unwrap!(OperationalNode::new(
+            "87E1B004E235A130-000000000000001E",
+            &[],
+            [b"SAT=4000".as_slice(), b"T=6".as_slice()]
+        ));
+
+        assert_eq!(node.compressed_fabric_id, 0x87E1B004E235A130);
+        assert_eq!(node.node_id, 0x1E);
+        assert_eq!(node.mrp.session_active_threshold, Some(4000));
+        assert_eq!(node.tcp, Some(6));
+
+        assert!(OperationalNode::new("87E1B004E235A130", &[], []).is_none());
+        assert!(OperationalNode::new("foo-bar", &[], []).is_none());
+    }
+
+    #[test]
+    fn can_compute_service_types() {
+        assert_eq!(CommissionableFilter::Any.service_type(), "_matterc._udp");
+        assert_eq!(
+            CommissionableFilter::LongDiscriminator(840).service_type(),
+            "_L840._sub._matterc._udp"
+        );
+        assert_eq!(
+            CommissionableFilter::ShortDiscriminator(3).service_type(),
+            "_S3._sub._matterc._udp"
+        );
+        assert_eq!(
+            CommissionableFilter::CommissioningMode.service_type(),
+            "_CM._sub._matterc._udp"
+        );
+        assert_eq!(operational_service_type(None), "_matter._tcp");
+        assert_eq!(
+            operational_service_type(Some(0x87E1B004E235A130)),
+            "_I87E1B004E235A130._sub._matter._tcp"
+        );
+    }
 }
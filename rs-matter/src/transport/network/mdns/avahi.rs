@@ -19,18 +19,35 @@
 //!
 //! Requires the Avahi daemon to be installed and running.
 
+use core::net::{IpAddr, SocketAddr, SocketAddrV6};
+
 use std::collections::{HashMap, HashSet};
 use std::io::Write as _;
 
+use embassy_futures::select::{select, Either};
+use embassy_time::{Duration, Instant, Timer};
+
+use futures_lite::StreamExt;
+
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 use zbus::Connection;
 
-use crate::error::Error;
-use crate::transport::network::mdns::Service;
+use crate::error::{Error, ErrorCode};
+use crate::transport::network::mdns::{
+    operational_service_type, CommissionableFilter, CommissionableNode, MdnsBrowser, MdnsResolver,
+    OperationalNode, Service,
+};
 use crate::utils::zbus_proxies::avahi::entry_group::EntryGroupProxy;
 use crate::utils::zbus_proxies::avahi::server2::Server2Proxy;
+use crate::utils::zbus_proxies::avahi::service_browser::ServiceBrowserProxy;
 use crate::{Matter, MatterMdnsService};
 
+/// Any network interface, for Avahi APIs which take an interface index
+const AVAHI_IF_UNSPEC: i32 = -1;
+
+/// Any IP protocol, for Avahi APIs which take a protocol
+const AVAHI_PROTO_UNSPEC: i32 = -1;
+
 /// An mDNS responder for Matter utilizing the Avahi daemon over DBus.
 pub struct AvahiMdnsResponder<'a> {
     matter: &'a Matter<'a>,
@@ -204,3 +221,273 @@ impl<'a> AvahiMdnsResponder<'a> {
         Ok(())
     }
 }
+
+/// An mDNS querier for Matter utilizing the Avahi daemon over DBus.
+///
+/// Resolves and browses for Matter nodes via the system Avahi daemon, which - unlike the
+/// built-in mDNS querier - does not need to compete with the daemon for the mDNS port.
+pub struct AvahiMdnsQuerier<'a> {
+    connection: &'a Connection,
+}
+
+impl<'a> AvahiMdnsQuerier<'a> {
+    /// Create a new instance of the Avahi mDNS querier.
+    ///
+    /// # Arguments
+    /// - `connection`: A reference to the DBus system connection to use for communication with Avahi.
+    pub const fn new(connection: &'a Connection) -> Self {
+        Self { connection }
+    }
+
+    /// Resolve the operational address of the Matter node with the provided node ID
+    /// on the fabric with the provided compressed fabric ID.
+    pub async fn resolve(
+        &self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        let avahi = Server2Proxy::new(self.connection).await?;
+
+        let instance = format!("{:016X}-{:016X}", compressed_fabric_id, node_id);
+
+        let (interface, _, _, _, _, _, _, address, port, _, _) = avahi
+            .resolve_service(
+                AVAHI_IF_UNSPEC,
+                AVAHI_PROTO_UNSPEC,
+                &instance,
+                "_matter._tcp",
+                "local",
+                AVAHI_PROTO_UNSPEC,
+                0,
+            )
+            .await
+            .map_err(|e| {
+                warn!("Failed to resolve mDNS instance {}: {}", instance, e);
+                ErrorCode::NotFound
+            })?;
+
+        let addr = Self::socket_addr(interface, &address, port).ok_or(ErrorCode::NotFound)?;
+
+        debug!("Resolved mDNS instance {} to {}", instance, addr);
+
+        Ok(addr)
+    }
+
+    /// Browse for commissionable Matter nodes.
+    ///
+    /// Browses for the `_matterc._udp` service (or its subtype corresponding to the filter)
+    /// and once `timeout` elapses, resolves and calls `f` with each discovered node matching the filter.
+    pub async fn browse_commissionable<F>(
+        &self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        self.browse(&filter.service_type(), timeout, |instance, txt, addrs| {
+            match CommissionableNode::new(instance, addrs, txt.iter().map(Vec::as_slice)) {
+                Some(node) if filter.matches(&node) => f(&node),
+                _ => Ok(()),
+            }
+        })
+        .await
+    }
+
+    /// Browse for operational Matter nodes.
+    ///
+    /// Same as `browse_commissionable`, but for the `_matter._tcp` service, or its `_I<compressed-fabric-id>`
+    /// subtype if `compressed_fabric_id` is provided.
+    pub async fn browse_operational<F>(
+        &self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        let service_type = operational_service_type(compressed_fabric_id);
+
+        self.browse(
+            &service_type,
+            timeout,
+            |instance, txt, addrs| match OperationalNode::new(
+                instance,
+                addrs,
+                txt.iter().map(Vec::as_slice),
+            ) {
+                Some(node)
+                    if compressed_fabric_id
+                        .is_none_or(|cfid| cfid == node.compressed_fabric_id) =>
+                {
+                    f(&node)
+                }
+                _ => Ok(()),
+            },
+        )
+        .await
+    }
+
+    /// Browse for the instances of the provided service type for the duration of `timeout`, and then
+    /// resolve and call `f` with the instance name, TXT entries and addresses of each instance
+    async fn browse<F>(&self, service_type: &str, timeout: Duration, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(&str, &[Vec<u8>], &[SocketAddr]) -> Result<(), Error>,
+    {
+        let avahi = Server2Proxy::new(self.connection).await?;
+
+        // Subscribe for the browser signals before starting it, so that no items are missed
+        let path = avahi
+            .service_browser_prepare(
+                AVAHI_IF_UNSPEC,
+                AVAHI_PROTO_UNSPEC,
+                service_type,
+                "local",
+                0,
+            )
+            .await?;
+
+        let browser = ServiceBrowserProxy::builder(self.connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        let mut items_new = browser.receive_item_new().await?;
+
+        browser.start().await?;
+
+        let deadline = Instant::now() + timeout;
+
+        // An instance is reported once for each interface and protocol it is visible on
+        let mut items = Vec::new();
+
+        loop {
+            let item = match select(items_new.next(), Timer::at(deadline)).await {
+                Either::First(Some(item)) => item,
+                _ => break,
+            };
+
+            let args = item.args()?;
+
+            items.push((
+                args.interface,
+                args.protocol,
+                args.name.to_string(),
+                args.type_.to_string(),
+                args.domain.to_string(),
+            ));
+        }
+
+        browser.free().await?;
+
+        let mut instances = items
+            .iter()
+            .map(|(_, _, name, _, _)| name.as_str())
+            .collect::<Vec<_>>();
+        instances.sort();
+        instances.dedup();
+
+        for instance in instances {
+            let mut txt = Vec::new();
+            let mut addrs = Vec::new();
+
+            for (interface, protocol, _, type_, domain) in
+                items.iter().filter(|item| item.2 == instance)
+            {
+                let resolved = avahi
+                    .resolve_service(
+                        *interface,
+                        *protocol,
+                        instance,
+                        type_,
+                        domain,
+                        AVAHI_PROTO_UNSPEC,
+                        0,
+                    )
+                    .await;
+
+                match resolved {
+                    Ok((interface, _, _, _, _, _, _, address, port, resolved_txt, _)) => {
+                        let Some(addr) = Self::socket_addr(interface, &address, port) else {
+                            continue;
+                        };
+
+                        if !addrs.contains(&addr) {
+                            addrs.push(addr);
+                        }
+
+                        txt = resolved_txt;
+                    }
+                    Err(e) => warn!("Failed to resolve mDNS instance {}: {}", instance, e),
+                }
+            }
+
+            if !addrs.is_empty() {
+                // Prefer IPv6 addresses, as these are mandatory in Matter
+                addrs.sort_by_key(SocketAddr::is_ipv4);
+
+                f(instance, &txt, &addrs)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create a socket address from the address and port resolved by Avahi on the provided interface.
+    ///
+    /// IPv6 link-local addresses are scoped to that interface.
+    fn socket_addr(interface: i32, address: &str, port: u16) -> Option<SocketAddr> {
+        let addr = match address.parse::<IpAddr>().ok()? {
+            IpAddr::V6(ip) => {
+                let scope_id = if ip.segments()[0] & 0xffc0 == 0xfe80 {
+                    interface as u32
+                } else {
+                    0
+                };
+
+                SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id))
+            }
+            IpAddr::V4(ip) => SocketAddr::new(IpAddr::V4(ip), port),
+        };
+
+        Some(addr)
+    }
+}
+
+impl MdnsResolver for AvahiMdnsQuerier<'_> {
+    async fn resolve(
+        &mut self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        AvahiMdnsQuerier::resolve(self, compressed_fabric_id, node_id).await
+    }
+}
+
+impl MdnsBrowser for AvahiMdnsQuerier<'_> {
+    async fn browse_commissionable<F>(
+        &mut self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        AvahiMdnsQuerier::browse_commissionable(self, filter, timeout, f).await
+    }
+
+    async fn browse_operational<F>(
+        &mut self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        AvahiMdnsQuerier::browse_operational(self, compressed_fabric_id, timeout, f).await
+    }
+}
@@ -23,7 +23,7 @@
 use core::net::IpAddr;
 use core::pin::pin;
 
-use embassy_futures::select::select;
+use embassy_futures::select::{select, select3};
 use embassy_sync::blocking_mutex::raw::{NoopRawMutex, RawMutex};
 use embassy_sync::mutex::Mutex;
 use embassy_time::{Duration, Timer};
@@ -43,14 +43,20 @@ use self::proto::Services;
 use super::Service;
 
 pub use proto::Host;
+pub use querier::{BuiltinMdnsQuerier, MdnsCache};
 
 mod proto;
+mod querier;
 
 /// A built-in mDNS responder for Matter, utilizing a custom mDNS protocol implementation.
 ///
 /// `no_std` and `no-alloc` and thus suitable for MCUs as well when there is no running mDNS service as part of the OS,
+///
+/// Optionally, the responder can also serve a `BuiltinMdnsQuerier`, by sending its queries and feeding it
+/// with the mDNS responses received on the responder socket.
 pub struct BuiltinMdnsResponder<'a> {
     matter: &'a Matter<'a>,
+    querier: Option<&'a BuiltinMdnsQuerier>,
 }
 
 impl<'a> BuiltinMdnsResponder<'a> {
@@ -59,7 +65,23 @@ impl<'a> BuiltinMdnsResponder<'a> {
     /// # Arguments
     /// * `matter` - A reference to the Matter instance that this responder will use.
     pub const fn new(matter: &'a Matter<'a>) -> Self {
-        Self { matter }
+        Self {
+            matter,
+            querier: None,
+        }
+    }
+
+    /// Create a new instance of the built-in mDNS responder, which also serves the provided querier.
+    ///
+    /// # Arguments
+    /// * `matter` - A reference to the Matter instance that this responder will use.
+    /// * `querier` - A reference to the querier whose queries this responder will send, and which
+    ///   this responder will feed with the received mDNS responses.
+    pub const fn new_with_querier(matter: &'a Matter<'a>, querier: &'a BuiltinMdnsQuerier) -> Self {
+        Self {
+            matter,
+            querier: Some(querier),
+        }
     }
 
     /// Run the mDNS responder.
@@ -87,8 +109,11 @@ impl<'a> BuiltinMdnsResponder<'a> {
 
         let mut broadcast = pin!(self.broadcast(&send, host, ipv4_interface, ipv6_interface));
         let mut respond = pin!(self.respond(&send, recv, host, ipv4_interface, ipv6_interface));
+        let mut query = pin!(self.query(&send, ipv4_interface, ipv6_interface));
 
-        select(&mut broadcast, &mut respond).coalesce().await
+        select3(&mut broadcast, &mut respond, &mut query)
+            .coalesce()
+            .await
     }
 
     async fn broadcast<S>(
@@ -107,21 +132,7 @@ impl<'a> BuiltinMdnsResponder<'a> {
 
             select(&mut notification, &mut timeout).await;
 
-            for addr in Iterator::chain(
-                ipv4_interface
-                    .map(|_| SocketAddr::V4(SocketAddrV4::new(MDNS_IPV4_BROADCAST_ADDR, MDNS_PORT)))
-                    .into_iter(),
-                ipv6_interface
-                    .map(|interface| {
-                        SocketAddr::V6(SocketAddrV6::new(
-                            MDNS_IPV6_BROADCAST_ADDR,
-                            MDNS_PORT,
-                            0,
-                            interface,
-                        ))
-                    })
-                    .into_iter(),
-            ) {
+            for addr in Self::broadcast_addrs(ipv4_interface, ipv6_interface) {
                 let buffer = self.matter.transport_tx_buffer();
 
                 let mut buf = buffer.get().await.ok_or(ErrorCode::NoSpace)?;
@@ -140,6 +151,43 @@ impl<'a> BuiltinMdnsResponder<'a> {
         }
     }
 
+    async fn query<S>(
+        &self,
+        send: &Mutex<impl RawMutex, S>,
+        ipv4_interface: Option<Ipv4Addr>,
+        ipv6_interface: Option<u32>,
+    ) -> Result<(), Error>
+    where
+        S: NetworkSend,
+    {
+        let Some(querier) = self.querier else {
+            return core::future::pending().await;
+        };
+
+        querier.set_ipv6_interface(ipv6_interface);
+
+        loop {
+            querier.wait_query().await;
+
+            let buffer = self.matter.transport_tx_buffer();
+
+            let mut buf = buffer.get().await.ok_or(ErrorCode::NoSpace)?;
+            let mut send = send.lock().await;
+
+            let len = querier.build_query(&mut buf)?;
+
+            if len > 0 {
+                for addr in Self::broadcast_addrs(ipv4_interface, ipv6_interface) {
+                    if let Err(e) = send.send_to(&buf[..len], Address::Udp(addr)).await {
+                        warn!("Failed to send mDNS query to {}: {}", addr, e);
+                    } else {
+                        debug!("Sent mDNS query to {}", addr);
+                    }
+                }
+            }
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn respond<S, R>(
         &self,
@@ -163,6 +211,20 @@ impl<'a> BuiltinMdnsResponder<'a> {
                 let mut rx = rx_buf.get().await.ok_or(ErrorCode::NoSpace)?;
                 let (len, addr) = recv.recv_from(&mut rx).await?;
 
+                if let Some(querier) = self.querier {
+                    match querier.handle_response(&rx[..len]) {
+                        Ok(true) => continue,
+                        Ok(false) => (),
+                        Err(err) => {
+                            warn!(
+                                "mDNS protocol error {} while parsing a packet from {}",
+                                err, addr
+                            );
+                            continue;
+                        }
+                    }
+                }
+
                 let mut tx = tx_buf.get().await.ok_or(ErrorCode::NoSpace)?;
                 let mut send = send.lock().await;
 
@@ -222,6 +284,27 @@ impl<'a> BuiltinMdnsResponder<'a> {
     }
 }
 
+impl BuiltinMdnsResponder<'_> {
+    fn broadcast_addrs(
+        ipv4_interface: Option<Ipv4Addr>,
+        ipv6_interface: Option<u32>,
+    ) -> impl Iterator<Item = SocketAddr> {
+        Iterator::chain(
+            ipv4_interface
+                .map(|_| SocketAddr::V4(SocketAddrV4::new(MDNS_IPV4_BROADCAST_ADDR, MDNS_PORT)))
+                .into_iter(),
+            ipv6_interface.map(|interface| {
+                SocketAddr::V6(SocketAddrV6::new(
+                    MDNS_IPV6_BROADCAST_ADDR,
+                    MDNS_PORT,
+                    0,
+                    interface,
+                ))
+            }),
+        )
+    }
+}
+
 impl Services for BuiltinMdnsResponder<'_> {
     fn for_each<F>(&self, mut callback: F) -> Result<(), Error>
     where
@@ -15,7 +15,7 @@
  *    limitations under the License.
  */
 
-use core::fmt::Write;
+use core::fmt::{self, Write};
 use core::net::{Ipv4Addr, Ipv6Addr};
 
 use domain::base::header::Flags;
@@ -27,7 +27,7 @@ use domain::base::wire::{Composer, ParseError};
 use domain::base::{Message, MessageBuilder, Name, RecordSectionBuilder, Rtype, ToName};
 use domain::dep::octseq::Truncate;
 use domain::dep::octseq::{OctetsBuilder, ShortBuf};
-use domain::rdata::{Aaaa, Ptr, Srv, Txt, A};
+use domain::rdata::{Aaaa, AllRecordData, Ptr, Srv, Txt, A};
 
 use crate::error::{Error, ErrorCode};
 use crate::utils::bitflags::bitflags;
@@ -391,9 +391,10 @@ impl Host<'_> {
                         *delay = true; // As we reply to a shared resource question, hence we need to avoid collissions
                         replied = true;
                     } else if name.name_eq(&service.service_type_fqdn(true)?) {
-                        service.add_service(answer, self.hostname, ttl_sec)?;
+                        service.add_service_type(answer, ttl_sec)?;
                         *ad |= AdditionalData::SRV;
                         *ad |= AdditionalData::TXT;
+                        *ad |= AdditionalData::IPS;
                         replied = true;
                     } else {
                         for subtype in service.service_subtypes {
@@ -402,6 +403,7 @@ impl Host<'_> {
                                 replied = true;
                                 *ad |= AdditionalData::SRV;
                                 *ad |= AdditionalData::TXT;
+                                *ad |= AdditionalData::IPS;
                                 break;
                             }
                         }
@@ -717,6 +719,165 @@ impl Service<'_> {
     }
 }
 
+/// The maximum length of a DNS name which can be stored in a `NameBuf`
+pub const MAX_NAME_LEN: usize = 64;
+
+/// A DNS name in its textual form, without the trailing dot
+pub type NameBuf = heapless::String<MAX_NAME_LEN>;
+
+/// The maximum length of the TXT data which can be stored in a `TxtBuf`
+pub const MAX_TXT_LEN: usize = 128;
+
+/// The entries of a TXT record in their wire form, i.e. each entry prefixed with its length.
+///
+/// Entries which do not fit are dropped.
+pub type TxtBuf = heapless::Vec<u8, MAX_TXT_LEN>;
+
+/// The data of a DNS record received in an mDNS response
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RecordData {
+    Ptr(NameBuf),
+    Srv { target: NameBuf, port: u16 },
+    Txt(TxtBuf),
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+}
+
+impl RecordData {
+    /// Return `true` if a record with this data should replace a record with the
+    /// other data, assuming both records have the same owner
+    pub fn replaces(&self, other: &RecordData) -> bool {
+        match (self, other) {
+            (Self::Srv { .. }, Self::Srv { .. }) | (Self::Txt(_), Self::Txt(_)) => true,
+            _ => self == other,
+        }
+    }
+}
+
+/// A DNS record received in an mDNS response
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Record {
+    pub owner: NameBuf,
+    pub ttl_sec: u32,
+    pub data: RecordData,
+}
+
+/// Build an mDNS query message with the provided questions
+///
+/// Returns the number of bytes written to the buffer
+pub fn query<'a, I>(id: u16, questions: I, buf: &mut [u8]) -> Result<usize, Error>
+where
+    I: IntoIterator<Item = (&'a str, Rtype)>,
+{
+    let message = MessageBuilder::from_target(Buf(buf, 0))?;
+
+    let mut qb = message.question();
+
+    let header = qb.header_mut();
+    header.set_id(id);
+    header.set_opcode(Opcode::QUERY);
+    header.set_rcode(Rcode::NOERROR);
+    header.set_flags(Flags::new());
+
+    for (name, qtype) in questions {
+        let name = Name::<heapless::Vec<u8, 64>>::from_chars(name.chars())?;
+
+        qb.push((name, qtype, Class::IN))?;
+    }
+
+    Ok(qb.finish().1)
+}
+
+/// Parse the records of interest in the answer and additional sections of an mDNS response
+///
+/// Returns `false` if the message is not a response, but a query
+pub fn parse_response<F>(data: &[u8], mut f: F) -> Result<bool, Error>
+where
+    F: FnMut(Record) -> Result<(), Error>,
+{
+    let message = Message::from_octets(data)?;
+
+    if !message.header().qr() {
+        return Ok(false);
+    }
+
+    for section in [message.answer()?, message.additional()?] {
+        for record in section {
+            let Ok(record) = record?.to_any_record::<AllRecordData<_, _>>() else {
+                // Skip records we cannot parse
+                continue;
+            };
+
+            let data = match record.data() {
+                AllRecordData::Ptr(ptr) => name_buf(ptr.ptrdname()).map(RecordData::Ptr),
+                AllRecordData::Srv(srv) => name_buf(srv.target()).map(|target| RecordData::Srv {
+                    target,
+                    port: srv.port(),
+                }),
+                AllRecordData::Txt(txt) => Some(RecordData::Txt(txt_buf(txt.iter()))),
+                AllRecordData::A(a) => Some(RecordData::A(Ipv4Addr::from(a.addr().octets()))),
+                AllRecordData::Aaaa(aaaa) => {
+                    Some(RecordData::Aaaa(Ipv6Addr::from(aaaa.addr().octets())))
+                }
+                _ => None,
+            };
+
+            let (Some(owner), Some(data)) = (name_buf(record.owner()), data) else {
+                continue;
+            };
+
+            f(Record {
+                owner,
+                ttl_sec: record.ttl().as_secs(),
+                data,
+            })?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Render the DNS name into a `NameBuf`, or return `None` if the name is too long
+fn name_buf(name: impl fmt::Display) -> Option<NameBuf> {
+    let mut buf = NameBuf::new();
+    write!(buf, "{}", name).ok()?;
+
+    if buf.ends_with('.') {
+        buf.pop();
+    }
+
+    Some(buf)
+}
+
+/// Collect the TXT entries into a `TxtBuf`, dropping the entries which do not fit
+fn txt_buf<'a>(entries: impl Iterator<Item = &'a [u8]>) -> TxtBuf {
+    let mut buf = TxtBuf::new();
+
+    for entry in entries {
+        if !entry.is_empty() && buf.len() + entry.len() < MAX_TXT_LEN {
+            unwrap!(buf.push(entry.len() as u8));
+            unwrap!(buf.extend_from_slice(entry));
+        }
+    }
+
+    buf
+}
+
+/// Iterate over the entries of a TXT record stored in a `TxtBuf`
+pub fn txt_entries(txt: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut txt = txt;
+
+    core::iter::from_fn(move || {
+        let (len, rest) = txt.split_first()?;
+        let len = (*len as usize).min(rest.len());
+
+        let (entry, rest) = rest.split_at(len);
+        txt = rest;
+
+        Some(entry)
+    })
+}
+
 struct Buf<'a>(pub &'a mut [u8], pub usize);
 
 impl Composer for Buf<'_> {}
@@ -767,7 +928,7 @@ mod tests {
     use crate::error::Error;
     use crate::transport::network::mdns::Service;
 
-    use super::{Buf, Host, Services};
+    use super::{parse_response, query, txt_entries, Buf, Host, RecordData, Services};
 
     static TEST_HOST_ONLY: TestRun = TestRun {
         host: Host {
@@ -949,6 +1110,102 @@ mod tests {
         TEST_SERVICES.run();
     }
 
+    #[test]
+    fn test_query_and_parse_response() {
+        let mut buf1 = [0; 1500];
+        let mut buf2 = [0; 1500];
+
+        let len = unwrap!(query(
+            0,
+            [("bar._matterc._udp.local", Rtype::SRV)],
+            &mut buf1
+        ));
+
+        // A query is not a response
+        assert!(!unwrap!(parse_response(&buf1[..len], |_| Ok(()))));
+
+        let (len, _) = unwrap!(TEST_SERVICES.host.respond(
+            TEST_SERVICES.services,
+            &buf1[..len],
+            &mut buf2,
+            60
+        ));
+
+        let mut records = heapless::Vec::<_, 4>::new();
+        assert!(unwrap!(parse_response(&buf2[..len], |record| {
+            unwrap!(records.push(record));
+            Ok(())
+        })));
+
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|record| record.ttl_sec == 60));
+
+        assert_eq!(records[0].owner, "bar._matterc._udp.local");
+        assert_eq!(
+            records[0].data,
+            RecordData::Srv {
+                target: unwrap!("foo.local".try_into()),
+                port: 1234
+            }
+        );
+
+        assert_eq!(records[1].owner, "foo.local");
+        assert_eq!(
+            records[1].data,
+            RecordData::A(Ipv4Addr::new(192, 168, 0, 1))
+        );
+
+        assert_eq!(records[2].owner, "foo.local");
+        assert_eq!(
+            records[2].data,
+            RecordData::Aaaa(Ipv6Addr::new(0xfb, 0, 0, 0, 0, 0, 0, 1))
+        );
+    }
+
+    #[test]
+    fn test_browse_response() {
+        let mut buf1 = [0; 1500];
+        let mut buf2 = [0; 1500];
+
+        let len = unwrap!(query(0, [("_matterc._udp.local", Rtype::PTR)], &mut buf1));
+
+        let (len, _) = unwrap!(TEST_SERVICES.host.respond(
+            TEST_SERVICES.services,
+            &buf1[..len],
+            &mut buf2,
+            60
+        ));
+
+        let mut records = heapless::Vec::<_, 8>::new();
+        assert!(unwrap!(parse_response(&buf2[..len], |record| {
+            unwrap!(records.push(record));
+            Ok(())
+        })));
+
+        // The service type PTR record in the answer
+        assert_eq!(records[0].owner, "_matterc._udp.local");
+        assert_eq!(
+            records[0].data,
+            RecordData::Ptr(unwrap!("bar._matterc._udp.local".try_into()))
+        );
+
+        // The SRV, TXT and address records in the additional section
+        assert!(records
+            .iter()
+            .any(|record| record.owner == "bar._matterc._udp.local"
+                && matches!(record.data, RecordData::Srv { port: 1234, .. })));
+        assert!(records
+            .iter()
+            .any(|record| matches!(record.data, RecordData::A(_))));
+
+        let txt = unwrap!(records.iter().find_map(|record| match &record.data {
+            RecordData::Txt(txt) if record.owner == "bar._matterc._udp.local" => Some(txt),
+            _ => None,
+        }));
+
+        assert!(txt_entries(txt).eq([b"a=b".as_slice(), b"c=d".as_slice()]));
+    }
+
     struct TestRun<'a> {
         host: Host<'a>,
         services: &'a [Service<'a>],
@@ -0,0 +1,741 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+//! The querying side of the built-in mDNS implementation.
+
+use core::cell::Cell;
+use core::fmt::Write;
+use core::net::{IpAddr, Ipv6Addr};
+
+use domain::base::Rtype;
+
+use embassy_futures::select::{select, Either};
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::error::{Error, ErrorCode};
+use crate::transport::network::mdns::{
+    operational_service_type, CommissionableFilter, CommissionableNode, MdnsBrowser, MdnsResolver,
+    OperationalNode, MAX_NODE_ADDRS,
+};
+use crate::transport::network::{SocketAddr, SocketAddrV4, SocketAddrV6};
+use crate::utils::cell::RefCell;
+use crate::utils::sync::Notification;
+
+use super::proto::{self, NameBuf, Record, RecordData, TxtBuf};
+
+/// The maximum number of records the querier keeps in its cache
+const MAX_CACHED_RECORDS: usize = 24;
+
+/// The maximum number of questions pending to be sent
+const MAX_QUESTIONS: usize = 8;
+
+/// The maximum number of service instances reported when browsing
+const MAX_BROWSED_INSTANCES: usize = 8;
+
+/// How many times to send a query before giving up on resolving a node
+const QUERY_ATTEMPTS: usize = 3;
+
+/// How long to wait for answers after sending a query
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct CacheEntry {
+    record: Record,
+    expires: Instant,
+}
+
+/// A cache of the records received in mDNS responses, which expires the records according to their TTLs.
+pub struct MdnsCache<const N: usize> {
+    entries: heapless::Vec<CacheEntry, N>,
+}
+
+impl<const N: usize> MdnsCache<N> {
+    /// Create a new, empty cache
+    pub const fn new() -> Self {
+        Self {
+            entries: heapless::Vec::new(),
+        }
+    }
+
+    /// Update the cache with the provided record, received at `now`.
+    ///
+    /// A record with a TTL of 0 removes the matching record from the cache.
+    /// If the cache is full, the record which is closest to expiring is evicted.
+    pub fn update(&mut self, record: Record, now: Instant) {
+        self.purge(now);
+
+        let existing = self.entries.iter().position(|entry| {
+            entry.record.owner.eq_ignore_ascii_case(&record.owner)
+                && record.data.replaces(&entry.record.data)
+        });
+
+        if record.ttl_sec == 0 {
+            if let Some(index) = existing {
+                self.entries.swap_remove(index);
+            }
+
+            return;
+        }
+
+        let entry = CacheEntry {
+            expires: now + Duration::from_secs(record.ttl_sec as _),
+            record,
+        };
+
+        if let Some(index) = existing {
+            self.entries[index] = entry;
+        } else {
+            if self.entries.is_full() {
+                let oldest = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, entry)| entry.expires)
+                    .map(|(index, _)| index);
+
+                self.entries.swap_remove(unwrap!(oldest));
+            }
+
+            unwrap!(self.entries.push(entry).map_err(|_| ErrorCode::NoSpace));
+        }
+    }
+
+    /// Remove all records which had expired at `now`
+    pub fn purge(&mut self, now: Instant) {
+        self.entries.retain(|entry| entry.expires > now);
+    }
+
+    /// Iterate over all records which are not expired at `now`
+    pub fn iter(&self, now: Instant) -> impl Iterator<Item = &Record> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.expires > now)
+            .map(|entry| &entry.record)
+    }
+
+    /// Return the target host and port of the SRV record of the provided service instance
+    pub fn srv(&self, instance: &str, now: Instant) -> Option<(&str, u16)> {
+        self.iter(now).find_map(|record| match &record.data {
+            RecordData::Srv { target, port } if record.owner.eq_ignore_ascii_case(instance) => {
+                Some((target.as_str(), *port))
+            }
+            _ => None,
+        })
+    }
+
+    /// Iterate over the service instances the PTR records of the provided service point to
+    pub fn ptrs<'a>(
+        &'a self,
+        service: &'a str,
+        now: Instant,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.iter(now).filter_map(move |record| match &record.data {
+            RecordData::Ptr(instance) if record.owner.eq_ignore_ascii_case(service) => {
+                Some(instance.as_str())
+            }
+            _ => None,
+        })
+    }
+
+    /// Return the TXT data of the provided service instance, as a sequence of length-prefixed entries
+    pub fn txt(&self, instance: &str, now: Instant) -> Option<&[u8]> {
+        self.iter(now).find_map(|record| match &record.data {
+            RecordData::Txt(txt) if record.owner.eq_ignore_ascii_case(instance) => {
+                Some(txt.as_slice())
+            }
+            _ => None,
+        })
+    }
+
+    /// Iterate over the IP addresses of the provided host
+    pub fn addrs<'a>(&'a self, host: &'a str, now: Instant) -> impl Iterator<Item = IpAddr> + 'a {
+        self.iter(now)
+            .filter(move |record| record.owner.eq_ignore_ascii_case(host))
+            .filter_map(|record| match &record.data {
+                RecordData::A(ip) => Some(IpAddr::V4(*ip)),
+                RecordData::Aaaa(ip) => Some(IpAddr::V6(*ip)),
+                _ => None,
+            })
+    }
+}
+
+impl<const N: usize> Default for MdnsCache<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The outcome of looking up a service instance in the cache
+enum Lookup {
+    /// The service instance is resolved to a socket address
+    Resolved(SocketAddr),
+    /// The SRV record of the service instance is missing
+    Srv,
+    /// The addresses of the service instance target host are missing
+    Addr(NameBuf),
+}
+
+/// A built-in mDNS querier, which resolves Matter nodes by sending mDNS queries and caching the answers.
+///
+/// `no_std` and `no-alloc` and thus suitable for MCUs as well when there is no running mDNS service as part of the OS.
+///
+/// The querier does not do any network IO on its own. Instead, it piggybacks on the socket of the
+/// `BuiltinMdnsResponder` it is registered with (see `BuiltinMdnsResponder::new_with_querier`), which sends
+/// the pending queries of the querier and feeds it with the mDNS responses it receives.
+pub struct BuiltinMdnsQuerier {
+    cache: RefCell<MdnsCache<MAX_CACHED_RECORDS>>,
+    questions: RefCell<heapless::Vec<(NameBuf, Rtype), MAX_QUESTIONS>>,
+    ipv6_interface: Cell<Option<u32>>,
+    query_notification: Notification<NoopRawMutex>,
+    answer_notification: Notification<NoopRawMutex>,
+    lock: Mutex<NoopRawMutex, ()>,
+}
+
+impl BuiltinMdnsQuerier {
+    /// Create a new instance of the built-in mDNS querier.
+    pub const fn new() -> Self {
+        Self {
+            cache: RefCell::new(MdnsCache::new()),
+            questions: RefCell::new(heapless::Vec::new()),
+            ipv6_interface: Cell::new(None),
+            query_notification: Notification::new(),
+            answer_notification: Notification::new(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Resolve the operational address of the Matter node with the provided node ID
+    /// on the fabric with the provided compressed fabric ID.
+    ///
+    /// Answers are served from the cache if possible; otherwise, the querier queries the network
+    /// for the SRV record of the node, and then for the addresses of the node host, if these
+    /// were not included in the response.
+    pub async fn resolve(
+        &self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        // Only one resolution or browsing at a time, as there is a single waiter for the answers
+        let _lock = self.lock.lock().await;
+
+        let mut instance = NameBuf::new();
+        write!(
+            &mut instance,
+            "{:016X}-{:016X}._matter._tcp.local",
+            compressed_fabric_id, node_id
+        )
+        .map_err(|_| ErrorCode::NoSpace)?;
+
+        for _ in 0..QUERY_ATTEMPTS {
+            let deadline = Instant::now() + QUERY_TIMEOUT;
+
+            // What we last queried for during this attempt, so that we query again right away
+            // should the answers reveal that we are missing something else
+            // (i.e. we got the SRV record, but not the addresses of the host)
+            let mut queried = None;
+
+            loop {
+                match self.lookup(&instance) {
+                    Lookup::Resolved(addr) => {
+                        debug!("Resolved mDNS instance {} to {}", instance, addr);
+                        return Ok(addr);
+                    }
+                    Lookup::Srv if queried != Some(Rtype::SRV) => {
+                        self.query(&[(instance.as_str(), Rtype::SRV)]);
+                        queried = Some(Rtype::SRV);
+                    }
+                    Lookup::Addr(host) if queried != Some(Rtype::AAAA) => {
+                        self.query(&[(host.as_str(), Rtype::AAAA), (host.as_str(), Rtype::A)]);
+                        queried = Some(Rtype::AAAA);
+                    }
+                    _ => (),
+                }
+
+                let answer = select(self.answer_notification.wait(), Timer::at(deadline)).await;
+
+                if matches!(answer, Either::Second(_)) {
+                    break;
+                }
+            }
+        }
+
+        warn!("Failed to resolve mDNS instance {}", instance);
+
+        Err(ErrorCode::NotFound.into())
+    }
+
+    /// Browse for commissionable Matter nodes.
+    ///
+    /// The querier queries the network for the `_matterc._udp` service (or its subtype corresponding
+    /// to the filter), follows up with queries for the SRV, TXT and address records which were not included
+    /// in the responses, and once `timeout` elapses, calls `f` with each discovered node matching the filter.
+    pub async fn browse_commissionable<F>(
+        &self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        let mut service = NameBuf::new();
+        write!(&mut service, "{}.local", filter.service_type()).map_err(|_| ErrorCode::NoSpace)?;
+
+        self.browse(
+            &service,
+            timeout,
+            |instance, txt, addrs| match CommissionableNode::new(
+                instance,
+                addrs,
+                proto::txt_entries(txt),
+            ) {
+                Some(node) if filter.matches(&node) => f(&node),
+                _ => Ok(()),
+            },
+        )
+        .await
+    }
+
+    /// Browse for operational Matter nodes.
+    ///
+    /// Same as `browse_commissionable`, but for the `_matter._tcp` service, or its `_I<compressed-fabric-id>`
+    /// subtype if `compressed_fabric_id` is provided.
+    pub async fn browse_operational<F>(
+        &self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        let mut service = NameBuf::new();
+        write!(
+            &mut service,
+            "{}.local",
+            operational_service_type(compressed_fabric_id)
+        )
+        .map_err(|_| ErrorCode::NoSpace)?;
+
+        self.browse(
+            &service,
+            timeout,
+            |instance, txt, addrs| match OperationalNode::new(
+                instance,
+                addrs,
+                proto::txt_entries(txt),
+            ) {
+                Some(node)
+                    if compressed_fabric_id
+                        .is_none_or(|cfid| cfid == node.compressed_fabric_id) =>
+                {
+                    f(&node)
+                }
+                _ => Ok(()),
+            },
+        )
+        .await
+    }
+
+    /// Update the cache with the records of the provided mDNS message, if it is a response.
+    ///
+    /// Returns `false` if the message is not a response but a query.
+    pub(crate) fn handle_response(&self, data: &[u8]) -> Result<bool, Error> {
+        let now = Instant::now();
+        let mut updated = false;
+
+        let response = proto::parse_response(data, |record| {
+            trace!("Caching mDNS record {:?}", debug2format!(&record));
+
+            self.cache.borrow_mut().update(record, now);
+            updated = true;
+
+            Ok(())
+        })?;
+
+        if updated {
+            self.answer_notification.notify();
+        }
+
+        Ok(response)
+    }
+
+    /// Wait until there are questions pending to be sent
+    pub(crate) async fn wait_query(&self) {
+        self.query_notification.wait().await
+    }
+
+    /// Build a query message with all pending questions, removing them from the pending ones.
+    ///
+    /// Returns the number of bytes written to the buffer, or 0 if there are no pending questions.
+    pub(crate) fn build_query(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut questions = self.questions.borrow_mut();
+
+        if questions.is_empty() {
+            return Ok(0);
+        }
+
+        // Multicast queries should have an ID of 0, as per RFC 6762
+        let len = proto::query(
+            0,
+            questions
+                .iter()
+                .map(|(name, qtype)| (name.as_str(), *qtype)),
+            buf,
+        )?;
+
+        questions.clear();
+
+        Ok(len)
+    }
+
+    /// Set the index of the IPv6 interface on which the mDNS queries are sent,
+    /// so that resolved link-local addresses can be scoped to it
+    pub(crate) fn set_ipv6_interface(&self, ipv6_interface: Option<u32>) {
+        self.ipv6_interface.set(ipv6_interface);
+    }
+
+    fn query(&self, questions: &[(&str, Rtype)]) {
+        {
+            let mut pending = self.questions.borrow_mut();
+
+            for (name, qtype) in questions {
+                let Ok(name) = NameBuf::try_from(*name) else {
+                    continue;
+                };
+
+                if !pending.iter().any(|q| q.0 == name && q.1 == *qtype) {
+                    let _ = pending.push((name, *qtype));
+                }
+            }
+        }
+
+        self.query_notification.notify();
+    }
+
+    /// Browse for the instances of the provided service for the duration of `timeout`, and then
+    /// call `f` with the instance name, TXT data and addresses of each resolved instance
+    async fn browse<F>(&self, service: &str, timeout: Duration, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(&str, &[u8], &[SocketAddr]) -> Result<(), Error>,
+    {
+        let _lock = self.lock.lock().await;
+
+        let deadline = Instant::now() + timeout;
+
+        // The service instances and hosts we already sent follow-up queries for
+        let mut queried = heapless::Vec::<NameBuf, { MAX_BROWSED_INSTANCES * 2 }>::new();
+
+        self.query(&[(service, Rtype::PTR)]);
+
+        loop {
+            self.query_missing(service, &mut queried);
+
+            let answer = select(self.answer_notification.wait(), Timer::at(deadline)).await;
+
+            if matches!(answer, Either::Second(_)) {
+                break;
+            }
+        }
+
+        let now = Instant::now();
+
+        let mut instances = heapless::Vec::<NameBuf, MAX_BROWSED_INSTANCES>::new();
+        for instance in self.cache.borrow().ptrs(service, now) {
+            if !instances.iter().any(|i| i.eq_ignore_ascii_case(instance)) {
+                let _ = instances.push(unwrap!(instance.try_into()));
+            }
+        }
+
+        for instance in &instances {
+            let mut txt = TxtBuf::new();
+            let mut addrs = heapless::Vec::<_, MAX_NODE_ADDRS>::new();
+
+            {
+                let cache = self.cache.borrow();
+
+                let Some((host, port)) = cache.srv(instance, now) else {
+                    continue;
+                };
+
+                if let Some(data) = cache.txt(instance, now) {
+                    unwrap!(txt.extend_from_slice(data));
+                }
+
+                // IPv6 addresses first, as these are mandatory in Matter
+                for ip in cache
+                    .addrs(host, now)
+                    .filter(IpAddr::is_ipv6)
+                    .chain(cache.addrs(host, now).filter(IpAddr::is_ipv4))
+                {
+                    if addrs.push(self.socket_addr(ip, port)).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            if addrs.is_empty() {
+                continue;
+            }
+
+            let name = instance.split('.').next().unwrap_or_default();
+
+            f(name, &txt, &addrs)?;
+        }
+
+        Ok(())
+    }
+
+    /// Send queries for the SRV, TXT and address records which are missing in the cache
+    /// for the instances of the provided service
+    fn query_missing(
+        &self,
+        service: &str,
+        queried: &mut heapless::Vec<NameBuf, { MAX_BROWSED_INSTANCES * 2 }>,
+    ) {
+        let now = Instant::now();
+        let cache = self.cache.borrow();
+
+        for instance in cache.ptrs(service, now).take(MAX_BROWSED_INSTANCES) {
+            let (name, questions) = match cache.srv(instance, now) {
+                None => (instance, [Rtype::SRV, Rtype::TXT]),
+                Some(_) if cache.txt(instance, now).is_none() => {
+                    (instance, [Rtype::SRV, Rtype::TXT])
+                }
+                Some((host, _)) if cache.addrs(host, now).next().is_none() => {
+                    (host, [Rtype::AAAA, Rtype::A])
+                }
+                _ => continue,
+            };
+
+            if queried.iter().any(|q| q.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+
+            let Ok(name_buf) = NameBuf::try_from(name) else {
+                continue;
+            };
+
+            if queried.push(name_buf).is_ok() {
+                self.query(&[(name, questions[0]), (name, questions[1])]);
+            }
+        }
+    }
+
+    fn lookup(&self, instance: &str) -> Lookup {
+        let now = Instant::now();
+        let cache = self.cache.borrow();
+
+        let Some((host, port)) = cache.srv(instance, now) else {
+            return Lookup::Srv;
+        };
+
+        let mut resolved = None;
+
+        for ip in cache.addrs(host, now) {
+            match ip {
+                IpAddr::V6(_) => {
+                    // Prefer IPv6 addresses, as these are mandatory in Matter
+                    resolved = Some(self.socket_addr(ip, port));
+                    break;
+                }
+                IpAddr::V4(_) if resolved.is_none() => {
+                    resolved = Some(self.socket_addr(ip, port));
+                }
+                _ => (),
+            }
+        }
+
+        resolved
+            .map(Lookup::Resolved)
+            .unwrap_or_else(|| Lookup::Addr(unwrap!(host.try_into())))
+    }
+
+    /// Create a socket address from the provided IP address and port, scoping
+    /// IPv6 link-local addresses to the interface the mDNS queries are sent on
+    fn socket_addr(&self, ip: IpAddr, port: u16) -> SocketAddr {
+        match ip {
+            IpAddr::V6(ip) => {
+                let scope_id = if Self::is_link_local(&ip) {
+                    self.ipv6_interface.get().unwrap_or(0)
+                } else {
+                    0
+                };
+
+                SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id))
+            }
+            IpAddr::V4(ip) => SocketAddr::V4(SocketAddrV4::new(ip, port)),
+        }
+    }
+
+    fn is_link_local(ip: &Ipv6Addr) -> bool {
+        ip.segments()[0] & 0xffc0 == 0xfe80
+    }
+}
+
+impl Default for BuiltinMdnsQuerier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MdnsResolver for &BuiltinMdnsQuerier {
+    async fn resolve(
+        &mut self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        BuiltinMdnsQuerier::resolve(self, compressed_fabric_id, node_id).await
+    }
+}
+
+impl MdnsBrowser for &BuiltinMdnsQuerier {
+    async fn browse_commissionable<F>(
+        &mut self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        BuiltinMdnsQuerier::browse_commissionable(self, filter, timeout, f).await
+    }
+
+    async fn browse_operational<F>(
+        &mut self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        BuiltinMdnsQuerier::browse_operational(self, compressed_fabric_id, timeout, f).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use embassy_time::Instant;
+
+    use super::{MdnsCache, Record, RecordData};
+
+    fn record(owner: &str, ttl_sec: u32, data: RecordData) -> Record {
+        Record {
+            owner: unwrap!(owner.try_into()),
+            ttl_sec,
+            data,
+        }
+    }
+
+    fn srv(target: &str, port: u16) -> RecordData {
+        RecordData::Srv {
+            target: unwrap!(target.try_into()),
+            port,
+        }
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let mut cache = MdnsCache::<4>::new();
+
+        let now = Instant::from_secs(100);
+
+        cache.update(
+            record("foo._matter._tcp.local", 10, srv("foo.local", 5540)),
+            now,
+        );
+        cache.update(
+            record("FOO.local", 20, RecordData::A(Ipv4Addr::new(1, 2, 3, 4))),
+            now,
+        );
+
+        assert_eq!(
+            cache.srv("foo._matter._tcp.local", now),
+            Some(("foo.local", 5540))
+        );
+        assert_eq!(
+            cache
+                .addrs("foo.local", now)
+                .collect::<heapless::Vec<_, 2>>(),
+            [IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))]
+        );
+
+        // The SRV record expires first
+        let later = Instant::from_secs(115);
+        assert!(cache.srv("foo._matter._tcp.local", later).is_none());
+        assert_eq!(cache.addrs("foo.local", later).count(), 1);
+
+        cache.purge(later);
+        assert_eq!(cache.iter(later).count(), 1);
+
+        // A TTL of 0 removes the record
+        cache.update(
+            record("foo.local", 0, RecordData::A(Ipv4Addr::new(1, 2, 3, 4))),
+            later,
+        );
+        assert_eq!(cache.iter(later).count(), 0);
+    }
+
+    #[test]
+    fn test_cache_replace_and_evict() {
+        let mut cache = MdnsCache::<2>::new();
+
+        let now = Instant::from_secs(100);
+
+        cache.update(
+            record("foo._matter._tcp.local", 10, srv("foo.local", 5540)),
+            now,
+        );
+        cache.update(
+            record("foo._matter._tcp.local", 30, srv("bar.local", 5541)),
+            now,
+        );
+
+        // The new SRV record replaces the old one
+        assert_eq!(cache.iter(now).count(), 1);
+        assert_eq!(
+            cache.srv("foo._matter._tcp.local", now),
+            Some(("bar.local", 5541))
+        );
+
+        // Different addresses of the same host are all kept
+        cache.update(
+            record("bar.local", 20, RecordData::Aaaa(Ipv6Addr::LOCALHOST)),
+            now,
+        );
+        assert_eq!(cache.iter(now).count(), 2);
+
+        // The record closest to expiring is evicted when the cache is full
+        cache.update(
+            record("bar.local", 60, RecordData::A(Ipv4Addr::LOCALHOST)),
+            now,
+        );
+        assert_eq!(cache.iter(now).count(), 2);
+        assert_eq!(
+            cache
+                .addrs("bar.local", now)
+                .collect::<heapless::Vec<_, 2>>(),
+            [IpAddr::V4(Ipv4Addr::LOCALHOST)]
+        );
+        assert!(cache.srv("foo._matter._tcp.local", now).is_some());
+    }
+}
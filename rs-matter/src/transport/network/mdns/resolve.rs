@@ -19,16 +19,42 @@
 //!
 //! Requires the systemd-resolved daemon to be installed, configured with mDNS enabled and running.
 
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
 use std::collections::{HashMap, HashSet};
 
+use domain::base::iana::Class;
+use domain::base::name::ParsedName;
+use domain::base::{Record, Rtype};
+use domain::dep::octseq::Parser;
+use domain::rdata::Ptr;
+
+use embassy_time::{with_timeout, Duration};
+
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 use zbus::Connection;
 
-use crate::error::Error;
-use crate::transport::network::mdns::Service;
+use crate::error::{Error, ErrorCode};
+use crate::transport::network::mdns::{
+    operational_service_type, CommissionableFilter, CommissionableNode, MdnsBrowser, MdnsResolver,
+    OperationalNode, Service,
+};
 use crate::utils::zbus_proxies::resolve::manager::ManagerProxy;
 use crate::{Matter, MatterMdnsService};
 
+/// Resolve over mDNS on IPv4 (`SD_RESOLVED_MDNS_IPV4`)
+const SD_RESOLVED_MDNS_IPV4: u64 = 1 << 3;
+
+/// Resolve over mDNS on IPv6 (`SD_RESOLVED_MDNS_IPV6`)
+const SD_RESOLVED_MDNS_IPV6: u64 = 1 << 4;
+
+/// Resolve over mDNS only
+const SD_RESOLVED_MDNS: u64 = SD_RESOLVED_MDNS_IPV4 | SD_RESOLVED_MDNS_IPV6;
+
+/// An SRV record as returned by the systemd-resolved `ResolveService` method:
+/// priority, weight, port, host name, addresses (interface index, address family, address) and canonical host name
+type ResolvedSrv = (u16, u16, u16, String, Vec<(i32, i32, Vec<u8>)>, String);
+
 /// An mDNS responder for Matter utilizing the systemd-resolved daemon over DBus.
 ///
 /// Note that typically Ubuntu Desktop and other desktop distros - while distributing and running `systemd-resolved` -
@@ -172,3 +198,275 @@ impl<'a> ResolveMdnsResponder<'a> {
         Ok(())
     }
 }
+
+/// An mDNS querier for Matter utilizing the systemd-resolved daemon over DBus.
+///
+/// Resolves and browses for Matter nodes via the system resolver, which - unlike the
+/// built-in mDNS querier - does not need to compete with the daemon for the mDNS port.
+///
+/// See `ResolveMdnsResponder` for how to configure the systemd-resolved daemon with mDNS enabled.
+pub struct ResolveMdnsQuerier<'a> {
+    connection: &'a Connection,
+}
+
+impl<'a> ResolveMdnsQuerier<'a> {
+    /// Create a new instance of the systemd-resolved mDNS querier.
+    ///
+    /// # Arguments
+    /// - `connection`: A reference to the DBus system connection to use for communication with systemd-resolved.
+    pub const fn new(connection: &'a Connection) -> Self {
+        Self { connection }
+    }
+
+    /// Resolve the operational address of the Matter node with the provided node ID
+    /// on the fabric with the provided compressed fabric ID.
+    pub async fn resolve(
+        &self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        let resolve = ManagerProxy::new(self.connection).await?;
+
+        let instance = format!("{:016X}-{:016X}", compressed_fabric_id, node_id);
+
+        let (srvs, _, _, _, _, _) = resolve
+            .resolve_service(0, &instance, "_matter._tcp", "local", 0, SD_RESOLVED_MDNS)
+            .await
+            .map_err(|e| {
+                warn!("Failed to resolve mDNS instance {}: {}", instance, e);
+                ErrorCode::NotFound
+            })?;
+
+        let addr = Self::socket_addrs(srvs)
+            .first()
+            .copied()
+            .ok_or(ErrorCode::NotFound)?;
+
+        debug!("Resolved mDNS instance {} to {}", instance, addr);
+
+        Ok(addr)
+    }
+
+    /// Browse for commissionable Matter nodes.
+    ///
+    /// Queries for the PTR records of the `_matterc._udp` service (or its subtype corresponding to the filter),
+    /// and then resolves and calls `f` with each discovered node matching the filter.
+    ///
+    /// Note that systemd-resolved completes the PTR query on its own terms (usually with the first responses),
+    /// so `timeout` only bounds the duration of the whole operation.
+    pub async fn browse_commissionable<F>(
+        &self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        self.browse(
+            &filter.service_type(),
+            "_matterc._udp",
+            timeout,
+            |instance, txt, addrs| match CommissionableNode::new(
+                instance,
+                addrs,
+                txt.iter().map(Vec::as_slice),
+            ) {
+                Some(node) if filter.matches(&node) => f(&node),
+                _ => Ok(()),
+            },
+        )
+        .await
+    }
+
+    /// Browse for operational Matter nodes.
+    ///
+    /// Same as `browse_commissionable`, but for the `_matter._tcp` service, or its `_I<compressed-fabric-id>`
+    /// subtype if `compressed_fabric_id` is provided.
+    pub async fn browse_operational<F>(
+        &self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        mut f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        self.browse(
+            &operational_service_type(compressed_fabric_id),
+            "_matter._tcp",
+            timeout,
+            |instance, txt, addrs| match OperationalNode::new(
+                instance,
+                addrs,
+                txt.iter().map(Vec::as_slice),
+            ) {
+                Some(node)
+                    if compressed_fabric_id
+                        .is_none_or(|cfid| cfid == node.compressed_fabric_id) =>
+                {
+                    f(&node)
+                }
+                _ => Ok(()),
+            },
+        )
+        .await
+    }
+
+    /// Query for the instances of the provided service type (which might be a subtype of `service`),
+    /// and then resolve and call `f` with the instance name, TXT entries and addresses of each instance
+    async fn browse<F>(
+        &self,
+        service_type: &str,
+        service: &str,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&str, &[Vec<u8>], &[SocketAddr]) -> Result<(), Error>,
+    {
+        match with_timeout(timeout, self.browse_all(service_type, service, f)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Browsing for mDNS service {} timed out", service_type);
+                Ok(())
+            }
+        }
+    }
+
+    async fn browse_all<F>(&self, service_type: &str, service: &str, mut f: F) -> Result<(), Error>
+    where
+        F: FnMut(&str, &[Vec<u8>], &[SocketAddr]) -> Result<(), Error>,
+    {
+        let resolve = ManagerProxy::new(self.connection).await?;
+
+        let name = format!("{service_type}.local");
+
+        let (records, _) = resolve
+            .resolve_record(
+                0,
+                &name,
+                Class::IN.to_int(),
+                Rtype::PTR.to_int(),
+                SD_RESOLVED_MDNS,
+            )
+            .await?;
+
+        let mut instances = Vec::new();
+
+        for (_, _, _, data) in records {
+            // The records are returned in their wire format
+            let record = Record::<ParsedName<&[u8]>, Ptr<ParsedName<&[u8]>>>::parse(
+                &mut Parser::from_ref(&data.as_slice()),
+            )?
+            .ok_or(ErrorCode::MdnsError)?;
+
+            let Ok(instance) = core::str::from_utf8(record.data().ptrdname().first().as_slice())
+            else {
+                continue;
+            };
+
+            if !instances.iter().any(|i: &String| i == instance) {
+                instances.push(instance.to_string());
+            }
+        }
+
+        for instance in instances {
+            let resolved = resolve
+                .resolve_service(0, &instance, service, "local", 0, SD_RESOLVED_MDNS)
+                .await;
+
+            let (srvs, txt) = match resolved {
+                Ok((srvs, txt, _, _, _, _)) => (srvs, txt),
+                Err(e) => {
+                    warn!("Failed to resolve mDNS instance {}: {}", instance, e);
+                    continue;
+                }
+            };
+
+            let addrs = Self::socket_addrs(srvs);
+
+            if !addrs.is_empty() {
+                f(&instance, &txt, &addrs)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collect the socket addresses from the SRV records resolved by systemd-resolved,
+    /// with the IPv6 addresses first, as these are mandatory in Matter.
+    ///
+    /// IPv6 link-local addresses are scoped to the interface they were resolved on.
+    fn socket_addrs(srvs: Vec<ResolvedSrv>) -> Vec<SocketAddr> {
+        let mut addrs = Vec::new();
+
+        for (_, _, port, _, srv_addrs, _) in srvs {
+            for (ifindex, family, addr) in srv_addrs {
+                let addr = match (family, addr.len()) {
+                    (libc::AF_INET6, 16) => {
+                        let ip = Ipv6Addr::from(unwrap!(<[u8; 16]>::try_from(addr.as_slice())));
+
+                        let scope_id = if ip.segments()[0] & 0xffc0 == 0xfe80 {
+                            ifindex as u32
+                        } else {
+                            0
+                        };
+
+                        SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id))
+                    }
+                    (libc::AF_INET, 4) => {
+                        let ip = Ipv4Addr::from(unwrap!(<[u8; 4]>::try_from(addr.as_slice())));
+
+                        SocketAddr::V4(SocketAddrV4::new(ip, port))
+                    }
+                    _ => continue,
+                };
+
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+        }
+
+        addrs.sort_by_key(SocketAddr::is_ipv4);
+
+        addrs
+    }
+}
+
+impl MdnsResolver for ResolveMdnsQuerier<'_> {
+    async fn resolve(
+        &mut self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        ResolveMdnsQuerier::resolve(self, compressed_fabric_id, node_id).await
+    }
+}
+
+impl MdnsBrowser for ResolveMdnsQuerier<'_> {
+    async fn browse_commissionable<F>(
+        &mut self,
+        filter: &CommissionableFilter,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&CommissionableNode) -> Result<(), Error>,
+    {
+        ResolveMdnsQuerier::browse_commissionable(self, filter, timeout, f).await
+    }
+
+    async fn browse_operational<F>(
+        &mut self,
+        compressed_fabric_id: Option<u64>,
+        timeout: Duration,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&OperationalNode) -> Result<(), Error>,
+    {
+        ResolveMdnsQuerier::browse_operational(self, compressed_fabric_id, timeout, f).await
+    }
+}
@@ -0,0 +1,578 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+#![cfg(all(feature = "std", feature = "async-io"))]
+
+//! TCP transport implementation for async-io
+//!
+//! Matter messages over TCP are framed by prefixing each message with its length,
+//! as a 32-bit little-endian unsigned integer.
+
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::Poll;
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::rc::Rc;
+
+use async_io::Async;
+
+use embassy_futures::select::{select4, Either4};
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::error::*;
+use crate::transport::network::Address;
+use crate::utils::cell::RefCell;
+use crate::utils::sync::Notification;
+
+use super::{NetworkReceive, NetworkSend};
+
+/// The length of the message length prefix of each Matter message sent over TCP
+const FRAME_LEN_SIZE: usize = 4;
+
+/// The maximum length of an incoming Matter message; connections announcing longer messages are closed
+const MAX_FRAME_SIZE: usize = super::MAX_RX_LARGE_PACKET_SIZE;
+
+/// The default maximum number of TCP connections kept open at the same time
+pub const DEFAULT_MAX_CONNECTIONS: usize = 8;
+
+/// The default duration after which a TCP connection without any traffic is closed
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+struct Connection {
+    peer: SocketAddr,
+    stream: Rc<Async<TcpStream>>,
+    last_used: Instant,
+    /// The data of the incoming message which is still being received, including its length prefix
+    rx: Vec<u8>,
+}
+
+/// What woke up the waiting for an incoming message
+enum Event {
+    Accepted(Async<TcpStream>, SocketAddr),
+    Readable(SocketAddr),
+    Closed(SocketAddr),
+    Changed,
+    Idle,
+}
+
+/// A TCP network implementation for the Matter transport, based on async-io.
+///
+/// Keeps a pool of TCP connections keyed by the peer address:
+/// - Connections are accepted from the (optional) listener, or established on demand
+///   when sending a message to a peer the pool does not have a connection to yet;
+/// - When the pool is full, the least recently used connection is closed to make room for a new one;
+/// - Connections without any traffic for longer than the idle timeout are closed.
+///
+/// Incoming data is buffered per connection until a complete message is received,
+/// so that a peer sending a message slowly (or only partially) does not hold up the other connections.
+///
+/// Since TCP is a reliable transport, the Matter transport does not use MRP for messages sent over it.
+///
+/// Just like with `&Async<UdpSocket>`, a `&TcpNetwork` reference is both the `NetworkSend`
+/// and the `NetworkReceive` part of the network.
+/// It is typically chained with the UDP network, as in `ChainedNetwork::new(Address::is_tcp, &tcp, &udp)`,
+/// and the result is passed to `TransportMgr::run`.
+pub struct TcpNetwork {
+    listener: Option<Async<TcpListener>>,
+    connections: RefCell<Vec<Connection>>,
+    changed: Notification<NoopRawMutex>,
+    max_connections: usize,
+    idle_timeout: Duration,
+}
+
+impl TcpNetwork {
+    /// Create a new TCP network with the default connection limit and idle timeout.
+    ///
+    /// # Arguments
+    /// - `listener`: The listener to accept incoming connections from; if `None`, the network
+    ///   only establishes outgoing connections.
+    pub fn new(listener: Option<Async<TcpListener>>) -> Self {
+        Self::new_with_limits(listener, DEFAULT_MAX_CONNECTIONS, DEFAULT_IDLE_TIMEOUT)
+    }
+
+    /// Create a new TCP network.
+    ///
+    /// # Arguments
+    /// - `listener`: The listener to accept incoming connections from; if `None`, the network
+    ///   only establishes outgoing connections.
+    /// - `max_connections`: The maximum number of connections kept open at the same time.
+    /// - `idle_timeout`: The duration after which a connection without any traffic is closed.
+    pub fn new_with_limits(
+        listener: Option<Async<TcpListener>>,
+        max_connections: usize,
+        idle_timeout: Duration,
+    ) -> Self {
+        Self {
+            listener,
+            connections: RefCell::new(Vec::new()),
+            changed: Notification::new(),
+            max_connections: max_connections.max(1),
+            idle_timeout,
+        }
+    }
+
+    /// Return the number of currently open connections
+    pub fn connections(&self) -> usize {
+        self.connections.borrow().len()
+    }
+
+    /// Close the connection to the provided peer, if there is one
+    pub fn close(&self, peer: &SocketAddr) {
+        self.connections
+            .borrow_mut()
+            .retain(|conn| conn.peer != *peer);
+    }
+
+    /// Wait until a complete message is received on one of the connections, and return its peer address.
+    async fn wait_frame(&self) -> Result<SocketAddr, Error> {
+        loop {
+            let peer = self
+                .connections
+                .borrow()
+                .iter()
+                .find(|conn| Self::frame_len(&conn.rx).is_some())
+                .map(|conn| conn.peer);
+
+            if let Some(peer) = peer {
+                break Ok(peer);
+            }
+
+            let peer = self.wait_readable().await?;
+
+            if let Err(e) = self.fill(&peer) {
+                // Either the peer closed the connection, or the framing is broken; in both cases
+                // the connection is unusable, but this should not bring down the whole transport
+                debug!("Closing TCP connection to {}: {:?}", peer, e);
+                self.close(&peer);
+            }
+        }
+    }
+
+    /// Read the data available on the connection to the provided peer into the buffer of the connection,
+    /// without reading past the end of the message being received.
+    fn fill(&self, peer: &SocketAddr) -> Result<(), Error> {
+        let mut connections = self.connections.borrow_mut();
+
+        let Some(conn) = connections.iter_mut().find(|conn| conn.peer == *peer) else {
+            return Ok(());
+        };
+
+        let missing = if let Some(len) = Self::announced_len(&conn.rx) {
+            if len > MAX_FRAME_SIZE {
+                warn!("Incoming TCP message of {}B is too large", len);
+                Err(ErrorCode::NoSpace)?;
+            }
+
+            FRAME_LEN_SIZE + len - conn.rx.len()
+        } else {
+            FRAME_LEN_SIZE - conn.rx.len()
+        };
+
+        if missing == 0 {
+            return Ok(());
+        }
+
+        let start = conn.rx.len();
+        conn.rx.resize(start + missing, 0);
+
+        // The socket is non-blocking, so this returns right away even if the readiness was spurious
+        let result = (&mut conn.stream.get_ref()).read(&mut conn.rx[start..]);
+
+        let len = match result {
+            Ok(0) => {
+                // The peer closed the connection
+                conn.rx.truncate(start);
+                Err(ErrorCode::NoNetworkInterface)?
+            }
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => 0,
+            Err(e) => {
+                conn.rx.truncate(start);
+                Err(e)?
+            }
+        };
+
+        conn.rx.truncate(start + len);
+
+        Ok(())
+    }
+
+    /// Move the message received on the connection to the provided peer into the buffer
+    fn take_frame(&self, peer: &SocketAddr, buffer: &mut [u8]) -> Result<usize, Error> {
+        let mut connections = self.connections.borrow_mut();
+
+        let conn = connections
+            .iter_mut()
+            .find(|conn| conn.peer == *peer)
+            .ok_or(ErrorCode::NoNetworkInterface)?;
+
+        let len = Self::frame_len(&conn.rx).ok_or(ErrorCode::InvalidState)?;
+        if len > buffer.len() {
+            warn!(
+                "Incoming TCP message of {}B does not fit in the RX buffer",
+                len
+            );
+            Err(ErrorCode::NoSpace)?;
+        }
+
+        buffer[..len].copy_from_slice(&conn.rx[FRAME_LEN_SIZE..FRAME_LEN_SIZE + len]);
+
+        // Nothing is ever read past the end of a message
+        conn.rx.clear();
+
+        Ok(len)
+    }
+
+    /// Return the length announced by the length prefix of the message being received, if the prefix is complete
+    fn announced_len(rx: &[u8]) -> Option<usize> {
+        let prefix = rx.get(..FRAME_LEN_SIZE)?;
+
+        Some(u32::from_le_bytes(unwrap!(prefix.try_into())) as usize)
+    }
+
+    /// Return the length of the message being received, if it is complete
+    fn frame_len(rx: &[u8]) -> Option<usize> {
+        Self::announced_len(rx).filter(|len| rx.len() == FRAME_LEN_SIZE + len)
+    }
+
+    /// Wait until one of the connections has incoming data, and return its peer address.
+    ///
+    /// While waiting, accepts incoming connections and closes the idle ones.
+    async fn wait_readable(&self) -> Result<SocketAddr, Error> {
+        loop {
+            let (streams, idle_deadline) = {
+                let connections = self.connections.borrow();
+
+                let streams = connections
+                    .iter()
+                    .map(|conn| (conn.peer, conn.stream.clone()))
+                    .collect::<Vec<_>>();
+
+                let idle_deadline = connections
+                    .iter()
+                    .map(|conn| conn.last_used + self.idle_timeout)
+                    .min();
+
+                (streams, idle_deadline)
+            };
+
+            let mut readable = streams
+                .iter()
+                .map(|(peer, stream)| {
+                    let peer = *peer;
+                    let fut: Pin<Box<dyn Future<Output = Event> + '_>> = Box::pin(async move {
+                        match stream.readable().await {
+                            Ok(()) => Event::Readable(peer),
+                            Err(_) => Event::Closed(peer),
+                        }
+                    });
+
+                    fut
+                })
+                .collect::<Vec<_>>();
+
+            let readable = poll_fn(|cx| {
+                for fut in readable.iter_mut() {
+                    if let Poll::Ready(event) = fut.as_mut().poll(cx) {
+                        return Poll::Ready(event);
+                    }
+                }
+
+                Poll::Pending
+            });
+
+            let accept = async {
+                let Some(listener) = self.listener.as_ref() else {
+                    return core::future::pending().await;
+                };
+
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, peer)) => break Event::Accepted(stream, peer),
+                        Err(e) => warn!("Failed to accept a TCP connection: {}", e),
+                    }
+                }
+            };
+
+            let changed = async {
+                self.changed.wait().await;
+                Event::Changed
+            };
+
+            let idle = async {
+                match idle_deadline {
+                    Some(deadline) => Timer::at(deadline).await,
+                    None => core::future::pending().await,
+                }
+
+                Event::Idle
+            };
+
+            let event = match select4(readable, accept, changed, idle).await {
+                Either4::First(event)
+                | Either4::Second(event)
+                | Either4::Third(event)
+                | Either4::Fourth(event) => event,
+            };
+
+            match event {
+                Event::Readable(peer) => break Ok(peer),
+                Event::Accepted(stream, peer) => {
+                    debug!("Accepted TCP connection from {}", peer);
+                    self.add(stream, peer);
+                }
+                Event::Closed(peer) => self.close(&peer),
+                Event::Idle => {
+                    let now = Instant::now();
+
+                    self.connections.borrow_mut().retain(|conn| {
+                        let keep = conn.last_used + self.idle_timeout > now;
+
+                        if !keep {
+                            debug!("Closing idle TCP connection to {}", conn.peer);
+                        }
+
+                        keep
+                    });
+                }
+                Event::Changed => (),
+            }
+        }
+    }
+
+    /// Return the connection to the provided peer, establishing a new one if there is none
+    async fn connect(&self, peer: SocketAddr) -> Result<Rc<Async<TcpStream>>, Error> {
+        if let Some(stream) = self.stream(&peer) {
+            return Ok(stream);
+        }
+
+        debug!("Connecting to TCP peer {}", peer);
+
+        let stream = Async::<TcpStream>::connect(peer).await?;
+
+        Ok(self.add(stream, peer))
+    }
+
+    /// Add a new connection to the pool, closing the least recently used one if the pool is full
+    fn add(&self, stream: Async<TcpStream>, peer: SocketAddr) -> Rc<Async<TcpStream>> {
+        // Matter messages are complete units, so do not delay them
+        let _ = stream.get_ref().set_nodelay(true);
+
+        let stream = Rc::new(stream);
+
+        {
+            let mut connections = self.connections.borrow_mut();
+
+            connections.retain(|conn| conn.peer != peer);
+
+            if connections.len() >= self.max_connections {
+                let lru = connections
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, conn)| conn.last_used)
+                    .map(|(index, _)| index);
+
+                let conn = connections.swap_remove(unwrap!(lru));
+                debug!(
+                    "Closing least recently used TCP connection to {}",
+                    conn.peer
+                );
+            }
+
+            connections.push(Connection {
+                peer,
+                stream: stream.clone(),
+                last_used: Instant::now(),
+                rx: Vec::new(),
+            });
+        }
+
+        // Wake up the receiving side, so that it starts waiting for data on the new connection as well
+        self.changed.notify();
+
+        stream
+    }
+
+    fn stream(&self, peer: &SocketAddr) -> Option<Rc<Async<TcpStream>>> {
+        self.connections
+            .borrow()
+            .iter()
+            .find(|conn| conn.peer == *peer)
+            .map(|conn| conn.stream.clone())
+    }
+
+    fn touch(&self, peer: &SocketAddr) {
+        if let Some(conn) = self
+            .connections
+            .borrow_mut()
+            .iter_mut()
+            .find(|conn| conn.peer == *peer)
+        {
+            conn.last_used = Instant::now();
+        }
+    }
+
+    /// Write the data as a length-prefixed message to the stream
+    async fn write_frame(stream: &Async<TcpStream>, data: &[u8]) -> Result<(), Error> {
+        let len: u32 = data.len().try_into().map_err(|_| ErrorCode::NoSpace)?;
+
+        Self::write_all(stream, &len.to_le_bytes()).await?;
+        Self::write_all(stream, data).await
+    }
+
+    async fn write_all(stream: &Async<TcpStream>, mut data: &[u8]) -> Result<(), Error> {
+        while !data.is_empty() {
+            let len = stream.write_with(|mut s| s.write(data)).await?;
+            data = &data[len..];
+        }
+
+        Ok(())
+    }
+}
+
+impl NetworkSend for &TcpNetwork {
+    async fn send_to(&mut self, data: &[u8], addr: Address) -> Result<(), Error> {
+        let peer = addr.tcp().ok_or(ErrorCode::NoNetworkInterface)?;
+
+        let stream = self.connect(peer).await?;
+
+        if let Err(e) = TcpNetwork::write_frame(&stream, data).await {
+            self.close(&peer);
+            Err(e)?;
+        }
+
+        self.touch(&peer);
+
+        Ok(())
+    }
+}
+
+impl NetworkReceive for &TcpNetwork {
+    async fn wait_available(&mut self) -> Result<(), Error> {
+        self.wait_frame().await?;
+
+        Ok(())
+    }
+
+    async fn recv_from(&mut self, buffer: &mut [u8]) -> Result<(usize, Address), Error> {
+        loop {
+            let peer = self.wait_frame().await?;
+
+            match self.take_frame(&peer, buffer) {
+                Ok(len) => {
+                    self.touch(&peer);
+
+                    break Ok((len, Address::Tcp(peer)));
+                }
+                Err(e) => {
+                    debug!("Closing TCP connection to {}: {:?}", peer, e);
+                    self.close(&peer);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+
+    use async_io::Async;
+
+    use embassy_futures::block_on;
+
+    use crate::transport::network::{Address, NetworkReceive, NetworkSend};
+
+    use super::TcpNetwork;
+
+    #[test]
+    fn test_send_recv() {
+        let listener = unwrap!(Async::<TcpListener>::bind(SocketAddr::new(
+            Ipv4Addr::LOCALHOST.into(),
+            0
+        )));
+        let server_addr = unwrap!(listener.get_ref().local_addr());
+
+        let server = TcpNetwork::new(Some(listener));
+        let client = TcpNetwork::new(None);
+
+        let large = (0..4000).map(|i| i as u8).collect::<Vec<_>>();
+
+        block_on(async {
+            let mut buf = [0; 8192];
+
+            unwrap!((&client).send_to(b"hello", Address::Tcp(server_addr)).await);
+            unwrap!((&client).send_to(&large, Address::Tcp(server_addr)).await);
+            assert_eq!(client.connections(), 1);
+
+            let (len, peer) = unwrap!((&server).recv_from(&mut buf).await);
+            assert_eq!(&buf[..len], b"hello");
+            assert!(peer.is_tcp());
+
+            // Messages larger than the UDP MTU are not fragmented
+            let (len, _) = unwrap!((&server).recv_from(&mut buf).await);
+            assert_eq!(&buf[..len], large.as_slice());
+
+            // The reply goes over the accepted connection
+            unwrap!((&server).send_to(b"world", peer).await);
+            assert_eq!(server.connections(), 1);
+
+            let (len, peer) = unwrap!((&client).recv_from(&mut buf).await);
+            assert_eq!(&buf[..len], b"world");
+            assert_eq!(peer, Address::Tcp(server_addr));
+        });
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let listener = unwrap!(Async::<TcpListener>::bind(SocketAddr::new(
+            Ipv4Addr::LOCALHOST.into(),
+            0
+        )));
+        let server_addr = unwrap!(listener.get_ref().local_addr());
+
+        let server = TcpNetwork::new(Some(listener));
+        let client = TcpNetwork::new(None);
+
+        // A peer which only sends the length prefix of its message...
+        let mut slow = unwrap!(TcpStream::connect(server_addr));
+        unwrap!(slow.write_all(&5_u32.to_le_bytes()));
+
+        block_on(async {
+            let mut buf = [0; 64];
+
+            // ... does not hold up the messages of the other peers
+            unwrap!((&client).send_to(b"hello", Address::Tcp(server_addr)).await);
+
+            let (len, _) = unwrap!((&server).recv_from(&mut buf).await);
+            assert_eq!(&buf[..len], b"hello");
+            assert_eq!(server.connections(), 2);
+
+            // ... and its message is received once complete
+            unwrap!(slow.write_all(b"wor"));
+            unwrap!(slow.write_all(b"ld"));
+
+            let (len, peer) = unwrap!((&server).recv_from(&mut buf).await);
+            assert_eq!(&buf[..len], b"world");
+            assert_eq!(peer, Address::Tcp(unwrap!(slow.local_addr())));
+        });
+    }
+}
@@ -21,10 +21,12 @@
 
 use crate::error::*;
 
-use std::net::UdpSocket;
+use std::io::ErrorKind;
+use std::net::{Ipv6Addr, UdpSocket};
 
 use async_io::Async;
 
+use crate::fabric::FabricMgr;
 use crate::transport::network::Address;
 
 use super::{NetworkReceive, NetworkSend};
@@ -51,3 +53,49 @@ impl NetworkReceive for &Async<UdpSocket> {
         Ok((len, Address::Udp(addr)))
     }
 }
+
+/// Join the IPv6 multicast groups of all groups in the group tables of all fabrics on the provided network
+/// interface, so that group messages sent to those groups are received on the provided socket, and leave
+/// the previously joined multicast groups which are no longer in any group table.
+///
+/// `joined` holds the multicast groups joined by the previous calls and is updated accordingly,
+/// so the function can be called again each time the group tables of the fabrics change.
+pub fn join_groups(
+    socket: &UdpSocket,
+    fabric_mgr: &FabricMgr,
+    interface: u32,
+    joined: &mut Vec<Ipv6Addr>,
+) -> Result<(), Error> {
+    let addrs = || {
+        fabric_mgr
+            .iter()
+            .flat_map(|fabric| fabric.group_multicast_addrs())
+    };
+
+    joined.retain(|addr| {
+        if addrs().any(|current| current == *addr) {
+            return true;
+        }
+
+        if let Err(e) = socket.leave_multicast_v6(addr, interface) {
+            warn!("Failed to leave multicast group {}: {}", addr, e);
+        }
+
+        false
+    });
+
+    for addr in addrs() {
+        if joined.contains(&addr) {
+            continue;
+        }
+
+        match socket.join_multicast_v6(&addr, interface) {
+            Err(e) if e.kind() == ErrorKind::AddrInUse => (),
+            other => other?,
+        }
+
+        joined.push(addr);
+    }
+
+    Ok(())
+}
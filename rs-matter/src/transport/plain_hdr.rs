@@ -30,6 +30,17 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    #[repr(transparent)]
+    #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct SecFlags: u8 {
+        const PRIVACY = 0x80;
+        const CONTROL = 0x40;
+        const MSG_EXT = 0x20;
+        const GROUP_SESSION = 0x01;
+    }
+}
+
 impl fmt::Display for MsgFlags {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut sep = false;
@@ -92,6 +103,7 @@ impl defmt::Format for MsgFlags {
 pub struct PlainHdr {
     flags: MsgFlags,
     pub sess_id: u16,
+    sec_flags: SecFlags,
     pub ctr: u32,
     src_nodeid: u64,
     dst_nodeid: u64,
@@ -103,6 +115,7 @@ impl PlainHdr {
         Self {
             flags: MsgFlags::empty(),
             sess_id: 0,
+            sec_flags: SecFlags::empty(),
             ctr: 0,
             src_nodeid: 0,
             dst_nodeid: 0,
@@ -167,11 +180,31 @@ impl PlainHdr {
         }
     }
 
+    /// Return `true` if the message is sent on a group session
+    pub fn is_group_session(&self) -> bool {
+        self.sec_flags.contains(SecFlags::GROUP_SESSION)
+    }
+
+    pub fn set_group_session(&mut self, group: bool) {
+        self.sec_flags.set(SecFlags::GROUP_SESSION, group);
+    }
+
+    /// Return `true` if the message is a control message (i.e. a Message Counter Synchronization
+    /// Protocol message), which uses a separate message counter space
+    pub fn is_control(&self) -> bool {
+        self.sec_flags.contains(SecFlags::CONTROL)
+    }
+
+    /// Return `true` if the message headers are obfuscated with privacy encryption
+    pub fn is_privacy(&self) -> bool {
+        self.sec_flags.contains(SecFlags::PRIVACY)
+    }
+
     // it will have an additional 'message length' field first
     pub fn decode(&mut self, msg: &mut ParseBuf) -> Result<(), Error> {
         self.flags = MsgFlags::from_bits(msg.le_u8()?).ok_or(ErrorCode::Invalid)?;
         self.sess_id = msg.le_u16()?;
-        let _sec_flags = msg.le_u8()?;
+        self.sec_flags = SecFlags::from_bits_retain(msg.le_u8()?);
         self.ctr = msg.le_u32()?;
 
         if self.flags.contains(MsgFlags::SRC_ADDR_PRESENT) {
@@ -197,7 +230,7 @@ impl PlainHdr {
         trace!("[encode] {}", self);
         resp_buf.le_u8(self.flags.bits())?;
         resp_buf.le_u16(self.sess_id)?;
-        resp_buf.le_u8(0)?;
+        resp_buf.le_u8(self.sec_flags.bits())?;
         resp_buf.le_u32(self.ctr)?;
 
         if self.flags.contains(MsgFlags::SRC_ADDR_PRESENT) {
@@ -219,7 +252,7 @@ impl PlainHdr {
     }
 
     pub fn is_encrypted(&self) -> bool {
-        self.sess_id != 0
+        self.sess_id != 0 || self.is_group_session()
     }
 }
 
@@ -231,6 +264,10 @@ impl fmt::Display for PlainHdr {
 
         write!(f, "SID:{:x},CTR:{:x}", self.sess_id, self.ctr)?;
 
+        if !self.sec_flags.is_empty() {
+            write!(f, ",SEC:{:x}", self.sec_flags.bits())?;
+        }
+
         if let Some(src_nodeid) = self.get_src_nodeid() {
             write!(f, ",SRC:{:x}", src_nodeid)?;
         }
@@ -256,6 +293,10 @@ impl defmt::Format for PlainHdr {
 
         defmt::write!(f, "SID:{:x},CTR:{:x}", self.sess_id, self.ctr);
 
+        if !self.sec_flags.is_empty() {
+            defmt::write!(f, ",SEC:{:x}", self.sec_flags.bits());
+        }
+
         if let Some(src_nodeid) = self.get_src_nodeid() {
             defmt::write!(f, ",SRC:{:x}", src_nodeid);
         }
@@ -343,13 +343,15 @@ impl defmt::Format for ProtoHdr {
     }
 }
 
-fn get_iv(recvd_ctr: u32, peer_nodeid: u64, iv: &mut [u8]) -> Result<(), Error> {
-    // The IV is the source address (64-bit) followed by the message counter (32-bit)
+fn get_iv(plain_hdr: &[u8], ctr: u32, nodeid: u64, iv: &mut [u8]) -> Result<(), Error> {
+    // The IV is the security flags of the plain header, followed by the
+    // message counter (32-bit) and the source node ID (64-bit)
+    let sec_flags = *plain_hdr.get(3).ok_or(ErrorCode::InvalidAAD)?;
+
     let mut write_buf = WriteBuf::new(iv);
-    // For some reason, this is 0 in the 'bypass' mode
-    write_buf.le_u8(0)?;
-    write_buf.le_u32(recvd_ctr)?;
-    write_buf.le_u64(peer_nodeid)?;
+    write_buf.le_u8(sec_flags)?;
+    write_buf.le_u32(ctr)?;
+    write_buf.le_u64(nodeid)?;
     Ok(())
 }
 
@@ -362,7 +364,7 @@ pub fn encrypt_in_place(
 ) -> Result<(), Error> {
     // IV
     let mut iv = [0_u8; crypto::AEAD_NONCE_LEN_BYTES];
-    get_iv(send_ctr, peer_nodeid, &mut iv)?;
+    get_iv(plain_hdr, send_ctr, peer_nodeid, &mut iv)?;
 
     // Cipher Text
     let tag_space = [0u8; crypto::AEAD_MIC_LEN_BYTES];
@@ -388,21 +390,21 @@ fn decrypt_in_place(
     key: &[u8],
 ) -> Result<(), Error> {
     // AAD:
-    //    the unencrypted header of this packet
-    let mut aad = [0_u8; crypto::AEAD_AAD_LEN_BYTES];
+    //    the unencrypted header of this packet, which is variable sized
+    //    (i.e. group messages carry both the source node ID and the destination group ID)
+    let mut aad = [0_u8; plain_hdr::max_plain_hdr_len()];
     let parsed_slice = parsebuf.parsed_as_slice();
-    if parsed_slice.len() == aad.len() {
-        // The plain_header is variable sized in length, I wonder if the AAD is fixed at 8, or the variable size.
-        // If so, we need to handle it cleanly here.
-        aad.copy_from_slice(parsed_slice);
-    } else {
+    if parsed_slice.len() < crypto::AEAD_AAD_LEN_BYTES || parsed_slice.len() > aad.len() {
         Err(ErrorCode::InvalidAAD)?;
     }
 
+    let aad = &mut aad[..parsed_slice.len()];
+    aad.copy_from_slice(parsed_slice);
+
     // IV:
     //   the specific way for creating IV is in get_iv
     let mut iv = [0_u8; crypto::AEAD_NONCE_LEN_BYTES];
-    get_iv(recvd_ctr, peer_nodeid, &mut iv)?;
+    get_iv(aad, recvd_ctr, peer_nodeid, &mut iv)?;
 
     let cipher_text = parsebuf.as_mut_slice();
     //println!("AAD: {:x?}", aad);
@@ -410,7 +412,7 @@ fn decrypt_in_place(
     //println!("IV: {:x?}", iv);
     //println!("Key: {:x?}", key);
 
-    crypto::decrypt_in_place(key, &iv, &aad, cipher_text)?;
+    crypto::decrypt_in_place(key, &iv, aad, cipher_text)?;
     // println!("Plain Text: {:x?}", cipher_text);
     parsebuf.tail(crypto::AEAD_MIC_LEN_BYTES)?;
     Ok(())
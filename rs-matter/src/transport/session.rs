@@ -19,7 +19,9 @@ use core::fmt;
 use core::num::NonZeroU8;
 use core::time::Duration;
 
+use crate::crypto;
 use crate::error::*;
+use crate::tlv::{FromTLV, TLVElement, TLVTag, ToTLV};
 use crate::transport::exchange::ExchangeId;
 use crate::transport::mrp::ReliableMessage;
 use crate::utils::cell::RefCell;
@@ -40,6 +42,8 @@ use super::proto_hdr::ProtoHdr;
 pub const MAX_CAT_IDS_PER_NOC: usize = 3;
 pub type NocCatIds = [u32; MAX_CAT_IDS_PER_NOC];
 
+pub const RESUMPTION_ID_LEN: usize = 16;
+
 const MATTER_AES128_KEY_SIZE: usize = 16;
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
@@ -57,6 +61,12 @@ pub enum SessionMode {
     Pase {
         fab_idx: u8,
     },
+    // The Group session captures the local fabric index and the group ID
+    // of the group the messages are exchanged with
+    Group {
+        fab_idx: NonZeroU8,
+        group_id: u16,
+    },
     #[default]
     PlainText,
 }
@@ -66,6 +76,7 @@ impl SessionMode {
         match self {
             SessionMode::Case { fab_idx, .. } => fab_idx.get(),
             SessionMode::Pase { fab_idx, .. } => *fab_idx,
+            SessionMode::Group { fab_idx, .. } => fab_idx.get(),
             SessionMode::PlainText => 0,
         }
     }
@@ -180,11 +191,16 @@ impl Session {
 
     pub fn is_encrypted(&self) -> bool {
         match self.mode {
-            SessionMode::Case { .. } | SessionMode::Pase { .. } => true,
+            SessionMode::Case { .. } | SessionMode::Pase { .. } | SessionMode::Group { .. } => true,
             SessionMode::PlainText => false,
         }
     }
 
+    /// Return `true` if this is a group session
+    pub fn is_group(&self) -> bool {
+        matches!(self.mode, SessionMode::Group { .. })
+    }
+
     pub fn get_peer_node_id(&self) -> Option<u64> {
         self.peer_nodeid
     }
@@ -205,14 +221,18 @@ impl Session {
 
     pub fn get_dec_key(&self) -> Option<&[u8]> {
         match self.mode {
-            SessionMode::Case { .. } | SessionMode::Pase { .. } => Some(&self.dec_key),
+            SessionMode::Case { .. } | SessionMode::Pase { .. } | SessionMode::Group { .. } => {
+                Some(&self.dec_key)
+            }
             SessionMode::PlainText => None,
         }
     }
 
     pub fn get_enc_key(&self) -> Option<&[u8]> {
         match self.mode {
-            SessionMode::Case { .. } | SessionMode::Pase { .. } => Some(&self.enc_key),
+            SessionMode::Case { .. } | SessionMode::Pase { .. } | SessionMode::Group { .. } => {
+                Some(&self.enc_key)
+            }
             SessionMode::PlainText => None,
         }
     }
@@ -225,15 +245,35 @@ impl Session {
         self.get_local_fabric_idx() == fabric_idx
             && self.peer_nodeid == Some(peer_node_id)
             && self.is_encrypted() == secure
+            && !self.is_group()
             && !self.reserved
     }
 
     pub(crate) fn is_for_rx(&self, rx_peer: &Address, rx_plain: &PlainHdr) -> bool {
+        if let SessionMode::Group { group_id, .. } = self.mode {
+            // Group sessions are per group and per source node
+            return rx_plain.is_group_session()
+                && rx_plain.get_dst_groupcast_nodeid() == Some(group_id)
+                && rx_plain.get_src_nodeid().is_some()
+                && self.peer_nodeid == rx_plain.get_src_nodeid()
+                && self.local_sess_id == rx_plain.sess_id
+                && self.peer_addr == *rx_peer
+                && !self.reserved;
+        }
+
         let nodeid_matches = self.peer_nodeid.is_none()
             || rx_plain.get_src_nodeid().is_none()
             || self.peer_nodeid == rx_plain.get_src_nodeid();
 
+        // Unencrypted sessions initiated by us are addressed by the peer with our ephemeral node ID
+        let local_nodeid_matches = self.is_encrypted()
+            || self.local_nodeid == 0
+            || rx_plain.get_dst_unicast_nodeid().is_none()
+            || rx_plain.get_dst_unicast_nodeid() == Some(self.local_nodeid);
+
         nodeid_matches
+            && local_nodeid_matches
+            && !rx_plain.is_group_session()
             && self.local_sess_id == rx_plain.sess_id
             && self.peer_addr == *rx_peer
             && self.is_encrypted() == rx_plain.is_encrypted()
@@ -266,9 +306,12 @@ impl Session {
     ///
     /// Return `true` if a new exchange was created, and `false` otherwise.
     pub(crate) fn post_recv(&mut self, rx_header: &PacketHdr, epoch: Epoch) -> Result<bool, Error> {
-        if !self
-            .rx_ctr_state
-            .post_recv(rx_header.plain.ctr, self.is_encrypted())
+        // The message counters of group messages are tracked per source node rather than per session,
+        // see `SessionMgr::post_group_recv`
+        if !self.is_group()
+            && !self
+                .rx_ctr_state
+                .post_recv(rx_header.plain.ctr, self.is_encrypted())
         {
             Err(ErrorCode::Duplicate)?;
         }
@@ -323,12 +366,27 @@ impl Session {
 
         tx_header.plain.sess_id = self.get_peer_sess_id();
         tx_header.plain.ctr = ctr.unwrap_or_else(|| self.get_msg_ctr());
-        tx_header.plain.set_src_nodeid(None);
-        tx_header.plain.set_dst_unicast_nodeid(
-            (self.mode == SessionMode::PlainText)
-                .then_some(self.peer_nodeid)
-                .flatten(),
-        );
+
+        if let SessionMode::Group { group_id, .. } = self.mode {
+            // Group messages always identify their source and are never acknowledged
+            tx_header.plain.set_group_session(true);
+            tx_header.plain.set_src_nodeid(Some(self.local_nodeid));
+            tx_header.plain.set_dst_groupcast_nodeid(Some(group_id));
+
+            tx_header.proto.unset_reliable();
+            tx_header.proto.set_ack(None);
+        } else {
+            // As per spec, the initiator of an unencrypted session identifies itself with an ephemeral node ID
+            tx_header.plain.set_src_nodeid(
+                (self.mode == SessionMode::PlainText && self.local_nodeid != 0)
+                    .then_some(self.local_nodeid),
+            );
+            tx_header.plain.set_dst_unicast_nodeid(
+                (self.mode == SessionMode::PlainText)
+                    .then_some(self.peer_nodeid)
+                    .flatten(),
+            );
+        }
 
         tx_header.proto.adjust_reliability(false, &self.peer_addr);
 
@@ -542,6 +600,11 @@ impl<'a> ReservedSession<'a> {
         Ok(())
     }
 
+    /// Get the internal ID of the reserved session
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
     pub fn complete(mut self) {
         self.complete = true;
     }
@@ -559,16 +622,56 @@ impl Drop for ReservedSession<'_> {
     }
 }
 
+/// The state necessary for resuming a CASE session with a peer, without a full CASE handshake
+#[derive(Clone)]
+pub struct ResumptionState {
+    /// The ID under which the resumption state is known to both peers
+    pub resumption_id: [u8; RESUMPTION_ID_LEN],
+    /// The shared secret established by the last full CASE handshake
+    pub shared_secret: [u8; crypto::ECDH_SHARED_SECRET_LEN_BYTES],
+    /// The local fabric index of the resumable session
+    pub fab_idx: NonZeroU8,
+    /// The node ID of the peer
+    pub peer_nodeid: u64,
+    /// The CASE Authenticated Tags of the peer
+    pub cat_ids: NocCatIds,
+}
+
+/// The message counter state of a peer sending group messages to us
+struct GroupPeer {
+    fab_idx: NonZeroU8,
+    nodeid: u64,
+    control: bool,
+    rx_ctr_state: RxCtrState,
+}
+
 const MAX_SESSIONS: usize = 16;
 const MAX_EXCHANGES: usize = 5;
+const MAX_RESUMPTIONS: usize = 8;
+const MAX_GROUP_PEERS: usize = 8;
 
 const MATTER_MSG_CTR_RANGE: u32 = 0x0fffffff;
 
+/// The number of global group message counter values reserved (and persisted) ahead of their use
+const GROUP_MSG_CTR_WINDOW: u32 = 1000;
+
 pub struct SessionMgr {
     next_sess_unique_id: u32,
     next_sess_id: u16,
     next_exch_id: u16,
     sessions: crate::utils::storage::Vec<Session, MAX_SESSIONS>,
+    resumptions: crate::utils::storage::Vec<ResumptionState, MAX_RESUMPTIONS>,
+    group_peers: crate::utils::storage::Vec<GroupPeer, MAX_GROUP_PEERS>,
+    /// The global group message counter, shared by all outgoing group sessions
+    ///
+    /// Lazily initialized with a random value on first use, unless loaded from the persisted state
+    group_msg_ctr: Option<u32>,
+    /// The exclusive upper limit of the global group message counter values reserved so far
+    ///
+    /// This limit - rather than the counter itself - is what gets persisted, so that the counter
+    /// continues from a value never used before after a reboot
+    group_msg_ctr_limit: u32,
+    group_msg_ctr_changed: bool,
     pub(crate) epoch: Epoch,
     pub(crate) rand: Rand,
 }
@@ -579,6 +682,11 @@ impl SessionMgr {
     pub const fn new(epoch: Epoch, rand: Rand) -> Self {
         Self {
             sessions: crate::utils::storage::Vec::new(),
+            resumptions: crate::utils::storage::Vec::new(),
+            group_peers: crate::utils::storage::Vec::new(),
+            group_msg_ctr: None,
+            group_msg_ctr_limit: 0,
+            group_msg_ctr_changed: false,
             next_sess_unique_id: 0,
             next_sess_id: 1,
             next_exch_id: 1,
@@ -591,6 +699,11 @@ impl SessionMgr {
     pub fn init(epoch: Epoch, rand: Rand) -> impl Init<Self> {
         init!(Self {
             sessions <- crate::utils::storage::Vec::init(),
+            resumptions <- crate::utils::storage::Vec::init(),
+            group_peers <- crate::utils::storage::Vec::init(),
+            group_msg_ctr: None,
+            group_msg_ctr_limit: 0,
+            group_msg_ctr_changed: false,
             next_sess_unique_id: 0,
             next_sess_id: 1,
             next_exch_id: 1,
@@ -601,6 +714,8 @@ impl SessionMgr {
 
     pub fn reset(&mut self) {
         self.sessions.clear();
+        self.resumptions.clear();
+        self.group_peers.clear();
         self.next_sess_id = 1;
         self.next_exch_id = 1;
     }
@@ -659,8 +774,11 @@ impl SessionMgr {
         let mut lru_index = None;
         let mut lru_ts = (self.epoch)();
         for (i, s) in self.sessions.iter().enumerate() {
+            // Group sessions are never evicted, because there is no peer to notify;
+            // they are instead dropped silently once idle, see `SessionMgr::add`
             if (s.expired || s.last_use < lru_ts)
                 && !s.reserved
+                && !s.is_group()
                 && s.exchanges.iter().all(Option::is_none)
             {
                 lru_ts = s.last_use;
@@ -691,6 +809,12 @@ impl SessionMgr {
             self.next_sess_unique_id = 0;
         }
 
+        if self.sessions.is_full() {
+            // Group sessions do not carry any state worth keeping once they have no exchanges
+            self.sessions
+                .retain(|sess| !sess.is_group() || sess.exchanges.iter().any(Option::is_some));
+        }
+
         let session = Session::init(
             session_id,
             reserved,
@@ -708,6 +832,111 @@ impl SessionMgr {
         Ok(unwrap!(self.sessions.last_mut()))
     }
 
+    /// Add a new unencrypted session for initiating a session establishment (PASE or CASE)
+    /// with the peer at the provided address.
+    ///
+    /// The session is assigned a random ephemeral local node ID, as per the Matter spec.
+    pub fn add_unsecured(&mut self, peer_addr: Address) -> Result<&mut Session, Error> {
+        let mut local_nodeid = 0;
+        while local_nodeid == 0 {
+            let mut buf = [0; 8];
+            (self.rand)(&mut buf);
+
+            // Ephemeral node IDs are taken from the operational node ID range
+            local_nodeid = u64::from_le_bytes(buf) & 0x0fff_ffff_ffff_ffff;
+        }
+
+        let session = self.add(false, peer_addr, None)?;
+        session.local_nodeid = local_nodeid;
+
+        Ok(session)
+    }
+
+    /// Add a new group session for receiving messages sent by the provided peer to the provided group.
+    ///
+    /// `group_sess_id` and `key` are the group session ID and the operational group key the messages are encrypted with.
+    pub(crate) fn add_group_rx(
+        &mut self,
+        fab_idx: NonZeroU8,
+        group_id: u16,
+        group_sess_id: u16,
+        key: &[u8],
+        peer_addr: Address,
+        peer_nodeid: u64,
+    ) -> Result<&mut Session, Error> {
+        let session = self.add(false, peer_addr, Some(peer_nodeid))?;
+
+        session.mode = SessionMode::Group { fab_idx, group_id };
+        session.local_sess_id = group_sess_id;
+        session.dec_key.copy_from_slice(key);
+
+        Ok(session)
+    }
+
+    /// Add a new group session for sending messages to the provided group.
+    ///
+    /// `group_sess_id` and `key` are the group session ID and the operational group key the messages should be encrypted with,
+    /// and `multicast_addr` is the address of the group.
+    pub(crate) fn add_group_tx(
+        &mut self,
+        fab_idx: NonZeroU8,
+        group_id: u16,
+        group_sess_id: u16,
+        key: &[u8],
+        multicast_addr: Address,
+        local_nodeid: u64,
+    ) -> Result<&mut Session, Error> {
+        let session = self.add(false, multicast_addr, None)?;
+
+        session.mode = SessionMode::Group { fab_idx, group_id };
+        session.local_nodeid = local_nodeid;
+        session.peer_sess_id = group_sess_id;
+        session.enc_key.copy_from_slice(key);
+
+        Ok(session)
+    }
+
+    /// Update the message counter state of the peer which had sent the provided group message.
+    ///
+    /// As per the "trust first" group key security policy, the message counter of the first message received
+    /// from a peer is accepted as-is, and becomes the baseline for detecting duplicate messages from that peer.
+    ///
+    /// Messages from new peers are rejected once the counter state of `MAX_GROUP_PEERS` peers
+    /// is being tracked. Evicting the state of a known peer instead would make us "trust first" it
+    /// again, and thus accept replays of its older messages.
+    ///
+    /// Return `false` if the message is a duplicate or if it is coming from a new peer which cannot be tracked.
+    pub(crate) fn post_group_recv(&mut self, fab_idx: NonZeroU8, rx_plain: &PlainHdr) -> bool {
+        let nodeid = rx_plain.get_src_nodeid().unwrap_or_default();
+        let control = rx_plain.is_control();
+
+        if let Some(peer) = self.group_peers.iter_mut().find(|peer| {
+            peer.fab_idx == fab_idx && peer.nodeid == nodeid && peer.control == control
+        }) {
+            return peer.rx_ctr_state.post_recv(rx_plain.ctr, true);
+        }
+
+        if self
+            .group_peers
+            .push(GroupPeer {
+                fab_idx,
+                nodeid,
+                control,
+                rx_ctr_state: RxCtrState::new(rx_plain.ctr),
+            })
+            .is_err()
+        {
+            warn!(
+                "Too many group peers, dropping group message from new peer {:x}",
+                nodeid
+            );
+
+            return false;
+        }
+
+        true
+    }
+
     /// This assumes that the higher layer has taken care of doing anything required
     /// as per the spec before the session is removed
     pub fn remove(&mut self, id: u32) -> Option<Session> {
@@ -735,6 +964,10 @@ impl SessionMgr {
             self.sessions.swap_remove(index);
         }
 
+        self.resumptions
+            .retain(|resumption| resumption.fab_idx != fabric_idx);
+        self.group_peers.retain(|peer| peer.fab_idx != fabric_idx);
+
         if let Some(expire_sess_id) = expire_sess_id {
             let expire_sess = self
                 .sessions
@@ -767,6 +1000,91 @@ impl SessionMgr {
         session
     }
 
+    /// Get the session with the provided ID for sending a message on it.
+    ///
+    /// All group sessions share a single, node-wide message counter, as the receivers
+    /// track the message counters of group messages per source node, rather than per session.
+    pub(crate) fn get_for_tx(&mut self, id: u32) -> Option<&mut Session> {
+        let index = self.sessions.iter().position(|sess| sess.id == id)?;
+
+        if self.sessions[index].is_group() {
+            let ctr = match self.group_msg_ctr {
+                Some(ctr) => ctr,
+                None => {
+                    let ctr = Session::rand_msg_ctr(self.rand);
+                    self.reserve_group_msg_ctrs(ctr);
+
+                    ctr
+                }
+            };
+
+            // Reserve the next window of counter values well before the current one is exhausted,
+            // so that the new limit is likely persisted by the time the counter gets there
+            if self.group_msg_ctr_limit.wrapping_sub(ctr) <= GROUP_MSG_CTR_WINDOW / 2 {
+                self.reserve_group_msg_ctrs(ctr);
+            }
+
+            self.group_msg_ctr = Some(ctr.wrapping_add(1));
+            self.sessions[index].msg_ctr = ctr;
+        }
+
+        let session = &mut self.sessions[index];
+
+        session.update_last_used(self.epoch);
+
+        Some(session)
+    }
+
+    /// Load the global group message counter from the provided TLV data
+    ///
+    /// The counter continues from the persisted limit, as any value below it might have been
+    /// used before. A new window of counter values is reserved right away, so the user is expected
+    /// to store the counter back before sending any group messages.
+    pub fn load_group_msg_ctr(&mut self, data: &[u8]) -> Result<(), Error> {
+        let limit = u32::from_tlv(&TLVElement::new(data))?;
+
+        self.group_msg_ctr = Some(limit);
+        self.reserve_group_msg_ctrs(limit);
+
+        Ok(())
+    }
+
+    /// Store the limit of the reserved global group message counter values into the provided buffer
+    /// as TLV data
+    ///
+    /// If no new counter values were reserved since the last store operation, the function returns `None`
+    /// and does not store anything.
+    pub fn store_group_msg_ctr<'a>(
+        &mut self,
+        buf: &'a mut [u8],
+    ) -> Result<Option<&'a [u8]>, Error> {
+        if !self.group_msg_ctr_changed {
+            return Ok(None);
+        }
+
+        let mut wb = WriteBuf::new(buf);
+
+        self.group_msg_ctr_limit
+            .to_tlv(&TLVTag::Anonymous, &mut wb)
+            .map_err(|_| ErrorCode::NoSpace)?;
+
+        self.group_msg_ctr_changed = false;
+
+        let len = wb.get_tail();
+
+        Ok(Some(&buf[..len]))
+    }
+
+    /// Return `true` if new global group message counter values were reserved since the last store operation
+    pub fn group_msg_ctr_changed(&self) -> bool {
+        self.group_msg_ctr_changed
+    }
+
+    fn reserve_group_msg_ctrs(&mut self, ctr: u32) {
+        self.group_msg_ctr_limit = ctr.wrapping_add(GROUP_MSG_CTR_WINDOW);
+        self.group_msg_ctr_changed = true;
+    }
+
     pub(crate) fn get_for_node(
         &mut self,
         fabric_idx: u8,
@@ -837,6 +1155,42 @@ impl SessionMgr {
     pub fn iter(&self) -> impl Iterator<Item = &Session> {
         self.sessions.iter()
     }
+
+    /// Get the CASE resumption state with the provided resumption ID
+    pub fn get_resumption(&self, resumption_id: &[u8]) -> Option<&ResumptionState> {
+        self.resumptions
+            .iter()
+            .find(|resumption| resumption.resumption_id == resumption_id)
+    }
+
+    /// Get the CASE resumption state for the provided peer node ID on the provided fabric
+    pub fn get_resumption_for_node(
+        &self,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+    ) -> Option<&ResumptionState> {
+        self.resumptions.iter().find(|resumption| {
+            resumption.fab_idx == fabric_idx && resumption.peer_nodeid == peer_node_id
+        })
+    }
+
+    /// Add a CASE resumption state, replacing the existing one for the same peer and fabric (if any).
+    ///
+    /// If there is no space for the new resumption state, the oldest one is dropped.
+    pub fn add_resumption(&mut self, resumption: ResumptionState) {
+        self.resumptions.retain(|other| {
+            other.fab_idx != resumption.fab_idx || other.peer_nodeid != resumption.peer_nodeid
+        });
+
+        if self.resumptions.is_full() {
+            self.resumptions.remove(0);
+        }
+
+        unwrap!(self
+            .resumptions
+            .push(resumption)
+            .map_err(|_| ErrorCode::NoSpace));
+    }
 }
 
 impl fmt::Display for SessionMgr {
@@ -858,7 +1212,23 @@ mod tests {
         utils::{epoch::dummy_epoch, rand::dummy_rand},
     };
 
-    use super::SessionMgr;
+    use core::num::NonZeroU8;
+
+    use crate::transport::plain_hdr::PlainHdr;
+
+    use super::{
+        ResumptionState, SessionMgr, GROUP_MSG_CTR_WINDOW, MAX_GROUP_PEERS, MAX_RESUMPTIONS,
+    };
+
+    fn resumption(id: u8, fab_idx: u8, peer_nodeid: u64) -> ResumptionState {
+        ResumptionState {
+            resumption_id: [id; 16],
+            shared_secret: [id; 32],
+            fab_idx: unwrap!(NonZeroU8::new(fab_idx)),
+            peer_nodeid,
+            cat_ids: Default::default(),
+        }
+    }
 
     #[test]
     fn test_next_sess_id_doesnt_reuse() {
@@ -883,4 +1253,109 @@ mod tests {
         assert_eq!(sm.get_next_sess_id(), 65535);
         assert_eq!(sm.get_next_sess_id(), 2);
     }
+
+    #[test]
+    fn test_resumptions() {
+        let fab1 = unwrap!(NonZeroU8::new(1));
+        let fab2 = unwrap!(NonZeroU8::new(2));
+
+        let mut sm = SessionMgr::new(dummy_epoch, dummy_rand);
+        sm.add_resumption(resumption(1, 1, 100));
+        sm.add_resumption(resumption(2, 2, 100));
+
+        assert_eq!(unwrap!(sm.get_resumption(&[1; 16])).peer_nodeid, 100);
+        assert_eq!(
+            unwrap!(sm.get_resumption_for_node(fab2, 100)).resumption_id,
+            [2; 16]
+        );
+        assert!(sm.get_resumption_for_node(fab1, 200).is_none());
+
+        // A new resumption for the same peer on the same fabric replaces the old one
+        sm.add_resumption(resumption(3, 1, 100));
+        assert!(sm.get_resumption(&[1; 16]).is_none());
+        assert_eq!(
+            unwrap!(sm.get_resumption_for_node(fab1, 100)).resumption_id,
+            [3; 16]
+        );
+
+        // Removing a fabric drops its resumptions
+        sm.remove_for_fabric(fab1, None);
+        assert!(sm.get_resumption_for_node(fab1, 100).is_none());
+        assert!(sm.get_resumption_for_node(fab2, 100).is_some());
+    }
+
+    #[test]
+    fn test_resumptions_evict_oldest() {
+        let mut sm = SessionMgr::new(dummy_epoch, dummy_rand);
+        for peer in 0..MAX_RESUMPTIONS as u64 + 1 {
+            sm.add_resumption(resumption(peer as u8, 1, peer));
+        }
+
+        assert!(sm.get_resumption(&[0; 16]).is_none());
+        assert!(sm.get_resumption(&[1; 16]).is_some());
+        assert!(sm.get_resumption(&[MAX_RESUMPTIONS as u8; 16]).is_some());
+    }
+
+    #[test]
+    fn test_group_peers_not_evicted() {
+        let fab_idx = unwrap!(NonZeroU8::new(1));
+
+        let group_msg = |src_nodeid: u64, ctr: u32| {
+            let mut plain = PlainHdr::default();
+            plain.set_src_nodeid(Some(src_nodeid));
+            plain.ctr = ctr;
+            plain
+        };
+
+        let mut sm = SessionMgr::new(dummy_epoch, dummy_rand);
+        for peer in 0..MAX_GROUP_PEERS as u64 {
+            assert!(sm.post_group_recv(fab_idx, &group_msg(peer + 1, 100)));
+        }
+
+        // Messages from a new peer are dropped, as there is no room to track its counter
+        assert!(!sm.post_group_recv(fab_idx, &group_msg(1000, 100)));
+
+        // ... and the counters of the known peers are still tracked, so replays are detected
+        assert!(!sm.post_group_recv(fab_idx, &group_msg(1, 100)));
+        assert!(sm.post_group_recv(fab_idx, &group_msg(1, 101)));
+
+        // Removing the fabric makes room for new peers
+        sm.remove_for_fabric(fab_idx, None);
+        assert!(sm.post_group_recv(fab_idx, &group_msg(1000, 100)));
+    }
+
+    #[test]
+    fn test_group_msg_ctr_persisted() {
+        let fab_idx = unwrap!(NonZeroU8::new(1));
+
+        let mut sm = SessionMgr::new(dummy_epoch, dummy_rand);
+        let id = unwrap!(sm.add_group_tx(fab_idx, 1, 1, &[0; 16], Address::default(), 1)).id;
+
+        let first = unwrap!(sm.get_for_tx(id)).msg_ctr;
+        assert!(sm.group_msg_ctr_changed());
+
+        let mut buf = [0; 16];
+        let data = unwrap!(unwrap!(sm.store_group_msg_ctr(&mut buf)));
+        assert!(!sm.group_msg_ctr_changed());
+
+        // No new window is reserved while the counter is far from the persisted limit
+        for ctr in first + 1..first + GROUP_MSG_CTR_WINDOW / 2 {
+            assert_eq!(unwrap!(sm.get_for_tx(id)).msg_ctr, ctr);
+        }
+        assert!(!sm.group_msg_ctr_changed());
+
+        unwrap!(sm.get_for_tx(id));
+        assert!(sm.group_msg_ctr_changed());
+
+        // After a reboot, the counter continues from the persisted limit
+        let mut sm2 = SessionMgr::new(dummy_epoch, dummy_rand);
+        unwrap!(sm2.load_group_msg_ctr(data));
+        assert!(sm2.group_msg_ctr_changed());
+
+        let id = unwrap!(sm2.add_group_tx(fab_idx, 1, 1, &[0; 16], Address::default(), 1)).id;
+        assert_eq!(
+            unwrap!(sm2.get_for_tx(id)).msg_ctr,
+            first.wrapping_add(GROUP_MSG_CTR_WINDOW)
+        );
+    }
 }
@@ -15,32 +15,44 @@
  *    limitations under the License.
  */
 
+use core::cell::RefCell;
 use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use core::num::NonZeroU8;
 
-use embassy_futures::select::select3;
+use embassy_futures::select::select4;
 use embassy_sync::{
     blocking_mutex::raw::NoopRawMutex,
     zerocopy_channel::{Channel, Receiver, Sender},
 };
 
 use rs_matter::acl::{AclEntry, AuthMode};
+use rs_matter::bdx::{BdxResponder, PROTO_ID_BDX};
+use rs_matter::cert::ca::OperationalCa;
 use rs_matter::crypto::KeyPair;
+use rs_matter::dm::clusters::dev_att::DevAttDataFetcher;
 use rs_matter::dm::devices::test::{TEST_DEV_ATT, TEST_DEV_COMM, TEST_DEV_DET};
+use rs_matter::dm::events::Events;
 use rs_matter::dm::subscriptions::Subscriptions;
-use rs_matter::dm::{AsyncHandler, AsyncMetadata, Privilege};
+use rs_matter::dm::{AsyncHandler, AsyncMetadata, DataModelHandler, Privilege};
 use rs_matter::dm::{DataModel, IMBuffer};
 use rs_matter::error::Error;
-use rs_matter::respond::Responder;
+use rs_matter::im::client::{ImClient, ReportHandler};
+use rs_matter::im::{ReportDataMsg, PROTO_ID_INTERACTION_MODEL};
+use rs_matter::respond::{ChainedExchangeHandler, Responder};
+use rs_matter::sc::SecureChannel;
 use rs_matter::transport::exchange::Exchange;
+use rs_matter::transport::network::mdns::MdnsResolver;
 use rs_matter::transport::network::{
     Address, NetworkReceive, NetworkSend, MAX_RX_PACKET_SIZE, MAX_TX_PACKET_SIZE,
 };
 use rs_matter::transport::session::{NocCatIds, ReservedSession, SessionMode};
 use rs_matter::utils::select::Coalesce;
-use rs_matter::utils::storage::pooled::PooledBuffers;
+use rs_matter::utils::storage::pooled::{BufferAccess, PooledBuffers};
 use rs_matter::{Matter, MATTER_PORT};
 
+use self::bdx::TestBdxHandler;
+
+pub mod bdx;
 pub mod im;
 pub mod test;
 pub mod tlv;
@@ -65,11 +77,15 @@ pub struct E2eRunner {
     matter_client: Matter<'static>,
     buffers: PooledBuffers<10, NoopRawMutex, IMBuffer>,
     subscriptions: Subscriptions<1>,
+    pub events: Events,
+    pub bdx: TestBdxHandler,
     cat_ids: NocCatIds,
+    fabriced: bool,
 }
 
 impl E2eRunner {
-    const ADDR: Address = Address::Udp(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)));
+    pub const ADDR: Address =
+        Address::Udp(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)));
 
     /// The ID of the local Matter instance
     pub const PEER_ID: u64 = 445566;
@@ -77,6 +93,12 @@ impl E2eRunner {
     /// The ID of the remote (tested) Matter instance
     pub const REMOTE_PEER_ID: u64 = 123456;
 
+    /// The fabric ID, IPK and vendor ID used by tests which set up the fabrics
+    /// of an unfabriced runner with an operational CA
+    pub const CA_FABRIC_ID: u64 = 0xFAB000000000001D;
+    pub const CA_IPK: &'static [u8] = &[0x4a; 16];
+    pub const CA_VENDOR_ID: u16 = 0xFFF1;
+
     /// Create a new runner with default category IDs.
     pub fn new_default() -> Self {
         Self::new(NocCatIds::default())
@@ -84,18 +106,54 @@ impl E2eRunner {
 
     /// Create a new runner with the given category IDs.
     pub fn new(cat_ids: NocCatIds) -> Self {
+        Self::new_with(cat_ids, true)
+    }
+
+    /// Create a new runner where neither the local nor the remote (tested) Matter instance
+    /// has a fabric, and where no session is pre-set between the two.
+    ///
+    /// Useful for tests which set up the fabrics and establish the sessions themselves.
+    pub fn new_unfabriced() -> Self {
+        Self::new_with(NocCatIds::default(), false)
+    }
+
+    /// Create a new unfabriced runner, where the remote (tested) Matter instance
+    /// attests itself with the provided device attestation credentials.
+    ///
+    /// Useful for commissioning tests which verify the device attestation.
+    pub fn new_unfabriced_with_dev_att(dev_att: &'static dyn DevAttDataFetcher) -> Self {
+        Self::new_with_dev_att(NocCatIds::default(), false, dev_att)
+    }
+
+    fn new_with(cat_ids: NocCatIds, fabriced: bool) -> Self {
+        Self::new_with_dev_att(cat_ids, fabriced, &TEST_DEV_ATT)
+    }
+
+    fn new_with_dev_att(
+        cat_ids: NocCatIds,
+        fabriced: bool,
+        dev_att: &'static dyn DevAttDataFetcher,
+    ) -> Self {
         Self {
-            matter: Self::new_matter(),
-            matter_client: Self::new_matter(),
+            matter: Self::new_matter(fabriced, dev_att),
+            matter_client: Self::new_matter(fabriced, &TEST_DEV_ATT),
             buffers: PooledBuffers::new(0),
             subscriptions: Subscriptions::new(),
+            events: Events::new(),
+            bdx: TestBdxHandler::new(),
             cat_ids,
+            fabriced,
         }
     }
 
     /// Initialize the local and remote (tested) Matter instances
     /// that the runner owns
     pub fn init(&self) -> Result<(), Error> {
+        if !self.fabriced {
+            self.matter.transport_mgr.reset()?;
+            return self.matter_client.transport_mgr.reset();
+        }
+
         Self::init_matter(
             &self.matter,
             Self::REMOTE_PEER_ID,
@@ -128,6 +186,40 @@ impl E2eRunner {
             .unwrap();
     }
 
+    /// Add the fabric of the provided operational CA to both the local and the remote (tested)
+    /// Matter instances of an unfabriced runner, with `PEER_ID` and `REMOTE_PEER_ID` as their node IDs.
+    pub fn add_ca_fabrics(&self, ca: &OperationalCa) {
+        for (matter, node_id) in [
+            (&self.matter, Self::REMOTE_PEER_ID),
+            (&self.matter_client, Self::PEER_ID),
+        ] {
+            ca.add_fabric(
+                &mut matter.fabric_mgr.borrow_mut(),
+                node_id,
+                &[],
+                Self::CA_IPK,
+                Self::CA_VENDOR_ID,
+                &mut || (),
+            )
+            .unwrap();
+        }
+    }
+
+    /// Return the local ID of the CASE session of the provided Matter instance with the provided peer, if any
+    #[cfg(feature = "client")]
+    pub fn case_session_id(matter: &Matter<'_>, peer_node_id: u64) -> Option<u16> {
+        matter
+            .transport_mgr
+            .session_mgr
+            .borrow()
+            .iter()
+            .find(|sess| {
+                matches!(sess.get_session_mode(), SessionMode::Case { .. })
+                    && sess.get_peer_node_id() == Some(peer_node_id)
+            })
+            .map(|sess| sess.get_local_sess_id())
+    }
+
     /// Initiates a new exchange on the local Matter instance
     pub async fn initiate_exchange(&self) -> Result<Exchange<'_>, Error> {
         Exchange::initiate(
@@ -139,16 +231,49 @@ impl E2eRunner {
         .await
     }
 
+    /// Accepts an exchange initiated by the remote (tested) Matter instance for reporting
+    /// on a subscription, and handles the (possibly chunked) report with the provided callback.
+    pub async fn recv_report<F>(&self, f: F) -> Result<(), Error>
+    where
+        F: FnMut(&ReportDataMsg<'_>) -> Result<(), Error>,
+    {
+        let mut exchange = Exchange::accept(self.matter_client()).await?;
+
+        ImClient::handle_report(&mut exchange, FnReportHandler(RefCell::new(f))).await
+    }
+
     /// Runs both the local and the remote (tested) Matter instances,
     /// by connecting them with a fake UDP network.
     ///
-    /// The remote (tested) Matter instance will run with the provided DM handler.
+    /// The remote (tested) Matter instance will run with the provided DM handler,
+    /// and will report on the subscriptions established with it.
+    ///
+    /// The remote (tested) Matter instance also handles the Secure Channel protocol,
+    /// so that sessions with it can be established by the tests, as well as the BDX protocol
+    /// (with `TestBdxHandler`).
     ///
     /// The local Matter instance does not have a DM handler as it is only used to
     /// drive the tests (i.e. it does not have any server clusters and such).
     pub async fn run<H>(&self, handler: H) -> Result<(), Error>
     where
         H: AsyncHandler + AsyncMetadata,
+    {
+        let dm =
+            DataModel::new(&self.buffers, &self.subscriptions, handler).with_events(&self.events);
+
+        self.run_dm(&dm).await
+    }
+
+    /// Same as `run`, but the remote (tested) Matter instance runs with the provided data model.
+    ///
+    /// Useful for tests which need access to the data model itself, i.e. for persisting its subscriptions.
+    pub async fn run_dm<const N: usize, B, T>(
+        &self,
+        dm: &DataModel<'_, N, B, T>,
+    ) -> Result<(), Error>
+    where
+        B: BufferAccess<IMBuffer>,
+        T: DataModelHandler,
     {
         self.init()?;
 
@@ -165,26 +290,31 @@ impl E2eRunner {
 
         let responder = Responder::new(
             "Default",
-            DataModel::new(&self.buffers, &self.subscriptions, handler),
+            ChainedExchangeHandler::new(PROTO_ID_INTERACTION_MODEL, dm, SecureChannel::new())
+                .chain(PROTO_ID_BDX, BdxResponder::new(&self.bdx)),
             &self.matter,
             0,
         );
 
-        select3(
-            matter_client
-                .transport_mgr
-                .run(NetworkSendImpl(send_local), NetworkReceiveImpl(recv_local)),
+        select4(
+            matter_client.transport_mgr.run(
+                &matter_client.fabric_mgr,
+                NetworkSendImpl(send_local),
+                NetworkReceiveImpl(recv_local),
+            ),
             self.matter.transport_mgr.run(
+                &self.matter.fabric_mgr,
                 NetworkSendImpl(send_remote),
                 NetworkReceiveImpl(recv_remote),
             ),
             responder.run::<4>(),
+            dm.process_subscriptions(&self.matter),
         )
         .coalesce()
         .await
     }
 
-    fn new_matter() -> Matter<'static> {
+    fn new_matter(fabriced: bool, dev_att: &'static dyn DevAttDataFetcher) -> Matter<'static> {
         #[cfg(feature = "std")]
         use rs_matter::utils::epoch::sys_epoch as epoch;
 
@@ -200,17 +330,19 @@ impl E2eRunner {
         let matter = Matter::new(
             &TEST_DEV_DET,
             TEST_DEV_COMM,
-            &TEST_DEV_ATT,
+            dev_att,
             epoch,
             rand,
             MATTER_PORT,
         );
 
-        matter
-            .fabric_mgr
-            .borrow_mut()
-            .add_with_post_init(KeyPair::new(matter.rand()).unwrap(), |_| Ok(()))
-            .unwrap();
+        if fabriced {
+            matter
+                .fabric_mgr
+                .borrow_mut()
+                .add_with_post_init(KeyPair::new(matter.rand()).unwrap(), |_| Ok(()))
+                .unwrap();
+        }
 
         matter.initialize_transport_buffers().unwrap();
 
@@ -248,6 +380,37 @@ impl E2eRunner {
     }
 }
 
+/// A resolver which resolves all peers to the address of the other node of the E2E runner
+pub struct RunnerResolver;
+
+impl MdnsResolver for RunnerResolver {
+    async fn resolve(
+        &mut self,
+        _compressed_fabric_id: u64,
+        _node_id: u64,
+    ) -> Result<core::net::SocketAddr, Error> {
+        Ok(E2eRunner::ADDR.udp().unwrap())
+    }
+}
+
+struct FnReportHandler<F>(RefCell<F>);
+
+impl<F> ReportHandler for FnReportHandler<F>
+where
+    F: FnMut(&ReportDataMsg<'_>) -> Result<(), Error>,
+{
+    fn handle(
+        &self,
+        _fabric_idx: NonZeroU8,
+        _peer_node_id: u64,
+        report: &ReportDataMsg<'_>,
+    ) -> Result<bool, Error> {
+        (self.0.borrow_mut())(report)?;
+
+        Ok(true)
+    }
+}
+
 type NetworkPipe<'a, const N: usize> = Channel<'a, NoopRawMutex, heapless::Vec<u8, N>>;
 
 struct NetworkReceiveImpl<'a, const N: usize>(Receiver<'a, NoopRawMutex, heapless::Vec<u8, N>>);
@@ -0,0 +1,104 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::cell::RefCell;
+
+use rs_matter::bdx::{BdxHandler, BdxSink, BdxStatusCode, TransferInit};
+use rs_matter::error::Error;
+
+/// The file designator of the file which can be downloaded from the remote (tested) Matter instance
+pub const DOWNLOAD_FILE: &[u8] = b"download.bin";
+
+/// The file designator of the file which can be uploaded to the remote (tested) Matter instance
+pub const UPLOAD_FILE: &[u8] = b"upload.bin";
+
+/// The contents of the downloadable file
+pub fn download_data() -> Vec<u8> {
+    (0..5000).map(|i| (i % 251) as u8).collect()
+}
+
+/// A BDX handler for the remote (tested) Matter instance, which serves a single
+/// downloadable file and keeps the contents of the last uploaded file.
+pub struct TestBdxHandler {
+    download: Vec<u8>,
+    upload: RefCell<Vec<u8>>,
+}
+
+impl TestBdxHandler {
+    pub fn new() -> Self {
+        Self {
+            download: download_data(),
+            upload: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Return the contents of the last uploaded file
+    pub fn uploaded(&self) -> Vec<u8> {
+        self.upload.borrow().clone()
+    }
+}
+
+impl BdxHandler for TestBdxHandler {
+    type Sink<'a> = TestBdxSink<'a>;
+    type Source<'a> = &'a [u8];
+
+    fn accept_send(&self, init: &TransferInit<'_>) -> Result<Self::Sink<'_>, BdxStatusCode> {
+        if init.file_designator != UPLOAD_FILE {
+            return Err(BdxStatusCode::FileDesignatorUnknown);
+        }
+
+        if init.start_offset.is_some() {
+            return Err(BdxStatusCode::StartOffsetNotSupported);
+        }
+
+        self.upload.borrow_mut().clear();
+
+        Ok(TestBdxSink(&self.upload))
+    }
+
+    fn accept_receive(
+        &self,
+        init: &TransferInit<'_>,
+    ) -> Result<(Self::Source<'_>, Option<u64>), BdxStatusCode> {
+        if init.file_designator != DOWNLOAD_FILE {
+            return Err(BdxStatusCode::FileDesignatorUnknown);
+        }
+
+        let offset = init.start_offset.unwrap_or(0) as usize;
+        if offset > self.download.len() {
+            return Err(BdxStatusCode::LengthTooShort);
+        }
+
+        let data = &self.download[offset..];
+        let len = init
+            .length
+            .map(|max| (max as usize).min(data.len()))
+            .unwrap_or(data.len());
+
+        Ok((&data[..len], Some(len as u64)))
+    }
+}
+
+pub struct TestBdxSink<'a>(&'a RefCell<Vec<u8>>);
+
+impl BdxSink for TestBdxSink<'_> {
+    async fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.0.borrow_mut().extend_from_slice(data);
+
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@
 use bitflags::bitflags;
 
 use rs_matter::error::Error;
+use rs_matter::im::EventResp;
 use rs_matter::im::{AttrPath, AttrResp, AttrStatus, DataVersionFilter, EventFilter, EventPath};
 use rs_matter::im::{OpCode, PROTO_ID_INTERACTION_MODEL};
 use rs_matter::im::{ReportDataMsg, WriteReqTag};
@@ -67,6 +68,14 @@ impl<'a> TestReadReq<'a> {
             ..Self::new()
         }
     }
+
+    /// Create a new `TestReadReq` instance with the provided event requests.
+    pub const fn event_reqs(reqs: &'a [EventPath]) -> Self {
+        Self {
+            event_requests: Some(reqs),
+            ..Self::new()
+        }
+    }
 }
 
 /// A `ReadResp` alternative more suitable for testing.
@@ -209,8 +218,7 @@ impl<'a> TestSubscribeReq<'a> {
 pub struct TestReportDataMsg<'a> {
     pub subscription_id: Option<u32>,
     pub attr_reports: Option<&'a [TestAttrResp<'a>]>,
-    // TODO
-    pub event_reports: Option<bool>,
+    pub event_reports: Option<&'a [EventResp<'a>]>,
     pub more_chunks: Option<bool>,
     pub suppress_response: Option<bool>,
 }
@@ -235,6 +243,15 @@ impl<'a> TestReportDataMsg<'a> {
             ..Self::new()
         }
     }
+
+    /// Create a new `TestReportDataMsg` instance with the provided event reports.
+    pub const fn event_reports(reports: &'a [EventResp<'a>]) -> Self {
+        Self {
+            event_reports: Some(reports),
+            suppress_response: Some(true),
+            ..Self::new()
+        }
+    }
 }
 
 impl TestToTLV for TestReportDataMsg<'_> {
@@ -254,7 +271,11 @@ impl TestToTLV for TestReportDataMsg<'_> {
         }
 
         if let Some(event_reports) = self.event_reports {
-            tw.bool(&TLVTag::Context(2), event_reports)?;
+            tw.start_array(&TLVTag::Context(2))?;
+            for event_report in event_reports {
+                event_report.to_tlv(&TLVTag::Anonymous, &mut *tw)?;
+            }
+            tw.end_container()?;
         }
 
         if let Some(more_chunks) = self.more_chunks {
@@ -384,6 +405,7 @@ bitflags! {
     pub struct ReplyProcessor: u8 {
         const REMOVE_ATTRDATA_DATAVER = 0b01;
         const REMOVE_ATTRDATA_VALUE = 0b10;
+        const REMOVE_EVENTDATA_TIMESTAMP = 0b100;
     }
 }
 
@@ -430,7 +452,24 @@ impl ReplyProcessor {
         }
 
         if let Some(event_reports) = report_data.event_reports {
-            tw.bool(&TLVTag::Context(2), event_reports)?;
+            tw.start_array(&TLVTag::Context(2))?;
+
+            for event_report in event_reports {
+                let mut event_report = event_report?;
+
+                if let EventResp::Data(data) = &mut event_report {
+                    if self.contains(Self::REMOVE_EVENTDATA_TIMESTAMP) {
+                        data.epoch_timestamp = None;
+                        data.system_timestamp = None;
+                        data.delta_epoch_timestamp = None;
+                        data.delta_system_timestamp = None;
+                    }
+                }
+
+                event_report.to_tlv(&TLVTag::Anonymous, &mut tw)?;
+            }
+
+            tw.end_container()?;
         }
 
         if let Some(more_chunks) = report_data.more_chunks {
@@ -460,6 +499,11 @@ impl ReplyProcessor {
     pub fn remove_attr_data(element: &TLVElement, buf: &mut [u8]) -> Result<usize, Error> {
         (Self::REMOVE_ATTRDATA_VALUE | Self::REMOVE_ATTRDATA_DATAVER).process(element, buf)
     }
+
+    /// Process the supplied element with removing the timestamps from the `EventData` payload
+    pub fn remove_event_timestamp(element: &TLVElement, buf: &mut [u8]) -> Result<usize, Error> {
+        Self::REMOVE_EVENTDATA_TIMESTAMP.process(element, buf)
+    }
 }
 
 impl<I, E, F> TLVTest<I, E, F>
@@ -633,6 +677,19 @@ impl<'a>
             ReplyProcessor::remove_attr_dataver,
         )
     }
+
+    /// Create a new TLV test instance with input payload being the IM `ReadRequest` message
+    /// and the expected payload being the IM `ReportData` message and the input payload and the
+    /// expected payload being the provided event requests and responses.
+    ///
+    /// The reply will be processed to remove the timestamps from the `EventData` payload.
+    pub const fn read_events(input: &'a [EventPath], expected: &'a [EventResp<'a>]) -> Self {
+        Self::read(
+            TestReadReq::event_reqs(input),
+            TestReportDataMsg::event_reports(expected),
+            ReplyProcessor::remove_event_timestamp,
+        )
+    }
 }
 
 impl<'a>
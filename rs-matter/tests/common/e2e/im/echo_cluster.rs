@@ -26,13 +26,13 @@ use strum::{EnumDiscriminants, FromRepr};
 
 use rs_matter::dm::{
     Access, AttrDataEncoder, AttrDataWriter, AttrType, Attribute, Cluster, CmdDataEncoder,
-    CmdDataWriter, Command, Dataver, Handler, InvokeContext, NonBlockingHandler, Quality,
-    ReadContext, WriteContext,
+    CmdDataWriter, Command, Dataver, Event, EventPriority, Handler, InvokeContext,
+    NonBlockingHandler, Quality, ReadContext, WriteContext,
 };
 use rs_matter::error::{Error, ErrorCode};
 use rs_matter::im::{attr_list_write, ListOperation};
 use rs_matter::tlv::{TLVElement, TLVTag, TLVWrite};
-use rs_matter::{attribute_enum, attributes, command_enum, commands, with};
+use rs_matter::{attribute_enum, attributes, command_enum, commands, events, with};
 
 pub const WRITE_LIST_MAX: usize = 5;
 
@@ -66,6 +66,12 @@ pub enum RespCommands {
     EchoResp = 0x01,
 }
 
+#[derive(FromRepr)]
+#[repr(u32)]
+pub enum Events {
+    Pinged = 0x00,
+}
+
 pub const CLUSTER: Cluster<'static> = Cluster {
     id: ID,
     revision: 1,
@@ -102,8 +108,14 @@ pub const CLUSTER: Cluster<'static> = Cluster {
         Some(RespCommands::EchoResp as _),
         Access::WA,
     ),),
+    events: events!(Event::new(
+        Events::Pinged as _,
+        EventPriority::Info,
+        Access::READ.union(Access::NEED_VIEW),
+    ),),
     with_attrs: with!(all),
     with_cmds: with!(all),
+    with_events: with!(all),
 };
 
 /// This is used in the tests to validate any settings that may have happened
@@ -190,7 +190,7 @@ fn test_read_wc_endpoint_wc_attribute() {
 
     let acc_cmd_list: &[u32] = &[echo_cluster::Commands::EchoReq as _];
 
-    let event_list: &[u32] = &[];
+    let event_list: &[u32] = &[echo_cluster::Events::Pinged as _];
 
     let expected = &[
         attr_data!(
@@ -0,0 +1,190 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use embassy_futures::block_on;
+use embassy_futures::select::select;
+
+use rs_matter::bdx::{Bdx, BdxSink, TransferInit, TransferModes};
+use rs_matter::error::{Error, ErrorCode};
+use rs_matter::utils::select::Coalesce;
+
+use crate::common::e2e::bdx::{download_data, DOWNLOAD_FILE, UPLOAD_FILE};
+use crate::common::e2e::ImEngine;
+use crate::common::init_env_logger;
+
+/// The transfer modes to test, each with a block size that splits the data in multiple blocks
+const MODES: &[(TransferModes, u16)] = &[
+    (TransferModes::SENDER_DRIVE, 1000),
+    (TransferModes::RECEIVER_DRIVE, 512),
+    (TransferModes::SENDER_DRIVE.union(TransferModes::ASYNC), 700),
+];
+
+struct VecSink(Vec<u8>);
+
+impl BdxSink for VecSink {
+    async fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.0.extend_from_slice(data);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_bdx_send() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    let data: Vec<u8> = (0..3000).map(|i| (i % 241) as u8).collect();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            for (modes, max_block_size) in MODES {
+                let mut exchange = im.initiate_exchange().await?;
+
+                let sent = Bdx::new()
+                    .send(
+                        &mut exchange,
+                        &TransferInit {
+                            modes: *modes,
+                            max_block_size: *max_block_size,
+                            length: Some(data.len() as _),
+                            ..TransferInit::new(UPLOAD_FILE)
+                        },
+                        data.as_slice(),
+                    )
+                    .await?;
+
+                assert_eq!(sent, data.len() as u64);
+                assert_eq!(im.bdx.uploaded(), data);
+            }
+
+            // Data of an unknown length
+            let mut exchange = im.initiate_exchange().await?;
+            let sent = Bdx::new()
+                .send(&mut exchange, &TransferInit::new(UPLOAD_FILE), &data[..10])
+                .await?;
+
+            assert_eq!(sent, 10);
+            assert_eq!(im.bdx.uploaded(), &data[..10]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_bdx_receive() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    let data = download_data();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            for (modes, max_block_size) in MODES {
+                let mut exchange = im.initiate_exchange().await?;
+                let mut sink = VecSink(Vec::new());
+
+                let received = Bdx::new()
+                    .receive(
+                        &mut exchange,
+                        &TransferInit {
+                            modes: *modes,
+                            max_block_size: *max_block_size,
+                            ..TransferInit::new(DOWNLOAD_FILE)
+                        },
+                        &mut sink,
+                    )
+                    .await?;
+
+                assert_eq!(received, data.len() as u64);
+                assert_eq!(sink.0, data);
+            }
+
+            // A partial download
+            let mut exchange = im.initiate_exchange().await?;
+            let mut sink = VecSink(Vec::new());
+
+            Bdx::new()
+                .receive(
+                    &mut exchange,
+                    &TransferInit {
+                        start_offset: Some(100),
+                        length: Some(200),
+                        ..TransferInit::new(DOWNLOAD_FILE)
+                    },
+                    &mut sink,
+                )
+                .await?;
+
+            assert_eq!(sink.0, &data[100..300]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_bdx_rejected() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            // Unknown file designator
+            let mut exchange = im.initiate_exchange().await?;
+            let result = Bdx::new()
+                .receive(
+                    &mut exchange,
+                    &TransferInit::new(b"unknown.bin"),
+                    VecSink(Vec::new()),
+                )
+                .await;
+
+            assert_eq!(result.map_err(|e| e.code()), Err(ErrorCode::Failure));
+
+            // Start offsets are not supported for uploads
+            let mut exchange = im.initiate_exchange().await?;
+            let result = Bdx::new()
+                .send(
+                    &mut exchange,
+                    &TransferInit {
+                        start_offset: Some(10),
+                        ..TransferInit::new(UPLOAD_FILE)
+                    },
+                    &[0; 10][..],
+                )
+                .await;
+
+            assert_eq!(result.map_err(|e| e.code()), Err(ErrorCode::Failure));
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
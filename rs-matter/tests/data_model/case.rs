@@ -0,0 +1,227 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::num::NonZeroU8;
+
+use embassy_futures::block_on;
+use embassy_futures::select::select;
+
+use rs_matter::cert::ca::OperationalCa;
+use rs_matter::dm::clusters::decl::on_off;
+use rs_matter::sc::case::{Case, CaseSession};
+use rs_matter::transport::exchange::Exchange;
+use rs_matter::utils::select::Coalesce;
+use rs_matter::Matter;
+
+use crate::common::e2e::{E2eRunner, ImEngine};
+use crate::common::init_env_logger;
+
+/// Create an unfabriced runner whose nodes are then put on the fabric of a new operational CA
+/// (with an intermediate CA if `with_ica` is true)
+fn ca_runner(with_ica: bool) -> ImEngine {
+    let im = ImEngine::new_unfabriced();
+
+    let mut ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    if with_ica {
+        ca.generate_ica(2).unwrap();
+    }
+
+    im.add_ca_fabrics(&ca);
+    im.add_default_acl();
+
+    im
+}
+
+#[test]
+fn test_case_initiator() {
+    init_env_logger();
+
+    let im = ca_runner(true);
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let matter = im.matter_client();
+            let fab_idx = NonZeroU8::new(1).unwrap();
+
+            let mut exchange = Exchange::initiate_unsecured(matter, E2eRunner::ADDR).await?;
+            let session_id = Case::new()
+                .initiate(
+                    &mut exchange,
+                    fab_idx,
+                    E2eRunner::REMOTE_PEER_ID,
+                    &mut CaseSession::new(),
+                )
+                .await?;
+            drop(exchange);
+
+            // Both peers have a CASE session with each other
+            assert_eq!(
+                E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID),
+                Some(session_id as u16)
+            );
+            assert!(E2eRunner::case_session_id(&im.matter, E2eRunner::PEER_ID).is_some());
+
+            // The CASE session is usable
+            let mut exchange =
+                Exchange::initiate(matter, 1, E2eRunner::REMOTE_PEER_ID, true).await?;
+            assert!(
+                !on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_case_initiator_unknown_peer() {
+    init_env_logger();
+
+    let im = ca_runner(false);
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let matter = im.matter_client();
+            let fab_idx = NonZeroU8::new(1).unwrap();
+
+            // The responder does not recognize itself in the destination ID of Sigma1,
+            // hence the session establishment fails
+            let mut exchange = Exchange::initiate_unsecured(matter, E2eRunner::ADDR).await?;
+            let result = Case::new()
+                .initiate(
+                    &mut exchange,
+                    fab_idx,
+                    E2eRunner::REMOTE_PEER_ID + 1,
+                    &mut CaseSession::new(),
+                )
+                .await;
+            drop(exchange);
+
+            assert!(result.is_err());
+
+            assert!(E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID + 1).is_none());
+            assert!(E2eRunner::case_session_id(&im.matter, E2eRunner::PEER_ID).is_none());
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+/// Return the resumption ID and the shared secret of the CASE resumption state
+/// of the provided Matter instance with the provided peer
+fn resumption(matter: &Matter<'_>, peer_node_id: u64) -> Option<([u8; 16], [u8; 32])> {
+    matter
+        .transport_mgr
+        .session_mgr
+        .borrow()
+        .get_resumption_for_node(NonZeroU8::new(1).unwrap(), peer_node_id)
+        .map(|resumption| (resumption.resumption_id, resumption.shared_secret))
+}
+
+#[test]
+fn test_case_resumption() {
+    init_env_logger();
+
+    let im = ca_runner(false);
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let matter = im.matter_client();
+            let fab_idx = NonZeroU8::new(1).unwrap();
+
+            let establish = || async {
+                let mut exchange = Exchange::initiate_unsecured(matter, E2eRunner::ADDR).await?;
+
+                Case::new()
+                    .initiate(
+                        &mut exchange,
+                        fab_idx,
+                        E2eRunner::REMOTE_PEER_ID,
+                        &mut CaseSession::new(),
+                    )
+                    .await
+            };
+
+            // A full CASE handshake leaves both peers with the same resumption state
+            establish().await?;
+
+            let (resumption_id, shared_secret) =
+                resumption(matter, E2eRunner::REMOTE_PEER_ID).unwrap();
+            assert_eq!(
+                resumption(&im.matter, E2eRunner::PEER_ID),
+                Some((resumption_id, shared_secret))
+            );
+
+            // Sigma1 carries the resumption ID, and the responder answers with Sigma2_Resume:
+            // the new session is derived from the shared secret of the previous handshake,
+            // under a new resumption ID
+            let session_id = establish().await?;
+
+            let (resumed_id, resumed_secret) =
+                resumption(matter, E2eRunner::REMOTE_PEER_ID).unwrap();
+            assert_ne!(resumed_id, resumption_id);
+            assert_eq!(resumed_secret, shared_secret);
+            assert_eq!(
+                resumption(&im.matter, E2eRunner::PEER_ID),
+                Some((resumed_id, resumed_secret))
+            );
+
+            // The resumed session is usable
+            let mut exchange = Exchange::initiate_for_session(matter, session_id)?;
+            assert!(
+                !on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+            drop(exchange);
+
+            // Once the responder forgets the resumption state, the initiator falls back to a full handshake
+            im.matter
+                .transport_mgr
+                .session_mgr
+                .borrow_mut()
+                .remove_for_fabric(fab_idx, None);
+
+            establish().await?;
+
+            let (new_id, new_secret) = resumption(matter, E2eRunner::REMOTE_PEER_ID).unwrap();
+            assert_ne!(new_id, resumed_id);
+            assert_ne!(new_secret, resumed_secret);
+            assert_eq!(
+                resumption(&im.matter, E2eRunner::PEER_ID),
+                Some((new_id, new_secret))
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,701 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::cell::{Cell, RefCell};
+use core::num::NonZeroU8;
+
+use embassy_futures::block_on;
+use embassy_futures::select::select;
+use embassy_time::{Duration, Timer};
+
+use rs_matter::acl::{AclEntry, AuthMode};
+use rs_matter::cert::ca::OperationalCa;
+use rs_matter::dm::clusters::decl::{descriptor, on_off};
+use rs_matter::dm::devices::test::TEST_DEV_COMM;
+use rs_matter::dm::Privilege;
+use rs_matter::error::{Error, ErrorCode};
+use rs_matter::im::client::{
+    ImClient, InvokeRequest, ReadRequest, ReportHandler, SubscribeRequest, WriteRequest,
+};
+use rs_matter::im::{AttrData, AttrPath, AttrStatus, CmdData, CmdPath, CmdResp, GenericPath};
+use rs_matter::im::{IMStatusCode, ReportDataMsg};
+use rs_matter::pairing::DiscoveryCapabilities;
+use rs_matter::sc::pake::Pake;
+use rs_matter::sc::spake2p::Spake2P;
+use rs_matter::tlv::TLVElement;
+use rs_matter::transport::exchange::Exchange;
+use rs_matter::transport::network::mdns::MdnsResolver;
+use rs_matter::transport::network::SocketAddr;
+use rs_matter::transport::session::SessionMode;
+use rs_matter::utils::select::Coalesce;
+use rs_matter::Matter;
+
+use crate::common::e2e::im::echo_cluster;
+use crate::common::e2e::{E2eRunner, ImEngine, RunnerResolver};
+use crate::common::init_env_logger;
+
+/// Collect the u16 values of all attribute reports in the provided report
+fn collect_u16(
+    report: &ReportDataMsg<'_>,
+    values: &mut heapless::Vec<u16, 8>,
+) -> Result<(), Error> {
+    if let Some(attr_reports) = report.attr_reports.as_ref() {
+        for resp in attr_reports.iter() {
+            values.push(resp?.unwrap_data().data.u16()?).unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+fn att_path(endpoint: u16, attr: echo_cluster::AttributesDiscriminants) -> AttrPath {
+    AttrPath::new(&GenericPath::new(
+        Some(endpoint),
+        Some(echo_cluster::ID),
+        Some(attr as u32),
+    ))
+}
+
+#[test]
+fn test_client_read() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[
+                att_path(0, echo_cluster::AttributesDiscriminants::Att1),
+                att_path(1, echo_cluster::AttributesDiscriminants::Att2),
+            ];
+
+            let mut values = heapless::Vec::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::read(&mut exchange, &ReadRequest::attrs(paths), |report| {
+                collect_u16(report, &mut values)
+            })
+            .await?;
+
+            assert_eq!(values.as_slice(), &[0x1234, 0x5678]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_client_write() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let path = att_path(0, echo_cluster::AttributesDiscriminants::AttWrite);
+            let data = &[AttrData::new(
+                None,
+                path.clone(),
+                TLVElement::new(&[0x05, 0x34, 0x12]),
+            )];
+
+            let mut exchange = im.initiate_exchange().await?;
+            let ok = ImClient::write(&mut exchange, &WriteRequest::new(data), |resp| {
+                let mut statuses = resp.write_responses.iter();
+
+                Ok(statuses.next().transpose()?
+                    == Some(AttrStatus::new(&path.to_gp(), IMStatusCode::Success, 0))
+                    && statuses.next().is_none())
+            })
+            .await?;
+
+            assert_eq!(ok, Some(true));
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_client_timed_invoke() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let cmds = &[CmdData::new(
+                CmdPath::new(
+                    Some(0),
+                    Some(echo_cluster::ID),
+                    Some(echo_cluster::Commands::EchoReq as u32),
+                ),
+                TLVElement::new(&[0x04, 5]),
+            )];
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::timed(&mut exchange, 2000).await?;
+            let echo = ImClient::invoke(&mut exchange, &InvokeRequest::new(cmds).timed(), |resp| {
+                let resp = resp
+                    .inv_responses
+                    .as_ref()
+                    .unwrap()
+                    .iter()
+                    .next()
+                    .unwrap()?;
+
+                let CmdResp::Cmd(data) = resp else {
+                    panic!("Expected command data");
+                };
+
+                data.data.u8()
+            })
+            .await?;
+
+            assert_eq!(echo, Some(10));
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_client_subscribe() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[att_path(0, echo_cluster::AttributesDiscriminants::Att1)];
+
+            let mut values = heapless::Vec::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            let resp = ImClient::subscribe(
+                &mut exchange,
+                &SubscribeRequest::attrs(1, 10, paths),
+                |report| {
+                    assert!(report.subscription_id.is_some());
+
+                    collect_u16(report, &mut values)
+                },
+            )
+            .await?;
+
+            assert_eq!(values.as_slice(), &[0x1234]);
+            assert_eq!(resp.max_int, 10);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_client_read_chunked() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            // Reading all attributes of all endpoints does not fit in a single report
+            let paths = &[AttrPath::new(&GenericPath::new(None, None, None))];
+
+            let mut chunks = 0;
+            let mut last_more_chunks = None;
+            let mut endpoints = heapless::Vec::<u16, 8>::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::read(&mut exchange, &ReadRequest::attrs(paths), |report| {
+                chunks += 1;
+                last_more_chunks = report.more_chunks;
+
+                for resp in report.attr_reports.as_ref().unwrap().iter() {
+                    let endpoint = resp?.unwrap_data().path.endpoint.unwrap();
+
+                    if !endpoints.contains(&endpoint) {
+                        endpoints.push(endpoint).unwrap();
+                    }
+                }
+
+                Ok(())
+            })
+            .await?;
+
+            assert!(chunks > 1);
+            assert_ne!(last_more_chunks, Some(true));
+            assert_eq!(endpoints.as_slice(), &[0, 1]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+/// A report handler which only knows about a single subscription,
+/// and which collects the `OnOff` values reported for it
+struct OnOffReportHandler {
+    subscription_id: u32,
+    values: RefCell<heapless::Vec<bool, 8>>,
+}
+
+impl ReportHandler for OnOffReportHandler {
+    fn handle(
+        &self,
+        fabric_idx: NonZeroU8,
+        peer_node_id: u64,
+        report: &ReportDataMsg<'_>,
+    ) -> Result<bool, Error> {
+        assert_eq!(fabric_idx.get(), 1);
+        assert_eq!(peer_node_id, E2eRunner::REMOTE_PEER_ID);
+
+        if report.subscription_id != Some(self.subscription_id) {
+            return Ok(false);
+        }
+
+        if let Some(attr_reports) = report.attr_reports.as_ref() {
+            for resp in attr_reports.iter() {
+                let value = resp?.unwrap_data().data.bool()?;
+                self.values.borrow_mut().push(value).unwrap();
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[test]
+fn test_client_subscription_reports() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[AttrPath::new(&GenericPath::new(
+                Some(1),
+                Some(on_off::FULL_CLUSTER.id),
+                Some(on_off::AttributeId::OnOff as _),
+            ))];
+
+            let mut exchange = im.initiate_exchange().await?;
+            let resp = ImClient::subscribe(
+                &mut exchange,
+                &SubscribeRequest::attrs(0, 10, paths),
+                |_| Ok(()),
+            )
+            .await?;
+            drop(exchange);
+
+            let handler = OnOffReportHandler {
+                subscription_id: resp.subs_id,
+                values: RefCell::new(heapless::Vec::new()),
+            };
+
+            // Changing the subscribed attribute results in a report after the priming one
+            let mut exchange = im.initiate_exchange().await?;
+            on_off::ClusterClient::new()
+                .toggle(&mut exchange, 1)
+                .await?;
+            drop(exchange);
+
+            let mut exchange = Exchange::accept(im.matter_client()).await?;
+            ImClient::handle_report(&mut exchange, &handler).await?;
+            drop(exchange);
+
+            assert_eq!(handler.values.borrow().as_slice(), &[true]);
+
+            // ... and so does changing it again
+            let mut exchange = im.initiate_exchange().await?;
+            on_off::ClusterClient::new()
+                .toggle(&mut exchange, 1)
+                .await?;
+            drop(exchange);
+
+            let mut exchange = Exchange::accept(im.matter_client()).await?;
+            ImClient::handle_report(&mut exchange, &handler).await?;
+
+            assert_eq!(handler.values.borrow().as_slice(), &[true, false]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_cluster_client() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let on_off = on_off::ClusterClient::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            assert!(!on_off.read_on_off(&mut exchange, 1).await?);
+            drop(exchange);
+
+            let mut exchange = im.initiate_exchange().await?;
+            on_off.toggle(&mut exchange, 1).await?;
+            drop(exchange);
+
+            let mut exchange = im.initiate_exchange().await?;
+            assert!(on_off.read_on_off(&mut exchange, 1).await?);
+            drop(exchange);
+
+            let mut exchange = im.initiate_exchange().await?;
+            let parts = descriptor::ClusterClient::new()
+                .read_parts_list(&mut exchange, 0, |parts| {
+                    let mut vec = heapless::Vec::<u16, 4>::new();
+
+                    for part in parts.iter() {
+                        vec.push(part?).unwrap();
+                    }
+
+                    Ok(vec)
+                })
+                .await?;
+            drop(exchange);
+
+            assert_eq!(parts.as_slice(), &[1]);
+
+            let mut exchange = im.initiate_exchange().await?;
+            let result = on_off.read_on_off(&mut exchange, 2).await;
+
+            assert_eq!(
+                result.map_err(|e| e.code()),
+                Err(ErrorCode::EndpointNotFound)
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+/// A resolver which does not know about any peer
+struct NoPeersResolver;
+
+impl MdnsResolver for NoPeersResolver {
+    async fn resolve(
+        &mut self,
+        _compressed_fabric_id: u64,
+        _node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        Err(ErrorCode::NoNetworkInterface.into())
+    }
+}
+
+#[test]
+fn test_initiate_with_resolver() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let matter = im.matter_client();
+
+            // An existing session is re-used without resolving the peer
+            let mut exchange = Exchange::initiate_with(
+                matter,
+                NoPeersResolver,
+                1,
+                E2eRunner::REMOTE_PEER_ID,
+                true,
+            )
+            .await?;
+            assert!(
+                !on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+            drop(exchange);
+
+            // For peers without a session, the resolver is used
+            let result = Exchange::initiate_with(
+                matter,
+                NoPeersResolver,
+                1,
+                E2eRunner::REMOTE_PEER_ID + 1,
+                true,
+            )
+            .await;
+            assert_eq!(
+                result.map(|_| ()).map_err(|e| e.code()),
+                Err(ErrorCode::NoNetworkInterface)
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_pase_initiator() {
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+
+    /// Return the attestation challenge of the PASE session of the provided Matter instance
+    fn pase_att_challenge(matter: &Matter<'_>) -> Option<Vec<u8>> {
+        matter
+            .transport_mgr
+            .session_mgr
+            .borrow()
+            .iter()
+            .find(|sess| matches!(sess.get_session_mode(), SessionMode::Pase { .. }))
+            .map(|sess| sess.get_att_challenge().to_vec())
+    }
+
+    block_on(
+        select(im.run(im.handler()), async {
+            im.matter
+                .enable_basic_commissioning(DiscoveryCapabilities::IP, 0)
+                .await?;
+
+            let matter = im.matter_client();
+            let passcode = TEST_DEV_COMM.password;
+
+            let mut exchange = Exchange::initiate_unsecured(matter, E2eRunner::ADDR).await?;
+            let session_id = Pake::new()
+                .initiate(&mut exchange, &mut Spake2P::new(), passcode)
+                .await?;
+            drop(exchange);
+
+            // The PASE session is usable
+            let mut exchange = Exchange::initiate_for_session(matter, session_id)?;
+            assert!(
+                !on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+            drop(exchange);
+
+            // Both peers derived the same attestation challenge
+            let challenge = pase_att_challenge(matter);
+            assert!(challenge.is_some());
+            assert_eq!(pase_att_challenge(&im.matter), challenge);
+
+            // A wrong passcode fails the session establishment
+            let mut exchange = Exchange::initiate_unsecured(matter, E2eRunner::ADDR).await?;
+            assert!(Pake::new()
+                .initiate(&mut exchange, &mut Spake2P::new(), passcode + 1)
+                .await
+                .is_err());
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_group_invoke() {
+    const GROUP_ID: u16 = 0x0101;
+    const KEY_SET_ID: u16 = 0x01a1;
+    const EPOCH_KEY: &[u8] = &[0xa0; 16];
+
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    let fab_idx = NonZeroU8::new(1).unwrap();
+
+    for matter in [&im.matter, im.matter_client()] {
+        let mut fabric_mgr = matter.fabric_mgr.borrow_mut();
+
+        fabric_mgr
+            .group_key_set_add(fab_idx, KEY_SET_ID, &[(EPOCH_KEY, 0)])
+            .unwrap();
+        fabric_mgr
+            .group_key_map_add(fab_idx, GROUP_ID, KEY_SET_ID)
+            .unwrap();
+    }
+
+    // Only endpoint 1 of the remote node is a member of the group
+    im.matter
+        .fabric_mgr
+        .borrow_mut()
+        .group_add(fab_idx, GROUP_ID, 1, "Lights")
+        .unwrap();
+
+    let mut group_acl = AclEntry::new(None, Privilege::OPERATE, AuthMode::Group);
+    group_acl.add_subject(GROUP_ID as u64).unwrap();
+    im.matter
+        .fabric_mgr
+        .borrow_mut()
+        .acl_add(fab_idx, group_acl)
+        .unwrap();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let cmds = &[CmdData::new(
+                CmdPath::new(
+                    None,
+                    Some(on_off::FULL_CLUSTER.id),
+                    Some(on_off::CommandId::Toggle as u32),
+                ),
+                TLVElement::new(&[0x15, 0x18]),
+            )];
+
+            let mut exchange = Exchange::initiate_group(im.matter_client(), 1, GROUP_ID).await?;
+            let result = ImClient::invoke(
+                &mut exchange,
+                &InvokeRequest {
+                    suppress_response: Some(true),
+                    ..InvokeRequest::new(cmds)
+                },
+                |_| Ok(()),
+            )
+            .await?;
+            drop(exchange);
+
+            // Group messages are never responded to
+            assert!(result.is_none());
+
+            // Give the remote node time to process the group message
+            Timer::after(Duration::from_millis(100)).await;
+
+            let mut exchange = im.initiate_exchange().await?;
+            assert!(
+                on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+/// A resolver which resolves all peers to the address of the remote node of the E2E runner,
+/// counting the resolutions
+struct CountingResolver<'a>(&'a Cell<usize>);
+
+impl MdnsResolver for CountingResolver<'_> {
+    async fn resolve(
+        &mut self,
+        compressed_fabric_id: u64,
+        node_id: u64,
+    ) -> Result<SocketAddr, Error> {
+        self.0.set(self.0.get() + 1);
+
+        RunnerResolver.resolve(compressed_fabric_id, node_id).await
+    }
+}
+
+#[test]
+fn test_initiate_resolves_peer() {
+    init_env_logger();
+
+    let im = ImEngine::new_unfabriced();
+
+    let ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    im.add_ca_fabrics(&ca);
+
+    im.add_default_acl();
+
+    let resolutions = Cell::new(0);
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let matter = im.matter_client();
+
+            // Without a resolver, peers with unknown addresses cannot be reached
+            let result = Exchange::initiate(matter, 1, E2eRunner::REMOTE_PEER_ID, true).await;
+            assert_eq!(
+                result.map(|_| ()).map_err(|e| e.code()),
+                Err(ErrorCode::NoSession)
+            );
+
+            select(matter.run_resolver(CountingResolver(&resolutions)), async {
+                // The peer is resolved and a CASE session is established with it
+                let mut exchange =
+                    Exchange::initiate(matter, 1, E2eRunner::REMOTE_PEER_ID, true).await?;
+                assert!(
+                    !on_off::ClusterClient::new()
+                        .read_on_off(&mut exchange, 1)
+                        .await?
+                );
+                drop(exchange);
+
+                assert_eq!(resolutions.get(), 1);
+                assert!(E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID).is_some());
+
+                // Once the session is gone, the last known address of the peer is re-used
+                matter
+                    .transport_mgr
+                    .session_mgr
+                    .borrow_mut()
+                    .remove_for_fabric(NonZeroU8::new(1).unwrap(), None);
+                assert!(E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID).is_none());
+
+                let mut exchange =
+                    Exchange::initiate(matter, 1, E2eRunner::REMOTE_PEER_ID, true).await?;
+                assert!(
+                    !on_off::ClusterClient::new()
+                        .read_on_off(&mut exchange, 1)
+                        .await?
+                );
+
+                assert_eq!(resolutions.get(), 1);
+
+                Ok(())
+            })
+            .coalesce()
+            .await
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
@@ -15,11 +15,13 @@
  *    limitations under the License.
  */
 
+use rs_matter::dm::clusters::grp_key_mgmt::{self, ClusterHandler as _};
 use rs_matter::dm::clusters::on_off::{self, ClusterHandler as _};
 use rs_matter::im::IMStatusCode;
 use rs_matter::im::{CmdPath, CmdStatus};
+use rs_matter::tlv::ToTLV;
 
-use crate::common::e2e::im::commands::TestCmdResp;
+use crate::common::e2e::im::commands::{TestCmdData, TestCmdResp};
 use crate::common::e2e::im::echo_cluster;
 use crate::common::e2e::ImEngine;
 use crate::common::init_env_logger;
@@ -131,3 +133,33 @@ fn test_invoke_cmd_wc_endpoint_only_1_has_cluster() {
     ))];
     ImEngine::commands(input, expected);
 }
+
+#[test]
+fn test_invoke_cmd_not_found() {
+    // 1 KeySetRemove command for a group key set which does not exist
+    // should fail with the NotFound status rather than with a generic Failure
+    init_env_logger();
+
+    #[derive(Debug, ToTLV)]
+    struct KeySetRemoveReq {
+        group_key_set_id: u16,
+    }
+
+    let path = CmdPath::new(
+        Some(0),
+        Some(grp_key_mgmt::GrpKeyMgmtHandler::CLUSTER.id),
+        Some(grp_key_mgmt::CommandId::KeySetRemove as u32),
+    );
+    let input = &[TestCmdData::new(
+        path.clone(),
+        &KeySetRemoveReq {
+            group_key_set_id: 5,
+        },
+    )];
+    let expected = &[TestCmdResp::Status(CmdStatus::new(
+        path,
+        IMStatusCode::NotFound,
+        0,
+    ))];
+    ImEngine::commands(input, expected);
+}
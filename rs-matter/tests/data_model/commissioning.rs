@@ -0,0 +1,336 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::num::NonZeroU8;
+
+use embassy_futures::block_on;
+use embassy_futures::select::select;
+
+use rs_matter::cert::ca::OperationalCa;
+use rs_matter::cert::cd::{CertDeclarationContent, CD_FORMAT_VERSION};
+use rs_matter::cert::devatt::{AttestationCert, DevAttGenerator};
+use rs_matter::commissioner::attestation::{
+    DefaultAttestationVerifier, StaticTrustStore, TEST_CD_SIGNING_KEYS,
+};
+use rs_matter::commissioner::{Commissioner, CommissioningParams};
+use rs_matter::crypto::{BIGNUM_LEN_BYTES, EC_POINT_LEN_BYTES};
+use rs_matter::dm::clusters::decl::on_off;
+use rs_matter::dm::clusters::dev_att::{DataType, DevAttDataFetcher};
+use rs_matter::dm::devices::test::{TEST_DEV_COMM, TEST_PID, TEST_VID};
+use rs_matter::error::{Error, ErrorCode};
+use rs_matter::pairing::DiscoveryCapabilities;
+use rs_matter::transport::exchange::Exchange;
+use rs_matter::utils::epoch::sys_epoch;
+use rs_matter::utils::rand::sys_rand;
+use rs_matter::utils::select::Coalesce;
+
+use crate::common::e2e::{E2eRunner, ImEngine, RunnerResolver};
+use crate::common::init_env_logger;
+
+#[test]
+fn test_commissioning() {
+    init_env_logger();
+
+    let im = ImEngine::new_unfabriced();
+
+    let ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    ca.add_fabric(
+        &mut im.matter_client().fabric_mgr.borrow_mut(),
+        E2eRunner::PEER_ID,
+        &[],
+        E2eRunner::CA_IPK,
+        E2eRunner::CA_VENDOR_ID,
+        &mut || (),
+    )
+    .unwrap();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            im.matter
+                .enable_basic_commissioning(DiscoveryCapabilities::IP, 0)
+                .await?;
+
+            let matter = im.matter_client();
+
+            let mut commissioner =
+                Commissioner::new(matter, NonZeroU8::new(1).unwrap(), RunnerResolver, &ca, &());
+
+            commissioner
+                .commission_with_passcode(
+                    E2eRunner::ADDR,
+                    TEST_DEV_COMM.password,
+                    &CommissioningParams::new(E2eRunner::REMOTE_PEER_ID),
+                )
+                .await?;
+
+            // The device joined the fabric of the CA with the assigned node ID,
+            // and the commissioner is its administrator
+            {
+                let fabric_mgr = im.matter.fabric_mgr.borrow();
+                let fabric = fabric_mgr.iter().next().unwrap();
+
+                assert_eq!(fabric.fabric_id(), E2eRunner::CA_FABRIC_ID);
+                assert_eq!(fabric.node_id(), E2eRunner::REMOTE_PEER_ID);
+                assert_eq!(fabric.root_ca(), ca.rcac());
+            }
+
+            // The device is reachable over the CASE session established by the commissioner
+            assert!(E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID).is_some());
+
+            let mut exchange =
+                Exchange::initiate(matter, 1, E2eRunner::REMOTE_PEER_ID, true).await?;
+            assert!(
+                !on_off::ClusterClient::new()
+                    .read_on_off(&mut exchange, 1)
+                    .await?
+            );
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_commissioning_untrusted_device() {
+    init_env_logger();
+
+    let im = ImEngine::new_unfabriced();
+
+    let ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    ca.add_fabric(
+        &mut im.matter_client().fabric_mgr.borrow_mut(),
+        E2eRunner::PEER_ID,
+        &[],
+        E2eRunner::CA_IPK,
+        E2eRunner::CA_VENDOR_ID,
+        &mut || (),
+    )
+    .unwrap();
+
+    // The trust store does not have the PAA of the test device
+    let verifier = DefaultAttestationVerifier::new(
+        StaticTrustStore::new(&[], TEST_CD_SIGNING_KEYS),
+        im.matter.epoch(),
+    );
+
+    block_on(
+        select(im.run(im.handler()), async {
+            im.matter
+                .enable_basic_commissioning(DiscoveryCapabilities::IP, 0)
+                .await?;
+
+            let matter = im.matter_client();
+
+            let mut commissioner = Commissioner::new(
+                matter,
+                NonZeroU8::new(1).unwrap(),
+                RunnerResolver,
+                &ca,
+                &verifier,
+            );
+
+            let err = commissioner
+                .commission_with_passcode(
+                    E2eRunner::ADDR,
+                    TEST_DEV_COMM.password,
+                    &CommissioningParams::new(E2eRunner::REMOTE_PEER_ID),
+                )
+                .await
+                .unwrap_err();
+
+            assert_eq!(err.code(), ErrorCode::NotFound);
+
+            // The commissioning was aborted before the device got a NOC
+            assert!(im.matter.fabric_mgr.borrow().iter().next().is_none());
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_commissioning_generated_dev_att() {
+    init_env_logger();
+
+    let generator = DevAttGenerator::new(sys_epoch, sys_rand);
+
+    let paa = generator.generate_paa("Test PAA", Some(TEST_VID)).unwrap();
+    let pai = generator
+        .generate_pai(&paa, "Test PAI", TEST_VID, None)
+        .unwrap();
+    let dac = generator
+        .generate_dac(&pai, "Test DAC", TEST_VID, TEST_PID)
+        .unwrap();
+    let signer = generator.generate_cd_signer("Test CD Signer").unwrap();
+
+    let dev_att = GeneratedDevAtt::new(&signer, &pai, &dac);
+
+    let im = ImEngine::new_unfabriced_with_dev_att(Box::leak(Box::new(dev_att)));
+
+    let ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    ca.add_fabric(
+        &mut im.matter_client().fabric_mgr.borrow_mut(),
+        E2eRunner::PEER_ID,
+        &[],
+        E2eRunner::CA_IPK,
+        E2eRunner::CA_VENDOR_ID,
+        &mut || (),
+    )
+    .unwrap();
+
+    let mut signer_pubkey = [0; EC_POINT_LEN_BYTES];
+    signer.key().get_public_key(&mut signer_pubkey).unwrap();
+
+    let paas: &[&[u8]] = &[paa.cert()];
+    let cd_signing_keys: &[(&[u8], &[u8])] = &[(signer.key_id().unwrap(), &signer_pubkey)];
+    let verifier = DefaultAttestationVerifier::new(
+        StaticTrustStore::new(paas, cd_signing_keys),
+        im.matter.epoch(),
+    );
+
+    block_on(
+        select(im.run(im.handler()), async {
+            im.matter
+                .enable_basic_commissioning(DiscoveryCapabilities::IP, 0)
+                .await?;
+
+            let matter = im.matter_client();
+
+            let mut commissioner = Commissioner::new(
+                matter,
+                NonZeroU8::new(1).unwrap(),
+                RunnerResolver,
+                &ca,
+                &verifier,
+            );
+
+            commissioner
+                .commission_with_passcode(
+                    E2eRunner::ADDR,
+                    TEST_DEV_COMM.password,
+                    &CommissioningParams::new(E2eRunner::REMOTE_PEER_ID),
+                )
+                .await?;
+
+            // The attestation of the device was verified, and it joined the fabric of the CA
+            {
+                let fabric_mgr = im.matter.fabric_mgr.borrow();
+                let fabric = fabric_mgr.iter().next().unwrap();
+
+                assert_eq!(fabric.fabric_id(), E2eRunner::CA_FABRIC_ID);
+                assert_eq!(fabric.node_id(), E2eRunner::REMOTE_PEER_ID);
+            }
+
+            assert!(E2eRunner::case_session_id(matter, E2eRunner::REMOTE_PEER_ID).is_some());
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+/// Device attestation credentials, generated for the VID and PID of the test device
+struct GeneratedDevAtt {
+    cd: Vec<u8>,
+    pai: Vec<u8>,
+    dac: Vec<u8>,
+    dac_pubkey: [u8; EC_POINT_LEN_BYTES],
+    dac_privkey: [u8; BIGNUM_LEN_BYTES],
+}
+
+impl GeneratedDevAtt {
+    fn new(signer: &AttestationCert, pai: &AttestationCert, dac: &AttestationCert) -> Self {
+        let mut cd = vec![0; 600];
+        let cd_len = signer
+            .sign_cd(
+                &CertDeclarationContent {
+                    format_version: CD_FORMAT_VERSION,
+                    vendor_id: TEST_VID,
+                    product_id_array: &[TEST_PID],
+                    device_type_id: 0x16,
+                    certificate_id: "CSA00000SWC00000-00",
+                    security_level: 0,
+                    security_information: 0,
+                    version_number: 1,
+                    certification_type: 0,
+                    dac_origin_vendor_id: None,
+                    dac_origin_product_id: None,
+                    authorized_paa_list: None,
+                },
+                &mut cd,
+            )
+            .unwrap();
+        cd.truncate(cd_len);
+
+        let mut dac_pubkey = [0; EC_POINT_LEN_BYTES];
+        dac.key().get_public_key(&mut dac_pubkey).unwrap();
+
+        let mut dac_privkey = [0; BIGNUM_LEN_BYTES];
+        dac.key().get_private_key(&mut dac_privkey).unwrap();
+
+        Self {
+            cd,
+            pai: pai.cert().to_vec(),
+            dac: dac.cert().to_vec(),
+            dac_pubkey,
+            dac_privkey,
+        }
+    }
+}
+
+impl DevAttDataFetcher for GeneratedDevAtt {
+    fn get_devatt_data(&self, data_type: DataType, data: &mut [u8]) -> Result<usize, Error> {
+        let src = match data_type {
+            DataType::CertDeclaration => &self.cd[..],
+            DataType::PAI => &self.pai[..],
+            DataType::DAC => &self.dac[..],
+            DataType::DACPubKey => &self.dac_pubkey[..],
+            DataType::DACPrivKey => &self.dac_privkey[..],
+        };
+
+        let data = data.get_mut(..src.len()).ok_or(ErrorCode::NoSpace)?;
+        data.copy_from_slice(src);
+
+        Ok(src.len())
+    }
+}
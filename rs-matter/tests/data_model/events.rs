@@ -0,0 +1,380 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use core::num::NonZeroU8;
+
+use embassy_futures::block_on;
+use embassy_futures::select::select;
+
+use rs_matter::dm::clusters::basic_info::{self, BasicInfoHandler};
+use rs_matter::dm::devices::test::TEST_DEV_DET;
+use rs_matter::dm::{EventPriority, GlobalElements};
+use rs_matter::error::Error;
+use rs_matter::im::client::{ImClient, ReadRequest, SubscribeRequest};
+use rs_matter::im::ReportDataMsg;
+use rs_matter::im::{
+    EventData, EventFilter, EventPath, EventResp, EventStatus, GenericPath, IMStatusCode,
+};
+use rs_matter::tlv::{TLVElement, TLVTag, TLVWrite};
+use rs_matter::utils::select::Coalesce;
+
+use crate::common::e2e::im::{echo_cluster, ReplyProcessor, TestReadReq, TestReportDataMsg};
+use crate::common::e2e::tlv::TLVTest;
+use crate::common::e2e::ImEngine;
+use crate::common::init_env_logger;
+
+// An anonymous TLV u8 element with value 42
+const DATA_42: &[u8] = &[0x04, 42];
+// An anonymous TLV u8 element with value 43
+const DATA_43: &[u8] = &[0x04, 43];
+
+fn pinged(endpoint: u16, number: u64, data: &[u8]) -> EventResp<'_> {
+    EventResp::Data(EventData {
+        path: EventPath::new(&GenericPath::new(
+            Some(endpoint),
+            Some(echo_cluster::ID),
+            Some(echo_cluster::Events::Pinged as _),
+        )),
+        event_number: number,
+        priority: EventPriority::Info,
+        epoch_timestamp: None,
+        system_timestamp: None,
+        delta_epoch_timestamp: None,
+        delta_system_timestamp: None,
+        data: TLVElement::new(data),
+    })
+}
+
+fn emit_pinged(im: &ImEngine, endpoint: u16, fab_idx: Option<NonZeroU8>, value: u8) {
+    im.events
+        .emit(
+            endpoint,
+            echo_cluster::ID,
+            echo_cluster::Events::Pinged as _,
+            EventPriority::Info,
+            fab_idx,
+            |wb| wb.u8(&TLVTag::Anonymous, value),
+        )
+        .unwrap();
+}
+
+/// Collect the event numbers and the u8 payloads of all event reports in the provided report
+fn collect_events(
+    report: &ReportDataMsg<'_>,
+    events: &mut heapless::Vec<(u64, u8), 8>,
+) -> Result<(), Error> {
+    if let Some(event_reports) = report.event_reports.as_ref() {
+        for resp in event_reports.iter() {
+            let EventResp::Data(data) = resp? else {
+                panic!("Expected event data");
+            };
+
+            events.push((data.event_number, data.data.u8()?)).unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_read_events() {
+    // 2 events emitted, one on endpoint 0 and one on endpoint 1
+    // - wildcard endpoint read - both are reported, in event number order
+    // - endpoint 1 read - only the second one is reported
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    emit_pinged(&im, 0, None, 42);
+    emit_pinged(&im, 1, None, 43);
+
+    let wc_ep = EventPath::new(&GenericPath::new(
+        None,
+        Some(echo_cluster::ID),
+        Some(echo_cluster::Events::Pinged as _),
+    ));
+    let ep1 = EventPath::new(&GenericPath::new(Some(1), Some(echo_cluster::ID), None));
+
+    im.test_one(
+        im.handler(),
+        TLVTest::read_events(&[wc_ep], &[pinged(0, 0, DATA_42), pinged(1, 1, DATA_43)]),
+    );
+    im.test_one(
+        im.handler(),
+        TLVTest::read_events(&[ep1], &[pinged(1, 1, DATA_43)]),
+    );
+}
+
+#[test]
+fn test_read_events_event_min() {
+    // 2 events emitted, only the one with event number >= 1 is reported
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    emit_pinged(&im, 0, None, 42);
+    emit_pinged(&im, 0, None, 43);
+
+    let wc = EventPath::new(&GenericPath::new(None, None, None));
+
+    im.test_one(
+        im.handler(),
+        TLVTest::read(
+            TestReadReq {
+                event_filters: Some(&[EventFilter {
+                    node: None,
+                    event_min: Some(1),
+                }]),
+                ..TestReadReq::event_reqs(&[wc])
+            },
+            TestReportDataMsg::event_reports(&[pinged(0, 1, DATA_43)]),
+            ReplyProcessor::remove_event_timestamp,
+        ),
+    );
+}
+
+#[test]
+fn test_read_events_unsupported_paths() {
+    // 3 reads
+    // - endpoint doesn't exist - UnsupportedEndpoint
+    // - cluster doesn't exist - UnsupportedCluster
+    // - event doesn't exist - UnsupportedEvent
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    emit_pinged(&im, 0, None, 42);
+
+    let invalid_endpoint = GenericPath::new(
+        Some(2),
+        Some(echo_cluster::ID),
+        Some(echo_cluster::Events::Pinged as _),
+    );
+    let invalid_cluster = GenericPath::new(
+        Some(0),
+        Some(0x1234),
+        Some(echo_cluster::Events::Pinged as _),
+    );
+    let invalid_event = GenericPath::new(Some(0), Some(echo_cluster::ID), Some(0x1234));
+
+    im.test_one(
+        im.handler(),
+        TLVTest::read_events(
+            &[
+                EventPath::new(&invalid_endpoint),
+                EventPath::new(&invalid_cluster),
+                EventPath::new(&invalid_event),
+            ],
+            &[
+                EventResp::Status(EventStatus::new(
+                    &invalid_endpoint,
+                    IMStatusCode::UnsupportedEndpoint,
+                    0,
+                )),
+                EventResp::Status(EventStatus::new(
+                    &invalid_cluster,
+                    IMStatusCode::UnsupportedCluster,
+                    0,
+                )),
+                EventResp::Status(EventStatus::new(
+                    &invalid_event,
+                    IMStatusCode::UnsupportedEvent,
+                    0,
+                )),
+            ],
+        ),
+    );
+}
+
+#[test]
+fn test_read_events_no_access() {
+    // No ACL entries, hence:
+    // - wildcard read - silently drops the event
+    // - concrete read - UnsupportedAccess
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+
+    emit_pinged(&im, 0, None, 42);
+
+    let wc = EventPath::new(&GenericPath::new(None, None, None));
+    let ep0 = GenericPath::new(
+        Some(0),
+        Some(echo_cluster::ID),
+        Some(echo_cluster::Events::Pinged as _),
+    );
+
+    im.test_one(
+        im.handler(),
+        TLVTest::read_events(
+            &[wc, EventPath::new(&ep0)],
+            &[EventResp::Status(EventStatus::new(
+                &ep0,
+                IMStatusCode::UnsupportedAccess,
+                0,
+            ))],
+        ),
+    );
+}
+
+#[test]
+fn test_read_events_fabric_sensitive() {
+    // Events associated with a fabric are only reported to that fabric
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    emit_pinged(&im, 0, NonZeroU8::new(2), 42);
+    emit_pinged(&im, 0, NonZeroU8::new(1), 43);
+
+    let wc = EventPath::new(&GenericPath::new(None, None, None));
+
+    im.test_one(
+        im.handler(),
+        TLVTest::read_events(&[wc], &[pinged(0, 1, DATA_43)]),
+    );
+}
+
+#[test]
+fn test_subscribe_events() {
+    // The priming report contains the event emitted before subscribing,
+    // while an event emitted afterwards is reported on its own in a subsequent report
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    emit_pinged(&im, 0, None, 42);
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[EventPath::new(&GenericPath::new(
+                Some(0),
+                Some(echo_cluster::ID),
+                Some(echo_cluster::Events::Pinged as _),
+            ))];
+
+            let mut events = heapless::Vec::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::subscribe(
+                &mut exchange,
+                &SubscribeRequest {
+                    event_requests: Some(paths),
+                    ..SubscribeRequest::new(0, 10)
+                },
+                |report| collect_events(report, &mut events),
+            )
+            .await?;
+            drop(exchange);
+
+            assert_eq!(events.as_slice(), &[(0, 42)]);
+
+            emit_pinged(&im, 0, None, 43);
+
+            let mut events = heapless::Vec::new();
+            im.recv_report(|report| {
+                assert!(report.subscription_id.is_some());
+
+                collect_events(report, &mut events)
+            })
+            .await?;
+
+            assert_eq!(events.as_slice(), &[(1, 43)]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_read_start_up_event() {
+    // The StartUp event of the Basic Information cluster is advertised in its EventList,
+    // and once emitted on node start, it can be read with the software version of the node
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    BasicInfoHandler::emit_start_up(&im.events, &im.matter).unwrap();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[EventPath::new(&GenericPath::new(
+                Some(0),
+                Some(basic_info::FULL_CLUSTER.id),
+                Some(basic_info::EventId::StartUp as _),
+            ))];
+
+            let mut exchange = im.initiate_exchange().await?;
+            let event_list = ImClient::read_attr(
+                &mut exchange,
+                0,
+                basic_info::FULL_CLUSTER.id,
+                GlobalElements::EventList as _,
+                |list| {
+                    let mut ids = heapless::Vec::<u32, 4>::new();
+
+                    for id in list.array()?.iter() {
+                        ids.push(id?.u32()?).unwrap();
+                    }
+
+                    Ok(ids)
+                },
+            )
+            .await?;
+            drop(exchange);
+
+            assert_eq!(
+                event_list.as_slice(),
+                &[basic_info::EventId::StartUp as u32]
+            );
+
+            let mut sw_vers = heapless::Vec::<u32, 1>::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::read(&mut exchange, &ReadRequest::events(paths), |report| {
+                for resp in report.event_reports.as_ref().unwrap().iter() {
+                    let EventResp::Data(data) = resp? else {
+                        panic!("Expected event data");
+                    };
+
+                    assert_eq!(data.priority, EventPriority::Critical);
+
+                    sw_vers
+                        .push(basic_info::StartUp::new(data.data).software_version()?)
+                        .unwrap();
+                }
+
+                Ok(())
+            })
+            .await?;
+
+            assert_eq!(sw_vers.as_slice(), &[TEST_DEV_DET.sw_ver]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
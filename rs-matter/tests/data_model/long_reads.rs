@@ -256,7 +256,7 @@ fn test_long_read_success() {
 
 #[test]
 fn test_long_read_subscription_success() {
-    const PART_1: usize = 38;
+    const PART_1: usize = 37;
     const PART_2: usize = 37;
     const PART_3: usize = 37;
 
@@ -326,7 +326,7 @@ fn test_long_read_subscription_success() {
                 },
                 SubscribeResp {
                     subs_id: 1,
-                    max_int: 40,
+                    max_int: 10,
                     ..Default::default()
                 },
                 ReplyProcessor::none,
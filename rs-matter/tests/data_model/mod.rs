@@ -18,6 +18,17 @@
 mod acl_and_dataver;
 mod attribute_lists;
 mod attributes;
+mod bdx;
+// These exercise the typed cluster clients and the commissioner,
+// so they are only built with the `client` feature
+#[cfg(feature = "client")]
+mod case;
+#[cfg(feature = "client")]
+mod client;
 mod commands;
+#[cfg(feature = "client")]
+mod commissioning;
+mod events;
 mod long_reads;
+mod subscriptions;
 mod timed_requests;
@@ -0,0 +1,223 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use embassy_futures::block_on;
+use embassy_futures::select::{select, select3};
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_time::{Duration, Timer};
+
+use rs_matter::cert::ca::OperationalCa;
+use rs_matter::dm::clusters::decl::on_off;
+use rs_matter::dm::subscriptions::Subscriptions;
+use rs_matter::dm::{DataModel, IMBuffer};
+use rs_matter::error::Error;
+use rs_matter::im::client::{ImClient, InvokeRequest, SubscribeRequest};
+use rs_matter::im::{AttrPath, CmdData, CmdPath, GenericPath, ReportDataMsg};
+use rs_matter::persist::Psm;
+use rs_matter::sc::SecureChannel;
+use rs_matter::tlv::TLVElement;
+use rs_matter::transport::exchange::Exchange;
+use rs_matter::utils::select::Coalesce;
+use rs_matter::utils::storage::pooled::PooledBuffers;
+
+use crate::common::e2e::im::echo_cluster;
+use crate::common::e2e::{E2eRunner, ImEngine, RunnerResolver};
+use crate::common::init_env_logger;
+
+/// Collect the (endpoint, cluster) pairs of all attribute reports in the provided report
+fn collect_clusters(
+    report: &ReportDataMsg<'_>,
+    clusters: &mut heapless::Vec<(u16, u32), 8>,
+) -> Result<(), Error> {
+    if let Some(attr_reports) = report.attr_reports.as_ref() {
+        for resp in attr_reports.iter() {
+            let path = resp?.unwrap_data().path;
+
+            clusters
+                .push((path.endpoint.unwrap(), path.cluster.unwrap()))
+                .unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_subscribe_delta_reports() {
+    // Subscribe to an attribute of two clusters, then change only one of them:
+    // the priming report contains both clusters, while the subsequent report
+    // only contains the cluster whose data version had changed
+    init_env_logger();
+
+    let im = ImEngine::new_default();
+    im.add_default_acl();
+
+    block_on(
+        select(im.run(im.handler()), async {
+            let paths = &[
+                AttrPath::new(&GenericPath::new(
+                    Some(0),
+                    Some(echo_cluster::ID),
+                    Some(echo_cluster::AttributesDiscriminants::Att1 as _),
+                )),
+                AttrPath::new(&GenericPath::new(
+                    Some(1),
+                    Some(on_off::FULL_CLUSTER.id),
+                    Some(on_off::AttributeId::OnOff as _),
+                )),
+            ];
+
+            let mut clusters = heapless::Vec::new();
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::subscribe(
+                &mut exchange,
+                &SubscribeRequest::attrs(0, 10, paths),
+                |report| collect_clusters(report, &mut clusters),
+            )
+            .await?;
+            drop(exchange);
+
+            assert_eq!(
+                clusters.as_slice(),
+                &[(0, echo_cluster::ID), (1, on_off::FULL_CLUSTER.id)]
+            );
+
+            let cmds = &[CmdData::new(
+                CmdPath::new(
+                    Some(1),
+                    Some(on_off::FULL_CLUSTER.id),
+                    Some(on_off::CommandId::Toggle as u32),
+                ),
+                TLVElement::new(&[0x15, 0x18]),
+            )];
+
+            let mut exchange = im.initiate_exchange().await?;
+            ImClient::invoke(&mut exchange, &InvokeRequest::new(cmds), |_| Ok(())).await?;
+            drop(exchange);
+
+            let mut clusters = heapless::Vec::new();
+            im.recv_report(|report| collect_clusters(report, &mut clusters))
+                .await?;
+
+            assert_eq!(clusters.as_slice(), &[(1, on_off::FULL_CLUSTER.id)]);
+
+            Ok(())
+        })
+        .coalesce(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_resume_persisted_subscription() {
+    // Establish a subscription and persist it, then "reboot" the tested node:
+    // the rebooted node loads the persisted subscription and resumes it by
+    // establishing a CASE session with the subscriber and sending it a priming report
+    init_env_logger();
+
+    let im = ImEngine::new_unfabriced();
+
+    let ca = OperationalCa::new(
+        E2eRunner::CA_FABRIC_ID,
+        1,
+        im.matter.epoch(),
+        im.matter.rand(),
+    )
+    .unwrap();
+
+    im.add_ca_fabrics(&ca);
+
+    im.add_default_acl();
+
+    let dir = std::env::temp_dir().join(format!("rs-matter-test-resume-{}", std::process::id()));
+
+    let mut psm = Psm::<4096>::new();
+
+    let paths = &[AttrPath::new(&GenericPath::new(
+        Some(0),
+        Some(echo_cluster::ID),
+        Some(echo_cluster::AttributesDiscriminants::Att1 as _),
+    ))];
+
+    {
+        let buffers = PooledBuffers::<10, NoopRawMutex, IMBuffer>::new(0);
+        let subscriptions = Subscriptions::<1>::new();
+        let dm = DataModel::new(&buffers, &subscriptions, im.handler());
+
+        block_on(
+            select3(
+                im.matter_client().run_resolver(RunnerResolver),
+                im.run_dm(&dm),
+                async {
+                    let mut exchange = im.initiate_exchange().await?;
+                    ImClient::subscribe(
+                        &mut exchange,
+                        &SubscribeRequest::attrs(0, 10, paths),
+                        |_| Ok(()),
+                    )
+                    .await?;
+                    drop(exchange);
+
+                    // Give the tested node time to process the ack of its subscribe response,
+                    // as only then the subscription is established
+                    Timer::after(Duration::from_millis(100)).await;
+
+                    Ok(())
+                },
+            )
+            .coalesce(),
+        )
+        .unwrap();
+
+        assert!(dm.subscriptions_changed());
+        psm.store_subscriptions(&dir, &dm).unwrap();
+        assert!(!dm.subscriptions_changed());
+    }
+
+    let buffers = PooledBuffers::<10, NoopRawMutex, IMBuffer>::new(0);
+    let subscriptions = Subscriptions::<1>::new();
+    let dm = DataModel::new(&buffers, &subscriptions, im.handler());
+
+    block_on(psm.load_subscriptions(&dir, &dm)).unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    block_on(
+        select3(
+            im.matter.run_resolver(RunnerResolver),
+            im.run_dm(&dm),
+            async {
+                // The rebooted node establishes a CASE session with the subscriber first
+                let mut exchange = Exchange::accept(im.matter_client()).await?;
+                SecureChannel::new().handle(&mut exchange).await?;
+                drop(exchange);
+
+                // ... and then sends it a priming report over that session
+                let mut clusters = heapless::Vec::new();
+                im.recv_report(|report| collect_clusters(report, &mut clusters))
+                    .await?;
+
+                assert_eq!(clusters.as_slice(), &[(0, echo_cluster::ID)]);
+
+                Ok(())
+            },
+        )
+        .coalesce(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,284 @@
+/*
+ *
+ *    Copyright (c) 2025 Project CHIP Authors
+ *
+ *    Licensed under the Apache License, Version 2.0 (the "License");
+ *    you may not use this file except in compliance with the License.
+ *    You may obtain a copy of the License at
+ *
+ *        http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *    Unless required by applicable law or agreed to in writing, software
+ *    distributed under the License is distributed on an "AS IS" BASIS,
+ *    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *    See the License for the specific language governing permissions and
+ *    limitations under the License.
+ */
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{App, Arg, ArgMatches};
+use log::{error, info};
+use rs_matter::cert::cd::{CertDeclarationContent, CD_FORMAT_VERSION};
+use rs_matter::cert::devatt::{AttestationCert, DevAttGenerator};
+use rs_matter::cert::pem::{
+    self, PEM_LABEL_CERTIFICATE, PEM_LABEL_EC_PRIVATE_KEY, PEM_LABEL_PRIVATE_KEY,
+};
+use rs_matter::cert::sec1::{self, MAX_KEY_PAIR_DER_LEN};
+use rs_matter::error::{Error, ErrorCode};
+use rs_matter::utils::epoch::sys_epoch;
+use rs_matter::utils::rand::sys_rand;
+use simple_logger::SimpleLogger;
+
+/// The max number of Product IDs in a Certification Declaration, as per the Matter spec
+const MAX_PIDS: usize = 100;
+
+/// The max length of a CMS-enveloped Certification Declaration
+const MAX_CD_LEN: usize = 1024;
+
+fn main() {
+    SimpleLogger::new()
+        .with_level(log::LevelFilter::Info)
+        .with_colors(true)
+        .without_timestamps()
+        .init()
+        .unwrap();
+
+    let m = App::new("devatt")
+        .about("Generate test device attestation credentials and Certification Declarations")
+        .arg(
+            Arg::with_name("vid")
+                .long("vid")
+                .takes_value(true)
+                .required(true)
+                .help("The Vendor ID, in hexadecimal"),
+        )
+        .arg(
+            Arg::with_name("pids")
+                .long("pids")
+                .takes_value(true)
+                .required(true)
+                .help("The Product ID or the inclusive Product ID range (e.g. 8000-8005), in hexadecimal"),
+        )
+        .arg(
+            Arg::with_name("out")
+                .short("o")
+                .long("out")
+                .takes_value(true)
+                .default_value(".")
+                .help("The directory to write the generated files to"),
+        )
+        .arg(
+            Arg::with_name("paa-cert")
+                .long("paa-cert")
+                .takes_value(true)
+                .requires("paa-key")
+                .help("Issue the PAI with an existing PAA certificate, rather than with a generated one"),
+        )
+        .arg(
+            Arg::with_name("paa-key")
+                .long("paa-key")
+                .takes_value(true)
+                .requires("paa-cert")
+                .help("The key pair of the existing PAA certificate"),
+        )
+        .arg(
+            Arg::with_name("cd-signer-cert")
+                .long("cd-signer-cert")
+                .takes_value(true)
+                .requires("cd-signer-key")
+                .help("Sign the Certification Declaration with an existing certificate, rather than with a generated one"),
+        )
+        .arg(
+            Arg::with_name("cd-signer-key")
+                .long("cd-signer-key")
+                .takes_value(true)
+                .requires("cd-signer-cert")
+                .help("The key pair of the existing CD signing certificate"),
+        )
+        .arg(
+            Arg::with_name("device-type")
+                .long("device-type")
+                .takes_value(true)
+                .default_value("16")
+                .help("The device type of the Certification Declaration, in hexadecimal"),
+        )
+        .arg(
+            Arg::with_name("certificate-id")
+                .long("certificate-id")
+                .takes_value(true)
+                .default_value("CSA00000SWC00000-00")
+                .help("The certificate ID of the Certification Declaration"),
+        )
+        .arg(
+            Arg::with_name("pem")
+                .long("pem")
+                .help("Write the certificates and the key pairs in PEM rather than in DER format"),
+        )
+        .get_matches();
+
+    if let Err(e) = run(&m) {
+        error!("Generating the credentials failed: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(m: &ArgMatches) -> Result<(), Error> {
+    let vid = parse_hex(m.value_of("vid").unwrap())?;
+    let pids = parse_pids(m.value_of("pids").unwrap())?;
+    let device_type = u32::from_str_radix(m.value_of("device-type").unwrap(), 16)
+        .map_err(|_| ErrorCode::InvalidData)?;
+
+    let out = Path::new(m.value_of("out").unwrap());
+    let pem = m.is_present("pem");
+
+    fs::create_dir_all(out)?;
+
+    let generator = DevAttGenerator::new(sys_epoch, sys_rand);
+
+    let paa = if let Some(cert) = m.value_of("paa-cert") {
+        load(Path::new(cert), Path::new(m.value_of("paa-key").unwrap()))?
+    } else {
+        let paa = generator.generate_paa(&format!("Matter Test PAA {vid:04X}"), Some(vid))?;
+        save(&paa, out, "paa", pem)?;
+
+        paa
+    };
+
+    let pai = generator.generate_pai(&paa, &format!("Matter Test PAI {vid:04X}"), vid, None)?;
+    save(&pai, out, "pai", pem)?;
+
+    for pid in &pids {
+        let dac = generator.generate_dac(
+            &pai,
+            &format!("Matter Test DAC {vid:04X}/{pid:04X}"),
+            vid,
+            *pid,
+        )?;
+        save(&dac, out, &format!("dac-{vid:04X}-{pid:04X}"), pem)?;
+    }
+
+    let cd_signer = if let Some(cert) = m.value_of("cd-signer-cert") {
+        load(
+            Path::new(cert),
+            Path::new(m.value_of("cd-signer-key").unwrap()),
+        )?
+    } else {
+        let cd_signer = generator.generate_cd_signer("Matter Test CD Signing Authority")?;
+        save(&cd_signer, out, "cd-signer", pem)?;
+
+        cd_signer
+    };
+
+    let mut cd = [0; MAX_CD_LEN];
+    let cd_len = cd_signer.sign_cd(
+        &CertDeclarationContent {
+            format_version: CD_FORMAT_VERSION,
+            vendor_id: vid,
+            product_id_array: &pids,
+            device_type_id: device_type,
+            certificate_id: m.value_of("certificate-id").unwrap(),
+            security_level: 0,
+            security_information: 0,
+            version_number: 1,
+            certification_type: 0,
+            dac_origin_vendor_id: None,
+            dac_origin_product_id: None,
+            authorized_paa_list: None,
+        },
+        &mut cd,
+    )?;
+
+    // The Certification Declaration is always provisioned in DER format
+    write(&out.join("cd.der"), &cd[..cd_len])?;
+
+    Ok(())
+}
+
+/// Parse a Product ID or an inclusive Product ID range
+fn parse_pids(pids: &str) -> Result<Vec<u16>, Error> {
+    let (start, end) = match pids.split_once('-') {
+        Some((start, end)) => (parse_hex(start)?, parse_hex(end)?),
+        None => (parse_hex(pids)?, parse_hex(pids)?),
+    };
+
+    if start > end || (end - start) as usize >= MAX_PIDS {
+        error!("Invalid Product ID range {pids}: at most {MAX_PIDS} Product IDs are supported");
+        Err(ErrorCode::InvalidData)?;
+    }
+
+    Ok((start..=end).collect())
+}
+
+fn parse_hex(value: &str) -> Result<u16, Error> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+
+    u16::from_str_radix(value, 16).map_err(|_| ErrorCode::InvalidData.into())
+}
+
+/// Load an existing certificate and its key pair, in DER or PEM format
+fn load(cert: &Path, key: &Path) -> Result<AttestationCert, Error> {
+    let cert = read(cert, &[PEM_LABEL_CERTIFICATE])?;
+    let key = read(key, &[PEM_LABEL_EC_PRIVATE_KEY, PEM_LABEL_PRIVATE_KEY])?;
+
+    AttestationCert::new(sec1::decode(&key)?, &cert)
+}
+
+/// Save a generated certificate and its key pair, in DER or PEM format
+fn save(cert: &AttestationCert, out: &Path, name: &str, pem: bool) -> Result<(), Error> {
+    let mut key = [0; MAX_KEY_PAIR_DER_LEN];
+    let key_len = sec1::encode(cert.key(), &mut key)?;
+    let key = &key[..key_len];
+
+    if pem {
+        let mut cert_pem = String::new();
+        pem::encode(PEM_LABEL_CERTIFICATE, cert.cert(), &mut cert_pem)
+            .map_err(|_| ErrorCode::NoSpace)?;
+
+        let mut key_pem = String::new();
+        pem::encode(PEM_LABEL_EC_PRIVATE_KEY, key, &mut key_pem).map_err(|_| ErrorCode::NoSpace)?;
+
+        write(&path(out, name, "cert", "pem"), cert_pem.as_bytes())?;
+        write(&path(out, name, "key", "pem"), key_pem.as_bytes())
+    } else {
+        write(&path(out, name, "cert", "der"), cert.cert())?;
+        write(&path(out, name, "key", "der"), key)
+    }
+}
+
+fn path(out: &Path, name: &str, kind: &str, ext: &str) -> PathBuf {
+    out.join(format!("{name}-{kind}.{ext}"))
+}
+
+fn read(path: &Path, pem_labels: &[&str]) -> Result<Vec<u8>, Error> {
+    let data = fs::read(path)?;
+
+    if !data.starts_with(b"-----BEGIN") {
+        return Ok(data);
+    }
+
+    let pem = std::str::from_utf8(&data).map_err(|_| ErrorCode::InvalidData)?;
+    let mut der = vec![0; data.len()];
+
+    let len = pem_labels
+        .iter()
+        .find_map(|label| pem::decode(label, pem, &mut der).ok())
+        .ok_or(ErrorCode::InvalidData)?;
+
+    der.truncate(len);
+
+    Ok(der)
+}
+
+fn write(path: &Path, data: &[u8]) -> Result<(), Error> {
+    fs::write(path, data)?;
+
+    info!("Wrote {}", path.display());
+
+    Ok(())
+}